@@ -0,0 +1,241 @@
+//! What commands and hooks print, escape sequences and all, read the way a terminal would:
+//! colors become styles, and lines redrawn with `\r` or erased keep only their latest state.
+//!
+//! Only the command log needs this. The other screens are made from what libgit2 returns,
+//! which has no escape sequences, and diffs are highlighted by gitu itself, see
+//! [`crate::syntax_highlight`]. Colors git or a pager like delta would add never reach them.
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use std::{iter::Peekable, str::Chars};
+
+pub(crate) fn lines(output: &str) -> Vec<Line<'static>> {
+    output.lines().map(line).collect()
+}
+
+fn line(input: &str) -> Line<'static> {
+    let mut cells: Vec<(char, Style)> = vec![];
+    let mut cursor = 0;
+    let mut style = Style::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' => cursor = 0,
+            '\x1b' => match chars.next() {
+                Some('[') => {
+                    let (params, action) = csi(&mut chars);
+                    match (action, params.first()) {
+                        (Some('m'), _) => style = sgr(style, &params),
+                        (Some('K'), None | Some(Some(0))) => cells.truncate(cursor),
+                        (Some('K'), Some(Some(1 | 2))) => {
+                            cells.clear();
+                            cursor = 0;
+                        }
+                        _ => (),
+                    }
+                }
+                Some(']') => osc(&mut chars),
+                _ => (),
+            },
+            '\t' => put(&mut cells, &mut cursor, (c, style)),
+            c if c.is_control() => (),
+            c => put(&mut cells, &mut cursor, (c, style)),
+        }
+    }
+
+    Line::from(
+        cells
+            .into_iter()
+            .fold(Vec::<Span>::new(), |mut spans, (c, style)| {
+                match spans.last_mut() {
+                    Some(span) if span.style == style => span.content.to_mut().push(c),
+                    _ => spans.push(Span::styled(c.to_string(), style)),
+                }
+                spans
+            }),
+    )
+}
+
+fn put(cells: &mut Vec<(char, Style)>, cursor: &mut usize, cell: (char, Style)) {
+    match cells.get_mut(*cursor) {
+        Some(existing) => *existing = cell,
+        None => cells.push(cell),
+    }
+    *cursor += 1;
+}
+
+/// The numbers and final character of a control sequence, after its `ESC [`.
+/// Numbers that don't parse are `None`, an empty one is 0.
+fn csi(chars: &mut Peekable<Chars>) -> (Vec<Option<u16>>, Option<char>) {
+    let mut raw = String::new();
+    while let Some(&c) = chars.peek() {
+        if ('\x40'..='\x7e').contains(&c) {
+            chars.next();
+            let params = raw
+                .split(';')
+                .map(|param| match param {
+                    "" => Some(0),
+                    param => param.parse().ok(),
+                })
+                .collect();
+            return (params, Some(c));
+        }
+        raw.push(c);
+        chars.next();
+    }
+    (vec![], None)
+}
+
+/// Skips an operating system command, like the hyperlinks or titles some tools set.
+fn osc(chars: &mut Peekable<Chars>) {
+    while let Some(c) = chars.next() {
+        match c {
+            '\x07' => return,
+            '\x1b' if chars.peek() == Some(&'\\') => {
+                chars.next();
+                return;
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Select graphic rendition, the sequences setting colors and attributes.
+/// Invalid ones are ignored.
+fn sgr(mut style: Style, params: &[Option<u16>]) -> Style {
+    let mut params = params.iter().copied();
+
+    while let Some(param) = params.next() {
+        let Some(param) = param else {
+            continue;
+        };
+
+        style = match param {
+            0 => Style::new(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            5 => style.add_modifier(Modifier::SLOW_BLINK),
+            6 => style.add_modifier(Modifier::RAPID_BLINK),
+            7 => style.add_modifier(Modifier::REVERSED),
+            8 => style.add_modifier(Modifier::HIDDEN),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            25 => style.remove_modifier(Modifier::SLOW_BLINK | Modifier::RAPID_BLINK),
+            27 => style.remove_modifier(Modifier::REVERSED),
+            28 => style.remove_modifier(Modifier::HIDDEN),
+            29 => style.remove_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(indexed(param as u8 - 30)),
+            38 => match extended(&mut params) {
+                Some(color) => style.fg(color),
+                None => style,
+            },
+            39 => Style { fg: None, ..style },
+            40..=47 => style.bg(indexed(param as u8 - 40)),
+            48 => match extended(&mut params) {
+                Some(color) => style.bg(color),
+                None => style,
+            },
+            49 => Style { bg: None, ..style },
+            90..=97 => style.fg(indexed(param as u8 - 90 + 8)),
+            100..=107 => style.bg(indexed(param as u8 - 100 + 8)),
+            _ => style,
+        };
+    }
+
+    style
+}
+
+/// The `5;<n>` or `2;<r>;<g>;<b>` following a 38 or 48. All of its numbers are taken,
+/// even when one is out of range and there's no color.
+fn extended(params: &mut impl Iterator<Item = Option<u16>>) -> Option<Color> {
+    let mut component = || u8::try_from(params.next()??).ok();
+
+    match component()? {
+        5 => Some(indexed(component()?)),
+        2 => {
+            let (r, g, b) = (component(), component(), component());
+            Some(Color::Rgb(r?, g?, b?))
+        }
+        _ => None,
+    }
+}
+
+/// The 16 basic colors by their names, so they follow the terminal's palette like ratatui's do.
+fn indexed(index: u8) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::Gray,
+        8 => Color::DarkGray,
+        9 => Color::LightRed,
+        10 => Color::LightGreen,
+        11 => Color::LightYellow,
+        12 => Color::LightBlue,
+        13 => Color::LightMagenta,
+        14 => Color::LightCyan,
+        15 => Color::White,
+        _ => Color::Indexed(index),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Stylize;
+
+    #[test]
+    fn colors() {
+        let line = line("\x1b[1;31merror:\x1b[0m bad \x1b[38;5;208mthing\x1b[m");
+
+        assert_eq!(
+            line.spans,
+            [
+                Span::styled("error:", Style::new().red().bold()),
+                Span::raw(" bad "),
+                Span::styled("thing", Style::new().fg(Color::Indexed(208))),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_colors_ignored() {
+        let line = line("\x1b[31m\x1b[38;5;300mred \x1b[38;2;300;0;0;1mbold\x1b[99999;4mline");
+
+        assert_eq!(
+            line.spans,
+            [
+                Span::styled("red ", Style::new().red()),
+                Span::styled("bold", Style::new().red().bold()),
+                Span::styled("line", Style::new().red().bold().underlined()),
+            ]
+        );
+    }
+
+    #[test]
+    fn redrawn_lines() {
+        let lines = lines("Rebasing (1/2)\rRebasing (2/2)\r\x1b[KSuccessfully rebased\nCounting: 10%\rCounting: 100%\r\n")
+            .into_iter()
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines, ["Successfully rebased", "Counting: 100%"]);
+    }
+
+    #[test]
+    fn other_sequences_dropped() {
+        let line = line("\x1b]8;;https://example.com\x07link\x1b]8;;\x07 \x1b[2Aup\x07");
+        assert_eq!(line.to_string(), "link up");
+    }
+}
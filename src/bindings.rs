@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 
 use crate::{
+    items::TargetData,
     key_parser,
     menu::{Menu, PendingMenu},
     ops::Op,
@@ -31,12 +32,12 @@ impl From<&BTreeMap<Menu, BTreeMap<Op, Vec<String>>>> for Bindings {
 impl Bindings {
     pub(crate) fn match_bindings<'a>(
         &'a self,
-        pending: &'a Menu,
+        pending: Menu,
         events: &'a [(KeyModifiers, KeyCode)],
     ) -> impl Iterator<Item = &'a Binding> + 'a {
         self.vec
             .iter()
-            .filter(move |binding| &binding.menu == pending)
+            .filter(move |binding| binding.menu == pending)
             .filter(|binding| binding.keys.starts_with(events))
     }
 
@@ -53,6 +54,24 @@ impl Bindings {
             .filter(move |keybind| keybind.menu == expected)
     }
 
+    /// Bindings of the menu's target ops that can act on `target_data`.
+    pub(crate) fn target_list<'a>(
+        &'a self,
+        pending: &Menu,
+        target_data: &'a TargetData,
+    ) -> impl Iterator<Item = &'a Binding> {
+        self.list(pending)
+            .filter(|keybind| keybind.op.clone().implementation().is_target_op())
+            .filter(move |keybind| {
+                keybind
+                    .op
+                    .clone()
+                    .implementation()
+                    .get_action(Some(target_data))
+                    .is_some()
+            })
+    }
+
     pub(crate) fn arg_list<'a>(
         &'a self,
         pending: &'a PendingMenu,
@@ -1,4 +1,11 @@
+use crate::Res;
 use clap::{Parser, Subcommand};
+use git2::Repository;
+use regex::Regex;
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
 
 #[derive(Default, Debug, Parser)]
 #[command(name = "gitu")]
@@ -7,6 +14,10 @@ pub struct Args {
     #[command(subcommand)]
     pub command: Option<Commands>,
 
+    /// Open the status screen with the cursor on this file.
+    /// Put it after `--` when it's named like a subcommand (eg: `gitu -- log`).
+    pub path: Option<PathBuf>,
+
     /// Send keys on startup (eg: `gitu -k ll`).
     ///     It is possible to send:
     ///     - single char-keys: a, b, c, ...
@@ -23,6 +34,10 @@ pub struct Args {
     #[clap(long, action)]
     pub log: bool,
 
+    /// Print the effective configuration, including defaults, and exit.
+    #[clap(long, action)]
+    pub print_config: bool,
+
     #[clap(long, action)]
     /// Print version
     pub version: bool,
@@ -30,5 +45,95 @@ pub struct Args {
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
+    /// Show a commit, accepts any revision git understands (eg: `HEAD~2`, `main^{commit}`).
     Show { reference: String },
+    /// Open the log of a revision, HEAD by default.
+    Log {
+        rev: Option<String>,
+        /// Limit the number of commits shown.
+        #[clap(short = 'n', long)]
+        max_count: Option<usize>,
+        /// Only show commits with a message matching this regex.
+        #[clap(long)]
+        grep: Option<Regex>,
+    },
+    /// Print the status screen to stdout.
+    Status {
+        /// Print one `<section> <status> <path>` line per changed file instead.
+        #[clap(long, action)]
+        porcelain: bool,
+    },
+    /// Print a diff to stdout. Compares the worktree with the index by default.
+    Diff {
+        /// Compare the index with HEAD.
+        #[clap(long, action)]
+        staged: bool,
+        /// A range like `A..B` or `A...B`, or a single revision to compare the worktree with.
+        range: Option<String>,
+    },
+    /// Create a repository, in the current directory by default, and open it.
+    Init { dir: Option<PathBuf> },
+    /// Clone a repository, showing git's progress, and open it.
+    Clone {
+        url: String,
+        /// Named after the repository by default, like git does.
+        dir: Option<PathBuf>,
+    },
+}
+
+impl Args {
+    /// Checks that the revisions and path given refer to something in the repo.
+    pub fn validate(&self, repo: &Repository) -> Res<()> {
+        match &self.command {
+            Some(Commands::Show { reference }) => {
+                resolve_rev(repo, reference)?;
+            }
+            Some(Commands::Log { rev: Some(rev), .. }) => {
+                resolve_rev(repo, rev)?;
+            }
+            _ => (),
+        }
+
+        if let Some(path) = &self.path {
+            resolve_path(repo, path)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn resolve_rev(repo: &Repository, rev: &str) -> Res<git2::Oid> {
+    Ok(repo
+        .revparse_single(rev)
+        .map_err(|err| format!("Invalid revision '{}': {}", rev, err.message()))?
+        .id())
+}
+
+/// Turns a path relative to the current directory into one relative to the repository root.
+pub(crate) fn resolve_path(repo: &Repository, path: &Path) -> Res<PathBuf> {
+    let workdir = repo
+        .workdir()
+        .ok_or("Repository has no workdir")?
+        .canonicalize()?;
+    let absolute = env::current_dir()?.join(path);
+
+    // The file may have been deleted, so only the directory it's in has to exist.
+    let canonical = match absolute.canonicalize() {
+        Ok(canonical) => canonical,
+        Err(_) => match (absolute.parent(), absolute.file_name()) {
+            (Some(parent), Some(name)) => parent.canonicalize()?.join(name),
+            _ => absolute.clone(),
+        },
+    };
+
+    let relative = canonical
+        .strip_prefix(&workdir)
+        .map_err(|_| format!("Path is outside the repository: {}", path.display()))?
+        .to_path_buf();
+
+    if !canonical.exists() && repo.index()?.get_path(&relative, 0).is_none() {
+        return Err(format!("No such file: {}", path.display()).into());
+    }
+
+    Ok(relative)
 }
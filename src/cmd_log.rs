@@ -1,7 +1,7 @@
+use crate::ansi;
 use crate::config::Config;
+use crate::pipes::LiveOutput;
 use itertools::Itertools;
-use ratatui::style::Style;
-use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Text;
 use std::borrow::Cow;
@@ -9,26 +9,46 @@ use std::iter;
 use std::process::Command;
 use std::sync::Arc;
 use std::sync::RwLock;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Turned a notch every tick of the event loop.
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const SPINNER_TICK: Duration = Duration::from_millis(100);
 
 pub(crate) struct CmdLog {
     pub(crate) entries: Vec<Arc<RwLock<CmdLogEntry>>>,
+    /// What was last cleared, so it can still be looked at in full.
+    previous: Vec<Arc<RwLock<CmdLogEntry>>>,
 }
 
 impl CmdLog {
     pub fn new() -> Self {
-        Self { entries: vec![] }
+        Self {
+            entries: vec![],
+            previous: vec![],
+        }
     }
 
     pub fn push_cmd(&mut self, cmd: &Command) -> Arc<RwLock<CmdLogEntry>> {
         let value = Arc::new(RwLock::new(CmdLogEntry::Cmd {
             args: command_args(cmd),
             out: None,
+            started: Instant::now(),
+            live: None,
         }));
 
         self.entries.push(Arc::clone(&value));
         value
     }
 
+    /// A command to run after the one that's running.
+    pub fn push_queued_cmd(&mut self, cmd: &Command) -> Arc<RwLock<CmdLogEntry>> {
+        let value = Arc::new(RwLock::new(CmdLogEntry::Queued(command_args(cmd))));
+        self.entries.push(Arc::clone(&value));
+        value
+    }
+
     pub fn push_cmd_with_output(
         &mut self,
         cmd: &Command,
@@ -37,6 +57,8 @@ impl CmdLog {
         let value = Arc::new(RwLock::new(CmdLogEntry::Cmd {
             args: command_args(cmd),
             out: Some(out),
+            started: Instant::now(),
+            live: None,
         }));
 
         self.entries.push(Arc::clone(&value));
@@ -48,7 +70,18 @@ impl CmdLog {
     }
 
     pub fn clear(&mut self) {
-        self.entries.clear();
+        if !self.entries.is_empty() {
+            self.previous = std::mem::take(&mut self.entries);
+        }
+    }
+
+    /// The entries shown, or if there are none, those shown before.
+    pub(crate) fn latest(&self) -> &[Arc<RwLock<CmdLogEntry>>] {
+        if self.entries.is_empty() {
+            &self.previous
+        } else {
+            &self.entries
+        }
     }
 
     pub(crate) fn is_empty(&self) -> bool {
@@ -77,8 +110,16 @@ pub(crate) fn format_log_entry<'a>(
     log: &Arc<RwLock<CmdLogEntry>>,
 ) -> Vec<Line<'a>> {
     match &*log.read().unwrap() {
-        CmdLogEntry::Cmd { args, out } => [Line::styled(
-            format!("{}{}", if out.is_some() { "$ " } else { "Running: " }, args),
+        CmdLogEntry::Cmd {
+            args,
+            out,
+            started,
+            live,
+        } => [Line::styled(
+            match out {
+                Some(_) => format!("$ {}", args),
+                None => format!("{} Running: {}", running_indicator(started.elapsed()), args),
+            },
             &config.style.command,
         )]
         .into_iter()
@@ -86,30 +127,64 @@ pub(crate) fn format_log_entry<'a>(
             if out.is_empty() {
                 vec![]
             } else {
-                Text::raw(out.to_string()).lines
+                ansi::lines(out)
             }
         }))
+        .chain(
+            live.iter()
+                .filter(|_| out.is_none())
+                .flat_map(|live| ansi::lines(&live.lock().unwrap_or_else(|p| p.into_inner()))),
+        )
         .collect::<Vec<_>>(),
+        CmdLogEntry::Queued(args) => vec![Line::styled(
+            format!("Queued: {}", args),
+            &config.style.command,
+        )],
         CmdLogEntry::Error(err) => {
-            vec![Line::styled(
-                format!("! {}", err),
-                Style::new().red().bold(),
-            )]
+            vec![Line::styled(format!("! {}", err), &config.style.cmd_error)]
         }
         CmdLogEntry::Info(msg) => {
-            vec![Line::styled(
-                format!("> {}", msg),
-                Style::new().green().bold(),
-            )]
+            vec![Line::styled(format!("> {}", msg), &config.style.cmd_info)]
         }
     }
 }
 
+/// A spinner and the seconds spent so far, showing that a slow command is still going.
+fn running_indicator(elapsed: Duration) -> String {
+    let frame = SPINNER[(elapsed.as_millis() / SPINNER_TICK.as_millis()) as usize % SPINNER.len()];
+    format!("{} {}s", frame, elapsed.as_secs())
+}
+
 pub(crate) enum CmdLogEntry {
     Cmd {
         args: Cow<'static, str>,
         out: Option<Cow<'static, str>>,
+        started: Instant,
+        /// What the command printed so far, while it's running.
+        live: Option<LiveOutput>,
     },
+    Queued(Cow<'static, str>),
     Error(String),
     Info(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn live_lines_keep_latest_progress() {
+        let lines = ansi::lines("Counting: 10%\rCounting: 100%\r\nDone\n")
+            .into_iter()
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(lines, ["Counting: 100%", "Done"]);
+    }
+
+    #[test]
+    fn running_indicator_turns_every_tick() {
+        assert_eq!(running_indicator(Duration::ZERO), "⠋ 0s");
+        assert_eq!(running_indicator(Duration::from_millis(250)), "⠹ 0s");
+        assert_eq!(running_indicator(Duration::from_millis(3050)), "⠋ 3s");
+    }
+}
@@ -1,36 +1,271 @@
-use std::{collections::BTreeMap, path::PathBuf};
+use std::{collections::BTreeMap, fmt, path::PathBuf};
 
-use crate::{menu::Menu, ops::Op, Res};
+use crate::{
+    key_parser,
+    menu::{Menu, PendingMenu},
+    ops::Op,
+    Res,
+};
+use chrono::format::StrftimeItems;
 use etcetera::{choose_base_strategy, BaseStrategy};
 use figment::{
     providers::{Format, Toml},
     Figment,
 };
 use ratatui::style::{Color, Modifier, Style};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 const DEFAULT_CONFIG: &str = include_str!("default_config.toml");
+const MAGIT_KEYMAP: &str = include_str!("magit_keymap.toml");
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub(crate) struct Config {
     pub general: GeneralConfig,
     pub style: StyleConfig,
     pub bindings: BTreeMap<Menu, BTreeMap<Op, Vec<String>>>,
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct GeneralConfig {
     pub always_show_help: BoolConfigEntry,
     pub confirm_quit: BoolConfigEntry,
+    pub remember_menu_args: BoolConfigEntry,
+    pub title_bar: BoolConfigEntry,
+    pub key_hints: BoolConfigEntry,
+    #[serde(default)]
+    pub ci_status: BoolConfigEntry,
+    #[serde(default)]
+    pub whitespace_errors: BoolConfigEntry,
+    #[serde(default)]
+    pub file_tree: BoolConfigEntry,
+    #[serde(default)]
+    pub file_order: FileOrder,
+    pub confirm: ConfirmConfig,
+    #[serde(default)]
+    pub notify: NotifyConfig,
+    pub diff_context_lines: usize,
+    #[serde(default)]
+    pub diff_algorithm: DiffAlgorithm,
+    #[serde(default)]
+    pub screen_limit: usize,
+    #[serde(default)]
+    pub split_pane: SplitPaneConfig,
+    #[serde(default)]
+    pub popup_max_height: u16,
+    #[serde(default)]
+    pub cmd_output: CmdOutput,
+    #[serde(default)]
+    pub command_timeout: u64,
+    #[serde(default)]
+    pub index_lock_retries: u32,
+    #[serde(default)]
+    pub commit_template: Option<PathBuf>,
+    #[serde(default)]
+    pub commit_lint: Option<String>,
+    #[serde(default)]
+    pub editor_template: Option<String>,
+    #[serde(default)]
+    pub autostash: BoolConfigEntry,
+    #[serde(default)]
+    pub keymap: Keymap,
+    #[serde(default)]
+    pub key_timeout: u64,
+    #[serde(default)]
+    pub date_format: DateFormat,
+    #[serde(default)]
+    pub log: LogConfig,
+}
+
+/// The columns of commits listed in logs.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogConfig {
+    /// 0 abbreviates them like git does.
+    pub hash_length: usize,
+    pub author: LogAuthor,
+    pub refs: BoolConfigEntry,
+    /// 0 shows subjects in full.
+    pub subject_width: usize,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogAuthor {
+    #[default]
+    None,
+    Name,
+    Email,
+    Initials,
+}
+
+/// Named like git's `--date` formats.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum DateFormat {
+    #[default]
+    Rfc,
+    Iso,
+    Relative,
+    /// A `strftime` format, like `format:%Y-%m-%d`.
+    Format(String),
+}
+
+impl TryFrom<String> for DateFormat {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "rfc" => Ok(Self::Rfc),
+            "iso" => Ok(Self::Iso),
+            "relative" => Ok(Self::Relative),
+            _ => match value.strip_prefix("format:") {
+                Some(format)
+                    if !StrftimeItems::new(format).any(|item| item == chrono::format::Item::Error) =>
+                {
+                    Ok(Self::Format(format.to_string()))
+                }
+                _ => Err(format!(
+                    "Invalid date format {:?}, expected \"rfc\", \"iso\", \"relative\" or \"format:<strftime>\"",
+                    value
+                )),
+            },
+        }
+    }
+}
+
+impl From<DateFormat> for String {
+    fn from(value: DateFormat) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for DateFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rfc => f.write_str("rfc"),
+            Self::Iso => f.write_str("iso"),
+            Self::Relative => f.write_str("relative"),
+            Self::Format(format) => write!(f, "format:{}", format),
+        }
+    }
+}
+
+/// Which built-in bindings the user's are merged into.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Keymap {
+    #[default]
+    Default,
+    /// Laid over the default bindings, see `magit_keymap.toml`.
+    Magit,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfirmConfig {
+    pub discard: Confirm,
+    pub delete_branch: Confirm,
+    pub reset_hard: Confirm,
+    pub force_push: Confirm,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Confirm {
+    #[default]
+    Ask,
+    Never,
+    DoubleConfirm,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotifyConfig {
+    pub method: Notify,
+    pub after_seconds: u64,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SplitPaneConfig {
+    /// Names of the screens to split, matched regardless of case.
+    pub screens: Vec<String>,
+    /// In percent of the terminal width.
+    pub list_width: u16,
+}
+
+impl SplitPaneConfig {
+    pub(crate) fn splits(&self, screen_name: &str) -> bool {
+        self.screens
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(screen_name))
+    }
+}
+
+#[derive(
+    Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, derive_more::Display,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum CmdOutput {
+    /// Below the screen, which shrinks to make room for it.
+    #[default]
+    #[display(fmt = "docked")]
+    Docked,
+    /// Over the bottom of the screen.
+    #[display(fmt = "overlaid")]
+    Overlaid,
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Notify {
+    #[default]
+    Bell,
+    Desktop,
+    None,
+}
+
+#[derive(
+    Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, derive_more::Display,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileOrder {
+    #[default]
+    #[display(fmt = "path")]
+    Path,
+    #[display(fmt = "size")]
+    Size,
+    #[display(fmt = "status")]
+    Status,
+}
+
+#[derive(
+    Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, derive_more::Display,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffAlgorithm {
+    #[display(fmt = "myers")]
+    Myers,
+    #[display(fmt = "minimal")]
+    Minimal,
+    #[default]
+    #[display(fmt = "patience")]
+    Patience,
+    #[display(fmt = "histogram")]
+    Histogram,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct BoolConfigEntry {
     #[serde(default)]
     pub enabled: bool,
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct StyleConfig {
     pub section_header: StyleConfigEntry,
     pub file_header: StyleConfigEntry,
@@ -46,8 +281,26 @@ pub struct StyleConfig {
     pub selection_line: StyleConfigEntry,
     pub selection_bar: StyleConfigEntry,
     pub selection_area: StyleConfigEntry,
+    pub selection_hunk: StyleConfigEntry,
+    pub marked: StyleConfigEntry,
+    pub region: StyleConfigEntry,
+    pub scrollbar: StyleConfigEntry,
+    pub title_bar: StyleConfigEntry,
+    pub key_hints: StyleConfigEntry,
+    pub popup: StyleConfigEntry,
+    pub cmd_error: StyleConfigEntry,
+    pub cmd_info: StyleConfigEntry,
+    #[serde(default)]
+    pub ci_pass: StyleConfigEntry,
+    #[serde(default)]
+    pub ci_fail: StyleConfigEntry,
+    #[serde(default)]
+    pub ci_pending: StyleConfigEntry,
+    #[serde(default)]
+    pub issue_reference: StyleConfigEntry,
 
     pub hash: StyleConfigEntry,
+    pub author: StyleConfigEntry,
     pub branch: StyleConfigEntry,
     pub remote: StyleConfigEntry,
     pub tag: StyleConfigEntry,
@@ -57,7 +310,8 @@ pub struct StyleConfig {
     pub hotkey: StyleConfigEntry,
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct DiffHighlightConfig {
     #[serde(default)]
     pub tag_old: StyleConfigEntry,
@@ -71,9 +325,12 @@ pub struct DiffHighlightConfig {
     pub changed_old: StyleConfigEntry,
     #[serde(default)]
     pub changed_new: StyleConfigEntry,
+    #[serde(default)]
+    pub whitespace_error: StyleConfigEntry,
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SyntaxHighlightConfig {
     #[serde(default)]
     pub enabled: bool,
@@ -124,7 +381,8 @@ pub struct SyntaxHighlightConfig {
     pub variable_parameter: StyleConfigEntry,
 }
 
-#[derive(Default, Debug, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct StyleConfigEntry {
     #[serde(default)]
     fg: Option<Color>,
@@ -132,6 +390,38 @@ pub struct StyleConfigEntry {
     bg: Option<Color>,
     #[serde(default)]
     mods: Option<Modifier>,
+    #[serde(default)]
+    fallback_fg: Option<Color>,
+    #[serde(default)]
+    fallback_bg: Option<Color>,
+}
+
+impl StyleConfig {
+    /// Every style's `fallback_fg` and `fallback_bg` in place of its colors, where given.
+    fn with_fallbacks(self) -> Res<Self> {
+        let mut value = toml::Value::try_from(self)?;
+        use_fallbacks(&mut value);
+        Ok(value.try_into()?)
+    }
+}
+
+fn use_fallbacks(value: &mut toml::Value) {
+    let toml::Value::Table(table) = value else {
+        return;
+    };
+
+    for (fallback, color) in [("fallback_fg", "fg"), ("fallback_bg", "bg")] {
+        if let Some(fallback) = table.remove(fallback) {
+            table.insert(color.to_string(), fallback);
+        }
+    }
+    table.iter_mut().for_each(|(_, value)| use_fallbacks(value));
+}
+
+/// Whether the terminal says it shows 24-bit colors, like most modern ones do.
+fn truecolor() -> bool {
+    std::env::var("COLORTERM")
+        .is_ok_and(|colorterm| colorterm == "truecolor" || colorterm == "24bit")
 }
 
 impl From<&StyleConfigEntry> for Style {
@@ -155,14 +445,77 @@ pub(crate) fn init_config() -> Res<Config> {
         log::info!("No config file at {:?}", config_path);
     }
 
-    let config = Figment::new()
-        .merge(Toml::string(DEFAULT_CONFIG))
-        .merge(Toml::file(config_path))
-        .extract()?;
+    let mut config: Config = layered(Figment::from(Toml::file(&config_path)))
+        .extract()
+        .map_err(|err| format!("Invalid config {:?}: {}", config_path, err))?;
+
+    if !truecolor() {
+        config.style = config.style.with_fallbacks()?;
+    }
+
+    validate_bindings(&config)
+        .map_err(|err| format!("Invalid config {:?}: {}", config_path, err))?;
 
     Ok(config)
 }
 
+/// The defaults, then the preset of `general.keymap` the user picked, then the user's config.
+fn layered(user_config: Figment) -> Figment {
+    let defaults = Figment::new().merge(Toml::string(DEFAULT_CONFIG));
+    let keymap = defaults
+        .clone()
+        .merge(user_config.clone())
+        .extract_inner("general.keymap")
+        .unwrap_or_default();
+
+    match keymap {
+        Keymap::Default => defaults,
+        Keymap::Magit => defaults.merge(Toml::string(MAGIT_KEYMAP)),
+    }
+    .merge(user_config)
+}
+
+/// The effective configuration after merging the user's config file into the defaults.
+pub fn print_config() -> Res<String> {
+    Ok(toml::to_string(&init_config()?)?)
+}
+
+/// Catches bindings that deserialize fine, but can never be triggered.
+fn validate_bindings(config: &Config) -> Res<()> {
+    for (menu, ops) in &config.bindings {
+        let menu_name = serialized_name(menu);
+
+        for (op, binds) in ops {
+            if let Op::ToggleArg(arg) = op {
+                if !PendingMenu::init(*menu).args.contains_key(arg.as_str()) {
+                    return Err(format!("unknown key `bindings.{}.{}`", menu_name, arg).into());
+                }
+            }
+
+            for keys in binds {
+                if !matches!(key_parser::parse_keys(keys), Ok(("", _))) {
+                    return Err(format!(
+                        "invalid key chord {:?} for key `bindings.{}.{}`",
+                        keys,
+                        menu_name,
+                        serialized_name(op)
+                    )
+                    .into());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn serialized_name<T: Serialize>(value: &T) -> String {
+    toml::Value::try_from(value)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
 pub fn config_path() -> PathBuf {
     choose_base_strategy()
         .expect("Unable to find the config directory!")
@@ -186,9 +539,20 @@ mod tests {
         providers::{Format, Toml},
         Figment,
     };
-    use ratatui::style::Color;
+    use ratatui::style::{Color, Modifier};
+
+    use super::{layered, validate_bindings, Config, DateFormat, DEFAULT_CONFIG};
+    use crate::{menu::Menu, ops::Op};
+    use std::collections::HashSet;
 
-    use super::{Config, DEFAULT_CONFIG};
+    fn extract(user_config: &str) -> Result<Config, String> {
+        let config: Config = layered(Figment::from(Toml::string(user_config)))
+            .extract()
+            .map_err(|err| err.to_string())?;
+
+        validate_bindings(&config).map_err(|err| err.to_string())?;
+        Ok(config)
+    }
 
     #[test]
     fn config_merges() {
@@ -206,4 +570,111 @@ mod tests {
         assert_eq!(config.style.hunk_header.bg, Some(Color::LightGreen));
         assert_eq!(config.style.hunk_header.fg, Some(Color::Blue));
     }
+
+    #[test]
+    fn style_fallbacks() {
+        let config = extract(
+            r##"
+            [style]
+            hunk_header = { fg = "#5f87d7", bg = "#303030", fallback_fg = "blue", mods = "BOLD" }
+            "##,
+        )
+        .unwrap();
+
+        let style = config.style.with_fallbacks().unwrap();
+        assert_eq!(style.hunk_header.fg, Some(Color::Blue));
+        assert_eq!(style.hunk_header.bg, Some(Color::Rgb(0x30, 0x30, 0x30)));
+        assert_eq!(style.hunk_header.mods, Some(Modifier::BOLD));
+        assert_eq!(style.section_header.fg, Some(Color::Yellow));
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        extract("").unwrap();
+    }
+
+    #[test]
+    fn unknown_key() {
+        let err = extract("[general]\nconfirm_quit.enabledd = true").unwrap_err();
+        assert!(err.contains("unknown field: found `enabledd`"), "{}", err);
+        assert!(err.contains("general.confirm_quit"), "{}", err);
+    }
+
+    #[test]
+    fn date_formats() {
+        let config = extract("[general]\ndate_format = \"format:%Y-%m-%d\"").unwrap();
+        assert_eq!(
+            config.general.date_format,
+            DateFormat::Format("%Y-%m-%d".to_string())
+        );
+
+        let err = extract("[general]\ndate_format = \"format:%Q\"").unwrap_err();
+        assert!(err.contains("Invalid date format \"format:%Q\""), "{}", err);
+    }
+
+    #[test]
+    fn unknown_binding() {
+        let err = extract("[bindings]\nroot.stagee = [\"s\"]").unwrap_err();
+        assert_eq!(err, "unknown key `bindings.root.stagee`");
+    }
+
+    #[test]
+    fn invalid_key_chord() {
+        let err = extract("[bindings]\nroot.stage = [\"<ctrl+>\"]").unwrap_err();
+        assert_eq!(
+            err,
+            "invalid key chord \"<ctrl+>\" for key `bindings.root.stage`"
+        );
+    }
+
+    #[test]
+    fn bad_color() {
+        let err = extract("[style]\nhunk_header.fg = \"not a color\"").unwrap_err();
+        assert!(err.contains("style.hunk_header.fg"), "{}", err);
+    }
+
+    fn root_binding(config: &Config, op: Op) -> Vec<String> {
+        config.bindings[&Menu::Root][&op].clone()
+    }
+
+    #[test]
+    fn magit_keymap() {
+        let config = extract("[general]\nkeymap = \"magit\"").unwrap();
+        assert_eq!(root_binding(&config, Op::Discard), ["k"]);
+        assert_eq!(root_binding(&config, Op::MoveDown), ["n", "<down>"]);
+        assert_eq!(root_binding(&config, Op::Stage), ["s"]);
+    }
+
+    #[test]
+    fn bindings_override_keymap() {
+        let config =
+            extract("[general]\nkeymap = \"magit\"\n[bindings]\nroot.discard = [\"x\"]").unwrap();
+        assert_eq!(root_binding(&config, Op::Discard), ["x"]);
+        assert_eq!(root_binding(&config, Op::MoveUp), ["p", "<up>"]);
+    }
+
+    #[test]
+    fn keymaps_bind_keys_once_per_menu() {
+        for user_config in ["", "[general]\nkeymap = \"magit\""] {
+            let config = extract(user_config).unwrap();
+
+            for (menu, ops) in &config.bindings {
+                let mut seen = HashSet::new();
+                for keys in ops.values().flatten() {
+                    assert!(seen.insert(keys), "{:?} is bound twice in {:?}", keys, menu);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn printed_config_roundtrips() {
+        let printed = toml::to_string(&extract("").unwrap()).unwrap();
+        let reparsed: Config = Figment::new()
+            .merge(Toml::string(&printed))
+            .extract()
+            .unwrap();
+
+        assert_eq!(toml::to_string(&reparsed).unwrap(), printed);
+    }
 }
@@ -0,0 +1,213 @@
+//! Asking for credentials from within the TUI, rather than git prompting on the terminal.
+//!
+//! Commands run with `GIT_TERMINAL_PROMPT=0`, as git would otherwise read from the terminal
+//! behind the TUI's back. When that makes a command fail, the username and password are
+//! prompted for, and the command is run again with gitu itself as the `GIT_ASKPASS` program.
+//! The password is handed to it in a file only the user may read, not in the environment,
+//! which git passes on to hooks and anything else it runs. The file is removed as soon as
+//! the password has been read from it.
+//!
+//! ssh is likewise run in batch mode. Its passphrase and host key questions can't be
+//! answered ahead of time, so a command failing on those is run again with the TUI suspended.
+
+use crate::{ops::set_prompt, state::State, term::Term, Res};
+use git2::Repository;
+use std::{
+    env,
+    error::Error,
+    fmt,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    process::{self, Command},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const USERNAME_VAR: &str = "GITU_ASKPASS_USERNAME";
+const PASSWORD_FILE_VAR: &str = "GITU_ASKPASS_PASSWORD_FILE";
+const SSH_BATCH_MODE: &str = "ssh -o BatchMode=yes";
+
+/// What a failed command needed from the user.
+pub(crate) enum Needs {
+    Credentials,
+    Terminal,
+}
+
+/// Answers git's question when gitu was run as an askpass program, see `man gitcredentials`.
+/// The question is then its one argument, anything else is run as usual.
+pub fn askpass_answer() -> Option<String> {
+    let password_file = env::var_os(PASSWORD_FILE_VAR)?;
+    let [_, question] = &env::args().collect::<Vec<_>>()[..] else {
+        return None;
+    };
+
+    if question.starts_with("Username for ") {
+        env::var(USERNAME_VAR).ok()
+    } else if question.starts_with("Password for ") {
+        // Hooks git runs later on can't read it either
+        let password = fs::read_to_string(&password_file).ok();
+        let _ = fs::remove_file(&password_file);
+        password
+    } else {
+        None
+    }
+}
+
+/// So what gitu runs doesn't see the credentials, if it was itself run by a git asking for them.
+pub fn remove_askpass_env() {
+    env::remove_var(USERNAME_VAR);
+    env::remove_var(PASSWORD_FILE_VAR);
+}
+
+/// A command failed for lack of credentials, which are being asked for. It's run again once
+/// they've been entered, ahead of any queued after it.
+#[derive(Debug)]
+pub(crate) struct AwaitingCredentials;
+
+impl fmt::Display for AwaitingCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Waiting for credentials")
+    }
+}
+
+impl Error for AwaitingCredentials {}
+
+/// Holds the password for the askpass program. Removed when dropped,
+/// which is once the command it was written for has finished.
+pub(crate) struct PasswordFile(PathBuf);
+
+impl PasswordFile {
+    fn create(password: &str) -> Res<Self> {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+        let path = env::temp_dir().join(format!("gitu-askpass-{}-{}", process::id(), nanos));
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let file = Self(path);
+        options.open(&file.0)?.write_all(password.as_bytes())?;
+        Ok(file)
+    }
+}
+
+impl Drop for PasswordFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+pub(crate) fn disable_terminal_prompt(repo: &Repository, cmd: &mut Command) {
+    cmd.env("GIT_TERMINAL_PROMPT", "0");
+
+    // Leave any ssh command the user configured alone
+    let has_ssh_command = env::var_os("GIT_SSH_COMMAND").is_some()
+        || env::var_os("GIT_SSH").is_some()
+        || repo
+            .config()
+            .is_ok_and(|config| config.get_string("core.sshCommand").is_ok());
+
+    if !has_ssh_command {
+        cmd.env("GIT_SSH_COMMAND", SSH_BATCH_MODE);
+    }
+}
+
+/// Undoes `disable_terminal_prompt`, for running a command attached to the terminal.
+pub(crate) fn with_terminal_prompt(cmd: &Command) -> Command {
+    let mut cmd = copy_cmd(cmd);
+    cmd.env_remove("GIT_TERMINAL_PROMPT");
+
+    let has_credentials = cmd.get_envs().any(|(key, _)| key == PASSWORD_FILE_VAR);
+    if has_credentials {
+        cmd.env_remove("GIT_ASKPASS");
+        cmd.env_remove(USERNAME_VAR);
+        cmd.env_remove(PASSWORD_FILE_VAR);
+    }
+
+    if cmd
+        .get_envs()
+        .any(|(key, value)| key == "GIT_SSH_COMMAND" && value == Some(SSH_BATCH_MODE.as_ref()))
+    {
+        cmd.env_remove("GIT_SSH_COMMAND");
+    }
+
+    cmd
+}
+
+pub(crate) fn needs(output: &str) -> Option<Needs> {
+    if output.contains("terminal prompts disabled") {
+        Some(Needs::Credentials)
+    } else if output.contains("Host key verification failed")
+        || output.contains("Permission denied (publickey")
+    {
+        Some(Needs::Terminal)
+    } else {
+        None
+    }
+}
+
+pub(crate) fn prompt_credentials(state: &mut State, cmd: Command) {
+    set_prompt(
+        state,
+        "Username",
+        prompt_password,
+        Box::new(|_| None),
+        cmd,
+        true,
+    );
+}
+
+fn prompt_password(state: &mut State, _term: &mut Term, username: &str, cmd: &Command) -> Res<()> {
+    if username.is_empty() {
+        return Err("Username must not be empty".into());
+    }
+
+    set_prompt(
+        state,
+        "Password",
+        rerun_with_credentials,
+        Box::new(|_| None),
+        (copy_cmd(cmd), username.to_string()),
+        true,
+    );
+    state.prompt.mask_input();
+    Ok(())
+}
+
+fn rerun_with_credentials(
+    state: &mut State,
+    term: &mut Term,
+    password: &str,
+    (cmd, username): &(Command, String),
+) -> Res<()> {
+    let password_file = PasswordFile::create(password)?;
+
+    let mut cmd = copy_cmd(cmd);
+    cmd.env("GIT_ASKPASS", env::current_exe()?);
+    cmd.env(USERNAME_VAR, username);
+    cmd.env(PASSWORD_FILE_VAR, &password_file.0);
+
+    state.run_cmd_async_with_password(term, cmd, password_file)
+}
+
+pub(crate) fn copy_cmd(cmd: &Command) -> Command {
+    let mut copy = Command::new(cmd.get_program());
+    copy.args(cmd.get_args());
+
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => copy.env(key, value),
+            None => copy.env_remove(key),
+        };
+    }
+
+    if let Some(dir) = cmd.get_current_dir() {
+        copy.current_dir(dir);
+    }
+
+    copy
+}
@@ -0,0 +1,70 @@
+//! Dates of commits as `general.date_format` has them, or the format cycled to since.
+
+use crate::config::DateFormat;
+use chrono::{DateTime, FixedOffset, Utc};
+
+pub(crate) fn format(format: &DateFormat, date: &DateTime<FixedOffset>) -> String {
+    match format {
+        DateFormat::Rfc => date.to_rfc2822(),
+        DateFormat::Iso => date.format("%Y-%m-%d %H:%M:%S %z").to_string(),
+        DateFormat::Relative => relative(date, Utc::now()),
+        DateFormat::Format(format) => date.format(format).to_string(),
+    }
+}
+
+/// Rounded like git's `--date=relative`: "5 minutes ago", "3 weeks ago".
+fn relative(date: &DateTime<FixedOffset>, now: DateTime<Utc>) -> String {
+    let seconds = now.signed_duration_since(date).num_seconds();
+    if seconds < 0 {
+        return "in the future".to_string();
+    }
+
+    let minutes = (seconds + 30) / 60;
+    let hours = (minutes + 30) / 60;
+    let days = (hours + 12) / 24;
+
+    let (amount, unit) = if seconds < 90 {
+        (seconds, "second")
+    } else if minutes < 90 {
+        (minutes, "minute")
+    } else if hours < 36 {
+        (hours, "hour")
+    } else if days < 14 {
+        (days, "day")
+    } else if days < 70 {
+        ((days + 3) / 7, "week")
+    } else if days < 365 {
+        ((days + 15) / 30, "month")
+    } else {
+        ((days + 183) / 365, "year")
+    };
+
+    format!(
+        "{} {}{} ago",
+        amount,
+        unit,
+        if amount == 1 { "" } else { "s" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_dates() {
+        let now = DateTime::parse_from_rfc3339("2024-02-16T12:00:00+01:00")
+            .unwrap()
+            .to_utc();
+        let ago = |date: &str| relative(&DateTime::parse_from_rfc3339(date).unwrap(), now);
+
+        assert_eq!(ago("2024-02-16T11:59:59+01:00"), "1 second ago");
+        assert_eq!(ago("2024-02-16T11:15:00+01:00"), "45 minutes ago");
+        assert_eq!(ago("2024-02-16T07:00:00+00:00"), "4 hours ago");
+        assert_eq!(ago("2024-02-13T12:00:00+01:00"), "3 days ago");
+        assert_eq!(ago("2024-01-20T12:00:00+01:00"), "4 weeks ago");
+        assert_eq!(ago("2023-11-16T12:00:00+01:00"), "3 months ago");
+        assert_eq!(ago("2021-02-16T12:00:00+01:00"), "3 years ago");
+        assert_eq!(ago("2024-02-17T12:00:00+01:00"), "in the future");
+    }
+}
@@ -0,0 +1,253 @@
+//! CI status of commits, fetched in the background from the forge and cached.
+
+use super::Forge;
+use crate::Res;
+use git2::{Oid, Repository};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    process::Command,
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+    time::{Duration, Instant},
+};
+
+const MAX_IN_FLIGHT: usize = 4;
+/// A pending status is fetched again once it's this old.
+const PENDING_TTL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CiStatus {
+    Pass,
+    Fail,
+    Pending,
+}
+
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<Oid, Entry>,
+    in_flight: usize,
+    updated: bool,
+}
+
+enum Entry {
+    Fetching(Option<CiStatus>),
+    Fetched {
+        status: Option<CiStatus>,
+        at: Instant,
+    },
+}
+
+/// Of the commits of one repository, shared by its screens and written to by the threads
+/// fetching statuses.
+#[derive(Clone, Default)]
+pub(crate) struct CiStatuses(Arc<Mutex<Cache>>);
+
+impl CiStatuses {
+    fn lock(&self) -> MutexGuard<'_, Cache> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The cached status of a commit, starting to fetch it if it isn't known yet.
+    pub(crate) fn status(&self, repo: &Repository, oid: Oid) -> Option<CiStatus> {
+        let mut cache = self.lock();
+
+        let known = match cache.entries.get(&oid) {
+            Some(Entry::Fetching(status)) => return *status,
+            Some(Entry::Fetched { status, at }) => {
+                if *status != Some(CiStatus::Pending) || at.elapsed() < PENDING_TTL {
+                    return *status;
+                }
+                *status
+            }
+            None => None,
+        };
+
+        if cache.in_flight >= MAX_IN_FLIGHT {
+            return known;
+        }
+
+        let (Ok(forge), Some(dir)) = (Forge::detect(repo), repo.workdir()) else {
+            cache.entries.insert(
+                oid,
+                Entry::Fetched {
+                    status: None,
+                    at: Instant::now(),
+                },
+            );
+            return None;
+        };
+
+        cache.entries.insert(oid, Entry::Fetching(known));
+        cache.in_flight += 1;
+
+        let (statuses, dir) = (self.clone(), dir.to_path_buf());
+        thread::spawn(move || {
+            let status = fetch(forge, dir, oid)
+                .inspect_err(|err| log::warn!("Couldn't fetch CI status of {}: {}", oid, err))
+                .unwrap_or(None);
+
+            let mut cache = statuses.lock();
+            cache.entries.insert(
+                oid,
+                Entry::Fetched {
+                    status,
+                    at: Instant::now(),
+                },
+            );
+            cache.in_flight -= 1;
+            cache.updated = true;
+        });
+
+        known
+    }
+
+    /// Whether any status was fetched since it was last asked, so screens need refreshing.
+    pub(crate) fn take_updated(&self) -> bool {
+        std::mem::take(&mut self.lock().updated)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn set_status(&self, oid: Oid, status: Option<CiStatus>) {
+        self.lock().entries.insert(
+            oid,
+            Entry::Fetched {
+                status,
+                at: Instant::now(),
+            },
+        );
+    }
+}
+
+fn fetch(forge: Forge, dir: PathBuf, oid: Oid) -> Res<Option<CiStatus>> {
+    let mut cmd = match forge {
+        Forge::GitHub => {
+            let mut cmd = Command::new("gh");
+            cmd.args([
+                "api",
+                &format!("repos/{{owner}}/{{repo}}/commits/{}/check-runs", oid),
+            ]);
+            cmd
+        }
+        Forge::GitLab => {
+            let mut cmd = Command::new("glab");
+            cmd.args([
+                "api",
+                &format!("projects/:fullpath/repository/commits/{}", oid),
+            ]);
+            cmd
+        }
+    };
+
+    let output = cmd.current_dir(dir).output()?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr)
+            .trim()
+            .to_string()
+            .into());
+    }
+
+    parse(forge, &String::from_utf8(output.stdout)?)
+}
+
+fn parse(forge: Forge, json: &str) -> Res<Option<CiStatus>> {
+    Ok(match forge {
+        Forge::GitHub => {
+            let runs = serde_json::from_str::<GitHubCheckRuns>(json)?.check_runs;
+
+            if runs.is_empty() {
+                None
+            } else if runs
+                .iter()
+                .any(|run| matches!(run.conclusion.as_deref(), Some(conclusion) if !["success", "neutral", "skipped"].contains(&conclusion)))
+            {
+                Some(CiStatus::Fail)
+            } else if runs.iter().any(|run| run.status != "completed") {
+                Some(CiStatus::Pending)
+            } else {
+                Some(CiStatus::Pass)
+            }
+        }
+        Forge::GitLab => serde_json::from_str::<GitLabCommit>(json)?
+            .last_pipeline
+            .and_then(|pipeline| match pipeline.status.as_str() {
+                "success" => Some(CiStatus::Pass),
+                "failed" | "canceled" => Some(CiStatus::Fail),
+                "skipped" | "manual" => None,
+                _ => Some(CiStatus::Pending),
+            }),
+    })
+}
+
+#[derive(Deserialize)]
+struct GitHubCheckRuns {
+    check_runs: Vec<GitHubCheckRun>,
+}
+
+#[derive(Deserialize)]
+struct GitHubCheckRun {
+    status: String,
+    conclusion: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitLabCommit {
+    last_pipeline: Option<GitLabPipeline>,
+}
+
+#[derive(Deserialize)]
+struct GitLabPipeline {
+    status: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, CiStatus};
+    use crate::forge::Forge;
+
+    #[test]
+    fn github_check_runs() {
+        let runs = |runs: &str| parse(Forge::GitHub, &format!(r#"{{"check_runs":[{}]}}"#, runs));
+
+        assert_eq!(runs("").unwrap(), None);
+        assert_eq!(
+            runs(r#"{"status":"completed","conclusion":"success"},{"status":"completed","conclusion":"skipped"}"#).unwrap(),
+            Some(CiStatus::Pass)
+        );
+        assert_eq!(
+            runs(r#"{"status":"completed","conclusion":"success"},{"status":"in_progress","conclusion":null}"#).unwrap(),
+            Some(CiStatus::Pending)
+        );
+        assert_eq!(
+            runs(r#"{"status":"completed","conclusion":"failure"},{"status":"queued","conclusion":null}"#).unwrap(),
+            Some(CiStatus::Fail)
+        );
+    }
+
+    #[test]
+    fn gitlab_pipeline() {
+        assert_eq!(
+            parse(Forge::GitLab, r#"{"id":"abc","last_pipeline":null}"#).unwrap(),
+            None
+        );
+        assert_eq!(
+            parse(
+                Forge::GitLab,
+                r#"{"last_pipeline":{"id":1,"status":"running"}}"#
+            )
+            .unwrap(),
+            Some(CiStatus::Pending)
+        );
+        assert_eq!(
+            parse(
+                Forge::GitLab,
+                r#"{"last_pipeline":{"id":1,"status":"failed"}}"#
+            )
+            .unwrap(),
+            Some(CiStatus::Fail)
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! References to issues in commit messages, like `#123` or `JIRA-456`.
+//!
+//! Besides `#<number>` linking to the forge's issues, patterns may be configured per repository:
+//! `git config --add gitu.issueReference '<regex> <url>'`, where the url may refer to the
+//! regex's capture groups as in `https://jira.example.com/browse/$0`.
+
+use super::{forge_remote, parse_remote_url, Forge};
+use git2::Repository;
+use regex::Regex;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IssueRef {
+    pub text: String,
+    pub url: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+pub(crate) struct Pattern {
+    regex: Regex,
+    url: String,
+}
+
+/// Configured patterns first, so they can replace the default `#<number>` one.
+pub(crate) fn patterns(repo: &Repository) -> Vec<Pattern> {
+    let mut patterns = configured_patterns(repo);
+    patterns.extend(forge_pattern(repo));
+    patterns
+}
+
+fn configured_patterns(repo: &Repository) -> Vec<Pattern> {
+    let Ok(config) = repo.config() else {
+        return vec![];
+    };
+    let Ok(entries) = config.multivar("gitu.issueReference", None) else {
+        return vec![];
+    };
+
+    let mut patterns = vec![];
+    let _ = entries.for_each(|entry| match entry.value().map(parse_pattern) {
+        Some(Some(pattern)) => patterns.push(pattern),
+        _ => log::warn!("Invalid gitu.issueReference: {:?}", entry.value()),
+    });
+    patterns
+}
+
+fn parse_pattern(value: &str) -> Option<Pattern> {
+    let (regex, url) = value.trim().rsplit_once(char::is_whitespace)?;
+    Some(Pattern {
+        regex: Regex::new(regex.trim()).ok()?,
+        url: url.to_string(),
+    })
+}
+
+fn forge_pattern(repo: &Repository) -> Option<Pattern> {
+    let forge = Forge::detect(repo).ok()?;
+    let (_, url) = forge_remote(repo).ok()?;
+    let (host, path) = parse_remote_url(&url)?;
+
+    Some(Pattern {
+        regex: Regex::new(r"#(\d+)\b").unwrap(),
+        url: match forge {
+            Forge::GitHub => format!("https://{}/{}/issues/$1", host, path),
+            Forge::GitLab => format!("https://{}/{}/-/issues/$1", host, path),
+        },
+    })
+}
+
+/// References in `text` by position, leaving out those overlapping an earlier pattern's.
+pub(crate) fn find(patterns: &[Pattern], text: &str) -> Vec<IssueRef> {
+    let mut refs: Vec<IssueRef> = vec![];
+
+    for pattern in patterns {
+        for captures in pattern.regex.captures_iter(text) {
+            let whole = captures.get(0).unwrap();
+            if refs
+                .iter()
+                .any(|other| whole.start() < other.end && other.start < whole.end())
+            {
+                continue;
+            }
+
+            let mut url = String::new();
+            captures.expand(&pattern.url, &mut url);
+            refs.push(IssueRef {
+                text: whole.as_str().to_string(),
+                url,
+                start: whole.start(),
+                end: whole.end(),
+            });
+        }
+    }
+
+    refs.sort_by_key(|issue| issue.start);
+    refs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn github() -> Pattern {
+        Pattern {
+            regex: Regex::new(r"#(\d+)\b").unwrap(),
+            url: "https://github.com/altsem/gitu/issues/$1".into(),
+        }
+    }
+
+    #[test]
+    fn find_forge_issues() {
+        let refs = find(&[github()], "Fix crash (#12), see #3");
+        assert_eq!(
+            refs.iter().map(|r| r.url.as_str()).collect::<Vec<_>>(),
+            [
+                "https://github.com/altsem/gitu/issues/12",
+                "https://github.com/altsem/gitu/issues/3"
+            ]
+        );
+        assert_eq!((refs[0].start, refs[0].end), (11, 14));
+    }
+
+    #[test]
+    fn parse_configured_pattern() {
+        let pattern = parse_pattern(r"  [A-Z]+-\d+  https://jira.example.com/browse/$0 ").unwrap();
+        let refs = find(&[pattern, github()], "JIRA-456: Fix #7");
+        assert_eq!(refs[0].text, "JIRA-456");
+        assert_eq!(refs[0].url, "https://jira.example.com/browse/JIRA-456");
+        assert_eq!(refs[1].text, "#7");
+    }
+
+    #[test]
+    fn earlier_patterns_take_precedence() {
+        let pattern = parse_pattern(r"#(\d+) https://tracker.example.com/$1").unwrap();
+        let refs = find(&[pattern, github()], "#7");
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].url, "https://tracker.example.com/7");
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        assert!(parse_pattern("https://example.com").is_none());
+        assert!(parse_pattern("[ https://example.com").is_none());
+    }
+}
@@ -0,0 +1,408 @@
+//! Pull/merge requests of the repository's forge, by way of the `gh` and `glab` CLIs.
+
+pub(crate) mod ci;
+pub(crate) mod issues;
+
+use crate::Res;
+use git2::Repository;
+use itertools::Itertools;
+use serde::Deserialize;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Forge {
+    GitHub,
+    GitLab,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub branch: String,
+    pub author: String,
+}
+
+/// Listed in the background, so the screen showing them opens right away.
+#[derive(Clone)]
+pub(crate) struct PendingPullRequests {
+    pub(crate) forge: Forge,
+    workdir: Option<PathBuf>,
+    listing: Arc<Mutex<Listing>>,
+}
+
+#[derive(Default)]
+struct Listing {
+    /// None until first listed.
+    pull_requests: Option<Result<Vec<PullRequest>, String>>,
+    updated: bool,
+}
+
+impl PendingPullRequests {
+    pub(crate) fn list(forge: Forge, repo: &Repository) -> Self {
+        let pending = Self {
+            forge,
+            workdir: repo.workdir().map(Path::to_path_buf),
+            listing: Default::default(),
+        };
+        pending.list_again();
+        pending
+    }
+
+    /// Keeps showing the pull requests listed before until they've been listed again.
+    pub(crate) fn list_again(&self) {
+        let pending = self.clone();
+        thread::spawn(move || {
+            let pull_requests = pending
+                .forge
+                .list_pull_requests(pending.workdir.as_deref())
+                .map_err(|err| err.to_string());
+
+            let mut listing = pending.lock();
+            listing.pull_requests = Some(pull_requests);
+            listing.updated = true;
+        });
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Listing> {
+        self.listing
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(crate) fn pull_requests(&self) -> Option<Result<Vec<PullRequest>, String>> {
+        self.lock().pull_requests.clone()
+    }
+
+    /// Whether they were listed since it was last asked, so the screen needs refreshing.
+    pub(crate) fn take_updated(&self) -> bool {
+        std::mem::take(&mut self.lock().updated)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn unlisted(forge: Forge) -> Self {
+        Self {
+            forge,
+            workdir: None,
+            listing: Default::default(),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn set_listed(&self, pull_requests: Vec<PullRequest>) {
+        let mut listing = self.lock();
+        listing.pull_requests = Some(Ok(pull_requests));
+        listing.updated = true;
+    }
+}
+
+impl Forge {
+    /// Picks the forge hosting `origin`, or the first remote if there's no `origin`.
+    pub(crate) fn detect(repo: &Repository) -> Res<Forge> {
+        let (remote_name, url) = forge_remote(repo)?;
+        Self::from_url(&url)
+            .ok_or_else(|| format!("Remote {} isn't on GitHub or GitLab", remote_name).into())
+    }
+
+    fn from_url(url: &str) -> Option<Forge> {
+        if url.contains("github") {
+            Some(Forge::GitHub)
+        } else if url.contains("gitlab") {
+            Some(Forge::GitLab)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn pull_request_name(&self) -> &'static str {
+        match self {
+            Forge::GitHub => "pull request",
+            Forge::GitLab => "merge request",
+        }
+    }
+
+    pub(crate) fn list_pull_requests(&self, workdir: Option<&Path>) -> Res<Vec<PullRequest>> {
+        let mut cmd = match self {
+            Forge::GitHub => {
+                let mut cmd = Command::new("gh");
+                cmd.args(["pr", "list", "--json", "number,title,headRefName,author"]);
+                cmd
+            }
+            Forge::GitLab => {
+                let mut cmd = Command::new("glab");
+                cmd.args(["mr", "list", "--output", "json"]);
+                cmd
+            }
+        };
+
+        if let Some(dir) = workdir {
+            cmd.current_dir(dir);
+        }
+
+        let output = cmd.output().map_err(|err| {
+            format!(
+                "Couldn't run {}: {}",
+                cmd.get_program().to_string_lossy(),
+                err
+            )
+        })?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr)
+                .trim()
+                .to_string()
+                .into());
+        }
+
+        self.parse_pull_requests(&String::from_utf8(output.stdout)?)
+    }
+
+    fn parse_pull_requests(&self, json: &str) -> Res<Vec<PullRequest>> {
+        Ok(match self {
+            Forge::GitHub => serde_json::from_str::<Vec<GitHubPullRequest>>(json)?
+                .into_iter()
+                .map(|pr| PullRequest {
+                    number: pr.number,
+                    title: pr.title,
+                    branch: pr.head_ref_name,
+                    author: pr.author.login,
+                })
+                .collect(),
+            Forge::GitLab => serde_json::from_str::<Vec<GitLabMergeRequest>>(json)?
+                .into_iter()
+                .map(|mr| PullRequest {
+                    number: mr.iid,
+                    title: mr.title,
+                    branch: mr.source_branch,
+                    author: mr.author.username,
+                })
+                .collect(),
+        })
+    }
+
+    /// Web page for opening a pull request of `branch`.
+    pub(crate) fn new_pull_request_url(&self, repo: &Repository, branch: &str) -> Res<String> {
+        let (remote_name, url) = forge_remote(repo)?;
+        let (host, path) = parse_remote_url(&url)
+            .ok_or_else(|| format!("Couldn't parse url of remote {}: {}", remote_name, url))?;
+
+        Ok(match self {
+            Forge::GitHub => format!("https://{}/{}/compare/{}?expand=1", host, path, branch),
+            Forge::GitLab => format!(
+                "https://{}/{}/-/merge_requests/new?merge_request%5Bsource_branch%5D={}",
+                host, path, branch
+            ),
+        })
+    }
+
+    pub(crate) fn create_cmd(&self, branch: &str, title: &str, body: &str) -> Command {
+        match self {
+            Forge::GitHub => {
+                let mut cmd = Command::new("gh");
+                cmd.args([
+                    "pr", "create", "--head", branch, "--title", title, "--body", body,
+                ]);
+                cmd
+            }
+            Forge::GitLab => {
+                let mut cmd = Command::new("glab");
+                cmd.args([
+                    "mr",
+                    "create",
+                    "--yes",
+                    "--source-branch",
+                    branch,
+                    "--title",
+                    title,
+                    "--description",
+                    body,
+                ]);
+                cmd
+            }
+        }
+    }
+
+    /// Creates a local branch of the pull request, and checks it out.
+    pub(crate) fn checkout_cmd(&self, number: u64) -> Command {
+        let mut cmd = match self {
+            Forge::GitHub => {
+                let mut cmd = Command::new("gh");
+                cmd.arg("pr");
+                cmd
+            }
+            Forge::GitLab => {
+                let mut cmd = Command::new("glab");
+                cmd.arg("mr");
+                cmd
+            }
+        };
+
+        cmd.args(["checkout", &number.to_string()]);
+        cmd
+    }
+}
+
+fn forge_remote(repo: &Repository) -> Res<(String, String)> {
+    let remotes = repo.remotes()?;
+    let remote_name = remotes
+        .iter()
+        .flatten()
+        .find(|name| *name == "origin")
+        .or_else(|| remotes.iter().flatten().next())
+        .ok_or("No remote configured")?;
+
+    let remote = repo.find_remote(remote_name)?;
+    Ok((
+        remote_name.to_string(),
+        remote.url().unwrap_or("").to_string(),
+    ))
+}
+
+/// Host and repository path of urls like `git@host:owner/repo.git`, `https://host/owner/repo`.
+fn parse_remote_url(url: &str) -> Option<(String, String)> {
+    fn strip_user(rest: &str) -> &str {
+        rest.split_once('@').map(|(_, rest)| rest).unwrap_or(rest)
+    }
+
+    let (host, path) = match url.split_once("://") {
+        Some((_, rest)) => {
+            let (host, path) = strip_user(rest).split_once('/')?;
+            // Drops any port, which is that of ssh rather than of the web page
+            (host.split(':').next()?, path)
+        }
+        None => strip_user(url).split_once(':')?,
+    };
+
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    (!host.is_empty() && !path.is_empty()).then(|| (host.to_string(), path.to_string()))
+}
+
+/// Title and body for a pull request of the commits on HEAD that aren't on the remote's
+/// default branch. Like `gh pr create --fill`, a single commit provides both.
+pub(crate) fn pull_request_message(repo: &Repository, branch: &str) -> Res<(String, String)> {
+    let (remote_name, _) = forge_remote(repo)?;
+    let base = ["HEAD", "main", "master"].iter().find_map(|name| {
+        repo.find_reference(&format!("refs/remotes/{}/{}", remote_name, name))
+            .ok()?
+            .peel_to_commit()
+            .ok()
+    });
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    if let Some(base) = base {
+        revwalk.hide(base.id())?;
+    }
+
+    let commits = revwalk
+        .map(|oid| Ok(repo.find_commit(oid?)?))
+        .collect::<Res<Vec<_>>>()?;
+
+    Ok(match commits.as_slice() {
+        [commit] => (
+            commit.summary().unwrap_or(branch).to_string(),
+            commit.body().unwrap_or("").trim().to_string(),
+        ),
+        commits => (
+            branch.to_string(),
+            commits
+                .iter()
+                .rev()
+                .map(|commit| format!("- {}", commit.summary().unwrap_or("")))
+                .join("\n"),
+        ),
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GitHubPullRequest {
+    number: u64,
+    title: String,
+    head_ref_name: String,
+    author: GitHubUser,
+}
+
+#[derive(Deserialize)]
+struct GitHubUser {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct GitLabMergeRequest {
+    iid: u64,
+    title: String,
+    source_branch: String,
+    author: GitLabUser,
+}
+
+#[derive(Deserialize)]
+struct GitLabUser {
+    username: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_remote_url, Forge, PullRequest};
+
+    #[test]
+    fn detect_from_url() {
+        assert_eq!(
+            Forge::from_url("git@github.com:altsem/gitu.git"),
+            Some(Forge::GitHub)
+        );
+        assert_eq!(
+            Forge::from_url("https://gitlab.example.com/group/project.git"),
+            Some(Forge::GitLab)
+        );
+        assert_eq!(Forge::from_url("/srv/git/project.git"), None);
+    }
+
+    #[test]
+    fn remote_url() {
+        let expected = Some(("github.com".to_string(), "altsem/gitu".to_string()));
+
+        assert_eq!(parse_remote_url("git@github.com:altsem/gitu.git"), expected);
+        assert_eq!(parse_remote_url("https://github.com/altsem/gitu"), expected);
+        assert_eq!(
+            parse_remote_url("ssh://git@github.com:22/altsem/gitu.git"),
+            expected
+        );
+        assert_eq!(parse_remote_url("/srv/git/gitu.git"), None);
+    }
+
+    #[test]
+    fn parse_github() {
+        let json = r#"[{"author":{"id":"1","is_bot":false,"login":"octocat","name":""},"headRefName":"fix-typo","number":42,"title":"Fix typo"}]"#;
+
+        assert_eq!(
+            Forge::GitHub.parse_pull_requests(json).unwrap(),
+            vec![PullRequest {
+                number: 42,
+                title: "Fix typo".into(),
+                branch: "fix-typo".into(),
+                author: "octocat".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_gitlab() {
+        let json = r#"[{"id":1000,"iid":7,"title":"Add feature","source_branch":"feature","target_branch":"main","author":{"id":3,"username":"tanuki"}}]"#;
+
+        assert_eq!(
+            Forge::GitLab.parse_pull_requests(json).unwrap(),
+            vec![PullRequest {
+                number: 7,
+                title: "Add feature".into(),
+                branch: "feature".into(),
+                author: "tanuki".into(),
+            }]
+        );
+    }
+}
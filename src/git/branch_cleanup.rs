@@ -0,0 +1,88 @@
+//! Local branches that have served their purpose, listed for deleting them.
+
+use super::{branch_upstream_name, current_branch, upstream_remote};
+use crate::Res;
+use git2::{BranchType, Repository};
+
+#[derive(Debug, Default)]
+pub(crate) struct StaleBranches {
+    /// What the merged branches were merged into.
+    pub default_branch: Option<String>,
+    pub merged: Vec<String>,
+    /// Branches with the upstream they track, which no longer exists.
+    pub upstream_gone: Vec<(String, String)>,
+}
+
+impl StaleBranches {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.merged.is_empty() && self.upstream_gone.is_empty()
+    }
+}
+
+/// The local branch that the remote's `HEAD` points at, else `main` or `master`.
+pub(crate) fn default_branch(repo: &Repository) -> Option<String> {
+    let remote = upstream_remote(repo).unwrap_or_else(|| "origin".to_string());
+    let remote_head = repo
+        .find_reference(&format!("refs/remotes/{}/HEAD", remote))
+        .ok()
+        .and_then(|reference| {
+            reference
+                .symbolic_target()?
+                .strip_prefix(&format!("refs/remotes/{}/", remote))
+                .map(str::to_string)
+        });
+
+    remote_head
+        .into_iter()
+        .chain(["main".to_string(), "master".to_string()])
+        .find(|name| repo.find_branch(name, BranchType::Local).is_ok())
+}
+
+/// The upstream of a local branch that is configured but gone since,
+/// like after a pull request got merged and its branch deleted.
+pub(crate) fn gone_upstream(repo: &Repository, branch: &str) -> Option<String> {
+    let upstream = branch_upstream_name(repo, branch)?;
+    let local = repo.find_branch(branch, BranchType::Local).ok()?;
+
+    local.upstream().is_err().then_some(upstream)
+}
+
+/// Leaves out the default and current branches.
+pub(crate) fn stale_branches(repo: &Repository) -> Res<StaleBranches> {
+    let default_branch = default_branch(repo);
+    let default_tip = default_branch
+        .as_ref()
+        .and_then(|name| repo.find_branch(name, BranchType::Local).ok())
+        .and_then(|branch| branch.get().target());
+    let current = current_branch(repo);
+
+    let mut stale = StaleBranches {
+        default_branch: default_branch.clone(),
+        ..Default::default()
+    };
+
+    for (branch, _) in repo.branches(Some(BranchType::Local))?.flatten() {
+        let Some(name) = branch.name()?.map(str::to_string) else {
+            continue;
+        };
+        if Some(&name) == default_branch.as_ref() || Some(&name) == current.as_ref() {
+            continue;
+        }
+        let Some(tip) = branch.get().target() else {
+            continue;
+        };
+
+        if let Some(default_tip) = default_tip {
+            if tip == default_tip || repo.graph_descendant_of(default_tip, tip)? {
+                stale.merged.push(name);
+                continue;
+            }
+        }
+
+        if let Some(upstream) = gone_upstream(repo, &name) {
+            stale.upstream_gone.push((name, upstream));
+        }
+    }
+
+    Ok(stale)
+}
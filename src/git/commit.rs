@@ -1,5 +1,23 @@
+use chrono::{DateTime, FixedOffset};
+
 #[derive(Debug)]
 pub(crate) struct Commit {
     pub hash: String,
-    pub details: String,
+    pub author: String,
+    pub author_date: DateTime<FixedOffset>,
+    pub committer: String,
+    pub committer_date: DateTime<FixedOffset>,
+    pub signature: Option<String>,
+    pub parents: Vec<Parent>,
+    pub message: String,
+    /// Attached with `git notes`, from the default notes ref.
+    pub note: Option<String>,
+    pub stats: String,
+}
+
+#[derive(Debug)]
+pub(crate) struct Parent {
+    pub hash: String,
+    pub short_id: String,
+    pub summary: String,
 }
@@ -0,0 +1,66 @@
+use crate::Res;
+use derive_more::Display;
+use std::{collections::BTreeMap, path::Path, process::Command, str};
+
+/// The config values that can be edited from the git config screen.
+pub(crate) const EDITABLE_KEYS: &[&str] =
+    &["user.name", "user.email", "pull.rebase", "core.editor"];
+
+pub(crate) const PULL_REBASE_VALUES: &[&str] = &["true", "false", "merges", "interactive"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display)]
+pub(crate) enum ConfigScope {
+    #[display(fmt = "local")]
+    Local,
+    #[display(fmt = "global")]
+    Global,
+}
+
+impl ConfigScope {
+    pub(crate) fn flag(&self) -> &'static str {
+        match self {
+            ConfigScope::Local => "--local",
+            ConfigScope::Global => "--global",
+        }
+    }
+}
+
+/// All values set at the given scope. A missing config file counts as empty.
+pub(crate) fn list(dir: &Path, scope: ConfigScope) -> Res<BTreeMap<String, String>> {
+    let output = Command::new("git")
+        .args(["config", "--list", scope.flag()])
+        .current_dir(dir)
+        .output()?;
+
+    if !output.status.success() {
+        return Ok(BTreeMap::new());
+    }
+
+    Ok(str::from_utf8(&output.stdout)?
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect())
+}
+
+/// Rejects values that git would accept, but that would leave the setup broken.
+pub(crate) fn validate(key: &str, value: &str) -> Res<()> {
+    if value.trim().is_empty() {
+        return Err(format!("{} may not be empty", key).into());
+    }
+
+    match key {
+        "user.name" if value.contains(['<', '>', '\n']) => {
+            Err("Name may not contain '<', '>' or newlines".into())
+        }
+        "user.email" if !value.contains('@') || value.contains(char::is_whitespace) => {
+            Err("Email must be formatted as: user@host".into())
+        }
+        "pull.rebase" if !PULL_REBASE_VALUES.contains(&value) => Err(format!(
+            "pull.rebase must be one of: {}",
+            PULL_REBASE_VALUES.join(", ")
+        )
+        .into()),
+        _ => Ok(()),
+    }
+}
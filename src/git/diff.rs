@@ -1,5 +1,7 @@
+use super::{histogram, lfs};
 use crate::{
-    config::Config,
+    config::{Config, DiffAlgorithm},
+    git2_opts,
     syntax_highlight::{self},
     Res,
 };
@@ -9,14 +11,17 @@ use ratatui::{
     style::Style,
     text::{Line, Span, Text},
 };
-use similar::{Algorithm, DiffOp, DiffTag, DiffableStr, TextDiff};
+use similar::{udiff::UnifiedHunkHeader, Algorithm, DiffOp, DiffTag, DiffableStr, TextDiff};
 use std::{
+    cell::RefCell,
+    collections::HashMap,
     fs,
     iter::{self},
     ops::Range,
     path::PathBuf,
     rc::Rc,
     str,
+    time::{Duration, Instant},
 };
 
 #[derive(Debug, Clone)]
@@ -31,6 +36,43 @@ pub(crate) struct Delta {
     pub new_file: PathBuf,
     pub hunks: Vec<Rc<Hunk>>,
     pub status: git2::Delta,
+    pub old_mode: git2::FileMode,
+    pub new_mode: git2::FileMode,
+    pub submodule: Option<Rc<SubmoduleChange>>,
+    pub lfs: Option<Rc<LfsChange>>,
+}
+
+/// The commits a submodule pointer moved between, in place of a diff of its content.
+#[derive(Debug, Clone)]
+pub(crate) struct SubmoduleChange {
+    pub path: PathBuf,
+    pub old: Option<SubmoduleCommit>,
+    pub new: Option<SubmoduleCommit>,
+}
+
+/// Both sides of a file tracked by git-lfs, whose content is never diffed.
+#[derive(Debug, Clone)]
+pub(crate) struct LfsChange {
+    pub old: Option<LfsFile>,
+    pub new: Option<LfsFile>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum LfsFile {
+    Pointer(lfs::Pointer),
+    /// A pointer in the working tree, its content hasn't been pulled.
+    NotPulled(lfs::Pointer),
+    /// Content in the working tree, not yet stored by git-lfs.
+    Content {
+        size: u64,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SubmoduleCommit {
+    pub hash: String,
+    pub short_id: String,
+    pub summary: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -104,16 +146,76 @@ impl Hunk {
             .unwrap_or(0) as u32
             + self.new_start
     }
+
+    /// The first and last line of the file that the changes of `lines` (indices into the content)
+    /// are on, in the old or new version of the file. Lines missing from that version touch the
+    /// line they'd be inserted before. `None` when that version has no lines of the hunk at all.
+    pub(crate) fn line_range(&self, lines: Range<usize>, new_side: bool) -> Option<(u32, u32)> {
+        let (missing, changed) = if new_side { ('-', '+') } else { ('+', '-') };
+        let mut number = if new_side {
+            self.new_start
+        } else {
+            self.old_start()
+        };
+
+        // (line number at or after the line, whether it's in this version, whether it changed)
+        let mut numbered = vec![];
+        for line in &self.content.lines {
+            let prefix = line
+                .spans
+                .first()
+                .and_then(|span| span.content.chars().next());
+            let present = prefix != Some(missing) && prefix != Some('\\');
+            numbered.push((number, present, prefix == Some(changed)));
+            if present {
+                number += 1;
+            }
+        }
+
+        let last = numbered.iter().rev().find(|(_, present, _)| *present)?.0;
+        let selected = numbered.get(lines)?;
+        let numbers = |include: fn(&(u32, bool, bool)) -> bool| {
+            selected
+                .iter()
+                .filter(|line| include(line))
+                .map(|&(number, _, _)| number.min(last))
+                .collect::<Vec<_>>()
+        };
+
+        let candidates = [
+            numbers(|&(_, present, changed)| present && changed),
+            numbers(|&(_, present, _)| present),
+            numbers(|_| true),
+        ]
+        .into_iter()
+        .find(|numbers| !numbers.is_empty())?;
+
+        Some((*candidates.iter().min()?, *candidates.iter().max()?))
+    }
+
+    /// From the header, like `@@ -12,3 +12,4 @@`.
+    fn old_start(&self) -> u32 {
+        self.header
+            .split_whitespace()
+            .nth(1)
+            .and_then(|old| old.trim_start_matches('-').split(',').next())
+            .and_then(|start| start.parse().ok())
+            .unwrap_or(1)
+    }
 }
 
 pub(crate) fn convert_diff(
     config: &Config,
+    settings: &DiffSettings,
+    cache: &HunkCache,
     repo: &Repository,
-    diff: git2::Diff,
+    mut diff: git2::Diff,
     workdir: bool,
 ) -> Res<Diff> {
     let mut deltas = vec![];
 
+    diff.find_similar(Some(&mut git2_opts::find_similar()))?;
+
     diff.print(
         git2::DiffFormat::PatchHeader,
         |diffdelta, _maybe_hunk, line| {
@@ -128,9 +230,21 @@ pub(crate) fn convert_diff(
                     new_file: path(&diffdelta.new_file()),
                     hunks: vec![],
                     status: diffdelta.status(),
+                    old_mode: diffdelta.old_file().mode(),
+                    new_mode: diffdelta.new_file().mode(),
+                    submodule: None,
+                    lfs: None,
                 };
 
-                if let Ok(hunks) = diff_files(repo, diffdelta, workdir, config, &delta) {
+                if delta.old_mode == git2::FileMode::Commit
+                    || delta.new_mode == git2::FileMode::Commit
+                {
+                    delta.submodule = Some(Rc::new(submodule_change(repo, diffdelta)));
+                } else if lfs::is_tracked(repo, &delta.new_file) {
+                    delta.lfs = Some(Rc::new(lfs_change(repo, diffdelta, workdir)));
+                } else if let Ok(hunks) =
+                    diff_files(repo, diffdelta, workdir, config, settings, cache, &delta)
+                {
                     delta.hunks = hunks;
                 }
 
@@ -147,26 +261,259 @@ pub(crate) fn convert_diff(
     Ok(Diff { deltas })
 }
 
+fn submodule_change(repo: &Repository, diffdelta: git2::DiffDelta<'_>) -> SubmoduleChange {
+    let path = repo
+        .workdir()
+        .unwrap_or(repo.path())
+        .join(path(&diffdelta.new_file()));
+    let submodule_repo = Repository::open(&path).ok();
+
+    let commit = |file: git2::DiffFile<'_>| {
+        if file.mode() != git2::FileMode::Commit || file.id().is_zero() {
+            return None;
+        }
+
+        let hash = file.id().to_string();
+        let found = submodule_repo
+            .as_ref()
+            .and_then(|repo| repo.find_commit(file.id()).ok());
+
+        Some(SubmoduleCommit {
+            short_id: found
+                .as_ref()
+                .and_then(|commit| commit.as_object().short_id().ok())
+                .and_then(|short_id| short_id.as_str().map(String::from))
+                .unwrap_or_else(|| hash[..7].to_string()),
+            summary: found.and_then(|commit| commit.summary().map(String::from)),
+            hash,
+        })
+    };
+
+    SubmoduleChange {
+        old: commit(diffdelta.old_file()),
+        new: commit(diffdelta.new_file()),
+        path,
+    }
+}
+
+fn lfs_change(repo: &Repository, diffdelta: git2::DiffDelta<'_>, workdir: bool) -> LfsChange {
+    let read_pointer = |file: git2::DiffFile<'_>| {
+        let blob = repo.find_blob(file.id()).ok()?;
+        lfs::parse_pointer(blob.content()).map(LfsFile::Pointer)
+    };
+
+    let new = match diffdelta.status() {
+        git2::Delta::Deleted => None,
+        _ if workdir => read_workdir_lfs(repo, &diffdelta.new_file()),
+        _ => read_pointer(diffdelta.new_file()),
+    };
+
+    LfsChange {
+        old: read_pointer(diffdelta.old_file()),
+        new,
+    }
+}
+
+fn read_workdir_lfs(repo: &Repository, new_file: &git2::DiffFile<'_>) -> Option<LfsFile> {
+    let path = repo.workdir()?.join(new_file.path()?);
+    let size = fs::metadata(&path).ok()?.len();
+
+    // Only small files can be pointers, there's no need to read in any large content
+    if size <= lfs::MAX_POINTER_SIZE as u64 {
+        if let Some(pointer) = lfs::parse_pointer(&fs::read(&path).ok()?) {
+            return Some(LfsFile::NotPulled(pointer));
+        }
+    }
+
+    Some(LfsFile::Content { size })
+}
+
 fn diff_files(
     repo: &Repository,
     diffdelta: git2::DiffDelta<'_>,
     workdir: bool,
     config: &Config,
+    settings: &DiffSettings,
+    cache: &HunkCache,
     delta: &Delta,
 ) -> Res<Vec<Rc<Hunk>>> {
-    let old_content = read_blob(repo, &diffdelta.old_file())?.replace("\r\n", "\n");
-    let new_content = if workdir {
-        read_workdir(repo, &diffdelta.new_file())?
+    let old_content = read_blob(repo, &diffdelta.old_file())?;
+    let (new_content, new_id) = if diffdelta.status() == git2::Delta::Deleted {
+        ("".to_string(), git2::Oid::zero())
+    } else if workdir {
+        let content = read_workdir(repo, &diffdelta.new_file())?;
+        let id = git2::Oid::hash_object(git2::ObjectType::Blob, content.as_bytes())?;
+        (content, id)
     } else {
-        read_blob(repo, &diffdelta.new_file())?
+        (
+            read_blob(repo, &diffdelta.new_file())?,
+            diffdelta.new_file().id(),
+        )
+    };
+
+    let key = CacheKey {
+        old_id: diffdelta.old_file().id(),
+        new_id,
+        old_file: delta.old_file.clone(),
+        new_file: delta.new_file.clone(),
+        settings: *settings,
+    };
+
+    if let Some(hunks) = cache.get(&key) {
+        return Ok(hunks);
     }
-    .replace("\r\n", "\n");
 
-    diff_content(config, delta, &old_content, &new_content)
+    let hunks = diff_content(
+        config,
+        settings,
+        delta,
+        &old_content.replace("\r\n", "\n"),
+        &new_content.replace("\r\n", "\n"),
+    )?;
+    cache.insert(key, &hunks);
+    Ok(hunks)
+}
+
+/// How diffs are made, see [`crate::settings::Settings`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct DiffSettings {
+    pub context: Context,
+    /// As `--ignore-all-space`, lines differing only in whitespace are unchanged.
+    pub ignore_all_space: bool,
+    /// As `--ignore-space-change`, lines differing only in the amount of whitespace are unchanged.
+    pub ignore_space_change: bool,
+    /// As `--ignore-blank-lines`, hunks only adding or removing blank lines are left out.
+    pub ignore_blank_lines: bool,
+    pub algorithm: DiffAlgorithm,
+}
+
+/// Unchanged content shown around each change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Context {
+    Lines(usize),
+    WholeFile,
+}
+
+impl DiffSettings {
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self {
+            context: Context::Lines(config.general.diff_context_lines),
+            ignore_all_space: false,
+            ignore_space_change: false,
+            ignore_blank_lines: false,
+            algorithm: config.general.diff_algorithm,
+        }
+    }
+
+    /// What `git apply` needs to accept patches of hunks diffed like this.
+    pub(crate) fn apply_args(&self) -> Vec<&'static str> {
+        let mut args = vec![];
+        // Hunks without context are rejected otherwise
+        if self.context == Context::Lines(0) {
+            args.push("--unidiff-zero");
+        }
+        // Context lines differing only in whitespace are shown as they were before
+        if self.ignore_all_space || self.ignore_space_change {
+            args.push("--ignore-whitespace");
+        }
+        args
+    }
+}
+
+/// Files kept in the cache, beyond which the least recently used half is dropped.
+const CACHE_CAPACITY: usize = 1000;
+
+/// What the hunks of a file depend on. The paths pick its syntax highlighting.
+#[derive(PartialEq, Eq, Hash)]
+struct CacheKey {
+    old_id: git2::Oid,
+    new_id: git2::Oid,
+    old_file: PathBuf,
+    new_file: PathBuf,
+    settings: DiffSettings,
+}
+
+struct CacheEntry {
+    hunks: Vec<Rc<Hunk>>,
+    /// Of the cache's uses, the last one that was of this entry.
+    last_use: usize,
 }
 
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<CacheKey, CacheEntry>,
+    uses: usize,
+}
+
+/// Hunks by content, so refreshes and other screens showing the same change
+/// don't diff and highlight files again. Kept on `State` and shared with the screens.
+#[derive(Default)]
+pub(crate) struct HunkCache(RefCell<Cache>);
+
+impl HunkCache {
+    fn get(&self, key: &CacheKey) -> Option<Vec<Rc<Hunk>>> {
+        let mut cache = self.0.borrow_mut();
+        cache.uses += 1;
+        let uses = cache.uses;
+        let entry = cache.entries.get_mut(key)?;
+        entry.last_use = uses;
+        Some(entry.hunks.clone())
+    }
+
+    fn insert(&self, key: CacheKey, hunks: &[Rc<Hunk>]) {
+        let mut cache = self.0.borrow_mut();
+        cache.uses += 1;
+        if cache.entries.len() >= CACHE_CAPACITY {
+            let oldest_kept = cache.uses - CACHE_CAPACITY / 2;
+            cache
+                .entries
+                .retain(|_, entry| entry.last_use >= oldest_kept);
+        }
+
+        let last_use = cache.uses;
+        cache.entries.insert(
+            key,
+            CacheEntry {
+                hunks: hunks.to_vec(),
+                last_use,
+            },
+        );
+    }
+
+    /// Forgets all cached hunks, like when the user asks for a refresh.
+    pub(crate) fn clear(&self) {
+        self.0.borrow_mut().entries.clear();
+    }
+
+    /// Takes out the hunks, to move them to the cache of another thread.
+    pub(crate) fn into_cached(self) -> CachedHunks {
+        let entries = self.0.into_inner().entries.into_iter();
+        CachedHunks(
+            entries
+                .map(|(key, entry)| {
+                    let hunks = entry.hunks.into_iter().map(Rc::unwrap_or_clone);
+                    (key, hunks.collect())
+                })
+                .collect(),
+        )
+    }
+
+    pub(crate) fn extend(&self, cached: CachedHunks) {
+        for (key, hunks) in cached.0 {
+            self.insert(key, &hunks.into_iter().map(Rc::new).collect::<Vec<_>>());
+        }
+    }
+}
+
+/// Hunks of a [`HunkCache`], which unlike it can be sent to another thread.
+pub(crate) struct CachedHunks(Vec<(CacheKey, Vec<Hunk>)>);
+
+/// Time Myers may spend on a file before settling for a diff that's not the smallest.
+const MYERS_TIMEOUT: Duration = Duration::from_millis(500);
+
 fn diff_content(
     config: &Config,
+    settings: &DiffSettings,
     delta: &Delta,
     old_content: &str,
     new_content: &str,
@@ -178,9 +525,47 @@ fn diff_content(
     let old_line_indices = byte_ranges(&old_lines);
     let new_line_indices = byte_ranges(&new_lines);
 
-    let text_diff = TextDiff::configure()
-        .algorithm(Algorithm::Patience)
-        .diff_slices(&old_lines, &new_lines);
+    let old_compared = compared_lines(&old_lines, settings);
+    let new_compared = compared_lines(&new_lines, settings);
+
+    let old_compared = old_compared.iter().map(String::as_str).collect::<Vec<_>>();
+    let new_compared = new_compared.iter().map(String::as_str).collect::<Vec<_>>();
+    let ops = match settings.algorithm {
+        DiffAlgorithm::Histogram => {
+            histogram::diff_slices(&old_compared, &new_compared, Instant::now() + MYERS_TIMEOUT)
+        }
+        // Without a deadline Myers doesn't settle for an approximation on large files
+        DiffAlgorithm::Minimal => TextDiff::configure()
+            .algorithm(Algorithm::Myers)
+            .diff_slices(&old_compared, &new_compared)
+            .ops()
+            .to_vec(),
+        DiffAlgorithm::Myers => TextDiff::configure()
+            .algorithm(Algorithm::Myers)
+            .timeout(MYERS_TIMEOUT)
+            .diff_slices(&old_compared, &new_compared)
+            .ops()
+            .to_vec(),
+        DiffAlgorithm::Patience => TextDiff::configure()
+            .algorithm(Algorithm::Patience)
+            .diff_slices(&old_compared, &new_compared)
+            .ops()
+            .to_vec(),
+    };
+
+    let radius = match settings.context {
+        Context::Lines(lines) => lines,
+        Context::WholeFile => old_lines.len().max(new_lines.len()),
+    };
+
+    // Symlinks are the path they point to, never ending with a newline
+    let whitespace_error = (config.general.whitespace_errors.enabled
+        && delta.new_mode != git2::FileMode::Link)
+        .then(|| Style::from(&style.diff_highlight.whitespace_error));
+
+    let is_blank = |lines: &[&str], range: Range<usize>| {
+        lines[range].iter().all(|line| line.trim().is_empty())
+    };
 
     let mut old_syntax_highlights = if config.style.syntax_highlight.enabled {
         syntax_highlight::highlight(config, &delta.old_file, old_content)
@@ -198,13 +583,20 @@ fn diff_content(
     .into_iter()
     .peekable();
 
-    Ok(text_diff
-        .unified_diff()
-        .iter_hunks()
-        .map(|hunk| {
+    Ok(similar::group_diff_ops(ops, radius)
+        .into_iter()
+        .filter(|ops| {
+            !settings.ignore_blank_lines
+                || ops.iter().any(|op| {
+                    op.tag() != DiffTag::Equal
+                        && !(is_blank(&old_lines, op.old_range())
+                            && is_blank(&new_lines, op.new_range()))
+                })
+        })
+        .map(|ops| {
             let mut lines = vec![];
 
-            hunk.ops().iter().for_each(|op| {
+            ops.iter().for_each(|op| {
                 let (line_tag, old_line, new_line) = op.as_tag_tuple();
 
                 let old_prefix = match line_tag {
@@ -251,6 +643,7 @@ fn diff_content(
                     &mut old_diff_highlights,
                     old_prefix,
                     old_content,
+                    None,
                     &mut lines,
                 );
 
@@ -285,19 +678,20 @@ fn diff_content(
                         &mut new_diff_highlights,
                         new_prefix,
                         new_content,
+                        whitespace_error.as_ref(),
                         &mut lines,
                     );
                 }
             });
 
             let formatted_hunk = Text::from(lines);
+            let header = UnifiedHunkHeader::new(&ops);
 
-            let new_start = hunk
-                .header()
+            let new_start = header
                 .to_string()
                 .strip_prefix("@@ -")
                 .unwrap()
-                .split(|c| c == ' ' || c == ',')
+                .split([' ', ','])
                 .next()
                 .unwrap()
                 .parse()
@@ -307,13 +701,37 @@ fn diff_content(
                 file_header: delta.file_header.clone(),
                 new_file: delta.new_file.clone(),
                 new_start,
-                header: format!("{}", hunk.header()),
+                header: format!("{}", header),
                 content: formatted_hunk,
             })
         })
         .collect::<Vec<_>>())
 }
 
+/// Lines as compared when diffing, with whitespace ignored according to `settings`.
+fn compared_lines(lines: &[&str], settings: &DiffSettings) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| {
+            if settings.ignore_all_space {
+                line.chars().filter(|c| !c.is_whitespace()).collect()
+            } else if settings.ignore_space_change {
+                let mut compared = String::new();
+                for c in line.trim_end().chars() {
+                    if !c.is_whitespace() {
+                        compared.push(c);
+                    } else if !compared.ends_with(' ') {
+                        compared.push(' ');
+                    }
+                }
+                compared
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
 fn map_from_token_to_byte_range(
     word_range: &Range<usize>,
     old_lines_range: &Range<usize>,
@@ -357,6 +775,7 @@ fn create_lines(
     diff_highlights: &mut iter::Peekable<impl Iterator<Item = (Range<usize>, Style)>>,
     prefix: Span<'static>,
     content: &str,
+    whitespace_error: Option<&Style>,
     lines: &mut Vec<Line<'_>>,
 ) {
     for line in line_indices {
@@ -386,7 +805,7 @@ fn create_lines(
                         Span::styled(
                             content[h_range]
                                 // TODO only need to do this for the last span
-                                .trim_end_matches(|s| s == '\r' || s == '\n')
+                                .trim_end_matches(['\r', '\n'])
                                 .to_string(),
                             h_style,
                         )
@@ -394,14 +813,83 @@ fn create_lines(
             )
             .collect::<Vec<_>>();
 
+        let text = content[line.clone()].trim_end_matches(['\r', '\n']);
+        let spans = match whitespace_error {
+            Some(error) => whitespace_errors(text)
+                .into_iter()
+                .fold(spans, |spans, range| highlight_range(spans, range, error)),
+            None => spans,
+        };
+
         lines.push(Line::from(spans));
 
         if !content[line.clone()].ends_with('\n') {
-            lines.push(Line::from("\\ No newline at end of file"))
+            let no_newline = Line::from("\\ No newline at end of file");
+            lines.push(match whitespace_error {
+                Some(error) => no_newline.style(*error),
+                None => no_newline,
+            });
         }
     }
 }
 
+/// Byte ranges of trailing whitespace and of indentation mixing tabs and spaces.
+fn whitespace_errors(text: &str) -> Vec<Range<usize>> {
+    let mut errors = vec![];
+
+    let indent = &text[..text.len() - text.trim_start().len()];
+    if indent.contains(' ') && indent.contains('\t') && indent.len() < text.len() {
+        errors.push(0..indent.len());
+    }
+
+    let trimmed_len = text.trim_end().len();
+    if trimmed_len < text.len() {
+        errors.push(trimmed_len..text.len());
+    }
+
+    errors
+}
+
+/// Patches `style` onto the part of the line in `range`, the first span being the line's prefix.
+fn highlight_range(
+    spans: Vec<Span<'static>>,
+    range: Range<usize>,
+    style: &Style,
+) -> Vec<Span<'static>> {
+    let mut start = 0;
+    let mut result = vec![];
+
+    for (i, span) in spans.into_iter().enumerate() {
+        if i == 0 {
+            result.push(span);
+            continue;
+        }
+
+        let end = start + span.content.len();
+        let from = range.start.clamp(start, end) - start;
+        let to = range.end.clamp(start, end) - start;
+        start = end;
+
+        if from == to {
+            result.push(span);
+            continue;
+        }
+
+        let content = span.content.as_ref();
+        for (part, part_style) in [
+            (&content[..from], span.style),
+            (&content[from..to], span.style.patch(*style)),
+            (&content[to..], span.style),
+        ] {
+            if !part.is_empty() {
+                result.push(Span::styled(part.to_string(), part_style));
+            }
+        }
+    }
+
+    result
+}
+
 fn advance_to(iter: &mut iter::Peekable<impl Iterator<Item = (Range<usize>, Style)>>, to: usize) {
     while let Some((range, _style)) = iter.peek() {
         if range.end <= to {
@@ -473,11 +961,17 @@ fn next_merged_style_range(
 }
 
 fn read_workdir(repo: &Repository, new_file: &git2::DiffFile<'_>) -> Res<String> {
-    Ok(fs::read_to_string(
-        repo.workdir()
-            .expect("No workdir")
-            .join(new_file.path().unwrap()),
-    )?)
+    let path = repo
+        .workdir()
+        .expect("No workdir")
+        .join(new_file.path().unwrap());
+
+    // Git stores a symlink as a blob of the path it points to
+    if new_file.mode() == git2::FileMode::Link {
+        return Ok(fs::read_link(path)?.to_string_lossy().to_string());
+    }
+
+    Ok(fs::read_to_string(path)?)
 }
 
 fn read_blob(repo: &Repository, file: &git2::DiffFile<'_>) -> Res<String> {
@@ -513,15 +1007,40 @@ mod tests {
         insta::assert_snapshot!(hunks[0].format_patch());
     }
 
+    #[test]
+    fn whitespace_errors() {
+        assert_eq!(super::whitespace_errors("\t  x  "), vec![0..3, 4..6]);
+        assert!(super::whitespace_errors("    x").is_empty());
+        assert_eq!(super::whitespace_errors(" \t "), vec![0..3]);
+    }
+
+    #[test]
+    fn highlight_trailing_whitespace() {
+        let hunks = diff_content("line\n", "line  \n");
+        let added = &hunks[0].content.lines[1];
+        let trailing = added.spans.iter().find(|span| span.content == "  ");
+        assert_eq!(trailing.unwrap().style.bg, Some(ratatui::style::Color::Red));
+        assert_eq!(
+            hunks[0].format_patch(),
+            "header\n@@ -1 +1 @@\n-line\n+line  \n"
+        );
+    }
+
     fn diff_content(old_content: &str, new_content: &str) -> Vec<std::rc::Rc<super::Hunk>> {
+        let config = config::init_test_config().unwrap();
         super::diff_content(
-            &config::init_test_config().unwrap(),
+            &config,
+            &super::DiffSettings::from_config(&config),
             &Delta {
                 file_header: "header\n".into(),
                 new_file: "new_file".into(),
                 old_file: "old_file".into(),
                 hunks: vec![],
                 status: git2::Delta::Modified,
+                old_mode: git2::FileMode::Blob,
+                new_mode: git2::FileMode::Blob,
+                submodule: None,
+                lfs: None,
             },
             old_content,
             new_content,
@@ -0,0 +1,352 @@
+//! Histogram diffs like git's `--diff-algorithm=histogram`, which `similar` doesn't have.
+//!
+//! Regions are split around their longest run of matching lines that occur the fewest times
+//! in the old content, so rare lines like function signatures anchor the diff the way
+//! patience diffs do, while common lines like `}` can still match. Regions with nothing
+//! in common enough to anchor on fall back to Myers, as do those too large or split too often.
+
+use similar::{
+    algorithms::{myers, Capture, DiffHook, Replace},
+    DiffOp,
+};
+use std::{collections::HashMap, convert::Infallible, ops::Range, time::Instant};
+
+/// Lines occurring more often than this in a region aren't used as anchors.
+const MAX_OCCURRENCES: usize = 64;
+/// Regions split this many times, or with more lines than this, fall back to Myers. Finding
+/// anchors is quadratic in the worst case, like for files of mostly unique lines.
+const MAX_DEPTH: usize = 64;
+const MAX_REGION_LINES: usize = 10_000;
+
+/// Diffs `old` and `new`, falling back to Myers for what's left once past `deadline`. Myers
+/// settles for a diff that's not the smallest past it too.
+pub(crate) fn diff_slices(old: &[&str], new: &[&str], deadline: Instant) -> Vec<DiffOp> {
+    let mut hook = Replace::new(Capture::new());
+    let Ok(()) = diff_regions(&mut hook, old, new, deadline);
+    let Ok(()) = hook.finish();
+    hook.into_inner().into_ops()
+}
+
+enum Step {
+    Region {
+        old_range: Range<usize>,
+        new_range: Range<usize>,
+        depth: usize,
+    },
+    Equal {
+        old_index: usize,
+        new_index: usize,
+        len: usize,
+    },
+}
+
+/// Splits regions around anchors until none are left. The steps are kept on a stack rather
+/// than recursed into, as the left-most are to be reported first.
+fn diff_regions<D: DiffHook<Error = Infallible>>(
+    d: &mut D,
+    old: &[&str],
+    new: &[&str],
+    deadline: Instant,
+) -> Result<(), Infallible> {
+    let mut steps = vec![Step::Region {
+        old_range: 0..old.len(),
+        new_range: 0..new.len(),
+        depth: 0,
+    }];
+
+    while let Some(step) = steps.pop() {
+        let (old_range, new_range, depth) = match step {
+            Step::Equal {
+                old_index,
+                new_index,
+                len,
+            } => {
+                d.equal(old_index, new_index, len)?;
+                continue;
+            }
+            Step::Region {
+                old_range,
+                new_range,
+                depth,
+            } => (old_range, new_range, depth),
+        };
+
+        let prefix = old[old_range.clone()]
+            .iter()
+            .zip(&new[new_range.clone()])
+            .take_while(|(old, new)| old == new)
+            .count();
+        if prefix > 0 {
+            d.equal(old_range.start, new_range.start, prefix)?;
+        }
+        let old_range = old_range.start + prefix..old_range.end;
+        let new_range = new_range.start + prefix..new_range.end;
+
+        let suffix = old[old_range.clone()]
+            .iter()
+            .rev()
+            .zip(new[new_range.clone()].iter().rev())
+            .take_while(|(old, new)| old == new)
+            .count();
+        let old_range = old_range.start..old_range.end - suffix;
+        let new_range = new_range.start..new_range.end - suffix;
+        if suffix > 0 {
+            steps.push(Step::Equal {
+                old_index: old_range.end,
+                new_index: new_range.end,
+                len: suffix,
+            });
+        }
+
+        if old_range.is_empty() && !new_range.is_empty() {
+            d.insert(old_range.start, new_range.start, new_range.len())?;
+            continue;
+        } else if new_range.is_empty() {
+            if !old_range.is_empty() {
+                d.delete(old_range.start, old_range.len(), new_range.start)?;
+            }
+            continue;
+        }
+
+        let anchor = (depth < MAX_DEPTH
+            && old_range.len() + new_range.len() <= MAX_REGION_LINES
+            && Instant::now() < deadline)
+            .then(|| longest_rare_run(old, old_range.clone(), new, new_range.clone()))
+            .flatten();
+
+        match anchor {
+            Some((old_start, new_start, len)) => {
+                steps.push(Step::Region {
+                    old_range: old_start + len..old_range.end,
+                    new_range: new_start + len..new_range.end,
+                    depth: depth + 1,
+                });
+                steps.push(Step::Equal {
+                    old_index: old_start,
+                    new_index: new_start,
+                    len,
+                });
+                steps.push(Step::Region {
+                    old_range: old_range.start..old_start,
+                    new_range: new_range.start..new_start,
+                    depth: depth + 1,
+                });
+            }
+            None => myers::diff_deadline(
+                &mut Unfinished(d),
+                old,
+                old_range,
+                new,
+                new_range,
+                Some(deadline),
+            )?,
+        }
+    }
+
+    Ok(())
+}
+
+/// The run of matching lines whose rarest line occurs the fewest times in the old region,
+/// the longest one among those. As `(old_start, new_start, len)`.
+fn longest_rare_run(
+    old: &[&str],
+    old_range: Range<usize>,
+    new: &[&str],
+    new_range: Range<usize>,
+) -> Option<(usize, usize, usize)> {
+    let mut occurrences = HashMap::<&str, Vec<usize>>::new();
+    for i in old_range.clone() {
+        occurrences.entry(old[i]).or_default().push(i);
+    }
+
+    // (occurrences, old_start, new_start, len)
+    let mut best: Option<(usize, usize, usize, usize)> = None;
+    let mut j = new_range.start;
+    while j < new_range.end {
+        let mut next_j = j + 1;
+
+        for &i in occurrences
+            .get(new[j])
+            .filter(|positions| positions.len() <= MAX_OCCURRENCES)
+            .into_iter()
+            .flatten()
+        {
+            let mut start = (i, j);
+            while start.0 > old_range.start
+                && start.1 > new_range.start
+                && old[start.0 - 1] == new[start.1 - 1]
+            {
+                start = (start.0 - 1, start.1 - 1);
+            }
+
+            let mut end = (i + 1, j + 1);
+            while end.0 < old_range.end && end.1 < new_range.end && old[end.0] == new[end.1] {
+                end = (end.0 + 1, end.1 + 1);
+            }
+
+            let rarest = (start.0..end.0)
+                .map(|i| occurrences[old[i]].len())
+                .min()
+                .unwrap_or(usize::MAX);
+            let len = end.0 - start.0;
+            next_j = next_j.max(end.1);
+
+            if best.is_none_or(|(count, _, _, best_len)| {
+                rarest < count || (rarest == count && len > best_len)
+            }) {
+                best = Some((rarest, start.0, start.1, len));
+            }
+        }
+
+        j = next_j;
+    }
+
+    best.map(|(_, old_start, new_start, len)| (old_start, new_start, len))
+}
+
+/// Leaves finishing to the outer diff, as Myers finishes its hook when done with a region.
+struct Unfinished<'a, D>(&'a mut D);
+
+impl<D: DiffHook> DiffHook for Unfinished<'_, D> {
+    type Error = D::Error;
+
+    fn equal(&mut self, old_index: usize, new_index: usize, len: usize) -> Result<(), D::Error> {
+        self.0.equal(old_index, new_index, len)
+    }
+
+    fn delete(
+        &mut self,
+        old_index: usize,
+        old_len: usize,
+        new_index: usize,
+    ) -> Result<(), D::Error> {
+        self.0.delete(old_index, old_len, new_index)
+    }
+
+    fn insert(
+        &mut self,
+        old_index: usize,
+        new_index: usize,
+        new_len: usize,
+    ) -> Result<(), D::Error> {
+        self.0.insert(old_index, new_index, new_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use similar::DiffTag;
+    use std::time::Duration;
+
+    fn diff(old: &[&str], new: &[&str]) -> Vec<DiffOp> {
+        diff_slices(old, new, Instant::now() + Duration::from_secs(60))
+    }
+
+    /// Rebuilds the new content from the old one and the ops, as a unified diff would.
+    fn apply(old: &[&str], new: &[&str], ops: &[DiffOp]) -> Vec<String> {
+        ops.iter()
+            .flat_map(|op| match op.tag() {
+                DiffTag::Equal => old[op.old_range()].to_vec(),
+                DiffTag::Delete => vec![],
+                DiffTag::Insert | DiffTag::Replace => new[op.new_range()].to_vec(),
+            })
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn reconstructs_new_content() {
+        let old = ["a", "b", "c", "a", "b", "b", "a"];
+        let new = ["c", "b", "a", "b", "a", "c"];
+        let ops = diff(&old, &new);
+        assert_eq!(apply(&old, &new, &ops), new);
+    }
+
+    #[test]
+    fn empty_sides() {
+        assert_eq!(apply(&[], &["a"], &diff(&[], &["a"])), ["a"]);
+        assert!(diff(&["a"], &[])
+            .iter()
+            .all(|op| op.tag() == DiffTag::Delete));
+    }
+
+    #[test]
+    fn anchors_on_rare_lines() {
+        // The braces of `one` aren't to be matched with those of `three`
+        let old = ["fn one() {", "    1", "}", "fn two() {", "    2", "}"];
+        let new = ["fn two() {", "    2", "}", "fn three() {", "    3", "}"];
+        let ops = diff(&old, &new);
+
+        assert_eq!(apply(&old, &new, &ops), new);
+        assert!(ops.iter().any(|op| op.tag() == DiffTag::Equal
+            && op.old_range() == (3..5)
+            && op.new_range() == (0..2)));
+    }
+
+    #[test]
+    fn many_unique_lines() {
+        let old = (0..12_000).map(|i| i.to_string()).collect::<Vec<_>>();
+        let new = (0..12_000)
+            .map(|i| {
+                if i % 7 == 0 {
+                    format!("{}'", i)
+                } else {
+                    i.to_string()
+                }
+            })
+            .collect::<Vec<_>>();
+        let old = old.iter().map(String::as_str).collect::<Vec<_>>();
+        let new = new.iter().map(String::as_str).collect::<Vec<_>>();
+
+        let ops = diff(&old, &new);
+        assert_eq!(apply(&old, &new, &ops), new);
+    }
+
+    #[test]
+    fn deeply_split_regions() {
+        // Every other line changed, so each anchor splits off a region of one line
+        let old = (0..2_000)
+            .map(|i| {
+                if i % 2 == 0 {
+                    format!("same {}", i)
+                } else {
+                    "old".into()
+                }
+            })
+            .collect::<Vec<_>>();
+        let new = (0..2_000)
+            .map(|i| {
+                if i % 2 == 0 {
+                    format!("same {}", i)
+                } else {
+                    "new".into()
+                }
+            })
+            .collect::<Vec<_>>();
+        let old = old.iter().map(String::as_str).collect::<Vec<_>>();
+        let new = new.iter().map(String::as_str).collect::<Vec<_>>();
+
+        let ops = diff(&old, &new);
+        assert_eq!(apply(&old, &new, &ops), new);
+        assert_eq!(
+            ops.iter().filter(|op| op.tag() == DiffTag::Replace).count(),
+            1_000
+        );
+    }
+
+    #[test]
+    fn past_deadline() {
+        let old = (0..50_000)
+            .map(|i| (i % 100).to_string())
+            .collect::<Vec<_>>();
+        let new = (0..50_000)
+            .map(|i| (i % 101).to_string())
+            .collect::<Vec<_>>();
+        let old = old.iter().map(String::as_str).collect::<Vec<_>>();
+        let new = new.iter().map(String::as_str).collect::<Vec<_>>();
+
+        let ops = diff_slices(&old, &new, Instant::now());
+        assert_eq!(apply(&old, &new, &ops), new);
+    }
+}
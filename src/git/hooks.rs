@@ -0,0 +1,116 @@
+use crate::Res;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    str,
+};
+
+/// Where git looks for hooks, minding `core.hooksPath` and worktrees.
+fn hooks_dir(dir: &Path) -> Res<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .current_dir(dir)
+        .output()?;
+
+    if !output.status.success() {
+        return Err("Couldn't find the hooks directory".into());
+    }
+
+    Ok(dir.join(str::from_utf8(&output.stdout)?.trim_end()))
+}
+
+/// Names of the hooks git would run, leaving out the disabled `.sample` ones it installs.
+pub(crate) fn list(dir: &Path) -> Res<Vec<String>> {
+    let Ok(entries) = fs::read_dir(hooks_dir(dir)?) else {
+        return Ok(vec![]);
+    };
+
+    let mut hooks = entries
+        .flatten()
+        .filter(|entry| is_executable(&entry.path()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| !name.ends_with(".sample"))
+        .collect::<Vec<_>>();
+
+    hooks.sort();
+    Ok(hooks)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// The hooks a git command runs that `--no-verify` would skip.
+fn skippable_hooks(cmd: &Command) -> &'static [&'static str] {
+    let args = cmd
+        .get_args()
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>();
+
+    if args.iter().any(|arg| arg == "--no-verify") {
+        return &[];
+    }
+
+    match args.first().map(|arg| arg.as_ref()) {
+        Some("commit") if !args.iter().any(|arg| arg == "-n") => &["pre-commit", "commit-msg"],
+        Some("merge") => &["pre-merge-commit", "commit-msg"],
+        Some("push") => &["pre-push"],
+        _ => &[],
+    }
+}
+
+/// Explains a failed command when a hook may be what stopped it, since its output
+/// is easily mistaken for git's own.
+pub(crate) fn failure_hint(dir: &Path, cmd: &Command) -> Option<String> {
+    let skippable = skippable_hooks(cmd);
+    if skippable.is_empty() {
+        return None;
+    }
+
+    let installed = list(dir).ok()?;
+    let hooks = skippable
+        .iter()
+        .filter(|hook| installed.iter().any(|name| name == *hook))
+        .collect::<Vec<_>>();
+
+    match hooks[..] {
+        [] => None,
+        [hook] => Some(format!("({} hook? --no-verify skips it)", hook)),
+        [first, second, ..] => Some(format!(
+            "({} or {} hook? --no-verify skips them)",
+            first, second
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::skippable_hooks;
+    use std::process::Command;
+
+    fn git(args: &[&str]) -> Command {
+        let mut cmd = Command::new("git");
+        cmd.args(args);
+        cmd
+    }
+
+    #[test]
+    fn hooks_skipped_by_no_verify() {
+        assert_eq!(
+            skippable_hooks(&git(&["commit", "-m", "msg"])),
+            ["pre-commit", "commit-msg"]
+        );
+        assert_eq!(skippable_hooks(&git(&["push", "origin"])), ["pre-push"]);
+        assert!(skippable_hooks(&git(&["commit", "--no-verify"])).is_empty());
+        assert!(skippable_hooks(&git(&["commit", "-n"])).is_empty());
+        assert!(skippable_hooks(&git(&["fetch"])).is_empty());
+    }
+}
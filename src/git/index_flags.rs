@@ -0,0 +1,43 @@
+use crate::Res;
+use git2::{IndexEntryExtendedFlag, IndexEntryFlag, Repository};
+use std::path::PathBuf;
+
+/// Tracked files that `git update-index` flagged to be left out of the status.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct FlaggedFiles {
+    /// Changes to these go unnoticed, until something like a checkout overwrites them.
+    pub assume_unchanged: Vec<PathBuf>,
+    /// Not checked out, or local changes kept out of the way of pulls.
+    pub skip_worktree: Vec<PathBuf>,
+}
+
+impl FlaggedFiles {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.assume_unchanged.is_empty() && self.skip_worktree.is_empty()
+    }
+
+    pub(crate) fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.assume_unchanged.iter().chain(&self.skip_worktree)
+    }
+}
+
+pub(crate) fn flagged_files(repo: &Repository) -> Res<FlaggedFiles> {
+    let mut index = repo.index()?;
+    index.read(false)?;
+
+    let assume_unchanged = IndexEntryFlag::VALID.bits();
+    let skip_worktree = IndexEntryExtendedFlag::SKIP_WORKTREE.bits();
+    let mut files = FlaggedFiles::default();
+
+    for entry in index.iter() {
+        let path = || PathBuf::from(String::from_utf8_lossy(&entry.path).into_owned());
+        if entry.flags & assume_unchanged != 0 {
+            files.assume_unchanged.push(path());
+        }
+        if entry.flags_extended & skip_worktree != 0 {
+            files.skip_worktree.push(path());
+        }
+    }
+
+    Ok(files)
+}
@@ -0,0 +1,85 @@
+use git2::{AttrCheckFlags, Repository};
+use std::path::Path;
+
+const VERSION_LINE: &str = "version https://git-lfs.github.com/spec/v1";
+// The spec doesn't allow pointer files any larger than this
+pub(crate) const MAX_POINTER_SIZE: usize = 1024;
+
+/// What git stores in place of a file tracked by git-lfs.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Pointer {
+    pub oid: String,
+    pub size: u64,
+}
+
+pub(crate) fn is_tracked(repo: &Repository, path: &Path) -> bool {
+    repo.get_attr(path, "filter", AttrCheckFlags::FILE_THEN_INDEX)
+        .ok()
+        .flatten()
+        == Some("lfs")
+}
+
+pub(crate) fn parse_pointer(content: &[u8]) -> Option<Pointer> {
+    if content.len() > MAX_POINTER_SIZE {
+        return None;
+    }
+
+    let mut lines = std::str::from_utf8(content).ok()?.lines();
+    if lines.next()? != VERSION_LINE {
+        return None;
+    }
+
+    let mut oid = None;
+    let mut size = None;
+
+    for line in lines {
+        let (key, value) = line.split_once(' ')?;
+        match key {
+            "oid" => {
+                let hash = value.strip_prefix("sha256:")?;
+                if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return None;
+                }
+                oid = Some(hash.to_string());
+            }
+            "size" => size = Some(value.parse().ok()?),
+            _ => (),
+        }
+    }
+
+    Some(Pointer {
+        oid: oid?,
+        size: size?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_pointer, Pointer};
+
+    #[test]
+    fn pointer() {
+        let content = "version https://git-lfs.github.com/spec/v1\noid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\nsize 12345\n";
+
+        assert_eq!(
+            parse_pointer(content.as_bytes()),
+            Some(Pointer {
+                oid: "4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393".into(),
+                size: 12345,
+            })
+        );
+    }
+
+    #[test]
+    fn not_a_pointer() {
+        assert_eq!(parse_pointer(b"size 12345\n"), None);
+        assert_eq!(
+            parse_pointer(b"version https://git-lfs.github.com/spec/v1\nsize 12345\n"),
+            None
+        );
+        assert_eq!(
+            parse_pointer(b"version https://git-lfs.github.com/spec/v1\noid sha256:abc\nsize 1\n"),
+            None
+        );
+    }
+}
@@ -1,19 +1,35 @@
 use git2::Repository;
 use itertools::Itertools;
 
-use self::{commit::Commit, diff::Diff, merge_status::MergeStatus, rebase_status::RebaseStatus};
+use self::{
+    commit::{Commit, Parent},
+    diff::{Diff, DiffSettings, HunkCache},
+    merge_status::MergeStatus,
+    rebase_status::RebaseStatus,
+};
 use crate::{config::Config, git2_opts, Res};
 use std::{
+    collections::HashSet,
     fs,
     path::Path,
     process::Command,
     str::{self},
 };
 
+pub(crate) mod branch_cleanup;
 pub(crate) mod commit;
+pub(crate) mod config;
 pub(crate) mod diff;
+pub(crate) mod histogram;
+pub(crate) mod hooks;
+pub(crate) mod index_flags;
+pub(crate) mod lfs;
 pub(crate) mod merge_status;
 pub(crate) mod rebase_status;
+pub(crate) mod rebase_todo;
+pub(crate) mod rerere;
+pub(crate) mod sparse;
+pub(crate) mod stats;
 
 // TODO Use only plumbing commands
 
@@ -29,7 +45,8 @@ pub(crate) fn rebase_status(repo: &Repository) -> Res<Option<RebaseStatus>> {
         Ok(content) => {
             let onto_hash = content.trim().to_string();
             Ok(Some(RebaseStatus {
-                onto: branch_name(dir, &onto_hash)?.unwrap_or_else(|| onto_hash[..7].to_string()),
+                onto: branch_name(dir, &onto_hash)?
+                    .unwrap_or_else(|| abbreviated_hash(repo, &onto_hash)),
                 head_name: fs::read_to_string(rebase_head_name_file)?
                     .trim()
                     .strip_prefix("refs/heads/")
@@ -58,7 +75,7 @@ pub(crate) fn merge_status(repo: &Repository) -> Res<Option<MergeStatus>> {
         Ok(content) => {
             let head = content.trim().to_string();
             Ok(Some(MergeStatus {
-                head: branch_name(dir, &head)?.unwrap_or(head[..7].to_string()),
+                head: branch_name(dir, &head)?.unwrap_or_else(|| abbreviated_hash(repo, &head)),
             }))
         }
         Err(err) => {
@@ -85,7 +102,7 @@ pub(crate) fn revert_status(repo: &Repository) -> Res<Option<RevertStatus>> {
         Ok(content) => {
             let head = content.trim().to_string();
             Ok(Some(RevertStatus {
-                head: branch_name(dir, &head)?.unwrap_or(head[..7].to_string()),
+                head: branch_name(dir, &head)?.unwrap_or_else(|| abbreviated_hash(repo, &head)),
             }))
         }
         Err(err) => {
@@ -99,6 +116,55 @@ pub(crate) fn revert_status(repo: &Repository) -> Res<Option<RevertStatus>> {
     }
 }
 
+pub(crate) struct CherryPickStatus {
+    pub head: String,
+}
+
+pub(crate) fn cherry_pick_status(repo: &Repository) -> Res<Option<CherryPickStatus>> {
+    let dir = repo.workdir().expect("No workdir");
+    let cherry_pick_head_file = repo.path().join("CHERRY_PICK_HEAD");
+
+    match fs::read_to_string(&cherry_pick_head_file) {
+        Ok(content) => {
+            let head = content.trim().to_string();
+            Ok(Some(CherryPickStatus {
+                head: branch_name(dir, &head)?.unwrap_or_else(|| abbreviated_hash(repo, &head)),
+            }))
+        }
+        Err(err) => {
+            log::warn!(
+                "Couldn't read {}, due to {}",
+                cherry_pick_head_file.to_string_lossy(),
+                err
+            );
+            Ok(None)
+        }
+    }
+}
+
+pub(crate) struct AmStatus {
+    pub next: String,
+    pub last: String,
+}
+
+pub(crate) fn am_status(repo: &Repository) -> Res<Option<AmStatus>> {
+    let apply_dir = repo.path().join("rebase-apply");
+
+    // rebase-apply is shared with `git rebase --apply`, which doesn't leave this file.
+    if !apply_dir.join("applying").exists() {
+        return Ok(None);
+    }
+
+    Ok(Some(AmStatus {
+        next: fs::read_to_string(apply_dir.join("next"))?
+            .trim()
+            .to_string(),
+        last: fs::read_to_string(apply_dir.join("last"))?
+            .trim()
+            .to_string(),
+    }))
+}
+
 // TODO replace with libgit2
 fn branch_name(dir: &Path, hash: &str) -> Res<Option<String>> {
     let out = Command::new("git")
@@ -113,12 +179,155 @@ fn branch_name(dir: &Path, hash: &str) -> Res<Option<String>> {
         .map(|line| line.split(' ').nth(1).unwrap().to_string()))
 }
 
-pub(crate) fn diff_unstaged(config: &Config, repo: &Repository) -> Res<Diff> {
+/// Short names of all branches, remote branches and tags. Useful as completion candidates.
+pub(crate) fn ref_names(repo: &Repository) -> Res<Vec<String>> {
+    Ok(repo
+        .references()?
+        .filter_map(Result::ok)
+        .filter_map(|reference| reference.shorthand().map(str::to_string))
+        .filter(|name| !name.ends_with("/HEAD"))
+        .sorted()
+        .dedup()
+        .collect())
+}
+
+/// Short name and target of the current branch's upstream, if it has one.
+pub(crate) fn upstream(repo: &Repository) -> Option<(String, git2::Oid)> {
+    let head = repo.head().ok()?;
+    let upstream = repo.branch_upstream_name(head.name()?).ok()?;
+    let upstream_name = upstream.as_str()?;
+    let upstream_id = repo.refname_to_id(upstream_name).ok()?;
+
+    let shortname = upstream_name
+        .strip_prefix("refs/remotes/")
+        .unwrap_or(upstream_name)
+        .to_string();
+
+    Some((shortname, upstream_id))
+}
+
+pub(crate) fn remote_names(repo: &Repository) -> Res<Vec<String>> {
+    Ok(repo
+        .remotes()?
+        .iter()
+        .flatten()
+        .map(str::to_string)
+        .collect())
+}
+
+pub(crate) fn branch_names(repo: &Repository) -> Res<Vec<String>> {
+    Ok(repo
+        .branches(Some(git2::BranchType::Local))?
+        .filter_map(Result::ok)
+        .filter_map(|(branch, _)| branch.name().ok().flatten().map(str::to_string))
+        .sorted()
+        .collect())
+}
+
+/// Short names of all remote-tracking branches, e.g. `origin/main`.
+pub(crate) fn remote_branch_names(repo: &Repository) -> Res<Vec<String>> {
+    Ok(repo
+        .branches(Some(git2::BranchType::Remote))?
+        .filter_map(Result::ok)
+        .filter_map(|(branch, _)| branch.name().ok().flatten().map(str::to_string))
+        .filter(|name| !name.ends_with("/HEAD"))
+        .sorted()
+        .collect())
+}
+
+/// Name of the currently checked out branch, or `None` if HEAD is detached.
+pub(crate) fn current_branch(repo: &Repository) -> Option<String> {
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+
+    head.shorthand().map(str::to_string)
+}
+
+/// Short name of the upstream configured for a local branch, regardless of it having been fetched.
+pub(crate) fn branch_upstream_name(repo: &Repository, branch: &str) -> Option<String> {
+    let upstream = repo
+        .branch_upstream_name(&format!("refs/heads/{}", branch))
+        .ok()?;
+    let upstream_name = upstream.as_str()?;
+
+    Some(
+        upstream_name
+            .strip_prefix("refs/remotes/")
+            .unwrap_or(upstream_name)
+            .to_string(),
+    )
+}
+
+/// The remote that the current branch's upstream lives on.
+pub(crate) fn upstream_remote(repo: &Repository) -> Option<String> {
+    let head = repo.head().ok()?;
+    let remote = repo.branch_upstream_remote(head.name()?).ok()?;
+    remote.as_str().map(str::to_string)
+}
+
+/// The directory `git clone` would make for `url`, like `repo` for `https://host/user/repo.git`.
+pub(crate) fn clone_dir(url: &str) -> String {
+    let path = url.trim_end_matches('/').trim_end_matches("/.git");
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let name = path.rsplit(['/', ':']).next().unwrap_or(path);
+
+    if name.is_empty() {
+        "repo".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Distinct authors of the most recent commits on HEAD, formatted as `Name <email>`.
+pub(crate) fn recent_authors(repo: &Repository, limit: usize) -> Res<Vec<String>> {
+    let mut revwalk = repo.revwalk()?;
+    if revwalk.push_head().is_err() {
+        return Ok(vec![]);
+    }
+
+    Ok(revwalk
+        .filter_map(Result::ok)
+        .take(limit)
+        .filter_map(|oid| repo.find_commit(oid).ok())
+        .filter_map(|commit| {
+            let author = commit.author();
+            match (author.name(), author.email()) {
+                (Some(name), Some(email)) => Some(format!("{} <{}>", name, email)),
+                _ => None,
+            }
+        })
+        .unique()
+        .collect())
+}
+
+pub(crate) fn diff_unstaged(
+    config: &Config,
+    settings: &DiffSettings,
+    cache: &HunkCache,
+    repo: &Repository,
+) -> Res<Diff> {
     let diff = repo.diff_index_to_workdir(None, Some(&mut git2_opts::diff(repo)?))?;
-    diff::convert_diff(config, repo, diff, true)
+    let mut diff = diff::convert_diff(config, settings, cache, repo, diff, true)?;
+
+    // Like git, unlike libgit2, which shows files left out by a sparse checkout as deleted
+    let flagged = index_flags::flagged_files(repo)?;
+    if !flagged.is_empty() {
+        let hidden = flagged.paths().collect::<HashSet<_>>();
+        diff.deltas
+            .retain(|delta| !hidden.contains(&delta.old_file));
+    }
+
+    Ok(diff)
 }
 
-pub(crate) fn diff_staged(config: &Config, repo: &Repository) -> Res<Diff> {
+pub(crate) fn diff_staged(
+    config: &Config,
+    settings: &DiffSettings,
+    cache: &HunkCache,
+    repo: &Repository,
+) -> Res<Diff> {
     let opts = &mut git2_opts::diff(repo)?;
 
     let diff = match repo.head() {
@@ -126,40 +335,90 @@ pub(crate) fn diff_staged(config: &Config, repo: &Repository) -> Res<Diff> {
         Err(_) => repo.diff_tree_to_index(None, None, Some(opts))?,
     };
 
-    diff::convert_diff(config, repo, diff, false)
+    diff::convert_diff(config, settings, cache, repo, diff, false)
 }
 
-pub(crate) fn show(config: &Config, repo: &Repository, reference: &str) -> Res<Diff> {
+/// Diffs a range like `A..B` or `A...B`. A single revision is compared against the worktree.
+pub(crate) fn diff_range(
+    config: &Config,
+    settings: &DiffSettings,
+    cache: &HunkCache,
+    repo: &Repository,
+    range: &str,
+) -> Res<Diff> {
+    let revspec = repo.revparse(range)?;
+    let opts = &mut git2_opts::diff(repo)?;
+
+    let Some(to) = revspec.to() else {
+        let from = revspec.from().ok_or("No revision given")?.peel_to_tree()?;
+        let diff = repo.diff_tree_to_workdir_with_index(Some(&from), Some(opts))?;
+        return diff::convert_diff(config, settings, cache, repo, diff, true);
+    };
+
+    let from = revspec.from().ok_or("No revision given")?;
+    let from = if revspec.mode().contains(git2::RevparseMode::MERGE_BASE) {
+        repo.find_commit(repo.merge_base(from.id(), to.id())?)?
+            .tree()?
+    } else {
+        from.peel_to_tree()?
+    };
+
+    let diff = repo.diff_tree_to_tree(Some(&from), Some(&to.peel_to_tree()?), Some(opts))?;
+    diff::convert_diff(config, settings, cache, repo, diff, false)
+}
+
+pub(crate) fn show(
+    config: &Config,
+    settings: &DiffSettings,
+    cache: &HunkCache,
+    repo: &Repository,
+    reference: &str,
+) -> Res<Diff> {
     let object = &repo.revparse_single(reference)?;
 
     let commit = object.peel_to_commit()?;
-    let tree = commit.tree()?;
+    let diff = commit_diff(repo, &commit)?;
+
+    diff::convert_diff(config, settings, cache, repo, diff, false)
+}
+
+/// Against the first parent, stashes also with the untracked files of their third one.
+fn commit_diff<'a>(repo: &'a Repository, commit: &git2::Commit) -> Res<git2::Diff<'a>> {
     let parent_tree = commit
         .parents()
         .next()
         .and_then(|parent| parent.tree().ok());
 
-    let diff = repo.diff_tree_to_tree(
+    let mut diff = repo.diff_tree_to_tree(
         parent_tree.as_ref(),
-        Some(&tree),
+        Some(&commit.tree()?),
         Some(&mut git2_opts::diff(repo)?),
     )?;
 
-    diff::convert_diff(config, repo, diff, false)
+    // Stashed with `--include-untracked`, they're in a parent of their own
+    if let Some(untracked) = is_stash(repo, commit.id())
+        .then(|| commit.parent(2).ok())
+        .flatten()
+    {
+        diff.merge(&repo.diff_tree_to_tree(
+            None,
+            Some(&untracked.tree()?),
+            Some(&mut git2_opts::diff(repo)?),
+        )?)?;
+    }
+
+    Ok(diff)
+}
+
+fn is_stash(repo: &Repository, oid: git2::Oid) -> bool {
+    repo.reflog("refs/stash")
+        .is_ok_and(|reflog| reflog.iter().any(|entry| entry.id_new() == oid))
 }
 
 pub(crate) fn show_summary(repo: &Repository, reference: &str) -> Res<Commit> {
     let object = &repo.revparse_single(reference)?;
     let commit = object.peel_to_commit()?;
 
-    let author = commit.author();
-    let name = author.name().unwrap_or("");
-    let email = commit
-        .author()
-        .email()
-        .map(|email| format!("<{}>", email))
-        .unwrap_or("".to_string());
-
     let message = commit
         .message()
         .unwrap_or("")
@@ -168,21 +427,196 @@ pub(crate) fn show_summary(repo: &Repository, reference: &str) -> Res<Commit> {
         .map(|line| format!("    {}", line))
         .join("\n");
 
-    let offset = chrono::FixedOffset::east_opt(author.when().offset_minutes() * 60).unwrap();
-    let time = chrono::DateTime::with_timezone(
-        &chrono::DateTime::from_timestamp(author.when().seconds(), 0).unwrap(),
-        &offset,
-    );
+    let parents = commit
+        .parents()
+        .map(|parent| {
+            Ok(Parent {
+                hash: parent.id().to_string(),
+                short_id: parent.as_object().short_id()?.as_str().unwrap().to_string(),
+                summary: parent.summary().unwrap_or("").to_string(),
+            })
+        })
+        .collect::<Res<Vec<_>>>()?;
+
+    let mut diff = commit_diff(repo, &commit)?;
+    diff.find_similar(Some(&mut git2_opts::find_similar()))?;
 
-    let details = format!(
-        "Author: {}\nDate:   {}\n\n{}",
-        [name, &email].join(" "),
-        time.to_rfc2822(),
-        message
-    );
+    let stats = diff
+        .stats()?
+        .to_buf(git2::DiffStatsFormat::SHORT, 80)?
+        .as_str()
+        .unwrap_or("")
+        .trim_end()
+        .to_string();
+
+    let author = commit.author();
+    let committer = commit.committer();
 
     Ok(Commit {
         hash: commit.id().to_string(),
-        details,
+        author: format_signature(&author),
+        author_date: date_time(&author.when()),
+        committer: format_signature(&committer),
+        committer_date: date_time(&committer.when()),
+        signature: signature_kind(repo, commit.id()),
+        parents,
+        message,
+        note: repo
+            .find_note(None, commit.id())
+            .ok()
+            .and_then(|note| note.message().map(|message| message.trim_end().to_string())),
+        stats,
     })
 }
+
+fn format_signature(signature: &git2::Signature) -> String {
+    let name = signature.name().unwrap_or("");
+    let email = signature
+        .email()
+        .map(|email| format!("<{}>", email))
+        .unwrap_or("".to_string());
+
+    [name, &email].join(" ")
+}
+
+/// Like `git rev-parse --short`, as long as `core.abbrev` asks for and unambiguous.
+pub(crate) fn abbreviated_hash(repo: &Repository, hash: &str) -> String {
+    repo.revparse_single(hash)
+        .and_then(|object| object.short_id())
+        .ok()
+        .and_then(|short_id| short_id.as_str().map(String::from))
+        .unwrap_or_else(|| hash.to_string())
+}
+
+/// At least `length` long, longer where that would be ambiguous.
+pub(crate) fn hash_of_length(repo: &Repository, oid: git2::Oid, length: usize) -> Res<String> {
+    let hash = oid.to_string();
+    let odb = repo.odb()?;
+
+    Ok((length.max(4)..hash.len())
+        .find(|&len| {
+            git2::Oid::from_str(&hash[..len])
+                .and_then(|prefix| odb.exists_prefix(prefix, len))
+                .is_ok()
+        })
+        .map_or(hash.clone(), |len| hash[..len].to_string()))
+}
+
+fn date_time(when: &git2::Time) -> chrono::DateTime<chrono::FixedOffset> {
+    let offset = chrono::FixedOffset::east_opt(when.offset_minutes() * 60).unwrap();
+    chrono::DateTime::with_timezone(
+        &chrono::DateTime::from_timestamp(when.seconds(), 0).unwrap(),
+        &offset,
+    )
+}
+
+/// Signatures are only detected, verifying them is left to `git verify-commit`.
+fn signature_kind(repo: &Repository, oid: git2::Oid) -> Option<String> {
+    let (signature, _signed_data) = repo.extract_signature(&oid, None).ok()?;
+    let signature = String::from_utf8_lossy(&signature);
+
+    let kind = if signature.starts_with("-----BEGIN PGP SIGNATURE") {
+        "PGP"
+    } else if signature.starts_with("-----BEGIN SSH SIGNATURE") {
+        "SSH"
+    } else if signature.starts_with("-----BEGIN SIGNED MESSAGE") {
+        "X.509"
+    } else {
+        "Unknown"
+    };
+
+    Some(format!("{} (unverified)", kind))
+}
+
+/// Where `line` of `file` as of `reference` is in the working tree, following the changes since.
+/// Lines that were changed since end up at the start of what replaced them.
+pub(crate) fn worktree_line(
+    repo: &Repository,
+    reference: &str,
+    file: &Path,
+    line: u32,
+) -> Res<u32> {
+    let tree = repo.revparse_single(reference)?.peel_to_tree()?;
+    let mut opts = git2::DiffOptions::new();
+    opts.pathspec(file)
+        .disable_pathspec_match(true)
+        .context_lines(0);
+    let diff = repo.diff_tree_to_workdir_with_index(Some(&tree), Some(&mut opts))?;
+
+    let mut mapped = line as i64;
+    let mut replaced_by = None;
+    diff.foreach(
+        &mut |_delta, _progress| true,
+        None,
+        Some(&mut |_delta, hunk| {
+            let (old_start, old_lines) = (hunk.old_start(), hunk.old_lines());
+            let (new_start, new_lines) = (hunk.new_start(), hunk.new_lines());
+
+            // Additions come after `old_start`, other changes replace lines from it
+            let before = if old_lines == 0 {
+                old_start < line
+            } else {
+                old_start + old_lines <= line
+            };
+
+            if before {
+                mapped += new_lines as i64 - old_lines as i64;
+                true
+            } else {
+                if old_lines > 0 && old_start <= line {
+                    replaced_by = Some(new_start.max(1));
+                }
+                // Hunks are in order, those left are all after the line
+                false
+            }
+        }),
+        None,
+    )
+    .or_else(|err| match err.code() {
+        git2::ErrorCode::User => Ok(()),
+        _ => Err(err),
+    })?;
+
+    Ok(replaced_by.unwrap_or(mapped.max(1) as u32))
+}
+
+/// Finds the commits immediately before and after `oid` in the order a log of `rev`,
+/// or of `HEAD` without one, displays.
+pub(crate) fn log_neighbours(
+    repo: &Repository,
+    rev: Option<git2::Oid>,
+    oid: git2::Oid,
+) -> Res<(Option<git2::Oid>, Option<git2::Oid>)> {
+    let mut revwalk = repo.revwalk()?;
+    match rev {
+        Some(rev) => revwalk.push(rev)?,
+        None => revwalk.push_head()?,
+    }
+
+    let mut previous = None;
+    while let Some(current) = revwalk.next().transpose()? {
+        if current == oid {
+            return Ok((previous, revwalk.next().transpose()?));
+        }
+        previous = Some(current);
+    }
+
+    Err("Commit is not in the history of the log".into())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn clone_dir() {
+        for (url, dir) in [
+            ("https://github.com/altsem/gitu.git", "gitu"),
+            ("https://github.com/altsem/gitu/", "gitu"),
+            ("git@github.com:altsem/gitu.git", "gitu"),
+            ("host:gitu", "gitu"),
+            ("/srv/repos/gitu/.git", "gitu"),
+            ("../gitu", "gitu"),
+        ] {
+            assert_eq!(super::clone_dir(url), dir, "{}", url);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! Todo lists of interactive rebases, put together in gitu rather than in an editor.
+
+use crate::Res;
+use derive_more::Display;
+use git2::{Oid, Repository, Sort};
+use std::{ffi::OsString, fmt, mem};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display)]
+pub(crate) enum Action {
+    #[display(fmt = "pick")]
+    Pick,
+    #[display(fmt = "squash")]
+    Squash,
+    #[display(fmt = "fixup")]
+    Fixup,
+    #[display(fmt = "edit")]
+    Edit,
+    #[display(fmt = "drop")]
+    Drop,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Line {
+    pub action: Action,
+    pub oid: Oid,
+    pub summary: String,
+}
+
+#[derive(Debug)]
+pub(crate) struct Todo {
+    /// Rebased onto, `None` meaning the root.
+    pub base: Option<Oid>,
+    /// Oldest first, as they'll be applied.
+    pub lines: Vec<Line>,
+    /// Given to `git rebase` once the todo list is done.
+    pub args: Vec<OsString>,
+}
+
+impl Todo {
+    /// Picks every commit since `base` like git would, oldest first, merges being flattened.
+    pub(crate) fn since(repo: &Repository, base: Option<Oid>) -> Res<Self> {
+        let mut walk = repo.revwalk()?;
+        walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        walk.push_head()?;
+        if let Some(base) = base {
+            walk.hide(base)?;
+        }
+
+        let mut lines = vec![];
+        for oid in walk {
+            let commit = repo.find_commit(oid?)?;
+            if commit.parent_count() > 1 {
+                continue;
+            }
+
+            lines.push(Line {
+                action: Action::Pick,
+                oid: commit.id(),
+                summary: commit.summary().unwrap_or("").to_string(),
+            });
+        }
+
+        Ok(Self {
+            base,
+            lines,
+            args: vec![],
+        })
+    }
+
+    /// Returns whether `oid` is in the todo list.
+    pub(crate) fn set_action(&mut self, oid: Oid, action: Action) -> bool {
+        match self.lines.iter_mut().find(|line| line.oid == oid) {
+            Some(line) => {
+                line.action = action;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Swaps the line of `oid` with the one before (or after), returns whether there was one.
+    pub(crate) fn move_line(&mut self, oid: Oid, earlier: bool) -> bool {
+        let Some(i) = self.lines.iter().position(|line| line.oid == oid) else {
+            return false;
+        };
+
+        let other = if earlier {
+            i.checked_sub(1)
+        } else {
+            Some(i + 1).filter(|&next| next < self.lines.len())
+        };
+
+        match other {
+            Some(other) => {
+                self.lines.swap(i, other);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Like `--autosquash`, moves `fixup!` and `squash!` commits right after the ones they amend.
+    pub(crate) fn autosquash(&mut self) {
+        let mut groups: Vec<Vec<Line>> = vec![];
+
+        for mut line in mem::take(&mut self.lines) {
+            if let Some((action, subject)) = fixup_subject(&line.summary) {
+                let target = groups.iter_mut().find(|group| {
+                    group[0].summary == subject || group[0].oid.to_string().starts_with(subject)
+                });
+
+                if let Some(group) = target {
+                    line.action = action;
+                    group.push(line);
+                    continue;
+                }
+            }
+
+            groups.push(vec![line]);
+        }
+
+        self.lines = groups.into_iter().flatten().collect();
+    }
+}
+
+/// Of summaries like `fixup! squash! <subject>`, the first one deciding the action.
+fn fixup_subject(summary: &str) -> Option<(Action, &str)> {
+    let mut action = None;
+    let mut rest = summary;
+
+    loop {
+        if let Some(subject) = rest.strip_prefix("fixup! ") {
+            action.get_or_insert(Action::Fixup);
+            rest = subject;
+        } else if let Some(subject) = rest.strip_prefix("squash! ") {
+            action.get_or_insert(Action::Squash);
+            rest = subject;
+        } else {
+            break;
+        }
+    }
+
+    action
+        .filter(|_| !rest.is_empty())
+        .map(|action| (action, rest))
+}
+
+/// As git reads it.
+impl fmt::Display for Todo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{} {} {}", line.action, line.oid, line.summary)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo(summaries: &[&str]) -> Todo {
+        Todo {
+            base: None,
+            lines: summaries
+                .iter()
+                .enumerate()
+                .map(|(i, summary)| Line {
+                    action: Action::Pick,
+                    oid: Oid::from_str(&format!("{:040x}", i + 1)).unwrap(),
+                    summary: summary.to_string(),
+                })
+                .collect(),
+            args: vec![],
+        }
+    }
+
+    fn lines(todo: &Todo) -> Vec<String> {
+        todo.lines
+            .iter()
+            .map(|line| format!("{} {}", line.action, line.summary))
+            .collect()
+    }
+
+    #[test]
+    fn autosquash() {
+        let mut todo = todo(&[
+            "add a",
+            "add b",
+            "fixup! add a",
+            "squash! fixup! add a",
+            "fixup! add c",
+        ]);
+        todo.autosquash();
+
+        assert_eq!(
+            lines(&todo),
+            [
+                "pick add a",
+                "fixup fixup! add a",
+                "squash squash! fixup! add a",
+                "pick add b",
+                "pick fixup! add c",
+            ]
+        );
+    }
+
+    #[test]
+    fn move_line() {
+        let mut todo = todo(&["add a", "add b"]);
+        let first = todo.lines[0].oid;
+
+        assert!(!todo.move_line(first, true));
+        assert!(todo.move_line(first, false));
+        assert_eq!(lines(&todo), ["pick add b", "pick add a"]);
+        assert!(!todo.move_line(first, false));
+    }
+}
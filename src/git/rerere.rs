@@ -0,0 +1,96 @@
+//! What `git rerere` does about the ongoing conflicts, read from its `MERGE_RR` and `rr-cache`.
+
+use git2::Repository;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub(crate) struct RerereStatus {
+    pub conflicts: Vec<RerereConflict>,
+    /// Resolutions in the cache, of any conflict ever recorded.
+    pub recorded: usize,
+}
+
+pub(crate) struct RerereConflict {
+    pub path: PathBuf,
+    /// Rerere replayed a previous resolution, rather than waiting to record one.
+    pub resolved: bool,
+}
+
+/// `None` unless rerere is enabled and there are conflicts.
+pub(crate) fn status(repo: &Repository, unmerged_files: &[PathBuf]) -> Option<RerereStatus> {
+    let rr_cache = repo.path().join("rr-cache");
+    let enabled = repo
+        .config()
+        .ok()?
+        .get_bool("rerere.enabled")
+        .unwrap_or_else(|_| rr_cache.is_dir());
+
+    if !enabled || unmerged_files.is_empty() {
+        return None;
+    }
+
+    // Conflicts rerere didn't know yet, entries are `<id>[.<variant>]\t<path>\0`.
+    let merge_rr = fs::read(repo.path().join("MERGE_RR")).unwrap_or_default();
+    let recording = merge_rr
+        .split(|&byte| byte == 0)
+        .filter_map(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            let (_id, path) = entry.split_once('\t')?;
+            Some(PathBuf::from(path))
+        })
+        .collect::<HashSet<_>>();
+
+    let workdir = repo.workdir().expect("No workdir");
+    let conflicts = unmerged_files
+        .iter()
+        .filter_map(|path| {
+            if recording.contains(path) {
+                Some(false)
+            } else if !has_conflict_markers(&workdir.join(path)) {
+                Some(true)
+            } else {
+                // Rerere leaves some alone, like binary files
+                None
+            }
+            .map(|resolved| RerereConflict {
+                path: path.clone(),
+                resolved,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Some(RerereStatus {
+        conflicts,
+        recorded: recorded_resolutions(&rr_cache),
+    })
+}
+
+fn has_conflict_markers(path: &Path) -> bool {
+    fs::read(path).is_ok_and(|content| {
+        content
+            .split(|&byte| byte == b'\n')
+            .any(|line| line.starts_with(b"<<<<<<< "))
+    })
+}
+
+fn recorded_resolutions(rr_cache: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(rr_cache) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| {
+            fs::read_dir(entry.path()).is_ok_and(|mut files| {
+                files.any(|file| {
+                    file.is_ok_and(|file| {
+                        file.file_name().to_string_lossy().starts_with("postimage")
+                    })
+                })
+            })
+        })
+        .count()
+}
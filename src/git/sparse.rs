@@ -0,0 +1,59 @@
+use super::index_flags;
+use crate::Res;
+use git2::Repository;
+use std::{path::Path, process::Command};
+
+/// Which parts of the tree a sparse checkout leaves out of the worktree.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SparseCheckout {
+    /// Patterns are directories in cone mode, gitignore-style lines otherwise.
+    pub cone: bool,
+    pub patterns: Vec<String>,
+    /// Tracked files marked skip-worktree, so not checked out.
+    pub excluded: usize,
+}
+
+/// Asks git rather than libgit2, which doesn't read the `config.worktree`
+/// that `git sparse-checkout` writes its settings to.
+pub(crate) fn status(repo: &Repository) -> Res<Option<SparseCheckout>> {
+    // Only ever created by enabling a sparse checkout, spares running git for most repos
+    if !repo.path().join("info").join("sparse-checkout").exists() {
+        return Ok(None);
+    }
+
+    let dir = repo.workdir().expect("No workdir");
+    let settings = git(
+        dir,
+        &["config", "--bool", "--get-regexp", "^core\\.sparsecheckout"],
+    );
+    let enabled = |key: &str| {
+        settings
+            .lines()
+            .any(|line| line.split_once(' ') == Some((key, "true")))
+    };
+
+    if !enabled("core.sparsecheckout") {
+        return Ok(None);
+    }
+
+    Ok(Some(SparseCheckout {
+        cone: enabled("core.sparsecheckoutcone"),
+        patterns: git(dir, &["sparse-checkout", "list"])
+            .lines()
+            .map(|line| line.to_string())
+            .collect(),
+        excluded: index_flags::flagged_files(repo)?.skip_worktree.len(),
+    }))
+}
+
+/// The output, or nothing if git fails, like listing patterns that were never written.
+fn git(dir: &Path, args: &[&str]) -> String {
+    Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .unwrap_or_default()
+}
@@ -0,0 +1,184 @@
+//! Statistics sizing up a repository, computed with git in the background.
+
+use crate::Res;
+use itertools::Itertools;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::Command,
+    str,
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+};
+
+pub(crate) const TOP_CONTRIBUTORS: usize = 10;
+pub(crate) const LARGEST_FILES: usize = 10;
+pub(crate) const RECENT_MONTHS: usize = 12;
+
+#[derive(Debug, Default)]
+pub(crate) struct RepoStats {
+    pub commits: usize,
+    /// Authors by commits on the current branch, most active first.
+    pub contributors: Vec<(String, usize)>,
+    /// Commits on the current branch per month, oldest first.
+    pub months: Vec<(String, usize)>,
+    /// Files at `HEAD` by size in bytes, largest first.
+    pub largest_files: Vec<(String, u64)>,
+    pub local_branches: usize,
+    pub remote_branches: usize,
+    pub tags: usize,
+}
+
+/// Filled in once the statistics have been computed.
+#[derive(Clone, Default)]
+pub(crate) struct Pending(Arc<Mutex<Option<Result<RepoStats, String>>>>);
+
+impl Pending {
+    pub(crate) fn lock(&self) -> MutexGuard<'_, Option<Result<RepoStats, String>>> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Whether the statistics have been computed, so the screen showing them needs refreshing.
+    pub(crate) fn is_done(&self) -> bool {
+        self.lock().is_some()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn wait(&self) {
+        while !self.is_done() {
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+}
+
+pub(crate) fn compute_in_background(dir: PathBuf) -> Pending {
+    let pending = Pending::default();
+    let result = pending.clone();
+
+    thread::spawn(move || {
+        let stats = compute(&dir).map_err(|err| err.to_string());
+        *result.lock() = Some(stats);
+    });
+
+    pending
+}
+
+fn compute(dir: &Path) -> Res<RepoStats> {
+    let mut stats = RepoStats::default();
+
+    // An unborn branch has no history nor files yet
+    if git(dir, &["rev-parse", "--verify", "--quiet", "HEAD"]).is_ok() {
+        let log = git(
+            dir,
+            &["log", "--format=%aN%x09%ad", "--date=format:%Y-%m", "HEAD"],
+        )?;
+        let mut contributors = HashMap::<&str, usize>::new();
+        let mut months = HashMap::<&str, usize>::new();
+
+        for (author, month) in log.lines().filter_map(|line| line.split_once('\t')) {
+            stats.commits += 1;
+            *contributors.entry(author).or_default() += 1;
+            *months.entry(month).or_default() += 1;
+        }
+
+        stats.contributors = contributors
+            .into_iter()
+            .map(|(author, commits)| (author.to_string(), commits))
+            .sorted_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)))
+            .take(TOP_CONTRIBUTORS)
+            .collect();
+
+        stats.months = months
+            .into_iter()
+            .map(|(month, commits)| (month.to_string(), commits))
+            .sorted()
+            .collect();
+
+        stats.largest_files = git(dir, &["ls-tree", "-r", "-l", "HEAD"])?
+            .lines()
+            .filter_map(parse_ls_tree_line)
+            .sorted_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)))
+            .take(LARGEST_FILES)
+            .collect();
+    }
+
+    for name in git(dir, &["for-each-ref", "--format=%(refname)"])?.lines() {
+        if name.starts_with("refs/heads/") {
+            stats.local_branches += 1;
+        } else if name.starts_with("refs/remotes/") && !name.ends_with("/HEAD") {
+            stats.remote_branches += 1;
+        } else if name.starts_with("refs/tags/") {
+            stats.tags += 1;
+        }
+    }
+
+    Ok(stats)
+}
+
+/// `<mode> blob <hash> <size>\t<path>`, submodules having no size.
+fn parse_ls_tree_line(line: &str) -> Option<(String, u64)> {
+    let (info, path) = line.split_once('\t')?;
+    let size = info.split_whitespace().nth(3)?.parse().ok()?;
+    Some((path.to_string(), size))
+}
+
+fn git(dir: &Path, args: &[&str]) -> Res<String> {
+    let output = Command::new("git").args(args).current_dir(dir).output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "'git {}' failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Like `1.5 MiB`.
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ls_tree_line() {
+        assert_eq!(
+            parse_ls_tree_line(
+                "100644 blob 3b18e512dba79e4c8300dd08aeb37f8e728b8dad      12\tsrc/a b.rs"
+            ),
+            Some(("src/a b.rs".to_string(), 12))
+        );
+        assert_eq!(
+            parse_ls_tree_line(
+                "160000 commit 3b18e512dba79e4c8300dd08aeb37f8e728b8dad       -\tsub"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(format_size(12), "12 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+    }
+}
@@ -1,5 +1,5 @@
 use crate::Res;
-use git2::{DiffOptions, Repository, StatusOptions};
+use git2::{DiffFindOptions, DiffOptions, Repository, StatusOptions};
 
 pub(crate) fn status(repo: &Repository) -> Res<StatusOptions> {
     let mut opts = StatusOptions::new();
@@ -17,5 +17,13 @@ pub(crate) fn status(repo: &Repository) -> Res<StatusOptions> {
 pub(crate) fn diff(_repo: &Repository) -> Res<DiffOptions> {
     let mut diff_options = DiffOptions::new();
     diff_options.patience(true);
+    // Report a file replaced by a symlink (or vice versa) as one delta, not a deletion and an addition
+    diff_options.include_typechange(true);
     Ok(diff_options)
 }
+
+pub(crate) fn find_similar() -> DiffFindOptions {
+    let mut find_options = DiffFindOptions::new();
+    find_options.renames(true);
+    find_options
+}
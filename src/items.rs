@@ -1,7 +1,16 @@
-use crate::config::Config;
+use crate::config::{Config, FileOrder, LogAuthor};
+use crate::forge::ci::{CiStatus, CiStatuses};
+use crate::forge::issues::IssueRef;
+use crate::forge::Forge;
+use crate::git;
+use crate::git::config::ConfigScope;
 use crate::git::diff::Delta;
 use crate::git::diff::Diff;
 use crate::git::diff::Hunk;
+use crate::git::diff::LfsChange;
+use crate::git::diff::LfsFile;
+use crate::git::diff::SubmoduleChange;
+use crate::settings::Settings;
 use crate::Res;
 use git2::Commit;
 use git2::Oid;
@@ -11,8 +20,12 @@ use ratatui::text::Line;
 use ratatui::text::Span;
 use regex::Regex;
 use std::borrow::Cow;
+use std::collections::HashSet;
 use std::iter;
+use std::ops::Range;
+use std::path::Path;
 use std::path::PathBuf;
+use std::process::{Command, Stdio};
 use std::rc::Rc;
 
 #[derive(Default, Clone, Debug)]
@@ -31,49 +44,446 @@ pub(crate) enum TargetData {
     AllStaged,
     AllUnstaged,
     AllUntracked(Vec<PathBuf>),
+    /// A contributor in a shortlog of `range`, by their email.
+    Author {
+        range: String,
+        email: String,
+    },
     Branch(String),
     Commit(String),
+    ConfigEntry {
+        key: &'static str,
+        scope: ConfigScope,
+    },
     Delta(Delta),
+    /// The files changed in a directory of a diff, see [`Settings::file_tree`].
+    Directory(Vec<PathBuf>),
     File(PathBuf),
     Hunk(Rc<Hunk>),
-    HunkLine(Rc<Hunk>, usize),
-    Stash { commit: String, id: usize },
+    IssueReferences(Rc<Vec<IssueRef>>),
+    PullRequest {
+        forge: Forge,
+        number: u64,
+    },
+    /// Lines of a hunk, by their index into its content. Just one, unless a region is selected.
+    HunkLine(Rc<Hunk>, Range<usize>),
+    Stash {
+        commit: String,
+        id: usize,
+    },
+    SubmoduleCommit {
+        path: PathBuf,
+        commit: String,
+    },
 }
 
+/// Directories of a file tree get ids prefixed with `section`, as a screen may show the same
+/// ones in several of its sections.
 pub(crate) fn create_diff_items<'a>(
     config: Rc<Config>,
+    settings: &Settings,
+    section: &str,
     diff: &'a Diff,
     depth: &'a usize,
     default_collapsed: bool,
 ) -> impl Iterator<Item = Item> + 'a {
-    diff.deltas.iter().flat_map(move |delta| {
-        let target_data = TargetData::Delta(delta.clone());
-        let config = Rc::clone(&config);
+    let mut deltas = filtered_deltas(diff, settings.file_filter.as_ref());
 
-        iter::once(Item {
-            id: delta.file_header.to_string().into(),
+    let items = if settings.file_tree {
+        // Sorting by path keeps the files of a directory together
+        deltas.sort_by(|a, b| a.new_file.cmp(&b.new_file));
+
+        let tree = Tree {
+            config: &config,
+            section,
+            default_collapsed,
+        };
+        let mut items = vec![];
+        create_tree_items(&tree, &deltas, Path::new(""), *depth, "", &mut items);
+        items
+    } else {
+        match settings.file_order {
+            // As listed by git
+            FileOrder::Path => (),
+            FileOrder::Size => deltas.sort_by_key(|delta| std::cmp::Reverse(changed_lines(delta))),
+            FileOrder::Status => deltas.sort_by_key(|delta| format!("{:?}", delta.status)),
+        }
+
+        deltas
+            .into_iter()
+            .flat_map(|delta| {
+                create_delta_items(
+                    &config,
+                    delta,
+                    "",
+                    delta_path_display(delta),
+                    *depth,
+                    default_collapsed,
+                )
+            })
+            .collect()
+    };
+
+    items.into_iter()
+}
+
+/// A single file of a diff, expanded.
+pub(crate) fn create_single_delta_items(config: &Rc<Config>, delta: &Delta) -> Vec<Item> {
+    create_delta_items(config, delta, "", delta_path_display(delta), 0, false)
+}
+
+/// A glob like `*.rs` or `src/**/test_*`, or else a case-insensitive substring of the path.
+/// Globs without a `/` are matched against file names, like in `.gitignore`.
+#[derive(Clone, Debug)]
+pub(crate) enum FileFilter {
+    Glob { regex: Regex, file_name: bool },
+    Substring(String),
+}
+
+impl FileFilter {
+    pub(crate) fn new(pattern: &str) -> Res<Self> {
+        if !pattern.contains(['*', '?', '[']) {
+            return Ok(Self::Substring(pattern.to_lowercase()));
+        }
+
+        let mut regex = String::from("^");
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    regex.push_str(".*");
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                '[' if chars.peek() == Some(&'!') => {
+                    chars.next();
+                    regex.push_str("[^");
+                }
+                '[' => regex.push('['),
+                ']' => regex.push(']'),
+                _ => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        regex.push('$');
+
+        Ok(Self::Glob {
+            regex: Regex::new(&regex)?,
+            file_name: !pattern.contains('/'),
+        })
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        match self {
+            Self::Glob { regex, file_name } => {
+                let matched = if *file_name {
+                    path.file_name().unwrap_or_default()
+                } else {
+                    path.as_os_str()
+                };
+                regex.is_match(&matched.to_string_lossy())
+            }
+            Self::Substring(substring) => path
+                .to_string_lossy()
+                .to_lowercase()
+                .contains(substring.as_str()),
+        }
+    }
+}
+
+/// The deltas of `diff` matching `filter`, see [`Settings::file_filter`].
+pub(crate) fn filtered_deltas<'a>(diff: &'a Diff, filter: Option<&FileFilter>) -> Vec<&'a Delta> {
+    diff.deltas
+        .iter()
+        .filter(|delta| {
+            filter.is_none_or(|filter| {
+                filter.matches(&delta.old_file) || filter.matches(&delta.new_file)
+            })
+        })
+        .collect()
+}
+
+fn changed_lines(delta: &Delta) -> usize {
+    delta
+        .hunks
+        .iter()
+        .flat_map(|hunk| &hunk.content.lines)
+        .filter(|line| {
+            line.spans
+                .first()
+                .is_some_and(|span| span.content.starts_with(['+', '-']))
+        })
+        .count()
+}
+
+/// What's the same for all items of a file tree.
+struct Tree<'a> {
+    config: &'a Rc<Config>,
+    section: &'a str,
+    default_collapsed: bool,
+}
+
+/// Directories of `deltas` below `dir` as sections, those with a single subdirectory shown as one.
+fn create_tree_items(
+    tree: &Tree,
+    deltas: &[&Delta],
+    dir: &Path,
+    depth: usize,
+    indent: &str,
+    items: &mut Vec<Item>,
+) {
+    let mut i = 0;
+    while i < deltas.len() {
+        let delta = deltas[i];
+        let Some(mut subdir) = child_dir(dir, &delta.new_file) else {
+            let display = if delta.old_file == delta.new_file {
+                let name = delta.new_file.strip_prefix(dir).unwrap_or(&delta.new_file);
+                match delta_mode_display(delta) {
+                    Some(mode) => format!("{} ({})", name.to_string_lossy(), mode),
+                    None => name.to_string_lossy().to_string(),
+                }
+            } else {
+                delta_path_display(delta)
+            };
+
+            items.extend(create_delta_items(
+                tree.config,
+                delta,
+                indent,
+                display,
+                depth,
+                tree.default_collapsed,
+            ));
+            i += 1;
+            continue;
+        };
+
+        let len = deltas[i..]
+            .iter()
+            .take_while(|delta| delta.new_file.starts_with(&subdir))
+            .count();
+        let group = &deltas[i..i + len];
+
+        while let Some(deeper) = child_dir(&subdir, &group[0].new_file) {
+            if !group
+                .iter()
+                .all(|delta| delta.new_file.starts_with(&deeper))
+            {
+                break;
+            }
+            subdir = deeper;
+        }
+
+        let files = group
+            .iter()
+            .flat_map(|delta| match delta.status {
+                git2::Delta::Renamed => vec![delta.old_file.clone(), delta.new_file.clone()],
+                _ => vec![delta.new_file.clone()],
+            })
+            .collect();
+
+        items.push(Item {
+            id: format!("{}_{}/", tree.section, subdir.to_string_lossy()).into(),
             display: Line::styled(
                 format!(
-                    "{}   {}",
-                    format!("{:?}", delta.status).to_lowercase(),
-                    delta.new_file.to_string_lossy()
+                    "{}{}/",
+                    indent,
+                    subdir.strip_prefix(dir).unwrap().to_string_lossy()
                 ),
-                &config.style.file_header,
+                &tree.config.style.file_header,
             ),
             section: true,
-            default_collapsed,
-            depth: *depth,
-            target_data: Some(target_data),
+            depth,
+            target_data: Some(TargetData::Directory(files)),
             ..Default::default()
-        })
+        });
+
+        create_tree_items(
+            tree,
+            group,
+            &subdir,
+            depth + 1,
+            &format!("{}  ", indent),
+            items,
+        );
+        i += len;
+    }
+}
+
+/// The directory directly inside `dir` that contains `path`, if it isn't a file of `dir`.
+fn child_dir(dir: &Path, path: &Path) -> Option<PathBuf> {
+    let mut components = path.strip_prefix(dir).ok()?.components();
+    let first = components.next()?;
+    components.next()?;
+    Some(dir.join(first))
+}
+
+fn create_delta_items(
+    config: &Rc<Config>,
+    delta: &Delta,
+    indent: &str,
+    display: String,
+    depth: usize,
+    default_collapsed: bool,
+) -> Vec<Item> {
+    let target_data = TargetData::Delta(delta.clone());
+    let special_items = delta
+        .submodule
+        .iter()
+        .flat_map(|submodule| create_submodule_items(config, submodule, depth + 1))
         .chain(
             delta
-                .hunks
+                .lfs
                 .iter()
-                .cloned()
-                .flat_map(move |hunk| create_hunk_items(Rc::clone(&config), hunk, *depth + 1)),
-        )
+                .flat_map(|lfs| create_lfs_items(config, lfs, depth + 1)),
+        );
+
+    iter::once(Item {
+        id: delta.file_header.to_string().into(),
+        display: Line::styled(
+            format!(
+                "{}{}   {}",
+                indent,
+                format!("{:?}", delta.status).to_lowercase(),
+                display
+            ),
+            &config.style.file_header,
+        ),
+        section: true,
+        default_collapsed,
+        depth,
+        target_data: Some(target_data),
+        ..Default::default()
+    })
+    .chain(special_items)
+    .chain(
+        delta
+            .hunks
+            .iter()
+            .cloned()
+            .flat_map(|hunk| create_hunk_items(Rc::clone(config), hunk, depth + 1)),
+    )
+    .collect()
+}
+
+fn create_submodule_items(config: &Config, submodule: &SubmoduleChange, depth: usize) -> Vec<Item> {
+    let style = &config.style;
+
+    [
+        ("-", &style.diff_highlight.tag_old, &submodule.old),
+        ("+", &style.diff_highlight.tag_new, &submodule.new),
+    ]
+    .into_iter()
+    .filter_map(|(prefix, prefix_style, commit)| {
+        let commit = commit.as_ref()?;
+
+        Some(Item {
+            id: format!("submodule_{}_{}", prefix, commit.hash).into(),
+            display: Line::from(vec![
+                Span::styled(prefix, prefix_style),
+                Span::styled(commit.short_id.clone(), &style.hash),
+                Span::raw(" "),
+                Span::raw(commit.summary.clone().unwrap_or_default()),
+            ]),
+            depth,
+            target_data: Some(TargetData::SubmoduleCommit {
+                path: submodule.path.clone(),
+                commit: commit.hash.clone(),
+            }),
+            ..Default::default()
+        })
+    })
+    .collect()
+}
+
+fn create_lfs_items(config: &Config, lfs: &LfsChange, depth: usize) -> Vec<Item> {
+    let style = &config.style;
+
+    [
+        ("-", &style.diff_highlight.tag_old, &lfs.old),
+        ("+", &style.diff_highlight.tag_new, &lfs.new),
+    ]
+    .into_iter()
+    .filter_map(|(prefix, prefix_style, file)| {
+        let description = match file.as_ref()? {
+            LfsFile::Pointer(pointer) => {
+                format!("LFS object {}, {} bytes", &pointer.oid[..8], pointer.size)
+            }
+            LfsFile::NotPulled(pointer) => format!(
+                "LFS object {}, {} bytes (not pulled)",
+                &pointer.oid[..8],
+                pointer.size
+            ),
+            LfsFile::Content { size } => format!("{} bytes, not stored in LFS yet", size),
+        };
+
+        Some(Item {
+            id: format!("lfs_{}_{}", prefix, description).into(),
+            display: Line::from(vec![
+                Span::styled(prefix, prefix_style),
+                Span::raw(description),
+            ]),
+            unselectable: true,
+            depth,
+            ..Default::default()
+        })
     })
+    .collect()
+}
+
+fn delta_path_display(delta: &Delta) -> String {
+    let path = if delta.old_file == delta.new_file {
+        delta.new_file.to_string_lossy().to_string()
+    } else {
+        format!(
+            "{} → {}",
+            delta.old_file.to_string_lossy(),
+            delta.new_file.to_string_lossy()
+        )
+    };
+
+    match delta_mode_display(delta) {
+        Some(mode) => format!("{} ({})", path, mode),
+        None => path,
+    }
+}
+
+/// Describes symlinks, LFS files and changes of file mode, which don't show up in the hunks.
+fn delta_mode_display(delta: &Delta) -> Option<String> {
+    let is_link = |mode| mode == git2::FileMode::Link;
+
+    match delta.status {
+        _ if delta.lfs.is_some() => Some("lfs".into()),
+        git2::Delta::Added if is_link(delta.new_mode) => Some("symlink".into()),
+        git2::Delta::Deleted if is_link(delta.old_mode) => Some("symlink".into()),
+        git2::Delta::Added
+        | git2::Delta::Deleted
+        | git2::Delta::Untracked
+        | git2::Delta::Conflicted => None,
+        _ if delta.submodule.is_some() && delta.old_mode == delta.new_mode => {
+            Some("submodule".into())
+        }
+        git2::Delta::Typechange => Some(format!(
+            "{} → {}",
+            file_kind(delta.old_mode),
+            file_kind(delta.new_mode)
+        )),
+        _ if delta.old_mode != delta.new_mode => Some(format!(
+            "{:o} → {:o}",
+            u32::from(delta.old_mode),
+            u32::from(delta.new_mode)
+        )),
+        _ if is_link(delta.new_mode) => Some("symlink".into()),
+        _ => None,
+    }
+}
+
+fn file_kind(mode: git2::FileMode) -> &'static str {
+    match mode {
+        git2::FileMode::Link => "symlink",
+        git2::FileMode::Commit => "submodule",
+        _ => "file",
+    }
 }
 
 fn create_hunk_items(
@@ -106,12 +516,31 @@ fn format_diff_hunk_items(depth: usize, hunk: Rc<Hunk>) -> Vec<Item> {
                 .first()
                 .is_some_and(|s| s.content.starts_with(' ')),
             depth,
-            target_data: Some(TargetData::HunkLine(Rc::clone(&hunk), i)),
+            target_data: Some(TargetData::HunkLine(Rc::clone(&hunk), i..i + 1)),
             ..Default::default()
         })
         .collect()
 }
 
+/// A glyph of the commit's CI status, if enabled and known.
+pub(crate) fn ci_status_span(
+    config: &Config,
+    ci_statuses: &CiStatuses,
+    repo: &Repository,
+    oid: Oid,
+) -> Option<Span<'static>> {
+    if !config.general.ci_status.enabled {
+        return None;
+    }
+
+    let style = &config.style;
+    Some(match ci_statuses.status(repo, oid)? {
+        CiStatus::Pass => Span::styled("✓", &style.ci_pass),
+        CiStatus::Fail => Span::styled("✗", &style.ci_fail),
+        CiStatus::Pending => Span::styled("●", &style.ci_pending),
+    })
+}
+
 pub(crate) fn stash_list(config: &Config, repo: &Repository, limit: usize) -> Res<Vec<Item>> {
     let style = &config.style;
 
@@ -153,14 +582,65 @@ pub(crate) fn stash_list(config: &Config, repo: &Repository, limit: usize) -> Re
         .collect::<Vec<_>>())
 }
 
+/// Which commits a log leaves out.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LogFilter {
+    pub msg_regex: Option<Regex>,
+    /// An author's email, after applying the repository's `.mailmap`.
+    pub author: Option<String>,
+    /// Commits reachable from this one, like `git log hidden..rev`.
+    pub hidden: Option<Oid>,
+    /// Only commits changing these lines, like `git log -L`.
+    pub lines: Option<LineRange>,
+}
+
+/// Lines of a file as of the revision a log starts from, counting from 1.
+#[derive(Clone, Debug)]
+pub(crate) struct LineRange {
+    pub file: PathBuf,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl LineRange {
+    /// The commits git follows the lines through, which `git2` can't do.
+    fn commits(&self, repo: &Repository, rev: Option<Oid>) -> Res<HashSet<Oid>> {
+        let output = Command::new("git")
+            .arg("log")
+            .arg(format!(
+                "-L{},{}:{}",
+                self.start,
+                self.end,
+                self.file.to_string_lossy()
+            ))
+            .args(["--format=%H", "--no-patch"])
+            .arg(rev.map(|rev| rev.to_string()).unwrap_or("HEAD".to_string()))
+            .current_dir(repo.workdir().expect("No workdir"))
+            .stdin(Stdio::null())
+            .output()?;
+
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr)
+                .trim()
+                .to_string()
+                .into());
+        }
+
+        String::from_utf8(output.stdout)?
+            .lines()
+            .map(|line| Ok(Oid::from_str(line)?))
+            .collect()
+    }
+}
+
 pub(crate) fn log(
     config: &Config,
+    ci_statuses: &CiStatuses,
     repo: &Repository,
     limit: usize,
     rev: Option<Oid>,
-    msg_regex: Option<Regex>,
+    filter: &LogFilter,
 ) -> Res<Vec<Item>> {
-    let style = &config.style;
     let mut revwalk = repo.revwalk()?;
     if let Some(r) = rev {
         revwalk.push(r)?;
@@ -168,9 +648,70 @@ pub(crate) fn log(
         return Ok(vec![]);
     }
 
+    if let Some(hidden) = filter.hidden {
+        revwalk.hide(hidden)?;
+    }
+
+    let touching = filter
+        .lines
+        .as_ref()
+        .map(|lines| lines.commits(repo, rev))
+        .transpose()?;
+    let oids = revwalk.filter(|oid| match (&touching, oid) {
+        (Some(touching), Ok(oid)) => touching.contains(oid),
+        _ => true,
+    });
+
+    let items = revwalk_items(config, ci_statuses, repo, oids, limit, filter)?;
+
+    if items.is_empty() {
+        Ok(vec![Item {
+            display: Line::raw("No commits found"),
+            ..Default::default()
+        }])
+    } else {
+        Ok(items)
+    }
+}
+
+/// Commits reachable from `to` but not from `hidden`, like `git log hidden..to`.
+pub(crate) fn log_range(
+    config: &Config,
+    ci_statuses: &CiStatuses,
+    repo: &Repository,
+    hidden: Oid,
+    to: Oid,
+    limit: usize,
+) -> Res<Vec<Item>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(to)?;
+    revwalk.hide(hidden)?;
+
+    revwalk_items(
+        config,
+        ci_statuses,
+        repo,
+        revwalk,
+        limit,
+        &LogFilter::default(),
+    )
+}
+
+fn revwalk_items(
+    config: &Config,
+    ci_statuses: &CiStatuses,
+    repo: &Repository,
+    revwalk: impl Iterator<Item = Result<Oid, git2::Error>>,
+    limit: usize,
+    filter: &LogFilter,
+) -> Res<Vec<Item>> {
+    let style = &config.style;
+    let columns = &config.general.log;
+    let mailmap = filter.author.as_ref().map(|_| repo.mailmap()).transpose()?;
     let references = repo
         .references()?
         .filter_map(Result::ok)
+        .filter(|_| columns.refs.enabled)
         .filter_map(
             |reference| match (reference.peel_to_commit(), reference.shorthand()) {
                 (Ok(target), Some(name)) => {
@@ -198,27 +739,40 @@ pub(crate) fn log(
         .map(|oid_result| -> Res<Option<Item>> {
             let oid = oid_result?;
             let commit = repo.find_commit(oid)?;
-            let short_id = commit.as_object().short_id()?.as_str().unwrap().to_string();
+            let short_id = match columns.hash_length {
+                0 => commit.as_object().short_id()?.as_str().unwrap().to_string(),
+                length => git::hash_of_length(repo, oid, length)?,
+            };
+
+            if let Some(re) = &filter.msg_regex {
+                if !re.is_match(commit.message().unwrap_or("")) {
+                    return Ok(None);
+                }
+            }
+
+            if let (Some(author), Some(mailmap)) = (&filter.author, &mailmap) {
+                if commit.author_with_mailmap(mailmap)?.email() != Some(author.as_str()) {
+                    return Ok(None);
+                }
+            }
 
             let spans = itertools::intersperse(
                 iter::once(Span::styled(short_id, &style.hash))
+                    .chain(ci_status_span(config, ci_statuses, repo, oid))
+                    .chain(author_span(config, &commit.author()))
                     .chain(
                         references
                             .iter()
                             .filter(|(commit, _)| commit.id() == oid)
                             .map(|(_, name)| name.clone()),
                     )
-                    .chain([commit.summary().unwrap_or("").to_string().into()]),
+                    .chain([
+                        truncate(commit.summary().unwrap_or(""), columns.subject_width).into(),
+                    ]),
                 Span::raw(" "),
             )
             .collect::<Vec<_>>();
 
-            if let Some(re) = &msg_regex {
-                if !re.is_match(commit.message().unwrap_or("")) {
-                    return Ok(None);
-                }
-            }
-
             Ok(Some(Item {
                 id: oid.to_string().into(),
                 display: Line::from(spans),
@@ -238,13 +792,34 @@ pub(crate) fn log(
         .take(limit)
         .collect();
 
-    if items.is_empty() {
-        Ok(vec![Item {
-            display: Line::raw("No commits found"),
-            ..Default::default()
-        }])
+    Ok(items)
+}
+
+fn author_span(config: &Config, author: &git2::Signature) -> Option<Span<'static>> {
+    let name = author.name().unwrap_or("");
+    let author = match config.general.log.author {
+        LogAuthor::None => return None,
+        LogAuthor::Name => name.to_string(),
+        LogAuthor::Email => author.email().unwrap_or("").to_string(),
+        LogAuthor::Initials => name
+            .split_whitespace()
+            .filter_map(|word| word.chars().next())
+            .flat_map(char::to_uppercase)
+            .collect(),
+    };
+
+    Some(Span::styled(author, &config.style.author))
+}
+
+/// Cut off with "…" beyond `width` characters, unless it's 0.
+fn truncate(text: &str, width: usize) -> String {
+    if width == 0 || text.chars().count() <= width {
+        text.to_string()
     } else {
-        Ok(items)
+        text.chars()
+            .take(width.saturating_sub(1))
+            .chain(['…'])
+            .collect()
     }
 }
 
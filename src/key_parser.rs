@@ -15,6 +15,55 @@ pub(crate) fn parse_keys(input: &str) -> IResult<&str, Vec<(KeyModifiers, KeyCod
     all_consuming(many0(parse_key))(input)
 }
 
+/// Spells a key like bindings do, see [`parse_keys`].
+pub(crate) fn format_key(&(mods, code): &(KeyModifiers, KeyCode)) -> String {
+    let name = match code {
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Backspace => "backspace".to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Left => "left".to_string(),
+        KeyCode::Right => "right".to_string(),
+        KeyCode::Up => "up".to_string(),
+        KeyCode::Down => "down".to_string(),
+        KeyCode::Home => "home".to_string(),
+        KeyCode::End => "end".to_string(),
+        KeyCode::PageUp => "pageup".to_string(),
+        KeyCode::PageDown => "pagedown".to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::BackTab => "backtab".to_string(),
+        KeyCode::Delete => "delete".to_string(),
+        KeyCode::Insert => "insert".to_string(),
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::CapsLock => "capslock".to_string(),
+        other => format!("{:?}", other).to_lowercase(),
+    };
+
+    // Upper case characters are typed with shift anyway
+    let implied = match code {
+        KeyCode::Char(c) if c.is_uppercase() => KeyModifiers::SHIFT,
+        _ => KeyModifiers::NONE,
+    };
+
+    let mod_names = [
+        (KeyModifiers::SHIFT, "shift"),
+        (KeyModifiers::CONTROL, "ctrl"),
+        (KeyModifiers::ALT, "alt"),
+        (KeyModifiers::SUPER, "super"),
+        (KeyModifiers::HYPER, "hyper"),
+        (KeyModifiers::META, "meta"),
+    ]
+    .into_iter()
+    .filter(|&(modifier, _)| mods.difference(implied).contains(modifier))
+    .map(|(_, mod_name)| mod_name)
+    .collect::<Vec<_>>();
+
+    if mod_names.is_empty() && matches!(code, KeyCode::Char(_)) {
+        name
+    } else {
+        format!("<{}>", [mod_names, vec![&name]].concat().join("+"))
+    }
+}
+
 fn parse_key(input: &str) -> IResult<&str, (KeyModifiers, KeyCode)> {
     alt((parse_quoted, parse_char_key))(input)
 }
@@ -147,4 +196,19 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn format_roundtrips() {
+        for keys in [
+            "a",
+            "A",
+            "<backspace>",
+            "<ctrl+j>",
+            "<shift+ctrl+alt+k>",
+            "<alt+enter>",
+        ] {
+            let (_, parsed) = parse_keys(keys).unwrap();
+            assert_eq!(format_key(&parsed[0]), keys);
+        }
+    }
 }
@@ -1,15 +1,23 @@
+mod ansi;
 mod bindings;
 pub mod cli;
 mod cmd_log;
 pub mod config;
+pub mod credentials;
+mod dates;
+mod forge;
 mod git;
 mod git2_opts;
 mod items;
 mod key_parser;
 mod menu;
+mod notify;
 mod ops;
+mod pipes;
+mod print;
 mod prompt;
 mod screen;
+mod settings;
 pub mod state;
 mod syntax_highlight;
 pub mod term;
@@ -18,10 +26,10 @@ mod tests;
 mod ui;
 
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventState, KeyModifiers};
-use git2::Repository;
+use git2::{Repository, RepositoryOpenFlags};
 use items::Item;
 use ops::Action;
-use std::{error::Error, path::PathBuf, process::Command, rc::Rc, time::Duration};
+use std::{error::Error, ffi::OsStr, path::Path, process::Command, rc::Rc, time::Duration};
 use term::Term;
 
 //                                An overview of Gitu's ui and terminology:
@@ -64,20 +72,7 @@ use term::Term;
 pub type Res<T> = Result<T, Box<dyn Error>>;
 
 pub fn run(args: &cli::Args, term: &mut Term) -> Res<()> {
-    log::debug!("Finding git dir");
-    let dir = PathBuf::from(
-        String::from_utf8(
-            Command::new("git")
-                .args(["rev-parse", "--show-toplevel"])
-                .output()?
-                .stdout,
-        )?
-        .trim_end(),
-    );
-
-    log::debug!("Opening repo");
-    let repo = Repository::open_from_env()?;
-    repo.set_workdir(&dir, false)?;
+    let repo = open_repo()?;
 
     log::debug!("Initializing config");
     let config = config::init_config()?;
@@ -112,6 +107,110 @@ pub fn run(args: &cli::Args, term: &mut Term) -> Res<()> {
     Ok(())
 }
 
+/// Runs `gitu init` and `gitu clone`, then enters the repository they made for the TUI to open.
+pub fn create_repo(args: &cli::Args) -> Res<()> {
+    let mut cmd = Command::new("git");
+    let dir = match &args.command {
+        Some(cli::Commands::Init { dir }) => {
+            let dir = dir.clone().unwrap_or_else(|| ".".into());
+            cmd.arg("init").arg(&dir);
+            dir
+        }
+        Some(cli::Commands::Clone { url, dir }) => {
+            let dir = dir.clone().unwrap_or_else(|| git::clone_dir(url).into());
+            cmd.args(["clone", "--progress", url]).arg(&dir);
+            dir
+        }
+        _ => return Ok(()),
+    };
+
+    // Progress is shown straight on the terminal
+    let status = cmd.status()?;
+    if !status.success() {
+        return Err(format!("'{}' failed", cmd_log::command_args(&cmd)).into());
+    }
+
+    std::env::set_current_dir(dir)?;
+    Ok(())
+}
+
+/// Catches invalid revisions and paths before the terminal is taken over.
+pub fn validate_args(args: &cli::Args) -> Res<()> {
+    args.validate(&open_repo()?)
+}
+
+/// Runs subcommands that print to stdout rather than starting the TUI.
+/// Returns `false` if there was no such subcommand to run.
+pub fn run_non_interactive(args: &cli::Args, out: &mut impl std::io::Write) -> Res<bool> {
+    let Some(command) = &args.command else {
+        return Ok(false);
+    };
+
+    if matches!(
+        command,
+        cli::Commands::Show { .. }
+            | cli::Commands::Log { .. }
+            | cli::Commands::Init { .. }
+            | cli::Commands::Clone { .. }
+    ) {
+        return Ok(false);
+    }
+
+    let repo = open_repo()?;
+    let config = config::init_config()?;
+    print::print_command(Rc::new(config), Rc::new(repo), command, out)
+}
+
+fn open_repo() -> Res<Repository> {
+    open_repo_in(&std::env::current_dir()?)
+}
+
+/// Explains what's wrong when `dir` isn't in a repository gitu can work with.
+fn open_repo_in(dir: &Path) -> Res<Repository> {
+    log::debug!("Opening repo");
+    let repo = match Repository::open_ext(dir, RepositoryOpenFlags::FROM_ENV, &[] as &[&OsStr]) {
+        Ok(repo) => repo,
+        Err(error) if error.code() == git2::ErrorCode::NotFound => {
+            return Err(format!(
+                "{} isn't in a git repository.\n\
+                 Run `gitu init` to create one there, or start gitu from within a repository.",
+                dir.display()
+            )
+            .into());
+        }
+        Err(error) => return Err(error.into()),
+    };
+
+    if repo.is_bare() {
+        return Err(format!(
+            "{} is a bare repository, which has no worktree for gitu to show.\n\
+             Start gitu from within a worktree of it, see `git worktree add`.",
+            repo.path().display()
+        )
+        .into());
+    }
+
+    log::debug!("Finding worktree");
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(dir)
+        .output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "Couldn't find the worktree of {}: {}",
+            repo.path().display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    repo.set_workdir(
+        Path::new(String::from_utf8(output.stdout)?.trim_end()),
+        false,
+    )?;
+    Ok(repo)
+}
+
 fn handle_initial_send_keys(
     keys: &[(KeyModifiers, KeyCode)],
     state: &mut state::State,
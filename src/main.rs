@@ -2,9 +2,15 @@ use clap::Parser;
 use gitu::{cli::Args, term, Res};
 use log::LevelFilter;
 use ratatui::Terminal;
-use std::{backtrace::Backtrace, panic};
+use std::{backtrace::Backtrace, io, panic};
 
 pub fn main() -> Res<()> {
+    if let Some(answer) = gitu::credentials::askpass_answer() {
+        println!("{}", answer);
+        return Ok(());
+    }
+    gitu::credentials::remove_askpass_env();
+
     let args = Args::parse();
 
     if args.version {
@@ -14,11 +20,27 @@ pub fn main() -> Res<()> {
         return Ok(());
     }
 
+    if args.print_config {
+        print!("{}", gitu::config::print_config()?);
+        return Ok(());
+    }
+
+    // Like not being in a repository, explained better than a debug print would
+    if let Err(error) = gitu::create_repo(&args).and_then(|_| gitu::validate_args(&args)) {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+
+    if gitu::run_non_interactive(&args, &mut io::stdout().lock())? {
+        return Ok(());
+    }
+
     if args.log {
         simple_logging::log_to_file("gitu.log", LevelFilter::Trace)?;
     }
 
     panic::set_hook(Box::new(|panic_info| {
+        term::cleanup_focus_changes();
         term::cleanup_alternate_screen();
         term::cleanup_raw_mode();
 
@@ -29,7 +51,9 @@ pub fn main() -> Res<()> {
     if args.print {
         setup_term_and_run(&args)?;
     } else {
-        term::alternate_screen(|| term::raw_mode(|| setup_term_and_run(&args)))?
+        term::alternate_screen(|| {
+            term::raw_mode(|| term::focus_changes(|| setup_term_and_run(&args)))
+        })?
     }
 
     Ok(())
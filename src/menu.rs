@@ -13,34 +13,60 @@ pub(crate) mod arg;
 pub(crate) enum Menu {
     #[serde(rename = "root")]
     Root,
+    #[serde(rename = "am_menu")]
+    Am,
     #[serde(rename = "branch_menu")]
     Branch,
+    /// Not opened like the others, the keys of the branch cleanup screen.
+    #[serde(rename = "branch_cleanup")]
+    BranchCleanup,
+    #[serde(rename = "cherry_pick_menu")]
+    CherryPick,
     #[serde(rename = "commit_menu")]
     Commit,
+    #[serde(rename = "diff_menu")]
+    Diff,
     #[serde(rename = "fetch_menu")]
     Fetch,
+    #[serde(rename = "forge_menu")]
+    Forge,
     #[serde(rename = "help_menu")]
     Help,
+    #[serde(rename = "index_flags_menu")]
+    IndexFlags,
+    #[serde(rename = "layout_menu")]
+    Layout,
     #[serde(rename = "log_menu")]
     Log,
+    #[serde(rename = "notes_menu")]
+    Notes,
+    #[serde(rename = "patch_menu")]
+    Patch,
     #[serde(rename = "pull_menu")]
     Pull,
     #[serde(rename = "push_menu")]
     Push,
     #[serde(rename = "rebase_menu")]
     Rebase,
+    /// Not opened like the others, the keys of the rebase todo screen.
+    #[serde(rename = "rebase_todo")]
+    RebaseTodo,
     #[serde(rename = "reset_menu")]
     Reset,
     #[serde(rename = "revert_menu")]
     Revert,
+    #[serde(rename = "sparse_checkout_menu")]
+    SparseCheckout,
     #[serde(rename = "stash_menu")]
     Stash,
 }
 
+pub(crate) type MenuArgs = BTreeMap<Cow<'static, str>, arg::Arg>;
+
 pub(crate) struct PendingMenu {
     pub menu: Menu,
     pub is_hidden: bool,
-    pub(crate) args: BTreeMap<Cow<'static, str>, arg::Arg>,
+    pub(crate) args: MenuArgs,
 }
 
 impl PendingMenu {
@@ -50,16 +76,27 @@ impl PendingMenu {
             is_hidden: false,
             args: match menu {
                 Menu::Root => vec![],
+                Menu::Am => ops::am::init_args(),
                 Menu::Branch => ops::checkout::init_args(),
+                Menu::BranchCleanup => vec![],
+                Menu::CherryPick => ops::cherry_pick::init_args(),
                 Menu::Commit => ops::commit::init_args(),
+                Menu::Diff => ops::diff::init_args(),
                 Menu::Fetch => ops::fetch::init_args(),
+                Menu::Forge => vec![],
                 Menu::Help => vec![],
+                Menu::IndexFlags => vec![],
+                Menu::Layout => vec![],
                 Menu::Log => ops::log::init_args(),
+                Menu::Notes => vec![],
+                Menu::Patch => ops::patch::init_args(),
                 Menu::Pull => ops::pull::init_args(),
                 Menu::Push => ops::push::init_args(),
                 Menu::Rebase => ops::rebase::init_args(),
+                Menu::RebaseTodo => vec![],
                 Menu::Reset => ops::reset::init_args(),
                 Menu::Revert => ops::revert::init_args(),
+                Menu::SparseCheckout => vec![],
                 Menu::Stash => ops::stash::init_args(),
             }
             .into_iter()
@@ -72,7 +109,8 @@ impl PendingMenu {
         self.args
             .iter()
             .filter(|&(_k, arg)| arg.is_active())
-            .map(|(_, v)| v.get_cli_token().into())
+            .flat_map(|(_, v)| v.get_cli_tokens())
+            .map(OsString::from)
             .collect()
     }
 }
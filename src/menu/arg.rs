@@ -1,10 +1,14 @@
 use crate::Res;
+use git2::Repository;
 use regex::Regex;
 
+pub(crate) type CompletionsFn = fn(&Repository) -> Res<Vec<String>>;
+
 #[derive(Debug)]
 pub(crate) struct Arg {
     pub arg: &'static str,
     pub display: &'static str,
+    pub completions: Option<CompletionsFn>,
     value: Box<dyn ArgValue>,
 }
 
@@ -13,6 +17,7 @@ impl Arg {
         Arg {
             arg,
             display,
+            completions: None,
             value: Box::new(ArgBool { value: default }),
         }
     }
@@ -29,6 +34,7 @@ impl Arg {
         Arg {
             arg,
             display,
+            completions: None,
             value: Box::new(ArgT::<T> {
                 value: default.map(|fun| fun()),
                 default,
@@ -37,6 +43,22 @@ impl Arg {
         }
     }
 
+    /// An arg that's passed once per value, entered as a TOML array of strings.
+    pub fn new_list(arg: &'static str, display: &'static str) -> Self {
+        Arg {
+            arg,
+            display,
+            completions: None,
+            value: Box::new(ArgList { values: vec![] }),
+        }
+    }
+
+    /// Candidates offered for tab-completion when prompting for the value.
+    pub fn with_completions(mut self, completions: CompletionsFn) -> Self {
+        self.completions = Some(completions);
+        self
+    }
+
     pub fn is_active(&self) -> bool {
         self.value.is_set()
     }
@@ -74,6 +96,16 @@ impl Arg {
             None => self.arg.to_string(),
         }
     }
+
+    pub fn get_cli_tokens(&self) -> Vec<String> {
+        match self.value.cli_values() {
+            values if values.is_empty() => vec![self.arg.to_string()],
+            values => values
+                .into_iter()
+                .map(|value| format!("{}={}", self.arg, value))
+                .collect(),
+        }
+    }
 }
 
 trait ArgValue: std::fmt::Debug {
@@ -84,6 +116,10 @@ trait ArgValue: std::fmt::Debug {
     fn set(&mut self, value: &str) -> Res<()>;
     fn value_as_string(&self) -> Option<String>;
     fn value_as_any(&self) -> Option<&dyn std::any::Any>;
+
+    fn cli_values(&self) -> Vec<String> {
+        self.value_as_string().into_iter().collect()
+    }
 }
 
 #[derive(Debug)]
@@ -163,6 +199,59 @@ where
     }
 }
 
+#[derive(Debug)]
+struct ArgList {
+    values: Vec<String>,
+}
+
+impl ArgValue for ArgList {
+    fn is_set(&self) -> bool {
+        !self.values.is_empty()
+    }
+
+    fn unset(&mut self) {
+        self.values.clear();
+    }
+
+    fn expects_value(&self) -> bool {
+        true
+    }
+
+    fn default_as_string(&self) -> Option<String> {
+        None
+    }
+
+    fn set(&mut self, value: &str) -> Res<()> {
+        #[derive(serde::Deserialize)]
+        struct List {
+            list: Vec<String>,
+        }
+
+        let List { list: values } = toml::from_str(&format!("list = {value}"))
+            .map_err(|_| "Value must be a TOML array of strings")?;
+
+        if values.is_empty() {
+            return Err("Value must not be empty".into());
+        }
+
+        self.values = values;
+        Ok(())
+    }
+
+    fn value_as_string(&self) -> Option<String> {
+        self.is_set()
+            .then(|| toml::Value::from(self.values.clone()).to_string())
+    }
+
+    fn value_as_any(&self) -> Option<&dyn std::any::Any> {
+        self.is_set().then_some(&self.values as &dyn std::any::Any)
+    }
+
+    fn cli_values(&self) -> Vec<String> {
+        self.values.clone()
+    }
+}
+
 pub fn positive_number(s: &str) -> Res<u32> {
     let n = s.parse::<u32>().ok().unwrap_or(0);
     if n > 0 {
@@ -184,41 +273,69 @@ mod tests {
     fn flag_operations() {
         let mut arg = Arg::new_flag("--arg", "display", true);
 
-        assert_eq!(arg.expects_value(), false);
-        assert_eq!(arg.is_active(), true);
+        assert!(!arg.expects_value());
+        assert!(arg.is_active());
         assert_eq!(arg.default_as_string(), None);
         assert_eq!(arg.get_cli_token(), "--arg".to_string());
 
         arg.unset();
-        assert_eq!(arg.expects_value(), false);
-        assert_eq!(arg.is_active(), false);
+        assert!(!arg.expects_value());
+        assert!(!arg.is_active());
         assert_eq!(arg.default_as_string(), None);
         assert_eq!(arg.get_cli_token(), "--arg".to_string());
 
         assert_eq!(arg.set("").ok(), Some(()));
-        assert_eq!(arg.is_active(), true);
+        assert!(arg.is_active());
     }
 
     #[test]
     fn arg_operations() {
         let mut arg = Arg::new_arg("--arg", "display", Some(|| 1u32), arg::positive_number);
 
-        assert_eq!(arg.expects_value(), true);
-        assert_eq!(arg.is_active(), true);
+        assert!(arg.expects_value());
+        assert!(arg.is_active());
         assert_eq!(arg.default_as_string(), Some("1".to_string()));
         assert_eq!(arg.get_cli_token(), "--arg=1".to_string());
 
         arg.unset();
-        assert_eq!(arg.expects_value(), true);
-        assert_eq!(arg.is_active(), false);
+        assert!(arg.expects_value());
+        assert!(!arg.is_active());
         assert_eq!(arg.default_as_string(), Some("1".to_string()));
         assert_eq!(arg.get_cli_token(), "--arg".to_string());
 
         assert_eq!(arg.set("").ok(), None);
-        assert_eq!(arg.is_active(), false);
+        assert!(!arg.is_active());
 
         assert_eq!(arg.set("1").ok(), Some(()));
-        assert_eq!(arg.is_active(), true);
+        assert!(arg.is_active());
+    }
+
+    #[test]
+    fn list_operations() {
+        let mut arg = Arg::new_list("--arg", "display");
+
+        assert!(arg.expects_value());
+        assert!(!arg.is_active());
+        assert_eq!(arg.get_cli_tokens(), vec!["--arg".to_string()]);
+
+        assert_eq!(arg.set("[]").ok(), None);
+        assert_eq!(arg.set("one").ok(), None);
+        assert_eq!(arg.set("[1]").ok(), None);
+        assert!(!arg.is_active());
+
+        assert_eq!(arg.set(r#"["one", "two,three"]"#).ok(), Some(()));
+        assert!(arg.is_active());
+        assert_eq!(
+            arg.get_cli_token(),
+            r#"--arg=["one", "two,three"]"#.to_string()
+        );
+        assert_eq!(
+            arg.get_cli_tokens(),
+            vec!["--arg=one".to_string(), "--arg=two,three".to_string()]
+        );
+
+        arg.unset();
+        assert!(!arg.is_active());
     }
 
     #[test]
@@ -0,0 +1,39 @@
+//! Getting the user's attention when a long-running command finishes.
+
+use crate::{
+    config::Notify,
+    term::{self, Term},
+};
+use std::process::{Command, Stdio};
+
+pub(crate) fn notify(term: &mut Term, method: Notify, summary: &str) {
+    match method {
+        Notify::Bell => term::bell(term),
+        Notify::Desktop => desktop_notification(summary),
+        Notify::None => (),
+    }
+}
+
+/// Fire and forget, a missing `notify-send` shouldn't be reported as an error.
+fn desktop_notification(summary: &str) {
+    let mut cmd = if cfg!(target_os = "macos") {
+        let mut cmd = Command::new("osascript");
+        cmd.arg("-e").arg(format!(
+            "display notification {:?} with title \"gitu\"",
+            summary
+        ));
+        cmd
+    } else {
+        let mut cmd = Command::new("notify-send");
+        cmd.args(["gitu", summary]);
+        cmd
+    };
+
+    cmd.stdin(Stdio::null());
+    cmd.stdout(Stdio::null());
+    cmd.stderr(Stdio::null());
+
+    if let Err(error) = cmd.spawn() {
+        log::warn!("Couldn't send notification: {}", error);
+    }
+}
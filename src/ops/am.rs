@@ -0,0 +1,127 @@
+use std::{fs, path::Path, process::Command, rc::Rc};
+
+use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use derive_more::*;
+
+use super::{set_prompt, Action, OpTrait};
+
+const PATCH_EXTENSIONS: [&str; 3] = ["patch", "mbox", "eml"];
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![
+        Arg::new_flag("--3way", "Fall back on 3way merge", false),
+        Arg::new_flag("--signoff", "Add Signed-off-by lines", false),
+    ]
+}
+
+#[derive(Display)]
+#[display(fmt = "Apply patches")]
+pub(crate) struct AmApply;
+impl OpTrait for AmApply {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            set_prompt(
+                state,
+                "Apply patches",
+                apply_patches,
+                Box::new(selected_patch),
+                (),
+                true,
+            );
+
+            let completions = patch_files(state.repo.workdir().expect("No workdir"))?;
+            state.prompt.set_completions(completions);
+            Ok(())
+        }))
+    }
+}
+
+fn apply_patches(state: &mut State, term: &mut Term, input: &str, _context: &()) -> Res<()> {
+    if input.trim().is_empty() {
+        return Err("No patch given".into());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["am"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.args(input.split_whitespace());
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+fn selected_patch(state: &State) -> Option<String> {
+    match &state.screen().get_selected_item().target_data {
+        Some(TargetData::File(file)) if is_patch(file) => Some(file.to_string_lossy().to_string()),
+        _ => None,
+    }
+}
+
+fn is_patch(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| PATCH_EXTENSIONS.iter().any(|patch_ext| ext == *patch_ext))
+}
+
+/// Patch and mailbox files at the top of the work tree, where `git format-patch` puts them.
+fn patch_files(dir: &Path) -> Res<Vec<String>> {
+    let mut files = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_patch(path))
+        .filter_map(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .collect::<Vec<_>>();
+
+    files.sort();
+    Ok(files)
+}
+
+#[derive(Display)]
+#[display(fmt = "Apply continue")]
+pub(crate) struct AmContinue;
+impl OpTrait for AmContinue {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["am", "--continue"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)?;
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Apply skip")]
+pub(crate) struct AmSkip;
+impl OpTrait for AmSkip {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["am", "--skip"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)?;
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Apply abort")]
+pub(crate) struct AmAbort;
+impl OpTrait for AmAbort {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["am", "--abort"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)?;
+            Ok(())
+        }))
+    }
+}
@@ -0,0 +1,66 @@
+use super::{Action, OpTrait};
+use crate::{
+    git::diff::{Hunk, PatchMode},
+    items::TargetData,
+    state::State,
+    Res,
+};
+use derive_more::Display;
+use std::{ops::Range, process::Command, rc::Rc};
+
+#[derive(Display)]
+#[display(fmt = "Apply")]
+pub(crate) struct Apply;
+impl OpTrait for Apply {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let action = match target.cloned() {
+            Some(TargetData::Hunk(h)) => apply_patch(h),
+            Some(TargetData::HunkLine(h, lines)) => apply_lines(h, lines),
+            _ => return None,
+        };
+
+        Some(action)
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+/// Changes of the working tree are there already, only a shown commit's (or stash's) can be applied.
+fn check_shown_commit(state: &State) -> Res<()> {
+    match state.screen().commit {
+        Some(_) => Ok(()),
+        None => Err("Only changes of a commit or stash can be applied".into()),
+    }
+}
+
+fn apply_patch(h: Rc<Hunk>) -> Action {
+    Rc::new(move |state, term| {
+        check_shown_commit(state)?;
+
+        let mut cmd = Command::new("git");
+        cmd.args(["apply"]);
+        cmd.args(state.settings.borrow().diff.apply_args());
+
+        state.close_menu();
+        state.run_cmd(term, &h.format_patch().into_bytes(), cmd)
+    })
+}
+
+fn apply_lines(h: Rc<Hunk>, lines: Range<usize>) -> Action {
+    Rc::new(move |state, term| {
+        check_shown_commit(state)?;
+
+        let mut cmd = Command::new("git");
+        cmd.args(["apply", "--recount"]);
+        cmd.args(state.settings.borrow().diff.apply_args());
+
+        let input = h
+            .format_line_patch(lines.clone(), PatchMode::Normal)
+            .into_bytes();
+
+        state.close_menu();
+        state.run_cmd(term, &input, cmd)
+    })
+}
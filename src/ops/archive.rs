@@ -0,0 +1,57 @@
+use super::{create_rev_prompt, selected_rev, set_prompt, Action, OpTrait};
+use crate::{git, items::TargetData, state::State, term::Term, Res};
+use derive_more::Display;
+use git2::Oid;
+use std::process::Command;
+
+#[derive(Display)]
+#[display(fmt = "Archive")]
+pub(crate) struct Archive;
+impl OpTrait for Archive {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt(
+            "Archive",
+            prompt_path,
+            selected_rev,
+            true,
+        ))
+    }
+}
+
+fn prompt_path(state: &mut State, _term: &mut Term, rev: &str) -> Res<()> {
+    if rev.is_empty() {
+        return Err("No revision to archive".into());
+    }
+
+    // Hashes are shortened, and a `/` of a branch name would make for a directory.
+    // Short hex strings like `cafe` may well be branch names instead.
+    let is_hash = rev.len() > 7
+        && Oid::from_str(rev).is_ok()
+        && state.repo.resolve_reference_from_short_name(rev).is_err();
+    let name = if is_hash {
+        git::abbreviated_hash(&state.repo, rev)
+    } else {
+        rev.replace('/', "-")
+    };
+    let default_path = format!("{}.tar.gz", name);
+
+    set_prompt(
+        state,
+        "Archive to",
+        archive,
+        Box::new(move |_| Some(default_path.clone())),
+        rev.to_string(),
+        true,
+    );
+    Ok(())
+}
+
+/// The format follows the extension of the path, like `.zip` or `.tar.gz`.
+#[allow(clippy::ptr_arg)]
+fn archive(state: &mut State, term: &mut Term, path: &str, rev: &String) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["archive", "--output", path, rev]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
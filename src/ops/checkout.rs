@@ -1,11 +1,17 @@
-use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, prompt::PromptData, state::State, term::Term, Res};
+use super::{
+    create_prompt, create_rev_prompt, marked_branches, selected_rev, set_prompt, stash, Action,
+    OpTrait,
+};
+use crate::{git, items::TargetData, menu::arg::Arg, screen, state::State, term::Term, Res};
 use derive_more::Display;
-use std::{process::Command, rc::Rc};
-use tui_prompts::State as _;
+use git2::{BranchType, Repository};
+use std::{path::PathBuf, process::Command, rc::Rc};
 
 pub(crate) fn init_args() -> Vec<Arg> {
-    vec![]
+    vec![
+        Arg::new_flag("--orphan", "New branch without history", false),
+        Arg::new_flag("--no-checkout", "Don't checkout the new branch", false),
+    ]
 }
 
 #[derive(Display)]
@@ -13,24 +19,98 @@ pub(crate) fn init_args() -> Vec<Arg> {
 pub(crate) struct Checkout;
 impl OpTrait for Checkout {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_rev_prompt(
             "Checkout",
             checkout,
-            selected_rev,
+            selected_rev_or_remote_branch,
             true,
         ))
     }
 }
 
+/// Like [`selected_rev`], but a commit that only a remote branch points at stands for it,
+/// so that checking it out makes a local branch rather than detaching `HEAD`.
+fn selected_rev_or_remote_branch(state: &State) -> Option<String> {
+    let rev = selected_rev(state)?;
+    let Some(TargetData::Commit(_)) = state.screen().get_selected_item().target_data else {
+        return Some(rev);
+    };
+
+    let oid = state.repo.revparse_single(&rev).ok()?.id();
+    let mut remote_branches = vec![];
+    for (branch, kind) in state.repo.branches(None).ok()?.filter_map(Result::ok) {
+        if branch.get().target() != Some(oid) {
+            continue;
+        }
+
+        match (kind, branch.name().ok().flatten()) {
+            (BranchType::Local, _) => return Some(rev),
+            (BranchType::Remote, Some(name)) if !name.ends_with("/HEAD") => {
+                remote_branches.push(name.to_string())
+            }
+            _ => (),
+        }
+    }
+
+    match &remote_branches[..] {
+        [branch] => Some(branch.clone()),
+        _ => Some(rev),
+    }
+}
+
 fn checkout(state: &mut State, term: &mut Term, rev: &str) -> Res<()> {
+    // Like `git checkout feature-x` would for `origin/feature-x`
+    if let Some(name) = local_name_of_remote_branch(&state.repo, rev) {
+        let exists = state.repo.find_branch(&name, BranchType::Local).is_ok();
+        if !exists {
+            state.close_menu();
+            set_prompt(
+                state,
+                "Create tracking branch",
+                checkout_tracking_branch,
+                Box::new(move |_| Some(name.clone())),
+                rev.to_string(),
+                false,
+            );
+            return Ok(());
+        }
+
+        // Only while it's at the same commit, or it wouldn't be what was asked for
+        let tip = |rev: &str| state.repo.revparse_single(rev).ok().map(|obj| obj.id());
+        if git::branch_upstream_name(&state.repo, &name).as_deref() == Some(rev)
+            && tip(&name) == tip(rev)
+        {
+            return checkout(state, term, &name);
+        }
+    }
+
     let mut cmd = Command::new("git");
-    cmd.args(["checkout"]);
-    cmd.args(state.pending_menu.as_ref().unwrap().args());
-    cmd.arg(rev);
+    cmd.args(["checkout", rev]);
 
     state.close_menu();
-    state.run_cmd(term, &[], cmd)?;
-    Ok(())
+    stash::run_autostashed(state, term, cmd)
+}
+
+/// `feature-x` of a remote branch like `origin/feature-x`.
+fn local_name_of_remote_branch(repo: &Repository, rev: &str) -> Option<String> {
+    let branch = repo.find_branch(rev, BranchType::Remote).ok()?;
+    let remote = repo.branch_remote_name(branch.get().name()?).ok()?;
+    let name = rev.strip_prefix(remote.as_str()?)?.strip_prefix('/')?;
+
+    (name != "HEAD").then(|| name.to_string())
+}
+
+#[allow(clippy::ptr_arg)]
+fn checkout_tracking_branch(
+    state: &mut State,
+    term: &mut Term,
+    name: &str,
+    remote_branch: &String,
+) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["checkout", "-b", name, "--track", remote_branch]);
+
+    stash::run_autostashed(state, term, cmd)
 }
 
 #[derive(Display)]
@@ -39,25 +119,227 @@ pub(crate) struct CheckoutNewBranch;
 impl OpTrait for CheckoutNewBranch {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let args = state.pending_menu.as_ref().unwrap().args();
+            let is_orphan = args.iter().any(|arg| arg == "--orphan");
+            let is_no_checkout = args.iter().any(|arg| arg == "--no-checkout");
+
+            if is_orphan && is_no_checkout {
+                return Err("An orphan branch can't be created without checking it out".into());
+            }
+
             state.close_menu();
-            state.prompt.set(PromptData {
-                prompt_text: "Create and checkout branch:".into(),
-                update_fn: Rc::new(checkout_new_branch_prompt_update),
-            });
+            set_prompt(
+                state,
+                if is_no_checkout {
+                    "Create branch"
+                } else {
+                    "Create and checkout branch"
+                },
+                checkout_new_branch,
+                Box::new(|_| None),
+                (is_orphan, is_no_checkout),
+                false,
+            );
             Ok(())
         }))
     }
 }
 
-fn checkout_new_branch_prompt_update(state: &mut State, term: &mut Term) -> Res<()> {
-    if state.prompt.state.status().is_done() {
-        let name = state.prompt.state.value().to_string();
-        state.prompt.reset(term)?;
+fn checkout_new_branch(
+    state: &mut State,
+    term: &mut Term,
+    name: &str,
+    &(is_orphan, is_no_checkout): &(bool, bool),
+) -> Res<()> {
+    let mut cmd = Command::new("git");
+    if is_orphan {
+        cmd.args(["checkout", "--orphan", name]);
+    } else if is_no_checkout {
+        cmd.args(["branch", name]);
+    } else {
+        cmd.args(["checkout", "-b", name]);
+    }
 
-        let mut cmd = Command::new("git");
-        cmd.args(["checkout", "-b", &name]);
+    state.run_cmd(term, &[], cmd)?;
+    Ok(())
+}
+
+#[derive(Display)]
+#[display(fmt = "Checkout file from revision")]
+pub(crate) struct CheckoutFile;
+impl OpTrait for CheckoutFile {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let file = match target {
+            Some(TargetData::File(file)) => file.clone(),
+            Some(TargetData::Delta(d)) => d.new_file.clone(),
+            _ => return None,
+        };
+
+        Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+            set_prompt(
+                state,
+                "Checkout file from",
+                checkout_file,
+                Box::new(|_| None),
+                file.clone(),
+                true,
+            );
 
-        state.run_cmd(term, &[], cmd)?;
+            let completions = git::ref_names(&state.repo)?;
+            state.prompt.set_completions(completions);
+            Ok(())
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
     }
+}
+
+#[allow(clippy::ptr_arg)]
+fn checkout_file(state: &mut State, term: &mut Term, rev: &str, file: &PathBuf) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["checkout", rev, "--"]);
+    cmd.arg(file);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)?;
     Ok(())
 }
+
+#[derive(Display)]
+#[display(fmt = "Spin off branch")]
+pub(crate) struct Spinoff;
+impl OpTrait for Spinoff {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt("Spin off branch", spinoff, true))
+    }
+}
+
+/// Moves the commits not yet on the upstream, and any uncommitted changes,
+/// onto a new branch. The current branch is then reset to its upstream.
+fn spinoff(state: &mut State, term: &mut Term, name: &str) -> Res<()> {
+    let branch = git::current_branch(&state.repo).ok_or("Not on a branch")?;
+    let head = state.repo.head()?.peel_to_commit()?.id();
+    let upstream = git::upstream(&state.repo);
+
+    let mut cmd = Command::new("git");
+    cmd.args(["checkout", "-b", name]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)?;
+
+    let Some((upstream_name, upstream_id)) = upstream else {
+        return Ok(());
+    };
+
+    // A branch that diverged from its upstream is left alone, it can't simply be reset.
+    if upstream_id == head || !state.repo.graph_descendant_of(head, upstream_id)? {
+        return Ok(());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["update-ref", "-m"]);
+    cmd.arg(format!("spinoff: moved to {}", name));
+    cmd.arg(format!("refs/heads/{}", branch));
+    cmd.arg(upstream_name);
+
+    state.run_cmd(term, &[], cmd)?;
+    Ok(())
+}
+
+#[derive(Display)]
+#[display(fmt = "Set upstream")]
+pub(crate) struct SetUpstream;
+impl OpTrait for SetUpstream {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let selected = match target {
+            Some(TargetData::Branch(branch)) => Some(branch.clone()),
+            _ => None,
+        };
+
+        Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+            let branch = selected
+                .clone()
+                .filter(|branch| {
+                    state
+                        .repo
+                        .find_branch(branch, git2::BranchType::Local)
+                        .is_ok()
+                })
+                .or_else(|| git::current_branch(&state.repo))
+                .ok_or("No branch to set the upstream of")?;
+
+            let current = git::branch_upstream_name(&state.repo, &branch);
+            set_prompt(
+                state,
+                "Set upstream to",
+                set_upstream,
+                Box::new(move |_| current.clone()),
+                branch,
+                true,
+            );
+
+            let completions = git::remote_branch_names(&state.repo)?;
+            state.prompt.set_completions(completions);
+            Ok(())
+        }))
+    }
+}
+
+#[allow(clippy::ptr_arg)]
+fn set_upstream(state: &mut State, term: &mut Term, upstream: &str, branch: &String) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["branch", "--set-upstream-to", upstream, branch]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)?;
+    Ok(())
+}
+
+#[derive(Display)]
+#[display(fmt = "Clean up branches")]
+pub(crate) struct ShowBranchCleanup;
+impl OpTrait for ShowBranchCleanup {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+            state.push_screen(screen::branch_cleanup::create(
+                Rc::clone(&state.config),
+                Rc::clone(&state.repo),
+                term.size()?,
+            )?);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Delete branches")]
+pub(crate) struct DeleteBranches;
+impl OpTrait for DeleteBranches {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let selected = match target {
+            Some(TargetData::Branch(branch)) => branch.clone(),
+            _ => return None,
+        };
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let mut branches = marked_branches(state);
+            if branches.is_empty() {
+                branches.push(selected.clone());
+            }
+
+            // Merged into the default branch, rather than the current one `-d` would want
+            let mut cmd = Command::new("git");
+            cmd.args(["branch", "-D"]);
+            cmd.args(branches);
+
+            state.close_menu();
+            state.screen_mut().clear_marks();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
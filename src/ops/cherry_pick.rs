@@ -0,0 +1,72 @@
+use std::{process::Command, rc::Rc};
+
+use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use derive_more::*;
+
+use super::{create_rev_prompt, marked_or_selected_revs, Action, OpTrait};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![
+        Arg::new_flag("--ff", "Attempt fast-forward", false),
+        Arg::new_flag("-x", "Reference cherry in commit message", false),
+        Arg::new_flag("--edit", "Edit commit messages", false),
+        Arg::new_flag("--signoff", "Add Signed-off-by lines", false),
+    ]
+}
+
+#[derive(Display)]
+#[display(fmt = "Cherry-pick abort")]
+pub(crate) struct CherryPickAbort;
+impl OpTrait for CherryPickAbort {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["cherry-pick", "--abort"]);
+
+            state.close_menu();
+            state.run_cmd_interactive(term, cmd)?;
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Cherry-pick continue")]
+pub(crate) struct CherryPickContinue;
+impl OpTrait for CherryPickContinue {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["cherry-pick", "--continue"]);
+
+            state.close_menu();
+            state.run_cmd_interactive(term, cmd)?;
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Cherry-pick")]
+pub(crate) struct CherryPick;
+impl OpTrait for CherryPick {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt(
+            "Cherry-pick",
+            cherry_pick,
+            marked_or_selected_revs,
+            true,
+        ))
+    }
+}
+
+fn cherry_pick(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["cherry-pick"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.args(input.split_whitespace());
+
+    state.close_menu();
+    state.screen_mut().clear_marks();
+    state.run_cmd_interactive(term, cmd)
+}
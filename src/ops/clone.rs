@@ -0,0 +1,45 @@
+use super::{create_prompt, set_prompt, Action, OpTrait};
+use crate::{git, items::TargetData, state::State, term::Term, Res};
+use derive_more::Display;
+use std::process::Command;
+
+#[derive(Display)]
+#[display(fmt = "Clone")]
+pub(crate) struct Clone;
+impl OpTrait for Clone {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt("Clone repository (URL)", prompt_dir, true))
+    }
+}
+
+fn prompt_dir(state: &mut State, _term: &mut Term, url: &str) -> Res<()> {
+    if url.trim().is_empty() {
+        return Err("No repository to clone".into());
+    }
+
+    // Beside this repository, rather than inside it
+    let default_dir = format!("../{}", git::clone_dir(url.trim()));
+    set_prompt(
+        state,
+        "Clone into",
+        clone,
+        Box::new(move |_| Some(default_dir.clone())),
+        url.trim().to_string(),
+        true,
+    );
+    Ok(())
+}
+
+/// Runs on the terminal to stream git's progress, then opens the clone.
+#[allow(clippy::ptr_arg)]
+fn clone(state: &mut State, term: &mut Term, dir: &str, url: &String) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["clone", "--progress", url, dir]);
+
+    state.close_menu();
+    state.run_cmd_suspended(term, cmd)?;
+
+    let dir = state.repo.workdir().expect("No workdir").join(dir);
+    let repo = crate::open_repo_in(&dir)?;
+    state.switch_repo(term, repo)
+}
@@ -1,11 +1,19 @@
-use super::{Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term};
+use super::{set_prompt, show, Action, OpTrait};
+use crate::{
+    cmd_log::CmdLogEntry, git, items::TargetData, menu::arg::Arg, prompt::PromptData, state::State,
+    term::Term, Res,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
 use derive_more::Display;
+use git2::{Repository, RepositoryState};
 use std::{
     ffi::{OsStr, OsString},
+    fs,
+    path::Path,
     process::Command,
     rc::Rc,
 };
+use tui_prompts::State as _;
 
 pub(crate) fn init_args() -> Vec<Arg> {
     vec![
@@ -18,25 +26,76 @@ pub(crate) fn init_args() -> Vec<Arg> {
             "Claim authorship and reset author date",
             false,
         ),
-        // TODO -A Override the author (--author=)
+        Arg::new_arg("--author", "Override the author", None, author)
+            .with_completions(recent_authors),
+        Arg::new_arg("--date", "Override the author date", None, date),
         Arg::new_flag("--signoff", "Add Signed-off-by line", false),
+        Arg::new_arg("--trailer", "Add Co-authored-by trailer", None, co_author)
+            .with_completions(recent_authors),
         // TODO -C Reuse commit message (--reuse-message=)
     ]
 }
 
+fn co_author(s: &str) -> Res<String> {
+    if !is_identity(s) {
+        return Err("Co-author must be formatted as: Name <email>".into());
+    }
+
+    Ok(format!("Co-authored-by: {}", s.trim()))
+}
+
+fn author(s: &str) -> Res<String> {
+    if !is_identity(s) {
+        return Err("Author must be formatted as: Name <email>".into());
+    }
+
+    Ok(s.trim().to_string())
+}
+
+/// `Name <email>`, as git wants it.
+fn is_identity(s: &str) -> bool {
+    s.trim()
+        .split_once(" <")
+        .is_some_and(|(name, email)| !name.trim().is_empty() && email.ends_with('>'))
+}
+
+/// git makes sense of most anything, so only formats that can't be misread are let through.
+fn date(s: &str) -> Res<String> {
+    let s = s.trim();
+    let is_valid = s == "now"
+        || DateTime::parse_from_rfc3339(s).is_ok()
+        || DateTime::parse_from_rfc2822(s).is_ok()
+        || DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S %z").is_ok()
+        || NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").is_ok()
+        || NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").is_ok()
+        || NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok();
+
+    if !is_valid {
+        return Err("Date must be formatted like: 2024-01-31 13:37, or RFC 3339 / 2822".into());
+    }
+
+    Ok(s.to_string())
+}
+
+fn recent_authors(repo: &Repository) -> Res<Vec<String>> {
+    git::recent_authors(repo, 256)
+}
+
 #[derive(Display)]
 #[display(fmt = "Commit")]
 pub(crate) struct Commit;
 impl OpTrait for Commit {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state: &mut State, term: &mut Term| {
-            let mut cmd = Command::new("git");
-            cmd.args(["commit"]);
-            cmd.args(state.pending_menu.as_ref().unwrap().args());
+            let mut args = vec![];
+            if let Some(template) = &state.config.general.commit_template {
+                args.push("--template".into());
+                args.push(template.into());
+            }
+            args.extend(state.pending_menu.as_ref().unwrap().args());
 
             state.close_menu();
-            state.run_cmd_interactive(term, cmd)?;
-            Ok(())
+            commit_linted(state, term, args)
         }))
     }
 }
@@ -47,17 +106,116 @@ pub(crate) struct CommitAmend;
 impl OpTrait for CommitAmend {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state: &mut State, term: &mut Term| {
-            let mut cmd = Command::new("git");
-            cmd.args(["commit", "--amend"]);
-            cmd.args(state.pending_menu.as_ref().unwrap().args());
+            let mut args = vec!["--amend".into()];
+            args.extend(state.pending_menu.as_ref().unwrap().args());
 
             state.close_menu();
-            state.run_cmd_interactive(term, cmd)?;
-            Ok(())
+            commit_linted(state, term, args)
         }))
     }
 }
 
+/// Commits, with the message checked by `general.commit_lint` after editing, if configured.
+fn commit_linted(state: &mut State, term: &mut Term, args: Vec<OsString>) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.arg("commit");
+    cmd.args(&args);
+
+    let Some(lint) = &state.config.general.commit_lint else {
+        return state.run_cmd_interactive(term, cmd);
+    };
+
+    let rejected = state.repo.path().join(REJECTED_MSG);
+    // Left over from a commit that was given up on
+    let _ = fs::remove_file(&rejected);
+
+    let editor = show::configured_editor(&state.repo);
+    cmd.env("GIT_EDITOR", linting_editor(&editor, lint, &rejected));
+
+    let result = state.run_cmd_interactive(term, cmd);
+    if result.is_err() && rejected.exists() {
+        lint_rejected_prompt(state, Rc::new(args));
+        return Ok(());
+    }
+
+    result
+}
+
+/// Where a message the linter rejected is kept. git doesn't commit it, but it needn't be lost.
+const REJECTED_MSG: &str = "GITU_REJECTED_MSG";
+const RETRIED_MSG: &str = "GITU_RETRIED_MSG";
+
+/// For git to run in place of the editor: it runs the editor, then the linter. A rejected
+/// message is saved without git's comments, and making the "editor" fail keeps git from committing.
+fn linting_editor(editor: &str, lint: &str, rejected: &Path) -> String {
+    format!(
+        "gitu_lint() {{ {} \"$1\" && {{ {} \"$1\" >&2 || {{ git stripspace --strip-comments < \"$1\" > {}; exit 1; }}; }}; }}; gitu_lint",
+        editor,
+        lint,
+        sh_quote(&rejected.to_string_lossy())
+    )
+}
+
+/// Commands run in the workdir, shorter paths keep the command log readable.
+fn relative_to_workdir<'a>(repo: &Repository, path: &'a Path) -> &'a Path {
+    repo.workdir()
+        .and_then(|workdir| path.strip_prefix(workdir).ok())
+        .unwrap_or(path)
+}
+
+pub(crate) fn sh_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+fn lint_rejected_prompt(state: &mut State, args: Rc<Vec<OsString>>) {
+    let update_fn = Rc::new(move |state: &mut State, term: &mut Term| {
+        if !state.prompt.state.status().is_pending() {
+            return Ok(());
+        }
+
+        let rejected = state.repo.path().join(REJECTED_MSG);
+        match state.prompt.state.value() {
+            "e" => {
+                state.prompt.reset(term)?;
+                let retried = state.repo.path().join(RETRIED_MSG);
+                fs::rename(&rejected, &retried)?;
+
+                let mut retry_args = args.to_vec();
+                retry_args.extend(["--edit".into(), "--file".into()]);
+                retry_args.push(relative_to_workdir(&state.repo, &retried).into());
+                let result = commit_linted(state, term, retry_args);
+                let _ = fs::remove_file(retried);
+                result
+            }
+            "c" => {
+                state.prompt.reset(term)?;
+                let mut cmd = Command::new("git");
+                cmd.arg("commit");
+                cmd.args(args.iter());
+                cmd.arg("--file");
+                cmd.arg(relative_to_workdir(&state.repo, &rejected));
+
+                let result = state.run_cmd_interactive(term, cmd);
+                let _ = fs::remove_file(rejected);
+                result
+            }
+            "" => Ok(()),
+            _ => {
+                state
+                    .current_cmd_log
+                    .push(CmdLogEntry::Error("Aborted".to_string()));
+                state.prompt.reset(term)
+            }
+        }
+    });
+
+    state.prompt.set(PromptData {
+        prompt_text: "Message rejected by the linter, edit again or commit anyway? (e or c)".into(),
+        update_fn,
+        history_key: None,
+    });
+}
+
 #[derive(Display)]
 #[display(fmt = "Commit fixup")]
 pub(crate) struct CommitFixup;
@@ -89,3 +247,259 @@ fn commit_fixup_cmd(args: &[OsString], rev: &OsStr) -> Command {
     cmd.args(args);
     cmd
 }
+
+#[derive(Display)]
+#[display(fmt = "Commit instant fixup")]
+pub(crate) struct CommitInstantFixup;
+impl OpTrait for CommitInstantFixup {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Commit(r)) => {
+                let rev = r.clone();
+
+                Some(Rc::new(move |state: &mut State, term: &mut Term| {
+                    let args = state.pending_menu.as_ref().unwrap().args();
+                    state.close_menu();
+
+                    state.run_cmd(term, &[], commit_fixup_cmd(&args, OsStr::new(&rev)))?;
+                    autosquash_into(state, term, &rev)
+                }))
+            }
+            _ => None,
+        }
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Reword")]
+pub(crate) struct CommitReword;
+impl OpTrait for CommitReword {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Commit(r)) => {
+                let rev = r.clone();
+
+                Some(Rc::new(move |state: &mut State, term: &mut Term| {
+                    state.close_menu();
+
+                    let oid = state.repo.revparse_single(&rev)?.peel_to_commit()?.id();
+                    let is_head =
+                        state.repo.head().ok().and_then(|head| head.target()) == Some(oid);
+
+                    let mut cmd = Command::new("git");
+                    if is_head {
+                        // Staged changes aren't to be part of it
+                        cmd.args(["commit", "--amend", "--only"]);
+                        return state.run_cmd_interactive(term, cmd);
+                    }
+
+                    // An "amend!" commit carrying only the new message, staged changes are left be
+                    cmd.arg("commit");
+                    cmd.arg(format!("--fixup=reword:{}", rev));
+                    state.run_cmd_interactive(term, cmd)?;
+                    autosquash_into(state, term, &rev)
+                }))
+            }
+            _ => None,
+        }
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+/// Squashes fixups of `rev` made on top of the branch into it, other changes are stashed meanwhile.
+fn autosquash_into(state: &mut State, term: &mut Term, rev: &str) -> Res<()> {
+    let is_root = state
+        .repo
+        .revparse_single(rev)?
+        .peel_to_commit()?
+        .parent_count()
+        == 0;
+
+    let result = state.run_cmd(term, &[], instant_fixup_rebase_cmd(rev, is_root));
+    if result.is_err() && state.repo.state() != RepositoryState::Clean {
+        return Err("Rebase stopped due to conflicts, resolve them and continue the rebase".into());
+    }
+
+    result
+}
+
+fn instant_fixup_rebase_cmd(rev: &str, is_root: bool) -> Command {
+    let mut cmd = Command::new("git");
+    // Accept the autosquashed todo list as-is, making the rebase non-interactive
+    cmd.env("GIT_SEQUENCE_EDITOR", "true");
+    cmd.args([
+        "rebase",
+        "--interactive",
+        "--autosquash",
+        "--autostash",
+        "--keep-empty",
+    ]);
+
+    if is_root {
+        cmd.arg("--root");
+    } else {
+        cmd.arg(format!("{}^", rev));
+    }
+
+    cmd
+}
+
+const DEFAULT_CONVENTIONAL_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// Allowed types may be configured per repository via `git config gitu.conventionalCommitTypes`.
+fn conventional_types(repo: &Repository) -> Res<Vec<String>> {
+    let configured = repo
+        .config()?
+        .get_string("gitu.conventionalCommitTypes")
+        .ok();
+
+    Ok(match configured {
+        Some(types) => types
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|kind| !kind.is_empty())
+            .map(str::to_string)
+            .collect(),
+        None => DEFAULT_CONVENTIONAL_TYPES
+            .iter()
+            .map(|kind| kind.to_string())
+            .collect(),
+    })
+}
+
+#[derive(Clone, Default)]
+struct ConventionalCommit {
+    args: Vec<OsString>,
+    kind: String,
+    /// Typed as a `!` after the type, it goes after the scope in the header.
+    breaking: bool,
+    scope: String,
+}
+
+impl ConventionalCommit {
+    fn header(&self, summary: &str) -> String {
+        let breaking = if self.breaking { "!" } else { "" };
+        if self.scope.is_empty() {
+            format!("{}{}: {}", self.kind, breaking, summary)
+        } else {
+            format!("{}({}){}: {}", self.kind, self.scope, breaking, summary)
+        }
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Commit conventional")]
+pub(crate) struct CommitConventional;
+impl OpTrait for CommitConventional {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let commit = ConventionalCommit {
+                args: state.pending_menu.as_ref().unwrap().args(),
+                ..Default::default()
+            };
+
+            set_prompt(
+                state,
+                "Type",
+                conventional_type,
+                Box::new(|_| None),
+                commit,
+                true,
+            );
+
+            let completions = conventional_types(&state.repo)?;
+            state.prompt.set_completions(completions);
+            Ok(())
+        }))
+    }
+}
+
+fn conventional_type(
+    state: &mut State,
+    _term: &mut Term,
+    input: &str,
+    commit: &ConventionalCommit,
+) -> Res<()> {
+    state.close_menu();
+
+    let kind = input.strip_suffix('!').unwrap_or(input);
+    let types = conventional_types(&state.repo)?;
+    if !types.iter().any(|allowed| allowed == kind) {
+        return Err(format!("Type must be one of: {}", types.join(", ")).into());
+    }
+
+    set_prompt(
+        state,
+        "Scope (optional)",
+        conventional_scope,
+        Box::new(|_| None),
+        ConventionalCommit {
+            kind: kind.to_string(),
+            breaking: kind != input,
+            ..commit.clone()
+        },
+        false,
+    );
+
+    Ok(())
+}
+
+fn conventional_scope(
+    state: &mut State,
+    _term: &mut Term,
+    input: &str,
+    commit: &ConventionalCommit,
+) -> Res<()> {
+    if input.contains(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+        return Err("Scope may not contain whitespace or parentheses".into());
+    }
+
+    set_prompt(
+        state,
+        "Summary",
+        conventional_summary,
+        Box::new(|_| None),
+        ConventionalCommit {
+            scope: input.to_string(),
+            ..commit.clone()
+        },
+        false,
+    );
+
+    Ok(())
+}
+
+const CONVENTIONAL_HEADER_MAX_LEN: usize = 100;
+
+fn conventional_summary(
+    state: &mut State,
+    term: &mut Term,
+    input: &str,
+    commit: &ConventionalCommit,
+) -> Res<()> {
+    let summary = input.trim();
+    if summary.is_empty() {
+        return Err("Summary may not be empty".into());
+    }
+
+    let header = commit.header(summary);
+    if header.chars().count() > CONVENTIONAL_HEADER_MAX_LEN {
+        return Err(format!(
+            "Commit header exceeds {} characters",
+            CONVENTIONAL_HEADER_MAX_LEN
+        )
+        .into());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["commit", "--message", &header]);
+    cmd.args(&commit.args);
+
+    state.run_cmd(term, &[], cmd)
+}
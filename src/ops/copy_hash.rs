@@ -9,7 +9,11 @@ pub(crate) struct CopyHash;
 impl OpTrait for CopyHash {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
         match target {
-            Some(TargetData::Commit(r)) => copy_hash(r.clone()),
+            Some(TargetData::Commit(r)) => copy_hash(None, r.clone()),
+            Some(TargetData::Branch(name)) => copy_hash(Some(name.clone()), name.clone()),
+            Some(TargetData::Stash { commit, id }) => {
+                copy_hash(Some(format!("stash@{{{}}}", id)), commit.clone())
+            }
             _ => None,
         }
     }
@@ -18,12 +22,23 @@ impl OpTrait for CopyHash {
     }
 }
 
-fn copy_hash(r: String) -> Option<Action> {
+/// Of a branch, tag or stash, the commit it's at is copied, shown along with what it's of.
+fn copy_hash(name: Option<String>, r: String) -> Option<Action> {
     Some(Rc::new(move |state, _term| {
         state.close_menu();
+        let hash = state
+            .repo
+            .revparse_single(&r)?
+            .peel_to_commit()?
+            .id()
+            .to_string();
+        if let Some(name) = &name {
+            state.display_info(format!("{} is {}", name, hash));
+        }
+
         match &mut state.clipboard {
             Some(cb) => {
-                cb.set_text(r.clone())?;
+                cb.set_text(hash)?;
                 state.display_info("Commit hash copied to clipboard".to_owned());
             }
             None => state.display_error("Clipboard not available".to_owned()),
@@ -0,0 +1,46 @@
+use super::{Action, OpTrait};
+use crate::items::TargetData;
+use derive_more::Display;
+use git2::{DescribeFormatOptions, DescribeOptions};
+use std::rc::Rc;
+
+#[derive(Display)]
+#[display(fmt = "Describe")]
+pub(crate) struct Describe;
+impl OpTrait for Describe {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Commit(rev) | TargetData::Branch(rev)) => describe(rev.clone()),
+            _ => None,
+        }
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+/// Like `git describe --tags --always`, copying the result when there's a clipboard.
+fn describe(rev: String) -> Option<Action> {
+    Some(Rc::new(move |state, _term| {
+        state.close_menu();
+
+        let description = state
+            .repo
+            .revparse_single(&rev)?
+            .describe(
+                DescribeOptions::new()
+                    .describe_tags()
+                    .show_commit_oid_as_fallback(true),
+            )?
+            .format(Some(DescribeFormatOptions::new().abbreviated_size(7)))?;
+
+        match &mut state.clipboard {
+            Some(cb) => {
+                cb.set_text(description.clone())?;
+                state.display_info(format!("{} (copied to clipboard)", description));
+            }
+            None => state.display_info(description),
+        }
+        Ok(())
+    }))
+}
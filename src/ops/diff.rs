@@ -0,0 +1,298 @@
+use super::{create_prompt, create_rev_prompt, selected_rev, Action, OpTrait};
+use crate::{
+    config::{DiffAlgorithm, FileOrder},
+    git::diff::{Context, DiffSettings},
+    items::{FileFilter, TargetData},
+    menu::arg::Arg,
+    screen,
+    state::State,
+    term::Term,
+    Res,
+};
+use derive_more::Display;
+use std::rc::Rc;
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![]
+}
+
+#[derive(Display)]
+#[display(fmt = "Diff range")]
+pub(crate) struct DiffRange;
+impl OpTrait for DiffRange {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt(
+            "Diff range (A..B, A...B or A)",
+            diff_range,
+            selected_rev,
+            true,
+        ))
+    }
+}
+
+fn diff_range(state: &mut State, term: &mut Term, range: &str) -> Res<()> {
+    let title = if range.contains("..") {
+        format!("Changes in {}", range)
+    } else {
+        format!("Changes from {} to working tree", range)
+    };
+
+    state.close_menu();
+    goto_diff_range_screen(state, term, range.to_string(), title)
+}
+
+#[derive(Display)]
+#[display(fmt = "Diff worktree against commit")]
+pub(crate) struct DiffWorktree;
+impl OpTrait for DiffWorktree {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Commit(r) | TargetData::Branch(r)) => {
+                let rev = r.clone();
+                Some(Rc::new(move |state: &mut State, term: &mut Term| {
+                    state.close_menu();
+                    let title = format!("Changes from {} to working tree", rev);
+                    goto_diff_range_screen(state, term, rev.clone(), title)
+                }))
+            }
+            _ => None,
+        }
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Diff against merge-base")]
+pub(crate) struct DiffMergeBase;
+impl OpTrait for DiffMergeBase {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt(
+            "Diff against merge-base with",
+            diff_merge_base,
+            selected_rev_or_upstream,
+            true,
+        ))
+    }
+}
+
+fn selected_rev_or_upstream(state: &State) -> Option<String> {
+    selected_rev(state).or_else(|| {
+        let head = state.repo.head().ok()?;
+        let head_name = head.name()?;
+        let upstream = state.repo.branch_upstream_name(head_name).ok()?;
+        let upstream = state.repo.find_reference(upstream.as_str()?).ok()?;
+        upstream.shorthand().map(str::to_string)
+    })
+}
+
+/// Shows what the current branch adds on top of `target`, including uncommitted changes.
+fn diff_merge_base(state: &mut State, term: &mut Term, target: &str) -> Res<()> {
+    let target_oid = state.repo.revparse_single(target)?.peel_to_commit()?.id();
+    let head_oid = state.repo.head()?.peel_to_commit()?.id();
+    let merge_base = state.repo.merge_base(target_oid, head_oid)?;
+
+    state.close_menu();
+    goto_diff_range_screen(
+        state,
+        term,
+        merge_base.to_string(),
+        format!("Changes since merge-base with {}", target),
+    )
+}
+
+fn goto_diff_range_screen(
+    state: &mut State,
+    term: &mut Term,
+    range: String,
+    title: String,
+) -> Res<()> {
+    state.push_screen(screen::diff_range::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.settings),
+        Rc::clone(&state.hunk_cache),
+        Rc::clone(&state.repo),
+        term.size()?,
+        range,
+        title,
+    )?);
+
+    Ok(())
+}
+
+#[derive(Display)]
+#[display(fmt = "More diff context")]
+pub(crate) struct MoreContext;
+impl OpTrait for MoreContext {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            set_context(state, |context| match context {
+                Context::Lines(lines) => Context::Lines(lines + 1),
+                Context::WholeFile => Context::WholeFile,
+            })
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Less diff context")]
+pub(crate) struct LessContext;
+impl OpTrait for LessContext {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let default = state.config.general.diff_context_lines;
+            set_context(state, |context| match context {
+                Context::Lines(lines) => Context::Lines(lines.saturating_sub(1)),
+                Context::WholeFile => Context::Lines(default),
+            })
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Toggle whole file diff context")]
+pub(crate) struct WholeFileContext;
+impl OpTrait for WholeFileContext {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let default = state.config.general.diff_context_lines;
+            set_context(state, |context| match context {
+                Context::Lines(_) => Context::WholeFile,
+                Context::WholeFile => Context::Lines(default),
+            })
+        }))
+    }
+}
+
+fn set_context(state: &mut State, change: impl Fn(Context) -> Context) -> Res<()> {
+    change_settings(state, |settings| {
+        settings.context = change(settings.context)
+    })
+}
+
+#[derive(Display)]
+#[display(fmt = "Toggle ignoring all whitespace")]
+pub(crate) struct IgnoreAllSpace;
+impl OpTrait for IgnoreAllSpace {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            change_settings(state, |settings| {
+                settings.ignore_all_space = !settings.ignore_all_space
+            })
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Toggle ignoring whitespace changes")]
+pub(crate) struct IgnoreSpaceChange;
+impl OpTrait for IgnoreSpaceChange {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            change_settings(state, |settings| {
+                settings.ignore_space_change = !settings.ignore_space_change
+            })
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Toggle ignoring blank lines")]
+pub(crate) struct IgnoreBlankLines;
+impl OpTrait for IgnoreBlankLines {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            change_settings(state, |settings| {
+                settings.ignore_blank_lines = !settings.ignore_blank_lines
+            })
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Cycle diff algorithm")]
+pub(crate) struct CycleDiffAlgorithm;
+impl OpTrait for CycleDiffAlgorithm {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let algorithm = match state.settings.borrow().diff.algorithm {
+                DiffAlgorithm::Myers => DiffAlgorithm::Minimal,
+                DiffAlgorithm::Minimal => DiffAlgorithm::Patience,
+                DiffAlgorithm::Patience => DiffAlgorithm::Histogram,
+                DiffAlgorithm::Histogram => DiffAlgorithm::Myers,
+            };
+
+            change_settings(state, |settings| settings.algorithm = algorithm)?;
+            state.display_info(format!("Diffing with the {} algorithm", algorithm));
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Toggle file tree")]
+pub(crate) struct ToggleFileTree;
+impl OpTrait for ToggleFileTree {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let file_tree = state.settings.borrow().file_tree;
+            state.settings.borrow_mut().file_tree = !file_tree;
+
+            state.close_menu();
+            state.refresh_screen()
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Sort files by path, size or status")]
+pub(crate) struct SortFiles;
+impl OpTrait for SortFiles {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let order = match state.settings.borrow().file_order {
+                FileOrder::Path => FileOrder::Size,
+                FileOrder::Size => FileOrder::Status,
+                FileOrder::Status => FileOrder::Path,
+            };
+            state.settings.borrow_mut().file_order = order;
+
+            state.close_menu();
+            state.display_info(format!("Sorting files by {}", order));
+            state.refresh_screen()
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Filter files")]
+pub(crate) struct FilterFiles;
+impl OpTrait for FilterFiles {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt(
+            "Filter files by glob or substring, empty to show all",
+            filter_files,
+            true,
+        ))
+    }
+}
+
+fn filter_files(state: &mut State, _term: &mut Term, pattern: &str) -> Res<()> {
+    let filter = match pattern.trim() {
+        "" => None,
+        pattern => Some(FileFilter::new(pattern)?),
+    };
+    state.settings.borrow_mut().file_filter = filter;
+
+    state.close_menu();
+    state.refresh_screen()
+}
+
+/// Applies to every diff from now on, re-diffing the current screen.
+fn change_settings(state: &mut State, change: impl FnOnce(&mut DiffSettings)) -> Res<()> {
+    change(&mut state.settings.borrow_mut().diff);
+
+    state.close_menu();
+    state.refresh_screen()
+}
@@ -1,7 +1,10 @@
 use super::{Action, OpTrait};
-use crate::{git::diff::Hunk, items::TargetData};
+use crate::{
+    git::diff::{Hunk, PatchMode},
+    items::TargetData,
+};
 use derive_more::Display;
-use std::{path::PathBuf, process::Command, rc::Rc};
+use std::{ops::Range, path::PathBuf, process::Command, rc::Rc};
 
 #[derive(Display)]
 #[display(fmt = "Discard")]
@@ -10,16 +13,21 @@ impl OpTrait for Discard {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
         let action = match target.cloned() {
             Some(TargetData::Branch(branch)) => discard_branch(branch),
+            Some(TargetData::ConfigEntry { key, scope }) => {
+                super::git_config::unset_config_entry(key, scope)
+            }
             Some(TargetData::File(file)) => clean_file(file),
             Some(TargetData::Delta(d)) => match d.status {
                 git2::Delta::Added => remove_file(d.new_file),
+                git2::Delta::Renamed => discard_rename(d.old_file, d.new_file),
                 _ => checkout_file(d.old_file),
             },
             Some(TargetData::Hunk(h)) => discard_unstaged_patch(h),
+            Some(TargetData::HunkLine(h, lines)) => discard_unstaged_lines(h, lines),
             _ => return None,
         };
 
-        Some(super::create_y_n_prompt(action, "Really discard?"))
+        Some(action)
     }
 
     fn is_target_op(&self) -> bool {
@@ -71,12 +79,44 @@ fn checkout_file(file: PathBuf) -> Action {
     })
 }
 
+fn discard_rename(old_file: PathBuf, new_file: PathBuf) -> Action {
+    Rc::new(move |state, term| {
+        let mut rm_cmd = Command::new("git");
+        rm_cmd.args(["rm", "--force", "--"]);
+        rm_cmd.arg(&new_file);
+
+        let mut checkout_cmd = Command::new("git");
+        checkout_cmd.args(["checkout", "HEAD", "--"]);
+        checkout_cmd.arg(&old_file);
+
+        state.close_menu();
+        state.run_cmd(term, &[], rm_cmd)?;
+        state.run_cmd(term, &[], checkout_cmd)
+    })
+}
+
 fn discard_unstaged_patch(h: Rc<Hunk>) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
         cmd.args(["apply", "--reverse"]);
+        cmd.args(state.settings.borrow().diff.apply_args());
 
         state.close_menu();
         state.run_cmd(term, &h.format_patch().into_bytes(), cmd)
     })
 }
+
+fn discard_unstaged_lines(h: Rc<Hunk>, lines: Range<usize>) -> Action {
+    Rc::new(move |state, term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["apply", "--reverse", "--recount"]);
+        cmd.args(state.settings.borrow().diff.apply_args());
+
+        let input = h
+            .format_line_patch(lines.clone(), PatchMode::Reverse)
+            .into_bytes();
+
+        state.close_menu();
+        state.run_cmd(term, &input, cmd)
+    })
+}
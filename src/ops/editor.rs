@@ -1,8 +1,7 @@
-use super::{set_prompt, Action, OpTrait};
+use super::{create_prompt, set_prompt, Action, OpTrait};
 use crate::{
-    items::TargetData,
-    menu::PendingMenu,
-    screen::NavMode,
+    items::{Item, TargetData},
+    screen::{self, NavMode},
     state::{root_menu, State},
     term::Term,
     Res,
@@ -37,8 +36,9 @@ impl OpTrait for Quit {
                     Rc::get_mut(&mut action).unwrap()(state, term)?;
                 } else {
                     state.screens.pop();
-                    if let Some(screen) = state.screens.last_mut() {
-                        screen.update()?;
+                    state.forward_screens.clear();
+                    if !state.screens.is_empty() {
+                        state.refresh_screen()?;
                     }
                 }
             } else {
@@ -51,6 +51,44 @@ impl OpTrait for Quit {
     }
 }
 
+#[derive(Display)]
+#[display(fmt = "Back")]
+pub(crate) struct Back;
+impl OpTrait for Back {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            if state.screens.len() == 1 {
+                return Err("Already at the first screen".into());
+            }
+
+            let screen = state.screens.pop().unwrap();
+            state.forward_screens.push(screen);
+            state.refresh_screen()
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Forward")]
+pub(crate) struct Forward;
+impl OpTrait for Forward {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, term| {
+            state.close_menu();
+            let mut screen = state
+                .forward_screens
+                .pop()
+                .ok_or("No screen to go forward to")?;
+
+            // The terminal may have been resized meanwhile
+            screen.size = term.size()?;
+            state.screens.push(screen);
+            state.refresh_screen()
+        }))
+    }
+}
+
 #[derive(Display)]
 #[display(fmt = "Submenu")]
 pub(crate) struct OpenMenu(pub crate::menu::Menu);
@@ -58,7 +96,7 @@ impl OpTrait for OpenMenu {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         let submenu = self.0;
         Some(Rc::new(move |state, _term| {
-            state.pending_menu = Some(PendingMenu::init(submenu));
+            state.open_menu(submenu);
             Ok(())
         }))
     }
@@ -71,7 +109,47 @@ impl OpTrait for Refresh {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state, _term| {
             state.close_menu();
-            state.screen_mut().update()
+            state.hunk_cache.clear();
+            if let Some(pending) = state
+                .pull_requests
+                .as_ref()
+                .filter(|_| state.screen().name == screen::pull_requests::NAME)
+            {
+                pending.list_again();
+            }
+            state.refresh_screen()
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Cancel running command")]
+pub(crate) struct CancelCmd;
+impl OpTrait for CancelCmd {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| state.cancel_cmd()))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Show command output")]
+pub(crate) struct ShowCmdOutput;
+impl OpTrait for ShowCmdOutput {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, term| {
+            state.close_menu();
+
+            let entries = state.current_cmd_log.latest().to_vec();
+            if entries.is_empty() {
+                return Err("No command output to show".into());
+            }
+
+            state.push_screen(screen::cmd_output::create(
+                Rc::clone(&state.config),
+                term.size()?,
+                entries,
+            )?);
+            Ok(())
         }))
     }
 }
@@ -85,6 +163,7 @@ impl OpTrait for ToggleArg {
         Some(Rc::new(move |state, _term| {
             let mut need_prompt = None;
             let mut default = None;
+            let mut completions = None;
 
             let maybe_entry = if let Some(menu) = &mut state.pending_menu {
                 Some(menu.args.entry(arg_name.clone().into()))
@@ -98,6 +177,7 @@ impl OpTrait for ToggleArg {
                         arg.unset();
                     } else if arg.expects_value() {
                         default = arg.default_as_string();
+                        completions = arg.completions;
                         need_prompt = Some(arg.display);
                     } else {
                         arg.set("").expect("Should succeed");
@@ -114,6 +194,11 @@ impl OpTrait for ToggleArg {
                     arg_name.clone(),
                     false,
                 );
+
+                if let Some(completions) = completions {
+                    let candidates = completions(&state.repo)?;
+                    state.prompt.set_completions(candidates);
+                }
             }
 
             Ok(())
@@ -121,6 +206,7 @@ impl OpTrait for ToggleArg {
     }
 }
 
+#[allow(clippy::ptr_arg)]
 fn parse_and_set_arg(state: &mut State, _term: &mut Term, value: &str, arg: &String) -> Res<()> {
     let key: &str = arg;
     if let Some(menu) = &mut state.pending_menu {
@@ -145,6 +231,124 @@ impl OpTrait for ToggleSection {
     }
 }
 
+#[derive(Display)]
+#[display(fmt = "Toggle mark")]
+pub(crate) struct ToggleMark;
+impl OpTrait for ToggleMark {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(
+                TargetData::Commit(_)
+                | TargetData::Branch(_)
+                | TargetData::File(_)
+                | TargetData::Delta(_)
+                | TargetData::Hunk(_),
+            ) => Some(Rc::new(|state, _term| {
+                state.close_menu();
+                state.screen_mut().toggle_mark();
+                state.screen_mut().select_next(NavMode::Normal);
+                Ok(())
+            })),
+            _ => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Toggle region")]
+pub(crate) struct ToggleRegion;
+impl OpTrait for ToggleRegion {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::HunkLine(_, _)) => Some(Rc::new(|state, _term| {
+                state.close_menu();
+                state.screen_mut().toggle_region();
+                Ok(())
+            })),
+            _ => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Record macro")]
+pub(crate) struct ToggleMacroRecording;
+impl OpTrait for ToggleMacroRecording {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            state.unrecord_pending_keys();
+
+            match state.macro_recording.take() {
+                None => {
+                    state.macro_recording = Some(vec![]);
+                    state.display_info("Recording macro".to_string());
+                }
+                Some(keys) if keys.is_empty() => {
+                    state.display_info("No keys recorded, the previous macro is kept".to_string());
+                }
+                Some(keys) => {
+                    state.display_info(match keys.len() {
+                        1 => "Recorded a macro of 1 key".to_string(),
+                        count => format!("Recorded a macro of {} keys", count),
+                    });
+                    state.last_macro = keys;
+                }
+            }
+
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Replay macro")]
+pub(crate) struct ReplayMacro;
+impl OpTrait for ReplayMacro {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, term| {
+            state.close_menu();
+
+            if state.macro_recording.is_some() {
+                state.unrecord_pending_keys();
+                return Err("A macro can't be replayed while recording one".into());
+            }
+            if state.last_macro.is_empty() {
+                return Err("No macro has been recorded".into());
+            }
+
+            state.replay_macro(term)
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Repeat last action")]
+pub(crate) struct RepeatLastAction;
+impl OpTrait for RepeatLastAction {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, term| {
+            let Some((menu, op)) = state.last_target_op.clone() else {
+                return Err("No action to repeat".into());
+            };
+
+            match menu {
+                Some(menu) => state.open_menu(menu),
+                None => state.close_menu(),
+            }
+            state.handle_op(op, term)
+        }))
+    }
+}
+
 #[derive(Display)]
 #[display(fmt = "Up")]
 pub(crate) struct MoveUp;
@@ -270,3 +474,89 @@ impl OpTrait for HalfPageDown {
         }))
     }
 }
+
+#[derive(Display)]
+#[display(fmt = "Top")]
+pub(crate) struct MoveToTop;
+impl OpTrait for MoveToTop {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            state.screen_mut().select_top();
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Bottom")]
+pub(crate) struct MoveToBottom;
+impl OpTrait for MoveToBottom {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            state.screen_mut().select_bottom();
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Search")]
+pub(crate) struct Search;
+impl OpTrait for Search {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt("Search", search, true))
+    }
+}
+
+fn search(state: &mut State, _term: &mut Term, pattern: &str) -> Res<()> {
+    state.close_menu();
+    state.search = match pattern {
+        "" => None,
+        pattern => Some(pattern.to_string()),
+    };
+
+    select_next_match(state)
+}
+
+#[derive(Display)]
+#[display(fmt = "Search again")]
+pub(crate) struct SearchNext;
+impl OpTrait for SearchNext {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            select_next_match(state)
+        }))
+    }
+}
+
+/// Ignoring case, unless the pattern has upper case letters.
+fn select_next_match(state: &mut State) -> Res<()> {
+    let Some(pattern) = state.search.clone() else {
+        return Err("Nothing to search for".into());
+    };
+
+    let ignore_case = !pattern.chars().any(char::is_uppercase);
+    let matches = |item: &Item| {
+        let text = item
+            .display
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect::<String>();
+
+        if ignore_case {
+            text.to_lowercase().contains(&pattern)
+        } else {
+            text.contains(&pattern)
+        }
+    };
+
+    if !state.screen_mut().select_next_matching(matches) {
+        return Err(format!("No match for {:?}", pattern).into());
+    }
+
+    Ok(())
+}
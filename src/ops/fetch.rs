@@ -1,12 +1,22 @@
-use super::{create_prompt, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use super::{confirmed, create_y_n_prompt, set_prompt, Action, OpTrait};
+use crate::{
+    git::{self, branch_cleanup},
+    items::TargetData,
+    menu::arg::{positive_number, Arg},
+    state::State,
+    term::Term,
+    Res,
+};
 use derive_more::Display;
-use std::{process::Command, rc::Rc};
+use std::{ffi::OsString, process::Command, rc::Rc};
 
 pub(crate) fn init_args() -> Vec<Arg> {
     vec![
         Arg::new_flag("--prune", "Prune deleted branches", false),
         Arg::new_flag("--tags", "Fetch all tags", false),
+        Arg::new_arg("--depth", "Limit history to depth", None, positive_number),
+        Arg::new_arg("--deepen", "Deepen history by", None, positive_number),
+        Arg::new_flag("--unshallow", "Fetch all history", false),
     ]
 }
 
@@ -27,16 +37,90 @@ impl OpTrait for FetchAll {
     }
 }
 
+#[derive(Display)]
+#[display(fmt = "Prune remote branches")]
+pub(crate) struct FetchPrune;
+impl OpTrait for FetchPrune {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["fetch", "--all", "--prune"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)?;
+
+            let current = git::current_branch(&state.repo);
+            let gone = git::branch_names(&state.repo)?
+                .into_iter()
+                .filter(|name| Some(name) != current.as_ref())
+                .filter(|name| branch_cleanup::gone_upstream(&state.repo, name).is_some())
+                .collect::<Vec<_>>();
+            if gone.is_empty() {
+                return Ok(());
+            }
+
+            let prompt = match &gone[..] {
+                [branch] => format!("Delete {}, its upstream is gone?", branch),
+                branches => format!("Delete {}, their upstreams are gone?", branches.join(", ")),
+            };
+            let delete = Rc::new(move |state: &mut State, term: &mut Term| {
+                delete_gone_branches(state, term, &gone)
+            });
+
+            let policy = state.config.general.confirm.delete_branch;
+            let mut confirm = confirmed(policy, delete, prompt);
+            Rc::get_mut(&mut confirm).unwrap()(state, term)?;
+            Ok(())
+        }))
+    }
+}
+
+/// Deletes the branches with `git branch -d`. Any that aren't merged are left, unless the user
+/// confirms once more to force them.
+fn delete_gone_branches(state: &mut State, term: &mut Term, gone: &[String]) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["branch", "-d"]);
+    cmd.args(gone);
+    let result = state.run_cmd(term, &[], cmd);
+
+    let unmerged = gone
+        .iter()
+        .filter(|name| {
+            state
+                .repo
+                .find_branch(name, git2::BranchType::Local)
+                .is_ok()
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if unmerged.is_empty() {
+        return result;
+    }
+
+    let prompt = format!("Force delete {}, not fully merged?", unmerged.join(", "));
+    let force_delete = Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["branch", "-D"]);
+        cmd.args(&unmerged);
+        state.run_cmd(term, &[], cmd)
+    });
+
+    let mut confirm = create_y_n_prompt(force_delete, prompt);
+    Rc::get_mut(&mut confirm).unwrap()(state, term)
+}
+
 #[derive(Display)]
 #[display(fmt = "Fetch from elsewhere")]
 pub(crate) struct FetchElsewhere;
 impl OpTrait for FetchElsewhere {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt("Select remote", push_elsewhere, true))
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            set_remote_prompt(state, fetch_elsewhere, ())
+        }))
     }
 }
 
-fn push_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
+fn fetch_elsewhere(state: &mut State, term: &mut Term, remote: &str, _: &()) -> Res<()> {
     let mut cmd = Command::new("git");
     cmd.args(["fetch"]);
     cmd.args(state.pending_menu.as_ref().unwrap().args());
@@ -46,3 +130,107 @@ fn push_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
     state.run_cmd_async(term, &[], cmd)?;
     Ok(())
 }
+
+#[derive(Display)]
+#[display(fmt = "Fetch refspec")]
+pub(crate) struct FetchRefspec;
+impl OpTrait for FetchRefspec {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let args = state.pending_menu.as_ref().unwrap().args();
+            set_remote_prompt(state, fetch_refspec_remote, args)
+        }))
+    }
+}
+
+fn set_remote_prompt<T: 'static>(
+    state: &mut State,
+    callback: fn(&mut State, &mut Term, &str, &T) -> Res<()>,
+    context: T,
+) -> Res<()> {
+    set_prompt(
+        state,
+        "Fetch from remote",
+        callback,
+        Box::new(|state| git::upstream_remote(&state.repo)),
+        context,
+        true,
+    );
+
+    let completions = git::remote_names(&state.repo)?;
+    state.prompt.set_completions(completions);
+    Ok(())
+}
+
+#[allow(clippy::ptr_arg)]
+fn fetch_refspec_remote(
+    state: &mut State,
+    _term: &mut Term,
+    remote: &str,
+    args: &Vec<OsString>,
+) -> Res<()> {
+    state.close_menu();
+
+    set_prompt(
+        state,
+        "Fetch refspec",
+        fetch_refspec,
+        Box::new(|_| None),
+        (remote.to_string(), args.clone()),
+        false,
+    );
+
+    let prefix = format!("{}/", remote);
+    let completions = git::ref_names(&state.repo)?
+        .into_iter()
+        .filter_map(|name| name.strip_prefix(&prefix).map(str::to_string))
+        .collect();
+    state.prompt.set_completions(completions);
+    Ok(())
+}
+
+fn fetch_refspec(
+    state: &mut State,
+    term: &mut Term,
+    refspec: &str,
+    (remote, args): &(String, Vec<OsString>),
+) -> Res<()> {
+    if refspec.is_empty() {
+        return Err("No refspec given".into());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["fetch"]);
+    cmd.args(args);
+    cmd.args([remote, refspec]);
+
+    state.run_cmd_async(term, &[], cmd)?;
+    Ok(())
+}
+
+#[derive(Display)]
+#[display(fmt = "Pull LFS objects")]
+pub(crate) struct LfsPull;
+impl OpTrait for LfsPull {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        // Only the selected file if it's tracked by git-lfs, otherwise everything checked out
+        let include = match target {
+            Some(TargetData::Delta(delta)) if delta.lfs.is_some() => {
+                Some(delta.new_file.to_string_lossy().to_string())
+            }
+            _ => None,
+        };
+
+        Some(Rc::new(move |state, term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["lfs", "pull"]);
+            if let Some(include) = &include {
+                cmd.args(["--include", include, "--exclude", ""]);
+            }
+
+            state.close_menu();
+            state.run_cmd_async(term, &[], cmd)?;
+            Ok(())
+        }))
+    }
+}
@@ -0,0 +1,219 @@
+use super::{set_prompt, Action, OpTrait};
+use crate::{
+    forge::{
+        self,
+        issues::{self, IssueRef},
+        Forge, PendingPullRequests,
+    },
+    git,
+    items::TargetData,
+    screen,
+    state::State,
+    term::Term,
+    Res,
+};
+use derive_more::Display;
+use itertools::Itertools;
+use std::{process::Command, rc::Rc};
+
+#[derive(Display)]
+#[display(fmt = "List pull requests")]
+pub(crate) struct ForgeListPullRequests;
+impl OpTrait for ForgeListPullRequests {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            let forge = Forge::detect(&state.repo)?;
+            let size = state.screen().size;
+            let pending = PendingPullRequests::list(forge, &state.repo);
+            state.pull_requests = Some(pending.clone());
+
+            state.push_screen(screen::pull_requests::create(
+                Rc::clone(&state.config),
+                size,
+                pending,
+            )?);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Checkout pull request")]
+pub(crate) struct CheckoutPullRequest;
+impl OpTrait for CheckoutPullRequest {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::PullRequest { forge, number }) = target else {
+            return None;
+        };
+
+        let (forge, number) = (*forge, *number);
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            state.close_menu();
+            state.run_cmd_async(term, &[], forge.checkout_cmd(number))
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Create pull request")]
+pub(crate) struct ForgeCreatePullRequest;
+impl OpTrait for ForgeCreatePullRequest {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let (forge, branch) = pushed_branch(state)?;
+            let (title, body) = forge::pull_request_message(&state.repo, &branch)?;
+
+            state.close_menu();
+            set_prompt(
+                state,
+                "Pull request title",
+                create_pull_request,
+                Box::new(move |_| Some(title.clone())),
+                (forge, branch, body),
+                false,
+            );
+            Ok(())
+        }))
+    }
+}
+
+fn create_pull_request(
+    state: &mut State,
+    term: &mut Term,
+    title: &str,
+    (forge, branch, body): &(Forge, String, String),
+) -> Res<()> {
+    state.run_cmd_async(term, &[], forge.create_cmd(branch, title, body))
+}
+
+#[derive(Display)]
+#[display(fmt = "Open new pull request in browser")]
+pub(crate) struct ForgeOpenNewPullRequest;
+impl OpTrait for ForgeOpenNewPullRequest {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let (forge, branch) = pushed_branch(state)?;
+            let url = forge.new_pull_request_url(&state.repo, &branch)?;
+
+            state.close_menu();
+            state.run_cmd_async(term, &[], open_in_browser(&url))
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Open issue in browser")]
+pub(crate) struct OpenIssue;
+impl OpTrait for OpenIssue {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Commit(commit)) => {
+                let commit = commit.clone();
+                Some(Rc::new(move |state: &mut State, term: &mut Term| {
+                    let message = state
+                        .repo
+                        .revparse_single(&commit)?
+                        .peel_to_commit()?
+                        .message()
+                        .unwrap_or("")
+                        .to_string();
+                    let refs = issues::find(&issues::patterns(&state.repo), &message);
+                    open_issue(state, term, refs)
+                }))
+            }
+            Some(TargetData::IssueReferences(refs)) => {
+                let refs = Rc::clone(refs);
+                Some(Rc::new(move |state: &mut State, term: &mut Term| {
+                    open_issue(state, term, refs.to_vec())
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+/// Opens the only reference right away, otherwise asks which one.
+fn open_issue(state: &mut State, term: &mut Term, refs: Vec<IssueRef>) -> Res<()> {
+    let refs = refs
+        .into_iter()
+        .unique_by(|issue| issue.url.clone())
+        .collect::<Vec<_>>();
+
+    match &refs[..] {
+        [] => Err("No issue references in commit message".into()),
+        [issue] => {
+            state.close_menu();
+            state.run_cmd_async(term, &[], open_in_browser(&issue.url))
+        }
+        [first, ..] => {
+            let default = first.text.clone();
+            let completions = refs.iter().map(|issue| issue.text.clone()).collect();
+            set_prompt(
+                state,
+                "Open issue",
+                open_issue_reference,
+                Box::new(move |_| Some(default.clone())),
+                Rc::<[IssueRef]>::from(refs),
+                true,
+            );
+            state.prompt.set_completions(completions);
+            Ok(())
+        }
+    }
+}
+
+fn open_issue_reference(
+    state: &mut State,
+    term: &mut Term,
+    input: &str,
+    refs: &Rc<[IssueRef]>,
+) -> Res<()> {
+    let issue = refs
+        .iter()
+        .find(|issue| issue.text == input)
+        .ok_or_else(|| format!("No issue reference: {}", input))?;
+
+    state.close_menu();
+    state.run_cmd_async(term, &[], open_in_browser(&issue.url))
+}
+
+/// The current branch, which the forge needs to have been pushed to open a pull request.
+fn pushed_branch(state: &State) -> Res<(Forge, String)> {
+    let forge = Forge::detect(&state.repo)?;
+    let branch = git::current_branch(&state.repo).ok_or("Not on a branch")?;
+
+    if git::branch_upstream_name(&state.repo, &branch).is_none() {
+        return Err(format!(
+            "Push {} before creating a {}",
+            branch,
+            forge.pull_request_name()
+        )
+        .into());
+    }
+
+    Ok((forge, branch))
+}
+
+fn open_in_browser(url: &str) -> Command {
+    let mut cmd = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "start", ""]);
+        cmd
+    } else {
+        Command::new("xdg-open")
+    };
+
+    cmd.arg(url);
+    cmd
+}
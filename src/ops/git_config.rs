@@ -0,0 +1,76 @@
+use super::{set_prompt, Action, OpTrait};
+use crate::{
+    git::config::{self, ConfigScope, PULL_REBASE_VALUES},
+    items::TargetData,
+    screen,
+    state::State,
+    term::Term,
+    Res,
+};
+use derive_more::Display;
+use std::{process::Command, rc::Rc};
+
+#[derive(Display)]
+#[display(fmt = "Show git config")]
+pub(crate) struct ShowGitConfig;
+impl OpTrait for ShowGitConfig {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+            state.push_screen(screen::git_config::create(
+                Rc::clone(&state.config),
+                Rc::clone(&state.repo),
+                term.size()?,
+            )?);
+            Ok(())
+        }))
+    }
+}
+
+pub(crate) fn edit_config_entry(key: &'static str, scope: ConfigScope) -> Action {
+    Rc::new(move |state: &mut State, _term: &mut Term| {
+        let dir = state.repo.workdir().expect("No workdir");
+        let current = config::list(dir, scope)?.remove(key);
+
+        set_prompt(
+            state,
+            "Set value",
+            set_config_entry,
+            Box::new(move |_| current.clone()),
+            (key, scope),
+            true,
+        );
+
+        if key == "pull.rebase" {
+            let completions = PULL_REBASE_VALUES.iter().map(|v| v.to_string()).collect();
+            state.prompt.set_completions(completions);
+        }
+
+        Ok(())
+    })
+}
+
+fn set_config_entry(
+    state: &mut State,
+    term: &mut Term,
+    value: &str,
+    &(key, scope): &(&'static str, ConfigScope),
+) -> Res<()> {
+    config::validate(key, value)?;
+
+    let mut cmd = Command::new("git");
+    cmd.args(["config", scope.flag(), key, value]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+pub(crate) fn unset_config_entry(key: &'static str, scope: ConfigScope) -> Action {
+    Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["config", scope.flag(), "--unset", key]);
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    })
+}
@@ -0,0 +1,90 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, screen, state::State, term::Term};
+use derive_more::Display;
+use std::{path::PathBuf, process::Command, rc::Rc};
+
+#[derive(Display)]
+#[display(fmt = "Show hidden files")]
+pub(crate) struct ShowFlaggedFiles;
+impl OpTrait for ShowFlaggedFiles {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+            state.push_screen(screen::flagged_files::create(
+                Rc::clone(&state.config),
+                Rc::clone(&state.repo),
+                term.size()?,
+            )?);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Assume unchanged")]
+pub(crate) struct AssumeUnchanged;
+impl OpTrait for AssumeUnchanged {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        update_index(target, "--assume-unchanged")
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "No longer assume unchanged")]
+pub(crate) struct NoAssumeUnchanged;
+impl OpTrait for NoAssumeUnchanged {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        update_index(target, "--no-assume-unchanged")
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Skip worktree")]
+pub(crate) struct SkipWorktree;
+impl OpTrait for SkipWorktree {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        update_index(target, "--skip-worktree")
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "No longer skip worktree")]
+pub(crate) struct NoSkipWorktree;
+impl OpTrait for NoSkipWorktree {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        update_index(target, "--no-skip-worktree")
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+fn update_index(target: Option<&TargetData>, flag: &'static str) -> Option<Action> {
+    let file = target_file(target)?;
+
+    Some(Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["update-index", flag, "--"]);
+        cmd.arg(&file);
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    }))
+}
+
+fn target_file(target: Option<&TargetData>) -> Option<PathBuf> {
+    match target {
+        Some(TargetData::File(file)) => Some(file.clone()),
+        Some(TargetData::Delta(d)) => Some(d.new_file.clone()),
+        _ => None,
+    }
+}
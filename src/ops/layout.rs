@@ -0,0 +1,109 @@
+use super::{Action, OpTrait};
+use crate::{config::CmdOutput, items::TargetData, state::State, term::Term, ui};
+use derive_more::Display;
+use std::rc::Rc;
+
+/// Percent of the terminal width the list pane changes by.
+const LIST_WIDTH_STEP: u16 = 10;
+const POPUP_HEIGHT_STEP: u16 = 5;
+const MIN_POPUP_HEIGHT: u16 = 3;
+
+#[derive(Display)]
+#[display(fmt = "Widen the list pane")]
+pub(crate) struct WidenList;
+impl OpTrait for WidenList {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            resize_list(state, LIST_WIDTH_STEP as i16);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Narrow the list pane")]
+pub(crate) struct NarrowList;
+impl OpTrait for NarrowList {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            resize_list(state, -(LIST_WIDTH_STEP as i16));
+            Ok(())
+        }))
+    }
+}
+
+fn resize_list(state: &mut State, change: i16) {
+    let layout = &mut state.layout;
+    layout.list_width = layout
+        .list_width
+        .saturating_add_signed(change)
+        .clamp(ui::MIN_LIST_WIDTH, ui::MAX_LIST_WIDTH);
+
+    let message = format!("List pane at {}% of the width", layout.list_width);
+    state.display_info(message);
+}
+
+#[derive(Display)]
+#[display(fmt = "Taller popups")]
+pub(crate) struct TallerPopups;
+impl OpTrait for TallerPopups {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            if state.layout.popup_max_height == 0 {
+                state.display_info("Popups already fit their content".to_string());
+                return Ok(());
+            }
+
+            state.layout.popup_max_height += POPUP_HEIGHT_STEP;
+            display_popup_height(state);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Shorter popups")]
+pub(crate) struct ShorterPopups;
+impl OpTrait for ShorterPopups {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let height = match state.layout.popup_max_height {
+                // Unlimited, popups could take up at most the whole screen
+                0 => state.screen().size.height,
+                height => height,
+            };
+
+            state.layout.popup_max_height = height
+                .saturating_sub(POPUP_HEIGHT_STEP)
+                .max(MIN_POPUP_HEIGHT);
+            display_popup_height(state);
+            Ok(())
+        }))
+    }
+}
+
+fn display_popup_height(state: &mut State) {
+    let message = format!(
+        "Popups at most {} lines high",
+        state.layout.popup_max_height
+    );
+    state.display_info(message);
+}
+
+#[derive(Display)]
+#[display(fmt = "Dock or overlay command output")]
+pub(crate) struct ToggleCmdOutputDock;
+impl OpTrait for ToggleCmdOutputDock {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.layout.cmd_output = match state.layout.cmd_output {
+                CmdOutput::Docked => CmdOutput::Overlaid,
+                CmdOutput::Overlaid => CmdOutput::Docked,
+            };
+
+            let message = format!("Command output {}", state.layout.cmd_output);
+            state.display_info(message);
+            Ok(())
+        }))
+    }
+}
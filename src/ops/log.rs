@@ -1,6 +1,7 @@
-use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
+use super::{create_rev_prompt, selected_rev, Action, OpTrait};
 use crate::{
-    items::TargetData,
+    config::DateFormat,
+    items::{LineRange, LogFilter, TargetData},
     menu::arg::{any_regex, positive_number, Arg},
     screen,
     state::State,
@@ -42,12 +43,7 @@ impl OpTrait for LogCurrent {
 pub(crate) struct LogOther;
 impl OpTrait for LogOther {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
-            "Log rev",
-            log_other,
-            selected_rev,
-            true,
-        ))
+        Some(create_rev_prompt("Log rev", log_other, selected_rev, true))
     }
 }
 
@@ -67,6 +63,147 @@ fn log_other(state: &mut State, _term: &mut Term, result: &str) -> Res<()> {
     Ok(())
 }
 
+#[derive(Display)]
+#[display(fmt = "Shortlog")]
+pub(crate) struct Shortlog;
+impl OpTrait for Shortlog {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt(
+            "Shortlog range",
+            shortlog,
+            selected_rev_or_head,
+            true,
+        ))
+    }
+}
+
+fn selected_rev_or_head(state: &State) -> Option<String> {
+    selected_rev(state).or_else(|| Some("HEAD".to_string()))
+}
+
+fn shortlog(state: &mut State, term: &mut Term, range: &str) -> Res<()> {
+    state.close_menu();
+    state.push_screen(screen::shortlog::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        term.size()?,
+        range.to_string(),
+    )?);
+    Ok(())
+}
+
+/// The commits of `email` in the shortlog's `range`, on top of the shortlog.
+pub(crate) fn log_author(range: String, email: String) -> Action {
+    Rc::new(move |state: &mut State, term: &mut Term| {
+        let (rev, hidden) = resolve_range(&state.repo, &range)?;
+
+        state.close_menu();
+        state.push_screen(screen::log::create(
+            Rc::clone(&state.config),
+            state.ci_statuses.clone(),
+            Rc::clone(&state.repo),
+            term.size()?,
+            usize::MAX,
+            Some(rev),
+            LogFilter {
+                author: Some(email.clone()),
+                hidden,
+                ..Default::default()
+            },
+        )?);
+        Ok(())
+    })
+}
+
+#[derive(Display)]
+#[display(fmt = "Log lines")]
+pub(crate) struct LogLines;
+impl OpTrait for LogLines {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let (hunk, lines) = match target {
+            Some(TargetData::Hunk(hunk)) => (Rc::clone(hunk), 0..hunk.content.lines.len()),
+            Some(TargetData::HunkLine(hunk, lines)) => (Rc::clone(hunk), lines.clone()),
+            _ => return None,
+        };
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            // A shown commit's changes are followed back from it, other changes are
+            // of the working tree and followed back from the lines they replace at `HEAD`.
+            let rev = state
+                .screen()
+                .commit
+                .as_deref()
+                .map(Oid::from_str)
+                .transpose()?;
+
+            let (start, end) = hunk
+                .line_range(lines.clone(), rev.is_some())
+                .ok_or("The lines have no history")?;
+
+            state.close_menu();
+            state.push_screen(screen::log::create(
+                Rc::clone(&state.config),
+                state.ci_statuses.clone(),
+                Rc::clone(&state.repo),
+                term.size()?,
+                usize::MAX,
+                rev,
+                LogFilter {
+                    lines: Some(LineRange {
+                        file: hunk.new_file.clone(),
+                        start,
+                        end,
+                    }),
+                    ..Default::default()
+                },
+            )?);
+            Ok(())
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Cycle date format")]
+pub(crate) struct CycleDateFormat;
+impl OpTrait for CycleDateFormat {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            // A custom format from the config is cycled through after relative dates
+            let format = match state.settings.borrow().date_format {
+                DateFormat::Rfc => DateFormat::Iso,
+                DateFormat::Iso => DateFormat::Relative,
+                DateFormat::Relative => match &state.config.general.date_format {
+                    custom @ DateFormat::Format(_) => custom.clone(),
+                    _ => DateFormat::Rfc,
+                },
+                DateFormat::Format(_) => DateFormat::Rfc,
+            };
+            state.settings.borrow_mut().date_format = format.clone();
+
+            state.close_menu();
+            state.display_info(format!("Showing {} dates", format));
+            state.refresh_screen()
+        }))
+    }
+}
+
+/// The tip of a `from..to` range and the commit to hide, or just a revision.
+fn resolve_range(repo: &git2::Repository, range: &str) -> Res<(Oid, Option<Oid>)> {
+    let revspec = repo.revparse(range)?;
+    if revspec.mode().contains(git2::RevparseMode::MERGE_BASE) {
+        return Err(format!("Can't log the symmetric difference {}", range).into());
+    }
+
+    match (revspec.from(), revspec.to()) {
+        (Some(from), Some(to)) => Ok((to.id(), Some(from.id()))),
+        (Some(rev), None) | (None, Some(rev)) => Ok((rev.id(), None)),
+        (None, None) => Err(format!("Couldn't resolve {}", range).into()),
+    }
+}
+
 fn goto_log_screen(state: &mut State, rev: Option<Oid>) {
     state.screens.drain(1..);
     let size = state.screens.last().unwrap().size;
@@ -86,14 +223,18 @@ fn goto_log_screen(state: &mut State, rev: Option<Oid>) {
 
     state.close_menu();
 
-    state.screens.push(
+    state.push_screen(
         screen::log::create(
             Rc::clone(&state.config),
+            state.ci_statuses.clone(),
             Rc::clone(&state.repo),
             size,
             limit as usize,
             rev,
-            msg_regex,
+            LogFilter {
+                msg_regex,
+                ..Default::default()
+            },
         )
         .expect("Couldn't create screen"),
     );
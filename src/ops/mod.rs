@@ -1,28 +1,46 @@
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use tui_prompts::State as _;
 
 use crate::{
-    cmd_log::CmdLogEntry, items::TargetData, menu::Menu, prompt::PromptData, state::State,
-    term::Term, Res,
+    cmd_log::CmdLogEntry, config::Confirm, git, git::diff::Delta, items::TargetData, menu::Menu,
+    prompt::PromptData, state::State, term::Term, Res,
 };
-use std::{fmt::Display, rc::Rc};
+use std::{borrow::Cow, fmt::Display, rc::Rc};
 
+pub(crate) mod am;
+pub(crate) mod apply;
+pub(crate) mod archive;
 pub(crate) mod checkout;
+pub(crate) mod cherry_pick;
+pub(crate) mod clone;
 pub(crate) mod commit;
 pub(crate) mod copy_hash;
+pub(crate) mod describe;
+pub(crate) mod diff;
 pub(crate) mod discard;
 pub(crate) mod editor;
 pub(crate) mod fetch;
+pub(crate) mod forge;
+pub(crate) mod git_config;
+pub(crate) mod index_flags;
+pub(crate) mod layout;
 pub(crate) mod log;
+pub(crate) mod notes;
+pub(crate) mod patch;
+pub(crate) mod pre_commit;
 pub(crate) mod pull;
 pub(crate) mod push;
 pub(crate) mod rebase;
+pub(crate) mod rerere;
 pub(crate) mod reset;
 pub(crate) mod revert;
 pub(crate) mod show;
 pub(crate) mod show_refs;
+pub(crate) mod sparse_checkout;
 pub(crate) mod stage;
 pub(crate) mod stash;
+pub(crate) mod stats;
 pub(crate) mod unstage;
 
 pub(crate) type Action = Rc<dyn FnMut(&mut State, &mut Term) -> Res<()>>;
@@ -42,17 +60,56 @@ pub(crate) trait OpTrait: Display {
 #[derive(Clone, PartialOrd, Ord, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub(crate) enum Op {
+    AmApply,
+    AmContinue,
+    AmSkip,
+    AmAbort,
     Checkout,
+    CheckoutFile,
     CheckoutNewBranch,
+    SetUpstream,
+    Spinoff,
+    ShowBranchCleanup,
+    DeleteBranches,
+    CherryPick,
+    CherryPickAbort,
+    CherryPickContinue,
     Commit,
     CommitAmend,
+    CommitConventional,
+    PreCommitRun,
+    PreCommitStageFixes,
+    DiffMergeBase,
+    DiffRange,
+    DiffWorktree,
+    MoreContext,
+    LessContext,
+    WholeFileContext,
+    IgnoreAllSpace,
+    IgnoreSpaceChange,
+    IgnoreBlankLines,
+    CycleDiffAlgorithm,
+    ToggleFileTree,
+    SortFiles,
+    FilterFiles,
     FetchAll,
+    FetchPrune,
     FetchElsewhere,
+    FetchRefspec,
+    LfsPull,
+    ForgeListPullRequests,
+    ForgeCreatePullRequest,
+    ForgeOpenNewPullRequest,
+    CheckoutPullRequest,
+    OpenIssue,
+    FormatPatch,
+    Archive,
     LogCurrent,
     Pull,
     PullElsewhere,
     Push,
     PushElsewhere,
+    PushCommit,
     RebaseAbort,
     RebaseContinue,
     RebaseElsewhere,
@@ -62,26 +119,75 @@ pub(crate) enum Op {
     StashIndex,
     StashWorktree,
     StashKeepIndex,
+    StashSelected,
     StashPop,
     StashDrop,
     CommitFixup,
+    CommitInstantFixup,
+    CommitReword,
     LogOther,
+    Shortlog,
+    LogLines,
+    CycleDateFormat,
+    NoteEdit,
+    NoteRemove,
     RebaseAutosquash,
+    RebaseDrop,
+    RebaseSquash,
+    RebaseTodoPick,
+    RebaseTodoSquash,
+    RebaseTodoFixup,
+    RebaseTodoEdit,
+    RebaseTodoDrop,
+    RebaseTodoMoveUp,
+    RebaseTodoMoveDown,
+    RebaseTodoStart,
     RebaseInteractive,
     ResetSoft,
     ResetMixed,
     ResetHard,
+    RerereForget,
     RevertAbort,
     RevertContinue,
     RevertCommit,
+    WidenList,
+    NarrowList,
+    TallerPopups,
+    ShorterPopups,
+    ToggleCmdOutputDock,
+    SparseCheckoutShow,
+    SparseCheckoutAdd,
+    SparseCheckoutSet,
+    SparseCheckoutToggleCone,
+    SparseCheckoutDisable,
+    ShowFlaggedFiles,
+    AssumeUnchanged,
+    NoAssumeUnchanged,
+    SkipWorktree,
+    NoSkipWorktree,
 
     Stage,
     Unstage,
     Show,
+    Back,
+    Forward,
+    ShowGitConfig,
+    ShowStats,
+    ShowPrevCommit,
+    ShowNextCommit,
+    VisitWorktreeFile,
     Discard,
+    Apply,
     CopyHash,
+    Describe,
+    Clone,
 
     ToggleSection,
+    ToggleMark,
+    ToggleRegion,
+    ToggleMacroRecording,
+    ReplayMacro,
+    RepeatLastAction,
     MoveUp,
     MoveDown,
     MoveUpLine,
@@ -91,8 +197,14 @@ pub(crate) enum Op {
     MoveParentSection,
     HalfPageUp,
     HalfPageDown,
+    MoveToTop,
+    MoveToBottom,
+    Search,
+    SearchNext,
 
     Refresh,
+    CancelCmd,
+    ShowCmdOutput,
     Quit,
 
     #[serde(untagged)]
@@ -107,8 +219,15 @@ impl Op {
             Op::Quit => Box::new(editor::Quit),
             Op::OpenMenu(menu) => Box::new(editor::OpenMenu(menu)),
             Op::Refresh => Box::new(editor::Refresh),
+            Op::CancelCmd => Box::new(editor::CancelCmd),
+            Op::ShowCmdOutput => Box::new(editor::ShowCmdOutput),
             Op::ToggleArg(name) => Box::new(editor::ToggleArg(name)),
             Op::ToggleSection => Box::new(editor::ToggleSection),
+            Op::ToggleMark => Box::new(editor::ToggleMark),
+            Op::ToggleRegion => Box::new(editor::ToggleRegion),
+            Op::ToggleMacroRecording => Box::new(editor::ToggleMacroRecording),
+            Op::ReplayMacro => Box::new(editor::ReplayMacro),
+            Op::RepeatLastAction => Box::new(editor::RepeatLastAction),
             Op::MoveDown => Box::new(editor::MoveDown),
             Op::MoveUp => Box::new(editor::MoveUp),
             Op::MoveDownLine => Box::new(editor::MoveDownLine),
@@ -118,18 +237,76 @@ impl Op {
             Op::MoveParentSection => Box::new(editor::MoveParentSection),
             Op::HalfPageUp => Box::new(editor::HalfPageUp),
             Op::HalfPageDown => Box::new(editor::HalfPageDown),
+            Op::MoveToTop => Box::new(editor::MoveToTop),
+            Op::MoveToBottom => Box::new(editor::MoveToBottom),
+            Op::Search => Box::new(editor::Search),
+            Op::SearchNext => Box::new(editor::SearchNext),
 
+            Op::AmAbort => Box::new(am::AmAbort),
+            Op::AmApply => Box::new(am::AmApply),
+            Op::AmContinue => Box::new(am::AmContinue),
+            Op::AmSkip => Box::new(am::AmSkip),
             Op::Checkout => Box::new(checkout::Checkout),
+            Op::CheckoutFile => Box::new(checkout::CheckoutFile),
             Op::CheckoutNewBranch => Box::new(checkout::CheckoutNewBranch),
+            Op::SetUpstream => Box::new(checkout::SetUpstream),
+            Op::Spinoff => Box::new(checkout::Spinoff),
+            Op::ShowBranchCleanup => Box::new(checkout::ShowBranchCleanup),
+            Op::DeleteBranches => Box::new(checkout::DeleteBranches),
+            Op::CherryPick => Box::new(cherry_pick::CherryPick),
+            Op::CherryPickAbort => Box::new(cherry_pick::CherryPickAbort),
+            Op::CherryPickContinue => Box::new(cherry_pick::CherryPickContinue),
             Op::Commit => Box::new(commit::Commit),
             Op::CommitAmend => Box::new(commit::CommitAmend),
+            Op::CommitConventional => Box::new(commit::CommitConventional),
+            Op::PreCommitRun => Box::new(pre_commit::PreCommitRun),
+            Op::PreCommitStageFixes => Box::new(pre_commit::PreCommitStageFixes),
+            Op::DiffMergeBase => Box::new(diff::DiffMergeBase),
+            Op::DiffRange => Box::new(diff::DiffRange),
+            Op::DiffWorktree => Box::new(diff::DiffWorktree),
+            Op::MoreContext => Box::new(diff::MoreContext),
+            Op::LessContext => Box::new(diff::LessContext),
+            Op::WholeFileContext => Box::new(diff::WholeFileContext),
+            Op::IgnoreAllSpace => Box::new(diff::IgnoreAllSpace),
+            Op::IgnoreSpaceChange => Box::new(diff::IgnoreSpaceChange),
+            Op::IgnoreBlankLines => Box::new(diff::IgnoreBlankLines),
+            Op::CycleDiffAlgorithm => Box::new(diff::CycleDiffAlgorithm),
+            Op::WidenList => Box::new(layout::WidenList),
+            Op::NarrowList => Box::new(layout::NarrowList),
+            Op::TallerPopups => Box::new(layout::TallerPopups),
+            Op::ShorterPopups => Box::new(layout::ShorterPopups),
+            Op::ToggleCmdOutputDock => Box::new(layout::ToggleCmdOutputDock),
+            Op::SparseCheckoutShow => Box::new(sparse_checkout::SparseCheckoutShow),
+            Op::SparseCheckoutAdd => Box::new(sparse_checkout::SparseCheckoutAdd),
+            Op::SparseCheckoutSet => Box::new(sparse_checkout::SparseCheckoutSet),
+            Op::SparseCheckoutToggleCone => Box::new(sparse_checkout::SparseCheckoutToggleCone),
+            Op::SparseCheckoutDisable => Box::new(sparse_checkout::SparseCheckoutDisable),
+            Op::ShowFlaggedFiles => Box::new(index_flags::ShowFlaggedFiles),
+            Op::AssumeUnchanged => Box::new(index_flags::AssumeUnchanged),
+            Op::NoAssumeUnchanged => Box::new(index_flags::NoAssumeUnchanged),
+            Op::SkipWorktree => Box::new(index_flags::SkipWorktree),
+            Op::NoSkipWorktree => Box::new(index_flags::NoSkipWorktree),
+            Op::ToggleFileTree => Box::new(diff::ToggleFileTree),
+            Op::SortFiles => Box::new(diff::SortFiles),
+            Op::FilterFiles => Box::new(diff::FilterFiles),
             Op::FetchAll => Box::new(fetch::FetchAll),
+            Op::FetchPrune => Box::new(fetch::FetchPrune),
             Op::FetchElsewhere => Box::new(fetch::FetchElsewhere),
+            Op::FetchRefspec => Box::new(fetch::FetchRefspec),
+            Op::LfsPull => Box::new(fetch::LfsPull),
+            Op::ForgeListPullRequests => Box::new(forge::ForgeListPullRequests),
+            Op::ForgeCreatePullRequest => Box::new(forge::ForgeCreatePullRequest),
+            Op::ForgeOpenNewPullRequest => Box::new(forge::ForgeOpenNewPullRequest),
+            Op::CheckoutPullRequest => Box::new(forge::CheckoutPullRequest),
+            Op::OpenIssue => Box::new(forge::OpenIssue),
+            Op::FormatPatch => Box::new(patch::FormatPatch),
+            Op::Archive => Box::new(archive::Archive),
             Op::LogCurrent => Box::new(log::LogCurrent),
             Op::Pull => Box::new(pull::Pull),
             Op::PullElsewhere => Box::new(pull::PullElsewhere),
             Op::Push => Box::new(push::Push),
             Op::PushElsewhere => Box::new(push::PushElsewhere),
+            Op::PushCommit => Box::new(push::PushCommit),
             Op::RebaseAbort => Box::new(rebase::RebaseAbort),
             Op::RebaseContinue => Box::new(rebase::RebaseContinue),
             Op::RebaseElsewhere => Box::new(rebase::RebaseElsewhere),
@@ -139,54 +316,178 @@ impl Op {
             Op::StashIndex => Box::new(stash::StashIndex),
             Op::StashWorktree => Box::new(stash::StashWorktree),
             Op::StashKeepIndex => Box::new(stash::StashKeepIndex),
+            Op::StashSelected => Box::new(stash::StashSelected),
             Op::StashPop => Box::new(stash::StashPop),
             Op::StashDrop => Box::new(stash::StashDrop),
 
             Op::CommitFixup => Box::new(commit::CommitFixup),
+            Op::CommitInstantFixup => Box::new(commit::CommitInstantFixup),
+            Op::CommitReword => Box::new(commit::CommitReword),
             Op::Discard => Box::new(discard::Discard),
+            Op::Apply => Box::new(apply::Apply),
             Op::LogOther => Box::new(log::LogOther),
+            Op::Shortlog => Box::new(log::Shortlog),
+            Op::LogLines => Box::new(log::LogLines),
+            Op::CycleDateFormat => Box::new(log::CycleDateFormat),
+            Op::NoteEdit => Box::new(notes::NoteEdit),
+            Op::NoteRemove => Box::new(notes::NoteRemove),
             Op::RebaseAutosquash => Box::new(rebase::RebaseAutosquash),
+            Op::RebaseDrop => Box::new(rebase::RebaseDrop),
+            Op::RebaseSquash => Box::new(rebase::RebaseSquash),
+            Op::RebaseTodoPick => {
+                Box::new(rebase::RebaseTodoAction(git::rebase_todo::Action::Pick))
+            }
+            Op::RebaseTodoSquash => {
+                Box::new(rebase::RebaseTodoAction(git::rebase_todo::Action::Squash))
+            }
+            Op::RebaseTodoFixup => {
+                Box::new(rebase::RebaseTodoAction(git::rebase_todo::Action::Fixup))
+            }
+            Op::RebaseTodoEdit => {
+                Box::new(rebase::RebaseTodoAction(git::rebase_todo::Action::Edit))
+            }
+            Op::RebaseTodoDrop => {
+                Box::new(rebase::RebaseTodoAction(git::rebase_todo::Action::Drop))
+            }
+            Op::RebaseTodoMoveUp => Box::new(rebase::RebaseTodoMoveUp),
+            Op::RebaseTodoMoveDown => Box::new(rebase::RebaseTodoMoveDown),
+            Op::RebaseTodoStart => Box::new(rebase::RebaseTodoStart),
             Op::RebaseInteractive => Box::new(rebase::RebaseInteractive),
             Op::ResetSoft => Box::new(reset::ResetSoft),
             Op::ResetMixed => Box::new(reset::ResetMixed),
             Op::ResetHard => Box::new(reset::ResetHard),
+            Op::RerereForget => Box::new(rerere::RerereForget),
             Op::RevertAbort => Box::new(revert::RevertAbort),
             Op::RevertContinue => Box::new(revert::RevertContinue),
             Op::RevertCommit => Box::new(revert::RevertCommit),
             Op::Show => Box::new(show::Show),
+            Op::Back => Box::new(editor::Back),
+            Op::Forward => Box::new(editor::Forward),
+            Op::ShowGitConfig => Box::new(git_config::ShowGitConfig),
+            Op::ShowStats => Box::new(stats::ShowStats),
+            Op::ShowPrevCommit => Box::new(show::ShowPrevCommit),
+            Op::ShowNextCommit => Box::new(show::ShowNextCommit),
+            Op::VisitWorktreeFile => Box::new(show::VisitWorktreeFile),
             Op::Stage => Box::new(stage::Stage),
             Op::Unstage => Box::new(unstage::Unstage),
             Op::CopyHash => Box::new(copy_hash::CopyHash),
+            Op::Describe => Box::new(describe::Describe),
+            Op::Clone => Box::new(clone::Clone),
         }
     }
+
+    /// Whether it acts on the repository, rather than only on what's shown of it.
+    pub(crate) fn acts_on_repo(&self) -> bool {
+        !matches!(
+            self,
+            Op::MoreContext
+                | Op::LessContext
+                | Op::WholeFileContext
+                | Op::IgnoreAllSpace
+                | Op::IgnoreSpaceChange
+                | Op::IgnoreBlankLines
+                | Op::CycleDiffAlgorithm
+                | Op::ToggleFileTree
+                | Op::SortFiles
+                | Op::FilterFiles
+                | Op::CycleDateFormat
+                | Op::WidenList
+                | Op::NarrowList
+                | Op::TallerPopups
+                | Op::ShorterPopups
+                | Op::ToggleCmdOutputDock
+                | Op::Back
+                | Op::Forward
+                | Op::CopyHash
+                | Op::ToggleSection
+                | Op::ToggleMark
+                | Op::ToggleRegion
+                | Op::ToggleMacroRecording
+                | Op::ReplayMacro
+                | Op::RepeatLastAction
+                | Op::MoveUp
+                | Op::MoveDown
+                | Op::MoveUpLine
+                | Op::MoveDownLine
+                | Op::MovePrevSection
+                | Op::MoveNextSection
+                | Op::MoveParentSection
+                | Op::HalfPageUp
+                | Op::HalfPageDown
+                | Op::MoveToTop
+                | Op::MoveToBottom
+                | Op::Search
+                | Op::SearchNext
+                | Op::Refresh
+                | Op::CancelCmd
+                | Op::ShowCmdOutput
+                | Op::Quit
+                | Op::OpenMenu(_)
+                | Op::ToggleArg(_)
+        )
+    }
+
+    /// Whether it's done as many times as a count typed before it says, like `5j`.
+    pub(crate) fn takes_count(&self) -> bool {
+        matches!(
+            self,
+            Op::MoveUp
+                | Op::MoveDown
+                | Op::MoveUpLine
+                | Op::MoveDownLine
+                | Op::MovePrevSection
+                | Op::MoveNextSection
+                | Op::MoveParentSection
+                | Op::HalfPageUp
+                | Op::HalfPageDown
+                | Op::RebaseTodoMoveUp
+                | Op::RebaseTodoMoveDown
+                | Op::ReplayMacro
+        )
+    }
 }
 
 impl Display for Menu {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
             Menu::Root => "Root",
+            Menu::Am => "Apply",
             Menu::Branch => "Branch",
+            Menu::BranchCleanup => "Branch cleanup",
+            Menu::CherryPick => "Cherry-pick",
             Menu::Commit => "Commit",
+            Menu::Diff => "Diff",
             Menu::Fetch => "Fetch",
+            Menu::Forge => "Forge",
             Menu::Help => "Help",
+            Menu::IndexFlags => "Hidden files",
+            Menu::Layout => "Layout",
             Menu::Log => "Log",
+            Menu::Notes => "Notes",
+            Menu::Patch => "Patch",
             Menu::Pull => "Pull",
             Menu::Push => "Push",
             Menu::Rebase => "Rebase",
+            Menu::RebaseTodo => "Rebase todo",
             Menu::Reset => "Reset",
             Menu::Revert => "Revert",
+            Menu::SparseCheckout => "Sparse checkout",
             Menu::Stash => "Stash",
         })
     }
 }
 
-pub(crate) fn create_y_n_prompt(mut action: Action, prompt: &'static str) -> Action {
+pub(crate) fn create_y_n_prompt(
+    mut action: Action,
+    prompt: impl Into<Cow<'static, str>>,
+) -> Action {
+    let prompt = prompt.into();
     let update_fn = Rc::new(move |state: &mut State, term: &mut Term| {
         if state.prompt.state.status().is_pending() {
             match state.prompt.state.value() {
                 "y" => {
-                    Rc::get_mut(&mut action).unwrap()(state, term)?;
                     state.prompt.reset(term)?;
+                    Rc::get_mut(&mut action).unwrap()(state, term)?;
                 }
                 "" => (),
                 _ => {
@@ -204,12 +505,164 @@ pub(crate) fn create_y_n_prompt(mut action: Action, prompt: &'static str) -> Act
         state.prompt.set(PromptData {
             prompt_text: format!("{} (y or n)", prompt).into(),
             update_fn: update_fn.clone(),
+            history_key: None,
         });
 
         Ok(())
     })
 }
 
+/// Wraps actions of dangerous operations in a confirmation prompt, as configured in `general.confirm`.
+pub(crate) fn with_confirmation(
+    state: &State,
+    op: &Op,
+    target: Option<&TargetData>,
+    action: Action,
+) -> Action {
+    let confirm = &state.config.general.confirm;
+    let push_args = match op {
+        Op::Push | Op::PushElsewhere => state
+            .pending_menu
+            .as_ref()
+            .map(|menu| menu.args())
+            .unwrap_or_default(),
+        _ => vec![],
+    };
+    let is_plain_force = push_args.iter().any(|arg| arg == "--force");
+    let is_lease_force = push_args.iter().any(|arg| arg == "--force-with-lease");
+    let discarded = match op {
+        Op::Push if is_plain_force || is_lease_force => discarded_by_force_push(state),
+        _ => None,
+    };
+
+    let (policy, prompt): (_, Cow<'static, str>) = match (op, target) {
+        (Op::Discard, Some(TargetData::Branch(_))) => {
+            (confirm.delete_branch, "Really delete branch?".into())
+        }
+        (Op::Discard, Some(TargetData::ConfigEntry { .. })) => {
+            (confirm.discard, "Really unset?".into())
+        }
+        (Op::Discard, None) => (
+            confirm.discard,
+            match marked_changes(state).len() {
+                0 | 1 => "Really discard?".into(),
+                count => format!("Really discard {} marked changes?", count).into(),
+            },
+        ),
+        (Op::Discard, _) => (confirm.discard, "Really discard?".into()),
+        (Op::DeleteBranches, _) => (
+            confirm.delete_branch,
+            match marked_branches(state).len() {
+                0 | 1 => "Really delete branch?".into(),
+                count => format!("Really delete {} branches?", count).into(),
+            },
+        ),
+        (Op::ResetHard, _) => (confirm.reset_hard, "Really hard reset?".into()),
+        // A plain --force may overwrite commits pushed by others, so it's never done unasked.
+        (Op::Push | Op::PushElsewhere, _) if is_plain_force => (
+            match confirm.force_push {
+                Confirm::Never => Confirm::Ask,
+                policy => policy,
+            },
+            force_push_prompt(discarded.as_ref(), "Really force push without lease?"),
+        ),
+        (Op::Push | Op::PushElsewhere, _) if is_lease_force => (
+            confirm.force_push,
+            force_push_prompt(discarded.as_ref(), "Really force push?"),
+        ),
+        _ => return action,
+    };
+
+    let action = match discarded {
+        Some(discarded) => list_discarded(discarded, action),
+        None => action,
+    };
+    confirmed(policy, action, prompt)
+}
+
+/// Wraps an action in as many confirmation prompts as a `Confirm` policy asks for.
+pub(crate) fn confirmed(
+    policy: Confirm,
+    action: Action,
+    prompt: impl Into<Cow<'static, str>>,
+) -> Action {
+    match policy {
+        Confirm::Never => action,
+        Confirm::Ask => create_y_n_prompt(action, prompt),
+        Confirm::DoubleConfirm => {
+            create_y_n_prompt(create_y_n_prompt(action, "Are you sure?"), prompt)
+        }
+    }
+}
+
+/// Commits on the upstream a force push is about to discard, if there are any.
+/// Pushing elsewhere goes to a remote that's only asked for afterwards, so it's left out there.
+struct Discarded {
+    upstream: String,
+    commits: Vec<String>,
+}
+
+/// Discarded commits listed by their short hash and summary, at most this many.
+const MAX_DISCARDED_LISTED: usize = 10;
+
+fn discarded_by_force_push(state: &State) -> Option<Discarded> {
+    let (upstream, upstream_id) = git::upstream(&state.repo)?;
+    let head_id = state.repo.head().ok()?.target()?;
+
+    let mut revwalk = state.repo.revwalk().ok()?;
+    revwalk.push(upstream_id).ok()?;
+    revwalk.hide(head_id).ok()?;
+    let commits = revwalk
+        .flatten()
+        .filter_map(|id| state.repo.find_commit(id).ok())
+        .map(|commit| {
+            let hash = commit.id().to_string();
+            format!("{} {}", &hash[..7], commit.summary().unwrap_or(""))
+        })
+        .collect::<Vec<_>>();
+
+    if commits.is_empty() {
+        return None;
+    }
+
+    Some(Discarded { upstream, commits })
+}
+
+fn force_push_prompt(discarded: Option<&Discarded>, question: &'static str) -> Cow<'static, str> {
+    match discarded {
+        Some(discarded) => format!(
+            "This discards {} commit(s) on {}. {}",
+            discarded.commits.len(),
+            discarded.upstream,
+            question
+        )
+        .into(),
+        None => question.into(),
+    }
+}
+
+/// Lists the discarded commits before pushing, also when there's no prompt mentioning them.
+fn list_discarded(discarded: Discarded, mut action: Action) -> Action {
+    Rc::new(move |state: &mut State, term: &mut Term| {
+        state.display_info(format!(
+            "Discarding {} commit(s) on {}:",
+            discarded.commits.len(),
+            discarded.upstream
+        ));
+        for commit in discarded.commits.iter().take(MAX_DISCARDED_LISTED) {
+            state.display_info(commit.clone());
+        }
+        if discarded.commits.len() > MAX_DISCARDED_LISTED {
+            state.display_info(format!(
+                "and {} more",
+                discarded.commits.len() - MAX_DISCARDED_LISTED
+            ));
+        }
+
+        Rc::get_mut(&mut action).unwrap()(state, term)
+    })
+}
+
 pub(crate) fn create_prompt(
     prompt: &'static str,
     callback: fn(&mut State, &mut Term, &str) -> Res<()>,
@@ -237,6 +690,29 @@ pub(crate) fn create_prompt_with_default(
     })
 }
 
+/// Like `create_prompt_with_default`, with branches, remote branches and tags offered as completions.
+pub(crate) fn create_rev_prompt(
+    prompt: &'static str,
+    callback: fn(&mut State, &mut Term, &str) -> Res<()>,
+    default_fn: fn(&State) -> Option<String>,
+    hide_menu: bool,
+) -> Action {
+    Rc::new(move |state: &mut State, _term: &mut Term| {
+        set_prompt(
+            state,
+            prompt,
+            invoke_default,
+            Box::new(default_fn),
+            callback,
+            hide_menu,
+        );
+
+        let completions = git::ref_names(&state.repo)?;
+        state.prompt.set_completions(completions);
+        Ok(())
+    })
+}
+
 fn invoke_default(
     state: &mut State,
     term: &mut Term,
@@ -271,6 +747,7 @@ pub(crate) fn set_prompt<T: 'static>(
         update_fn: Rc::new(move |state, term| {
             if state.prompt.state.status().is_done() {
                 let input = state.prompt.state.value().to_string();
+                state.prompt.push_history(prompt, &input);
                 state.prompt.reset(term)?;
 
                 let default_value = default_fn(state);
@@ -288,9 +765,110 @@ pub(crate) fn set_prompt<T: 'static>(
             }
             Ok(())
         }),
+        history_key: Some(prompt),
     });
 }
 
+/// Short hashes of the commits marked on the current screen, newest first.
+pub(crate) fn marked_revs(state: &State) -> Vec<String> {
+    state
+        .screen()
+        .marked_items()
+        .filter_map(|item| match &item.target_data {
+            Some(TargetData::Commit(commit)) => Some(commit),
+            _ => None,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Names of the branches marked on the current screen.
+pub(crate) fn marked_branches(state: &State) -> Vec<String> {
+    state
+        .screen()
+        .marked_items()
+        .filter_map(|item| match &item.target_data {
+            Some(TargetData::Branch(branch)) => Some(branch.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Marked files and hunks, which stage, unstage and discard act on all at once.
+pub(crate) fn marked_changes(state: &State) -> Vec<TargetData> {
+    state
+        .screen()
+        .marked_items()
+        .filter_map(|item| match &item.target_data {
+            Some(target @ (TargetData::File(_) | TargetData::Delta(_) | TargetData::Hunk(_))) => {
+                Some(target.clone())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Runs `op` on each of the marked changes, clearing the marks once all of them succeeded.
+/// Changes within a marked file are left to it. `None` if `op` doesn't act on marks,
+/// or none of the marked changes apply.
+pub(crate) fn marked_changes_action(state: &State, op: &Op) -> Option<Action> {
+    if !matches!(op, Op::Stage | Op::Unstage | Op::Discard) {
+        return None;
+    }
+
+    let marked = marked_changes(state);
+    let implementation = op.clone().implementation();
+    let mut actions = marked
+        .iter()
+        .filter(|target| !marked.iter().any(|outer| covers(outer, target)))
+        .filter_map(|target| implementation.get_action(Some(target)))
+        .collect::<Vec<_>>();
+    if actions.is_empty() {
+        return None;
+    }
+
+    Some(Rc::new(move |state: &mut State, term: &mut Term| {
+        actions
+            .iter_mut()
+            .try_for_each(|action| Rc::get_mut(action).unwrap()(state, term))?;
+        state.screen_mut().clear_marks();
+        Ok(())
+    }))
+}
+
+/// Whether the change `inner` is part of the file or directory `outer`.
+fn covers(outer: &TargetData, inner: &TargetData) -> bool {
+    let outer_paths = match outer {
+        TargetData::File(path) => vec![path],
+        TargetData::Delta(delta) => vec![&delta.old_file, &delta.new_file],
+        _ => return false,
+    };
+
+    match inner {
+        TargetData::Hunk(hunk) => outer_paths
+            .iter()
+            .any(|path| hunk.new_file.starts_with(path)),
+        TargetData::File(inner_path)
+        | TargetData::Delta(Delta {
+            new_file: inner_path,
+            ..
+        }) => outer_paths
+            .iter()
+            .any(|path| inner_path != *path && inner_path.starts_with(path)),
+        _ => false,
+    }
+}
+
+/// The marked commits, oldest first, or else the selected one.
+pub(crate) fn marked_or_selected_revs(state: &State) -> Option<String> {
+    let marked = marked_revs(state);
+    if marked.is_empty() {
+        selected_rev(state)
+    } else {
+        Some(marked.into_iter().rev().join(" "))
+    }
+}
+
 pub(crate) fn selected_rev(state: &State) -> Option<String> {
     match &state.screen().get_selected_item().target_data {
         Some(TargetData::Branch(branch)) => Some(branch.to_owned()),
@@ -0,0 +1,44 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, state::State, term::Term};
+use derive_more::Display;
+use std::{process::Command, rc::Rc};
+
+#[derive(Display)]
+#[display(fmt = "Add/edit note")]
+pub(crate) struct NoteEdit;
+impl OpTrait for NoteEdit {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let rev = note_rev(target);
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["notes", "edit", &rev]);
+
+            state.close_menu();
+            state.run_cmd_interactive(term, cmd)
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Remove note")]
+pub(crate) struct NoteRemove;
+impl OpTrait for NoteRemove {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let rev = note_rev(target);
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["notes", "remove", &rev]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+}
+
+/// The selected commit, or `HEAD` when there's none.
+fn note_rev(target: Option<&TargetData>) -> String {
+    match target {
+        Some(TargetData::Commit(rev) | TargetData::Branch(rev)) => rev.clone(),
+        _ => "HEAD".to_string(),
+    }
+}
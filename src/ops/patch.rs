@@ -0,0 +1,62 @@
+use std::process::Command;
+
+use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use derive_more::*;
+
+use super::{create_rev_prompt, marked_or_selected_revs, Action, OpTrait};
+
+pub(crate) fn init_args() -> Vec<Arg> {
+    vec![
+        Arg::new_flag("--cover-letter", "Generate a cover letter", false),
+        Arg::new_flag("--signoff", "Add Signed-off-by lines", false),
+        Arg::new_arg("--output-directory", "Output directory", None, directory),
+    ]
+}
+
+fn directory(s: &str) -> Res<String> {
+    if s.trim().is_empty() {
+        return Err("Directory can't be empty".into());
+    }
+
+    Ok(s.trim().to_string())
+}
+
+#[derive(Display)]
+#[display(fmt = "Format patches")]
+pub(crate) struct FormatPatch;
+impl OpTrait for FormatPatch {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_rev_prompt(
+            "Format patches",
+            format_patch,
+            marked_or_selected_revs,
+            true,
+        ))
+    }
+}
+
+fn format_patch(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["format-patch"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+
+    // Revisions are formatted by themselves rather than everything since them,
+    // numbered oldest first like a range would be.
+    let revs = input.split_whitespace().collect::<Vec<_>>();
+    match revs.as_slice() {
+        [rev] if !rev.contains("..") => {
+            cmd.args(["-1", rev]);
+        }
+        revs if !input.contains("..") => {
+            cmd.args(["--no-walk", "--reverse"]);
+            cmd.args(revs);
+        }
+        revs => {
+            cmd.args(revs);
+        }
+    }
+
+    state.close_menu();
+    state.screen_mut().clear_marks();
+    state.run_cmd(term, &[], cmd)
+}
@@ -0,0 +1,88 @@
+use super::{Action, OpTrait};
+use crate::{git, items::TargetData, state::State, term::Term, Res};
+use derive_more::Display;
+use std::{collections::HashSet, io, process::Command, rc::Rc};
+
+const CONFIG_FILE: &str = ".pre-commit-config.yaml";
+
+#[derive(Display)]
+#[display(fmt = "Run pre-commit")]
+pub(crate) struct PreCommitRun;
+impl OpTrait for PreCommitRun {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            ensure_configured(state)?;
+
+            // Checks the staged files, like it would when installed as the pre-commit hook
+            let mut cmd = Command::new("pre-commit");
+            cmd.args(["run", "--color", "never"]);
+
+            state.close_menu();
+            state.run_cmd_async(term, &[], cmd).map_err(|error| {
+                match error.downcast_ref::<io::Error>() {
+                    Some(error) if error.kind() == io::ErrorKind::NotFound => {
+                        "pre-commit isn't installed, see https://pre-commit.com".into()
+                    }
+                    _ => error,
+                }
+            })
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Stage hook fixes")]
+pub(crate) struct PreCommitStageFixes;
+impl OpTrait for PreCommitStageFixes {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            ensure_configured(state)?;
+
+            // Hooks fixing up files leave the fixes unstaged, on top of the staged changes
+            let diff_settings = state.settings.borrow().diff;
+            let staged = git::diff_staged(
+                &state.config,
+                &diff_settings,
+                &state.hunk_cache,
+                &state.repo,
+            )?
+            .deltas
+            .into_iter()
+            .map(|delta| delta.new_file)
+            .collect::<HashSet<_>>();
+
+            let mut fixed = git::diff_unstaged(
+                &state.config,
+                &diff_settings,
+                &state.hunk_cache,
+                &state.repo,
+            )?
+            .deltas
+            .into_iter()
+            .map(|delta| delta.new_file)
+            .filter(|file| staged.contains(file))
+            .collect::<Vec<_>>();
+
+            if fixed.is_empty() {
+                return Err("No staged files were changed by hooks".into());
+            }
+            fixed.sort();
+
+            let mut cmd = Command::new("git");
+            cmd.args(["add", "--"]);
+            cmd.args(fixed);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+}
+
+fn ensure_configured(state: &State) -> Res<()> {
+    let workdir = state.repo.workdir().expect("No workdir");
+    if !workdir.join(CONFIG_FILE).exists() {
+        return Err(format!("No {} in this repository", CONFIG_FILE).into());
+    }
+
+    Ok(())
+}
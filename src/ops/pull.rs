@@ -1,10 +1,13 @@
-use super::{create_prompt, Action, OpTrait};
+use super::{create_prompt, stash::with_autostash_arg, Action, OpTrait};
 use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
 use derive_more::Display;
 use std::{process::Command, rc::Rc};
 
 pub(crate) fn init_args() -> Vec<Arg> {
-    vec![Arg::new_flag("--rebase", "Rebase local commits", false)]
+    vec![
+        Arg::new_flag("--rebase", "Rebase local commits", false),
+        Arg::new_flag("--autostash", "Stash changes meanwhile", false),
+    ]
 }
 
 #[derive(Display)]
@@ -15,7 +18,10 @@ impl OpTrait for Pull {
         Some(Rc::new(|state: &mut State, term: &mut Term| {
             let mut cmd = Command::new("git");
             cmd.arg("pull");
-            cmd.args(state.pending_menu.as_ref().unwrap().args());
+            cmd.args(with_autostash_arg(
+                &state.config,
+                state.pending_menu.as_ref().unwrap().args(),
+            ));
 
             state.close_menu();
             state.run_cmd_async(term, &[], cmd)?;
@@ -36,7 +42,10 @@ impl OpTrait for PullElsewhere {
 fn pull_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
     let mut cmd = Command::new("git");
     cmd.args(["pull"]);
-    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.args(with_autostash_arg(
+        &state.config,
+        state.pending_menu.as_ref().unwrap().args(),
+    ));
     cmd.arg(remote);
 
     state.close_menu();
@@ -1,7 +1,8 @@
-use super::{create_prompt, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use super::{create_prompt, set_prompt, Action, OpTrait};
+use crate::{git, items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
 use derive_more::Display;
-use std::{process::Command, rc::Rc};
+use git2::Repository;
+use std::{ffi::OsString, process::Command, rc::Rc};
 
 pub(crate) fn init_args() -> Vec<Arg> {
     vec![
@@ -9,6 +10,8 @@ pub(crate) fn init_args() -> Vec<Arg> {
         Arg::new_flag("--force", "Force", false),
         Arg::new_flag("--no-verify", "Disable hooks", false),
         Arg::new_flag("--dry-run", "Dry run", false),
+        Arg::new_flag("--signed", "Sign using gpg", false),
+        Arg::new_list("--push-option", "Push options (TOML array)"),
     ]
 }
 
@@ -18,9 +21,17 @@ pub(crate) struct Push;
 impl OpTrait for Push {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let args = state.pending_menu.as_ref().unwrap().args();
+
+            if let Some(branch) = git::current_branch(&state.repo) {
+                if git::branch_upstream_name(&state.repo, &branch).is_none() {
+                    return set_push_upstream_prompt(state, branch, args);
+                }
+            }
+
             let mut cmd = Command::new("git");
             cmd.args(["push"]);
-            cmd.args(state.pending_menu.as_ref().unwrap().args());
+            cmd.args(args);
 
             state.close_menu();
             state.run_cmd_async(term, &[], cmd)?;
@@ -48,3 +59,141 @@ fn push_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
     state.run_cmd_async(term, &[], cmd)?;
     Ok(())
 }
+
+fn set_push_upstream_prompt(state: &mut State, branch: String, args: Vec<OsString>) -> Res<()> {
+    set_prompt(
+        state,
+        "Set upstream and push to",
+        push_set_upstream,
+        Box::new(|state| {
+            let branch = git::current_branch(&state.repo)?;
+            let remote = default_push_remote(&state.repo, &branch)?;
+            Some(format!("{}/{}", remote, branch))
+        }),
+        (branch, args),
+        true,
+    );
+
+    let completions = git::remote_branch_names(&state.repo)?;
+    state.prompt.set_completions(completions);
+    Ok(())
+}
+
+/// Where `git push` would go without an upstream: `branch.<name>.pushRemote`, else
+/// `remote.pushDefault`, else `origin` or the first remote. None when `push.default` is `nothing`.
+fn default_push_remote(repo: &Repository, branch: &str) -> Option<String> {
+    let config = repo.config().ok()?;
+    if config.get_string("push.default").ok().as_deref() == Some("nothing") {
+        return None;
+    }
+
+    let configured = config
+        .get_string(&format!("branch.{}.pushRemote", branch))
+        .or_else(|_| config.get_string("remote.pushDefault"));
+    if let Ok(remote) = configured {
+        return Some(remote);
+    }
+
+    let remotes = git::remote_names(repo).ok()?;
+    remotes
+        .iter()
+        .find(|remote| *remote == "origin")
+        .or(remotes.first())
+        .cloned()
+}
+
+fn push_set_upstream(
+    state: &mut State,
+    term: &mut Term,
+    upstream: &str,
+    (branch, args): &(String, Vec<OsString>),
+) -> Res<()> {
+    let (remote, remote_branch) = parse_remote_branch(state, upstream, "Upstream")?;
+
+    let mut cmd = Command::new("git");
+    cmd.args(["push"]);
+    cmd.args(args);
+    cmd.args(["--set-upstream", remote]);
+    cmd.arg(format!("{}:{}", branch, remote_branch));
+
+    state.close_menu();
+    state.run_cmd_async(term, &[], cmd)?;
+    Ok(())
+}
+
+/// Splits `<remote>/<branch>`, making sure the remote exists. Remote names may contain
+/// slashes too, the longest one the input starts with is it.
+fn parse_remote_branch<'a>(state: &State, input: &'a str, what: &str) -> Res<(&'a str, &'a str)> {
+    let Some((first, _)) = input
+        .split_once('/')
+        .filter(|(remote, branch)| !remote.is_empty() && !branch.is_empty())
+    else {
+        return Err(format!("{} must be formatted as: <remote>/<branch>", what).into());
+    };
+
+    git::remote_names(&state.repo)?
+        .iter()
+        .filter_map(|remote| {
+            let branch = input.strip_prefix(remote.as_str())?.strip_prefix('/')?;
+            (!branch.is_empty()).then(|| (&input[..remote.len()], branch))
+        })
+        .max_by_key(|(remote, _)| remote.len())
+        .ok_or_else(|| format!("No remote named: {}", first).into())
+}
+
+#[derive(Display)]
+#[display(fmt = "Push commit to new branch")]
+pub(crate) struct PushCommit;
+impl OpTrait for PushCommit {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::Commit(commit)) = target else {
+            return None;
+        };
+
+        let commit = commit.clone();
+        Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+            set_prompt(
+                state,
+                "Push commit to",
+                push_commit,
+                Box::new(|_| None),
+                commit.clone(),
+                true,
+            );
+
+            let completions = git::remote_names(&state.repo)?
+                .into_iter()
+                .map(|remote| format!("{}/", remote))
+                .collect();
+            state.prompt.set_completions(completions);
+            Ok(())
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+fn push_commit(state: &mut State, term: &mut Term, input: &str, commit: &String) -> Res<()> {
+    let (remote, remote_branch) = parse_remote_branch(state, input, "Branch")?;
+
+    if state
+        .repo
+        .find_reference(&format!("refs/remotes/{}/{}", remote, remote_branch))
+        .is_ok()
+    {
+        return Err(format!("{} already exists", input).into());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["push"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg(remote);
+    // A commit can't be pushed to an unqualified name that doesn't exist on the remote yet
+    cmd.arg(format!("{}:refs/heads/{}", commit, remote_branch));
+
+    state.close_menu();
+    state.run_cmd_async(term, &[], cmd)?;
+    Ok(())
+}
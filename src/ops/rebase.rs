@@ -1,12 +1,25 @@
-use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use super::{commit::sh_quote, create_rev_prompt, selected_rev, Action, OpTrait};
+use crate::{
+    git::rebase_todo::{self as todo, Todo},
+    items::TargetData,
+    menu::{arg::Arg, Menu},
+    screen,
+    state::State,
+    term::Term,
+    Res,
+};
 use derive_more::Display;
+use git2::{Oid, Repository, RepositoryState};
 use std::{
+    cell::RefCell,
     ffi::{OsStr, OsString},
+    fs,
     process::Command,
     rc::Rc,
 };
 
+const GENERATED_TODO: &str = "GITU_REBASE_TODO";
+
 pub(crate) fn init_args() -> Vec<Arg> {
     vec![
         Arg::new_flag("--keep-empty", "Keep empty commits", false),
@@ -60,7 +73,7 @@ impl OpTrait for RebaseAbort {
 pub(crate) struct RebaseElsewhere;
 impl OpTrait for RebaseElsewhere {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_rev_prompt(
             "Rebase onto",
             rebase_elsewhere,
             selected_rev,
@@ -87,11 +100,37 @@ impl OpTrait for RebaseInteractive {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
         let action = match target {
             Some(TargetData::Commit(r) | TargetData::Branch(r)) => {
-                let rev = OsString::from(r);
+                let rev = r.clone();
                 Rc::new(move |state: &mut State, term: &mut Term| {
                     let args = state.pending_menu.as_ref().unwrap().args();
+                    let oid = state.repo.revparse_single(&rev)?.peel_to_commit()?.id();
+                    let base = nth_parent(&state.repo, oid, 1)?;
+
+                    let mut todo = Todo::since(&state.repo, base)?;
+                    if !todo.lines.iter().any(|line| line.oid == oid) {
+                        return Err("The commit isn't on the current branch".into());
+                    }
+
+                    // Arranged here, so the todo list shown is the one that's rebased with
+                    if args.iter().any(|arg| arg == "--autosquash") {
+                        todo.autosquash();
+                    }
+                    todo.args = args
+                        .into_iter()
+                        .filter(|arg| arg != "--autosquash" && arg != "--interactive")
+                        .collect();
+
+                    let todo = Rc::new(RefCell::new(todo));
+                    state.rebase_todo = Some(Rc::clone(&todo));
+
                     state.close_menu();
-                    state.run_cmd_interactive(term, rebase_interactive_cmd(&args, &rev))
+                    state.push_screen(screen::rebase_todo::create(
+                        Rc::clone(&state.config),
+                        Rc::clone(&state.repo),
+                        term.size()?,
+                        todo,
+                    )?);
+                    Ok(())
                 })
             }
             _ => return None,
@@ -104,18 +143,102 @@ impl OpTrait for RebaseInteractive {
     }
 }
 
-fn rebase_interactive_cmd(args: &[OsString], rev: &OsStr) -> Command {
-    let mut cmd = Command::new("git");
-    cmd.args(["rebase", "-i"]);
-    cmd.args(args);
-    cmd.arg(&parent(rev));
-    cmd
+#[derive(Display)]
+#[display(fmt = "{}", _0)]
+pub(crate) struct RebaseTodoAction(pub todo::Action);
+impl OpTrait for RebaseTodoAction {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let action = self.0;
+        match target {
+            Some(TargetData::Commit(r)) => {
+                let rev = r.clone();
+                Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+                    edit_todo(state, &rev, |todo, oid| todo.set_action(oid, action))
+                }))
+            }
+            _ => None,
+        }
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Move up")]
+pub(crate) struct RebaseTodoMoveUp;
+impl OpTrait for RebaseTodoMoveUp {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Commit(r)) => {
+                let rev = r.clone();
+                Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+                    edit_todo(state, &rev, |todo, oid| todo.move_line(oid, true))
+                }))
+            }
+            _ => None,
+        }
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
 }
 
-fn parent(reference: &OsStr) -> OsString {
-    let mut parent = reference.to_os_string();
-    parent.push("^");
-    parent
+#[derive(Display)]
+#[display(fmt = "Move down")]
+pub(crate) struct RebaseTodoMoveDown;
+impl OpTrait for RebaseTodoMoveDown {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Commit(r)) => {
+                let rev = r.clone();
+                Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+                    edit_todo(state, &rev, |todo, oid| todo.move_line(oid, false))
+                }))
+            }
+            _ => None,
+        }
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+/// Changes the todo list of the rebase todo screen at the selected commit, which stays selected.
+fn edit_todo(state: &mut State, rev: &str, edit: impl FnOnce(&mut Todo, Oid) -> bool) -> Res<()> {
+    let Some(todo) = state.rebase_todo.clone() else {
+        return Err("No rebase todo list is being edited".into());
+    };
+
+    if edit(&mut todo.borrow_mut(), Oid::from_str(rev)?) {
+        state.refresh_screen()?;
+        state.screen_mut().select_first(|item| item.id == rev);
+    }
+
+    Ok(())
+}
+
+#[derive(Display)]
+#[display(fmt = "Start rebase")]
+pub(crate) struct RebaseTodoStart;
+impl OpTrait for RebaseTodoStart {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            if state.screen().keymap != Some(Menu::RebaseTodo) {
+                return Err("No rebase todo list is being edited".into());
+            }
+            let Some(todo) = state.rebase_todo.take() else {
+                return Err("No rebase todo list is being edited".into());
+            };
+
+            let cmd = generated_rebase_cmd(state, &todo.borrow())?;
+            state.screens.pop();
+
+            // Squashing and rewording open the editor
+            let result = state.run_cmd_interactive(term, cmd);
+            finish_generated_rebase(state, result)
+        }))
+    }
 }
 
 #[derive(Display)]
@@ -149,3 +272,125 @@ fn rebase_autosquash_cmd(args: &[OsString], rev: &OsStr) -> Command {
     cmd.arg(rev);
     cmd
 }
+
+#[derive(Display)]
+#[display(fmt = "Drop commit")]
+pub(crate) struct RebaseDrop;
+impl OpTrait for RebaseDrop {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let action = match target {
+            Some(TargetData::Commit(r)) => {
+                let rev = r.clone();
+                Rc::new(move |state: &mut State, term: &mut Term| {
+                    let oid = state.repo.revparse_single(&rev)?.peel_to_commit()?.id();
+                    let base = nth_parent(&state.repo, oid, 1)?;
+                    let todo = todo_applying(&state.repo, base, oid, todo::Action::Drop)?;
+                    let cmd = generated_rebase_cmd(state, &todo)?;
+
+                    state.close_menu();
+                    let result = state.run_cmd(term, &[], cmd);
+                    finish_generated_rebase(state, result)
+                })
+            }
+            _ => return None,
+        };
+
+        Some(action)
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Squash into parent")]
+pub(crate) struct RebaseSquash;
+impl OpTrait for RebaseSquash {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let action = match target {
+            Some(TargetData::Commit(r)) => {
+                let rev = r.clone();
+                Rc::new(move |state: &mut State, term: &mut Term| {
+                    let oid = state.repo.revparse_single(&rev)?.peel_to_commit()?.id();
+                    if state.repo.find_commit(oid)?.parent_count() == 0 {
+                        return Err("The root commit has no parent to squash into".into());
+                    }
+
+                    let base = nth_parent(&state.repo, oid, 2)?;
+                    let todo = todo_applying(&state.repo, base, oid, todo::Action::Squash)?;
+                    let cmd = generated_rebase_cmd(state, &todo)?;
+
+                    state.close_menu();
+                    // The editor is opened to combine the commit messages
+                    let result = state.run_cmd_interactive(term, cmd);
+                    finish_generated_rebase(state, result)
+                })
+            }
+            _ => return None,
+        };
+
+        Some(action)
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+/// Follows first parents `n` generations back, `None` when history ends before that.
+fn nth_parent(repo: &Repository, oid: Oid, n: usize) -> Res<Option<Oid>> {
+    let mut commit = repo.find_commit(oid)?;
+    for _ in 0..n {
+        match commit.parents().next() {
+            Some(parent) => commit = parent,
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(commit.id()))
+}
+
+fn todo_applying(
+    repo: &Repository,
+    base: Option<Oid>,
+    target: Oid,
+    action: todo::Action,
+) -> Res<Todo> {
+    let mut todo = Todo::since(repo, base)?;
+    if !todo.set_action(target, action) {
+        return Err("The commit isn't on the current branch".into());
+    }
+    todo.args = vec!["--autostash".into()];
+
+    Ok(todo)
+}
+
+/// An interactive rebase that is handed `todo`, rather than asking for a todo list to be edited.
+fn generated_rebase_cmd(state: &State, todo: &Todo) -> Res<Command> {
+    let todo_path = state.repo.path().join(GENERATED_TODO);
+    fs::write(&todo_path, todo.to_string())?;
+
+    let mut cmd = Command::new("git");
+    cmd.env(
+        "GIT_SEQUENCE_EDITOR",
+        format!("cp {}", sh_quote(&todo_path.to_string_lossy())),
+    );
+    cmd.args(["rebase", "--interactive"]);
+    cmd.args(&todo.args);
+    match todo.base {
+        Some(base) => cmd.arg(base.to_string()),
+        None => cmd.arg("--root"),
+    };
+
+    Ok(cmd)
+}
+
+fn finish_generated_rebase(state: &State, result: Res<()>) -> Res<()> {
+    // Git has read the todo list by now, whether or not the rebase went through
+    let _ = fs::remove_file(state.repo.path().join(GENERATED_TODO));
+
+    if result.is_err() && state.repo.state() != RepositoryState::Clean {
+        return Err("Rebase stopped due to conflicts, resolve them and continue the rebase".into());
+    }
+
+    result
+}
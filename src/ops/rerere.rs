@@ -0,0 +1,29 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, state::State, term::Term};
+use derive_more::Display;
+use std::{process::Command, rc::Rc};
+
+#[derive(Display)]
+#[display(fmt = "Forget recorded resolution")]
+pub(crate) struct RerereForget;
+impl OpTrait for RerereForget {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let file = match target {
+            Some(TargetData::File(file)) => file.clone(),
+            Some(TargetData::Delta(d)) => d.new_file.clone(),
+            _ => return None,
+        };
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["rerere", "forget"]);
+            cmd.arg(&file);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
@@ -1,4 +1,4 @@
-use super::{create_prompt_with_default, selected_rev, OpTrait};
+use super::{create_rev_prompt, selected_rev, OpTrait};
 use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Action, Res};
 use derive_more::Display;
 use std::process::Command;
@@ -12,7 +12,7 @@ pub(crate) fn init_args() -> Vec<Arg> {
 pub(crate) struct ResetSoft;
 impl OpTrait for ResetSoft {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_rev_prompt(
             "Soft reset to",
             reset_soft,
             selected_rev,
@@ -36,7 +36,7 @@ fn reset_soft(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
 pub(crate) struct ResetMixed;
 impl OpTrait for ResetMixed {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_rev_prompt(
             "Mixed reset to",
             reset_mixed,
             selected_rev,
@@ -60,7 +60,7 @@ fn reset_mixed(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
 pub(crate) struct ResetHard;
 impl OpTrait for ResetHard {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_rev_prompt(
             "Hard reset to",
             reset_hard,
             selected_rev,
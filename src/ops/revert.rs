@@ -3,12 +3,13 @@ use std::{process::Command, rc::Rc};
 use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
 use derive_more::*;
 
-use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
+use super::{create_rev_prompt, marked_revs, selected_rev, Action, OpTrait};
 
 pub(crate) fn init_args() -> Vec<Arg> {
     vec![
         // -m Replay merge relative to parent (--mainline=)
         Arg::new_flag("--edit", "Edit commit message", true),
+        Arg::new_flag("--no-edit", "Don't edit commit message", false),
         // =s Strategy (--strategy=)
         Arg::new_flag("--signoff", "Add Signed-off-by lines", false),
     ]
@@ -51,10 +52,10 @@ impl OpTrait for RevertContinue {
 pub(crate) struct RevertCommit;
 impl OpTrait for RevertCommit {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_rev_prompt(
             "Revert commit",
             revert_commit,
-            selected_rev,
+            marked_or_selected_revs_newest_first,
             true,
         ))
     }
@@ -64,8 +65,19 @@ fn revert_commit(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
     let mut cmd = Command::new("git");
     cmd.args(["revert"]);
     cmd.args(state.pending_menu.as_ref().unwrap().args());
-    cmd.arg(input);
+    cmd.args(input.split_whitespace());
 
     state.close_menu();
+    state.screen_mut().clear_marks();
     state.run_cmd_interactive(term, cmd)
 }
+
+/// The marked commits, newest first so that each revert applies cleanly, or else the selected one.
+fn marked_or_selected_revs_newest_first(state: &State) -> Option<String> {
+    let marked = marked_revs(state);
+    if marked.is_empty() {
+        selected_rev(state)
+    } else {
+        Some(marked.join(" "))
+    }
+}
@@ -1,7 +1,12 @@
 use super::OpTrait;
-use crate::{items::TargetData, screen, Action};
+use crate::{git, items::TargetData, screen, state::State, term::Term, Action, Res};
 use derive_more::Display;
-use std::{path::Path, process::Command, rc::Rc};
+use git2::Repository;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    rc::Rc,
+};
 
 #[derive(Default, Clone, Copy, Debug, Display)]
 #[display(fmt = "Show")]
@@ -14,6 +19,15 @@ impl OpTrait for Show {
             Some(TargetData::Delta(d)) => editor(d.new_file.as_path(), None),
             Some(TargetData::Hunk(h)) => editor(h.new_file.as_path(), Some(h.first_diff_line())),
             Some(TargetData::Stash { id: _, commit }) => goto_show_screen(commit.clone()),
+            Some(TargetData::SubmoduleCommit { path, commit }) => {
+                goto_submodule_show_screen(path.clone(), commit.clone())
+            }
+            Some(TargetData::Author { range, email }) => {
+                Some(super::log::log_author(range.clone(), email.clone()))
+            }
+            Some(TargetData::ConfigEntry { key, scope }) => {
+                Some(super::git_config::edit_config_entry(key, *scope))
+            }
             _ => None,
         }
     }
@@ -25,53 +39,200 @@ impl OpTrait for Show {
 fn goto_show_screen(r: String) -> Option<Action> {
     Some(Rc::new(move |state, term| {
         state.close_menu();
-        state.screens.push(
-            screen::show::create(
+        let screen = match state.prepared_shows.take(&r, state.refresh_generation) {
+            Some(mut screen) => {
+                screen.size = term.size()?;
+                screen
+            }
+            None => screen::show::create(
                 Rc::clone(&state.config),
+                Rc::clone(&state.settings),
+                Rc::clone(&state.hunk_cache),
                 Rc::clone(&state.repo),
                 term.size()?,
                 r.clone(),
             )
             .expect("Couldn't create screen"),
-        );
+        };
+
+        state.push_screen(screen);
         Ok(())
     }))
 }
 
-fn editor(file: &Path, maybe_line: Option<u32>) -> Option<Action> {
-    let file = file.to_str().unwrap().to_string();
-
+fn goto_submodule_show_screen(path: PathBuf, commit: String) -> Option<Action> {
     Some(Rc::new(move |state, term| {
-        const EDITOR_VARS: [&str; 3] = ["GIT_EDITOR", "VISUAL", "EDITOR"];
-        let configured_editor = EDITOR_VARS
-            .into_iter()
-            .find_map(|var| std::env::var(var).ok());
-
-        let Some(editor) = configured_editor else {
-            return Err(format!(
-                "No editor environment variable set ({})",
-                EDITOR_VARS.join(", ")
-            )
-            .into());
+        state.close_menu();
+        let repo = Repository::open(&path)
+            .map_err(|_| format!("Submodule isn't checked out: {}", path.display()))?;
+
+        let mut screen = screen::show::create(
+            Rc::clone(&state.config),
+            Rc::clone(&state.settings),
+            Rc::clone(&state.hunk_cache),
+            Rc::new(repo),
+            term.size()?,
+            commit.clone(),
+        )?;
+        screen.other_repo = true;
+        state.push_screen(screen);
+        Ok(())
+    }))
+}
+
+#[derive(Display)]
+#[display(fmt = "Show previous commit")]
+pub(crate) struct ShowPrevCommit;
+impl OpTrait for ShowPrevCommit {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, term| step_shown_commit(state, term, true)))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Show next commit")]
+pub(crate) struct ShowNextCommit;
+impl OpTrait for ShowNextCommit {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, term| step_shown_commit(state, term, false)))
+    }
+}
+
+/// Replaces the current show screen with its neighbour as listed in the log it was shown from.
+fn step_shown_commit(state: &mut State, term: &mut Term, previous: bool) -> Res<()> {
+    state.close_menu();
+
+    let Some(hash) = &state.screen().commit else {
+        return Err("Not showing a commit".into());
+    };
+
+    let log_rev = state
+        .screens
+        .iter()
+        .rev()
+        .nth(1)
+        .and_then(|screen| screen.log_rev);
+    let (prev, next) = git::log_neighbours(&state.repo, log_rev, git2::Oid::from_str(hash)?)?;
+    let Some(oid) = (if previous { prev } else { next }) else {
+        return Err(if previous {
+            "Already at the newest commit".into()
+        } else {
+            "Already at the oldest commit".into()
+        });
+    };
+
+    *state.screen_mut() = screen::show::create(
+        Rc::clone(&state.config),
+        Rc::clone(&state.settings),
+        Rc::clone(&state.hunk_cache),
+        Rc::clone(&state.repo),
+        term.size()?,
+        oid.to_string(),
+    )?;
+
+    Ok(())
+}
+
+#[derive(Display)]
+#[display(fmt = "Visit file in worktree")]
+pub(crate) struct VisitWorktreeFile;
+impl OpTrait for VisitWorktreeFile {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let (file, line) = match target {
+            Some(TargetData::Hunk(h)) => (h.new_file.clone(), Some(h.first_diff_line())),
+            Some(TargetData::HunkLine(h, lines)) => (
+                h.new_file.clone(),
+                h.line_range(lines.clone(), true).map(|(start, _)| start),
+            ),
+            _ => return None,
         };
 
-        let cmd = parse_editor_command(&editor, &file, maybe_line);
+        Some(Rc::new(move |state, term| {
+            if !state
+                .repo
+                .workdir()
+                .expect("No workdir")
+                .join(&file)
+                .exists()
+            {
+                return Err(format!("{} isn't in the worktree", file.display()).into());
+            }
 
-        state.close_menu();
-        state
-            .run_cmd_interactive(term, cmd)
-            .map_err(|err| format!("Couldn't open editor {} due to: {}", editor, err))?;
+            // Lines of a shown commit may have moved since
+            let line = match (&state.screen().commit, line) {
+                (Some(commit), Some(line)) => {
+                    Some(git::worktree_line(&state.repo, commit, &file, line)?)
+                }
+                _ => line,
+            };
+
+            open_editor(state, term, file.to_str().unwrap(), line)
+        }))
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
 
-        state.screen_mut().update()
+fn editor(file: &Path, maybe_line: Option<u32>) -> Option<Action> {
+    let file = file.to_str().unwrap().to_string();
+    Some(Rc::new(move |state, term| {
+        open_editor(state, term, &file, maybe_line)
     }))
 }
 
-fn parse_editor_command(editor: &str, file: &str, maybe_line: Option<u32>) -> Command {
-    let args = &editor.split_whitespace().collect::<Vec<_>>();
-    let mut cmd = Command::new(args[0]);
-    cmd.args(&args[1..]);
+fn open_editor(state: &mut State, term: &mut Term, file: &str, maybe_line: Option<u32>) -> Res<()> {
+    let (editor, cmd) = match &state.config.general.editor_template {
+        Some(template) => (
+            template.clone(),
+            editor_command_from_template(template, file, maybe_line)?,
+        ),
+        None => {
+            let editor = configured_editor(&state.repo);
+            let cmd = parse_editor_command(&editor, file, maybe_line)?;
+            (editor, cmd)
+        }
+    };
+
+    state.close_menu();
+    state
+        .run_cmd_interactive(term, cmd)
+        .map_err(|err| format!("Couldn't open editor {} due to: {}", editor, err))?;
+
+    state.refresh_screen()
+}
+
+/// The editor git would pick: `GIT_EDITOR`, `core.editor`, `VISUAL`, `EDITOR`, and lastly `vi`.
+pub(crate) fn configured_editor(repo: &Repository) -> String {
+    let from_env = |var| {
+        std::env::var(var)
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+    };
+    let from_config = || {
+        repo.config()
+            .and_then(|config| config.get_string("core.editor"))
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+    };
+
+    from_env("GIT_EDITOR")
+        .or_else(from_config)
+        .or_else(|| from_env("VISUAL"))
+        .or_else(|| from_env("EDITOR"))
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+fn parse_editor_command(editor: &str, file: &str, maybe_line: Option<u32>) -> Res<Command> {
+    let args = split_args(editor)?;
+    let Some((program, args)) = args.split_first() else {
+        return Err(format!("Invalid editor {:?}", editor).into());
+    };
+
+    let mut cmd = Command::new(program);
+    cmd.args(args);
 
-    let lower = args[0].to_lowercase();
+    let lower = program.to_lowercase();
 
     if let Some(line) = maybe_line {
         if lower.ends_with("vi")
@@ -86,7 +247,104 @@ fn parse_editor_command(editor: &str, file: &str, maybe_line: Option<u32>) -> Co
     } else {
         cmd.args([file.to_string()]);
     }
-    cmd
+    Ok(cmd)
+}
+
+/// Fills in `general.editor_template`, a location not known being the start of the file.
+fn editor_command_from_template(
+    template: &str,
+    file: &str,
+    maybe_line: Option<u32>,
+) -> Res<Command> {
+    let line = maybe_line.unwrap_or(1).to_string();
+    let mut args = split_args(template)?;
+    if !args.iter().any(|arg| arg.contains("{file}")) {
+        args.push("{file}".to_string());
+    }
+
+    let placeholders = [("{file}", file), ("{line}", &line), ("{col}", "1")];
+    let mut args = args
+        .into_iter()
+        .map(|arg| fill_placeholders(&arg, &placeholders));
+
+    let Some(program) = args.next() else {
+        return Err(format!("Invalid editor template {:?}", template).into());
+    };
+
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    Ok(cmd)
+}
+
+/// Replaces each placeholder in one pass, so what a file's name contains is left as is.
+fn fill_placeholders(arg: &str, placeholders: &[(&str, &str)]) -> String {
+    let mut filled = String::new();
+    let mut rest = arg;
+
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        match placeholders
+            .iter()
+            .find(|(placeholder, _)| rest.starts_with(placeholder))
+        {
+            Some((placeholder, value)) => {
+                filled.push_str(value);
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                filled.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    filled.push_str(rest);
+    filled
+}
+
+/// Splits like a POSIX shell would, minus expansions: `code --wait` or `"/path with/ed" -f`.
+pub(crate) fn split_args(line: &str) -> Res<Vec<String>> {
+    let mut args = vec![];
+    let mut arg: Option<String> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => args.extend(arg.take()),
+            '\'' => {
+                let quoted = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => quoted.push(c),
+                        None => return Err(format!("Unterminated quote in {:?}", line).into()),
+                    }
+                }
+            }
+            '"' => {
+                let quoted = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => quoted.push(c),
+                            Some(c) => quoted.extend(['\\', c]),
+                            None => break,
+                        },
+                        Some(c) => quoted.push(c),
+                        None => return Err(format!("Unterminated quote in {:?}", line).into()),
+                    }
+                }
+            }
+            '\\' => arg.get_or_insert_with(String::new).extend(chars.next()),
+            c => arg.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    args.extend(arg);
+    Ok(args)
 }
 
 #[cfg(test)]
@@ -95,11 +353,64 @@ mod tests {
 
     #[test]
     fn parse_editor_command_test() {
-        let cmd = super::parse_editor_command("/bin/nAnO -f", "README.md", Some(42));
+        let cmd = super::parse_editor_command("/bin/nAnO -f", "README.md", Some(42)).unwrap();
         assert_eq!(cmd.get_program(), OsStr::new("/bin/nAnO"));
         assert_eq!(
             &cmd.get_args().collect::<Vec<_>>(),
             &["-f", "+42", "README.md"]
         );
     }
+
+    #[test]
+    fn parse_editor_command_quoted() {
+        let cmd =
+            super::parse_editor_command("'/opt/My Editor/ed' --wait", "README.md", None).unwrap();
+        assert_eq!(cmd.get_program(), OsStr::new("/opt/My Editor/ed"));
+        assert_eq!(
+            &cmd.get_args().collect::<Vec<_>>(),
+            &["--wait", "README.md"]
+        );
+    }
+
+    #[test]
+    fn parse_editor_command_empty() {
+        assert!(super::parse_editor_command("  ", "README.md", None).is_err());
+    }
+
+    #[test]
+    fn split_args() {
+        assert_eq!(
+            super::split_args(r#"code  --wait "a \"b\" c" d\ e '' f"g"h"#).unwrap(),
+            ["code", "--wait", "a \"b\" c", "d e", "", "fgh"]
+        );
+        assert!(super::split_args("emacs 'oops").is_err());
+    }
+
+    #[test]
+    fn editor_template() {
+        let cmd = super::editor_command_from_template(
+            "code --wait --goto {file}:{line}:{col}",
+            "src/my file.rs",
+            Some(7),
+        )
+        .unwrap();
+        assert_eq!(cmd.get_program(), OsStr::new("code"));
+        assert_eq!(
+            &cmd.get_args().collect::<Vec<_>>(),
+            &["--wait", "--goto", "src/my file.rs:7:1"]
+        );
+    }
+
+    #[test]
+    fn editor_template_placeholder_in_file_name() {
+        let cmd = super::editor_command_from_template("vim +{line} {file}", "{line}.rs", Some(3))
+            .unwrap();
+        assert_eq!(&cmd.get_args().collect::<Vec<_>>(), &["+3", "{line}.rs"]);
+    }
+
+    #[test]
+    fn editor_template_without_file() {
+        let cmd = super::editor_command_from_template("kak +{line}", "README.md", None).unwrap();
+        assert_eq!(&cmd.get_args().collect::<Vec<_>>(), &["+1", "README.md"]);
+    }
 }
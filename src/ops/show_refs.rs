@@ -19,7 +19,7 @@ fn goto_refs_screen(state: &mut State) {
     state.screens.drain(1..);
     let size = state.screens.last().unwrap().size;
     state.close_menu();
-    state.screens.push(
+    state.push_screen(
         screen::show_refs::create(Rc::clone(&state.config), Rc::clone(&state.repo), size)
             .expect("Couldn't create screen"),
     );
@@ -0,0 +1,96 @@
+use super::{create_prompt, show::split_args, Action, OpTrait};
+use crate::{git::sparse, items::TargetData, screen, state::State, term::Term, Res};
+use derive_more::Display;
+use std::{process::Command, rc::Rc};
+
+#[derive(Display)]
+#[display(fmt = "Show patterns")]
+pub(crate) struct SparseCheckoutShow;
+impl OpTrait for SparseCheckoutShow {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+            state.push_screen(screen::sparse_checkout::create(
+                Rc::clone(&state.config),
+                Rc::clone(&state.repo),
+                term.size()?,
+            )?);
+            Ok(())
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Add patterns")]
+pub(crate) struct SparseCheckoutAdd;
+impl OpTrait for SparseCheckoutAdd {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt(
+            "Add patterns",
+            |state, term, input| sparse_checkout(state, term, "add", input),
+            true,
+        ))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Set patterns")]
+pub(crate) struct SparseCheckoutSet;
+impl OpTrait for SparseCheckoutSet {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt(
+            "Set patterns",
+            |state, term, input| sparse_checkout(state, term, "set", input),
+            true,
+        ))
+    }
+}
+
+fn sparse_checkout(state: &mut State, term: &mut Term, subcommand: &str, input: &str) -> Res<()> {
+    let patterns = split_args(input)?;
+    if patterns.is_empty() {
+        return Err("No patterns given".into());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["sparse-checkout", subcommand]);
+    cmd.args(patterns);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+#[derive(Display)]
+#[display(fmt = "Toggle cone mode")]
+pub(crate) struct SparseCheckoutToggleCone;
+impl OpTrait for SparseCheckoutToggleCone {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let Some(sparse) = sparse::status(&state.repo)? else {
+                return Err("Sparse checkout isn't enabled".into());
+            };
+
+            let mut cmd = Command::new("git");
+            cmd.args(["sparse-checkout", "reapply"]);
+            cmd.arg(if sparse.cone { "--no-cone" } else { "--cone" });
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+}
+
+#[derive(Display)]
+#[display(fmt = "Disable")]
+pub(crate) struct SparseCheckoutDisable;
+impl OpTrait for SparseCheckoutDisable {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let mut cmd = Command::new("git");
+            cmd.args(["sparse-checkout", "disable"]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+}
@@ -7,7 +7,7 @@ use crate::{
     Action,
 };
 use derive_more::Display;
-use std::{ffi::OsString, process::Command, rc::Rc};
+use std::{ffi::OsString, ops::Range, process::Command, rc::Rc};
 
 #[derive(Display)]
 #[display(fmt = "Stage")]
@@ -17,10 +17,16 @@ impl OpTrait for Stage {
         let action = match target.cloned() {
             Some(TargetData::AllUnstaged) => stage_unstaged(),
             Some(TargetData::AllUntracked(untracked)) => stage_untracked(untracked),
-            Some(TargetData::File(u)) => stage_file(u.into()),
-            Some(TargetData::Delta(d)) => stage_file(d.new_file.into()),
+            Some(TargetData::File(u)) => stage_files(vec![u.into()]),
+            Some(TargetData::Directory(files)) => {
+                stage_files(files.into_iter().map(OsString::from).collect())
+            }
+            Some(TargetData::Delta(d)) => match d.status {
+                git2::Delta::Renamed => stage_files(vec![d.old_file.into(), d.new_file.into()]),
+                _ => stage_files(vec![d.new_file.into()]),
+            },
             Some(TargetData::Hunk(h)) => stage_patch(h),
-            Some(TargetData::HunkLine(h, i)) => stage_line(h, i),
+            Some(TargetData::HunkLine(h, lines)) => stage_lines(h, lines),
             _ => return None,
         };
 
@@ -52,11 +58,11 @@ fn stage_untracked(untracked: Vec<std::path::PathBuf>) -> Action {
     })
 }
 
-fn stage_file(file: OsString) -> Action {
+fn stage_files(files: Vec<OsString>) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
         cmd.args(["add"]);
-        cmd.arg(&file);
+        cmd.args(&files);
 
         state.close_menu();
         state.run_cmd(term, &[], cmd)
@@ -67,19 +73,21 @@ fn stage_patch(h: Rc<Hunk>) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
         cmd.args(["apply", "--cached"]);
+        cmd.args(state.settings.borrow().diff.apply_args());
 
         state.close_menu();
         state.run_cmd(term, &h.format_patch().into_bytes(), cmd)
     })
 }
 
-fn stage_line(h: Rc<Hunk>, i: usize) -> Action {
+fn stage_lines(h: Rc<Hunk>, lines: Range<usize>) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
         cmd.args(["apply", "--cached", "--recount"]);
+        cmd.args(state.settings.borrow().diff.apply_args());
 
         let input = h
-            .format_line_patch(i..(i + 1), PatchMode::Normal)
+            .format_line_patch(lines.clone(), PatchMode::Normal)
             .into_bytes();
 
         state.close_menu();
@@ -1,8 +1,11 @@
-use super::{create_prompt, create_prompt_with_default, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use super::{create_prompt, create_prompt_with_default, set_prompt, Action, OpTrait};
+use crate::{
+    config::Config, git::diff::Hunk, items::TargetData, menu::arg::Arg, state::State, term::Term,
+    Res,
+};
 use derive_more::Display;
-use git2::{Repository, Status, StatusOptions};
-use std::{process::Command, rc::Rc};
+use git2::{Oid, Repository, Status, StatusOptions};
+use std::{ffi::OsString, path::PathBuf, process::Command, rc::Rc};
 
 pub(crate) fn init_args() -> Vec<Arg> {
     vec![
@@ -140,6 +143,132 @@ fn is_something_staged(repo: &Repository) -> Res<bool> {
     }))
 }
 
+#[derive(Display)]
+#[display(fmt = "Stash selected")]
+pub(crate) struct StashSelected;
+impl OpTrait for StashSelected {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target.cloned() {
+            Some(TargetData::File(file)) => Some(stash_selected_prompt(stash_files, vec![file])),
+            // Both sides of a rename, or git would only stash the addition of the new file
+            Some(TargetData::Delta(d)) if d.status == git2::Delta::Renamed => Some(
+                stash_selected_prompt(stash_files, vec![d.old_file, d.new_file]),
+            ),
+            Some(TargetData::Delta(d)) => {
+                Some(stash_selected_prompt(stash_files, vec![d.new_file]))
+            }
+            Some(TargetData::Hunk(h)) => Some(stash_selected_prompt(stash_hunk, h)),
+            _ => None,
+        }
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+}
+
+fn stash_selected_prompt<T: Clone + 'static>(
+    callback: fn(&mut State, &mut Term, &str, &T) -> Res<()>,
+    selected: T,
+) -> Action {
+    Rc::new(move |state: &mut State, _term: &mut Term| {
+        set_prompt(
+            state,
+            "Stash message",
+            callback,
+            Box::new(|_| None),
+            selected.clone(),
+            true,
+        );
+        Ok(())
+    })
+}
+
+#[allow(clippy::ptr_arg)]
+fn stash_files(state: &mut State, term: &mut Term, input: &str, files: &Vec<PathBuf>) -> Res<()> {
+    let args = state.pending_menu.as_ref().unwrap().args();
+    state.close_menu();
+
+    // A staged deletion, like the old side of a rename, isn't in the index for git to match it.
+    // Unstaged again it's stashed as a deletion all the same.
+    let workdir = state.repo.workdir().expect("No workdir");
+    let index = state.repo.index()?;
+    let deleted = files
+        .iter()
+        .filter(|file| index.get_path(file, 0).is_none() && !workdir.join(file).exists())
+        .collect::<Vec<_>>();
+    if !deleted.is_empty() {
+        let mut cmd = Command::new("git");
+        cmd.args(["reset", "-q", "--"]);
+        cmd.args(deleted);
+        state.run_cmd(term, &[], cmd)?;
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["stash", "push"]);
+    cmd.args(args);
+    if !input.is_empty() {
+        cmd.args(["--message", input]);
+    }
+    cmd.arg("--");
+    cmd.args(files);
+
+    state.run_cmd(term, &[], cmd)?;
+    Ok(())
+}
+
+/// Stashes a single unstaged hunk by staging it on its own and stashing the index.
+/// Anything already staged is set aside beforehand, and restored afterwards.
+fn stash_hunk(state: &mut State, term: &mut Term, input: &str, hunk: &Rc<Hunk>) -> Res<()> {
+    let patch = hunk.format_patch().into_bytes();
+    let need_to_set_aside_index = is_something_staged(&state.repo)?;
+    let index_tree = write_index_tree(&state.repo)?;
+
+    state.close_menu();
+
+    let mut cmd = Command::new("git");
+    cmd.args(["apply", "--cached", "--check"]);
+    cmd.args(state.settings.borrow().diff.apply_args());
+    state.run_cmd(term, &patch, cmd)?;
+
+    if need_to_set_aside_index {
+        let mut cmd = Command::new("git");
+        cmd.args(["read-tree", "HEAD"]);
+        state.run_cmd(term, &[], cmd)?;
+    }
+
+    let result = stash_staged_hunk(state, term, input, &patch);
+
+    // Also unstages the hunk again if stashing it failed
+    if need_to_set_aside_index || result.is_err() {
+        let mut cmd = Command::new("git");
+        cmd.args(["read-tree", &index_tree.to_string()]);
+        state.run_cmd(term, &[], cmd)?;
+    }
+
+    result
+}
+
+fn stash_staged_hunk(state: &mut State, term: &mut Term, input: &str, patch: &[u8]) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["apply", "--cached"]);
+    cmd.args(state.settings.borrow().diff.apply_args());
+    state.run_cmd(term, patch, cmd)?;
+
+    let mut cmd = Command::new("git");
+    cmd.args(["stash", "push", "--staged"]);
+    if !input.is_empty() {
+        cmd.args(["--message", input]);
+    }
+    state.run_cmd(term, &[], cmd)
+}
+
+/// Records what's staged as a tree, for `git read-tree` to restore.
+fn write_index_tree(repo: &Repository) -> Res<Oid> {
+    let mut index = repo.index()?;
+    index.read(false)?;
+    Ok(index.write_tree()?)
+}
+
 #[derive(Display)]
 #[display(fmt = "Stash keeping index")]
 pub(crate) struct StashKeepIndex;
@@ -240,3 +369,48 @@ fn selected_stash(state: &State) -> Option<String> {
         _ => Some("0".to_string()),
     }
 }
+
+/// Adds `--autostash` to the arguments of a command supporting it, when `general.autostash` is enabled.
+pub(crate) fn with_autostash_arg(config: &Config, mut args: Vec<OsString>) -> Vec<OsString> {
+    if config.general.autostash.enabled && !args.iter().any(|arg| arg == "--autostash") {
+        args.push("--autostash".into());
+    }
+    args
+}
+
+/// Runs a command that can't stash by itself with uncommitted changes stashed,
+/// when `general.autostash` is enabled. They're restored even if the command fails.
+pub(crate) fn run_autostashed(state: &mut State, term: &mut Term, cmd: Command) -> Res<()> {
+    if !state.config.general.autostash.enabled || !has_uncommitted_changes(&state.repo)? {
+        return state.run_cmd(term, &[], cmd);
+    }
+
+    let mut push = Command::new("git");
+    push.args(["stash", "push", "--message", "gitu autostash"]);
+    state.run_cmd(term, &[], push)?;
+
+    let result = state.run_cmd(term, &[], cmd);
+
+    let mut pop = Command::new("git");
+    pop.args(["stash", "pop", "-q"]);
+    if state.run_cmd(term, &[], pop).is_err() {
+        return Err(
+            "Restoring the autostash conflicted, your changes are still in the stash".into(),
+        );
+    }
+
+    result
+}
+
+/// Staged or unstaged changes to tracked files, which is what git's `--autostash` stashes.
+fn has_uncommitted_changes(repo: &Repository) -> Res<bool> {
+    let statuses = repo.statuses(Some(
+        StatusOptions::new()
+            .include_untracked(false)
+            .include_ignored(false),
+    ))?;
+
+    Ok(statuses
+        .iter()
+        .any(|entry| !entry.status().is_empty() && !entry.status().is_ignored()))
+}
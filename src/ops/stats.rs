@@ -0,0 +1,24 @@
+use super::{Action, OpTrait};
+use crate::{git, items::TargetData, screen, state::State, term::Term};
+use derive_more::Display;
+use std::rc::Rc;
+
+#[derive(Display)]
+#[display(fmt = "Show repository statistics")]
+pub(crate) struct ShowStats;
+impl OpTrait for ShowStats {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+            let dir = state.repo.workdir().expect("No workdir").to_path_buf();
+            let pending = git::stats::compute_in_background(dir);
+            state.pending_stats = Some(pending.clone());
+            state.push_screen(screen::stats::create(
+                Rc::clone(&state.config),
+                term.size()?,
+                pending,
+            )?);
+            Ok(())
+        }))
+    }
+}
@@ -10,12 +10,17 @@ impl OpTrait for Unstage {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
         let action = match target.cloned() {
             Some(TargetData::AllStaged) => unstage_staged(),
-            Some(TargetData::Delta(d)) => unstage_file(d.new_file.into()),
+            Some(TargetData::Directory(files)) => {
+                unstage_files(files.into_iter().map(OsString::from).collect())
+            }
+            Some(TargetData::Delta(d)) => match d.status {
+                git2::Delta::Renamed => unstage_files(vec![d.old_file.into(), d.new_file.into()]),
+                _ => unstage_files(vec![d.new_file.into()]),
+            },
             Some(TargetData::Hunk(h)) => unstage_patch(h.format_patch().into_bytes()),
-            Some(TargetData::HunkLine(h, i)) => unstage_line(
-                h.format_line_patch(i..(i + 1), PatchMode::Reverse)
-                    .into_bytes(),
-            ),
+            Some(TargetData::HunkLine(h, lines)) => {
+                unstage_lines(h.format_line_patch(lines, PatchMode::Reverse).into_bytes())
+            }
             _ => return None,
         };
 
@@ -36,11 +41,11 @@ fn unstage_staged() -> Action {
     })
 }
 
-fn unstage_file(file: OsString) -> Action {
+fn unstage_files(files: Vec<OsString>) -> Action {
     Rc::new(move |state: &mut State, term: &mut Term| {
         let mut cmd = Command::new("git");
         cmd.args(["restore", "--staged"]);
-        cmd.arg(&file);
+        cmd.args(&files);
 
         state.close_menu();
         state.run_cmd(term, &[], cmd)
@@ -51,16 +56,18 @@ fn unstage_patch(input: Vec<u8>) -> Action {
     Rc::new(move |state: &mut State, term: &mut Term| {
         let mut cmd = Command::new("git");
         cmd.args(["apply", "--cached", "--reverse"]);
+        cmd.args(state.settings.borrow().diff.apply_args());
 
         state.close_menu();
         state.run_cmd(term, &input, cmd)
     })
 }
 
-fn unstage_line(input: Vec<u8>) -> Action {
+fn unstage_lines(input: Vec<u8>) -> Action {
     Rc::new(move |state: &mut State, term: &mut Term| {
         let mut cmd = Command::new("git");
         cmd.args(["apply", "--cached", "--reverse", "--recount"]);
+        cmd.args(state.settings.borrow().diff.apply_args());
 
         state.close_menu();
         state.run_cmd(term, &input, cmd)
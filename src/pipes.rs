@@ -0,0 +1,184 @@
+//! Feeding commands their input and collecting their output while they run.
+//! Doing either only before or after the other could block forever on a full pipe.
+
+use crate::Res;
+use std::{
+    io::{self, Read, Write},
+    process::Child,
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+
+/// Output beyond this is read but dropped, the log couldn't reasonably show it anyway.
+const MAX_OUTPUT_BYTES: usize = 1 << 20;
+
+/// Writes `input` to the command's stdin in the background, closing it once done.
+pub(crate) fn write_stdin(child: &mut Child, input: &[u8]) {
+    let Some(mut stdin) = child.stdin.take() else {
+        return;
+    };
+
+    let input = input.to_vec();
+    thread::spawn(move || {
+        // A command may well exit without reading all of it
+        if let Err(error) = stdin.write_all(&input) {
+            if error.kind() != io::ErrorKind::BrokenPipe {
+                log::warn!("Couldn't write command input: {}", error);
+            }
+        }
+    });
+}
+
+struct Captured {
+    bytes: Vec<u8>,
+    dropped: usize,
+}
+
+/// Drains the command's stderr and stdout as it writes to them.
+/// Output of both pipes as it arrives, to be shown while the command is still running.
+pub(crate) type LiveOutput = Arc<Mutex<String>>;
+
+pub(crate) struct OutputReaders {
+    stderr: Option<JoinHandle<io::Result<Captured>>>,
+    stdout: Option<JoinHandle<io::Result<Captured>>>,
+    live: LiveOutput,
+}
+
+impl OutputReaders {
+    pub(crate) fn start(child: &mut Child) -> Self {
+        let live = LiveOutput::default();
+        Self {
+            stderr: child
+                .stderr
+                .take()
+                .map(|pipe| read_in_background(pipe, Arc::clone(&live))),
+            stdout: child
+                .stdout
+                .take()
+                .map(|pipe| read_in_background(pipe, Arc::clone(&live))),
+            live,
+        }
+    }
+
+    pub(crate) fn live(&self) -> LiveOutput {
+        Arc::clone(&self.live)
+    }
+
+    /// Waits for both to be closed, and returns stderr followed by stdout.
+    pub(crate) fn join(self) -> Res<String> {
+        let mut out = vec![];
+        let mut dropped = 0;
+
+        for reader in [self.stderr, self.stdout].into_iter().flatten() {
+            let captured = reader
+                .join()
+                .map_err(|_| "Couldn't read cmd output")?
+                .map_err(|e| format!("Couldn't read cmd output: {}", e))?;
+
+            out.extend(captured.bytes);
+            dropped += captured.dropped;
+        }
+
+        let mut out = String::from_utf8_lossy(&out).into_owned();
+        if dropped > 0 {
+            out.push_str(&format!("\n… {} more bytes of output not shown\n", dropped));
+        }
+        Ok(out)
+    }
+}
+
+fn read_in_background(
+    mut pipe: impl Read + Send + 'static,
+    live: LiveOutput,
+) -> JoinHandle<io::Result<Captured>> {
+    thread::spawn(move || {
+        let mut captured = Captured {
+            bytes: vec![],
+            dropped: 0,
+        };
+        let mut buf = [0; 8192];
+
+        loop {
+            let len = match pipe.read(&mut buf) {
+                Ok(0) => return Ok(captured),
+                Ok(len) => len,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+
+            let kept = len.min(MAX_OUTPUT_BYTES.saturating_sub(captured.bytes.len()));
+            captured.bytes.extend_from_slice(&buf[..kept]);
+            captured.dropped += len - kept;
+
+            // A character split between reads is garbled, the joined output won't be
+            if kept > 0 {
+                live.lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .push_str(&String::from_utf8_lossy(&buf[..kept]));
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::{Command, Stdio};
+
+    fn run(cmd: &mut Command, input: &[u8]) -> String {
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        write_stdin(&mut child, input);
+        let output = OutputReaders::start(&mut child);
+        assert!(child.wait().unwrap().success());
+        output.join().unwrap()
+    }
+
+    #[test]
+    fn echoing_large_input() {
+        // Far more than a pipe holds, `cat` writes it back before having read it all
+        let input = "a line of a patch\n".repeat(50_000);
+        assert_eq!(run(&mut Command::new("cat"), input.as_bytes()), input);
+    }
+
+    #[test]
+    fn stderr_before_stdout() {
+        let out = run(
+            Command::new("sh").args(["-c", "echo out; echo err >&2"]),
+            b"",
+        );
+        assert_eq!(out, "err\nout\n");
+    }
+
+    #[test]
+    fn live_output_as_it_arrives() {
+        let mut child = Command::new("sh")
+            .args(["-c", "echo first; sleep 0.2; echo second"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let output = OutputReaders::start(&mut child);
+        while output.live().lock().unwrap().is_empty() {
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(*output.live().lock().unwrap(), "first\n");
+
+        child.wait().unwrap();
+        assert_eq!(output.join().unwrap(), "first\nsecond\n");
+    }
+
+    #[test]
+    fn output_beyond_limit_dropped() {
+        let input = vec![b'a'; MAX_OUTPUT_BYTES + 10];
+        let out = run(&mut Command::new("cat"), &input);
+
+        assert!(out.starts_with(&"a".repeat(MAX_OUTPUT_BYTES)));
+        assert!(out.ends_with("\n… 10 more bytes of output not shown\n"));
+    }
+}
@@ -0,0 +1,147 @@
+//! Non-interactive output of Gitu's model, for use in scripts and pipes.
+
+use crate::{
+    cli::Commands,
+    config::Config,
+    forge::ci::CiStatuses,
+    git::{
+        self,
+        diff::{Diff, HunkCache},
+    },
+    git2_opts, screen,
+    settings::Settings,
+    Res,
+};
+use git2::Repository;
+use ratatui::{prelude::Rect, text::Line};
+use std::{cell::RefCell, io::Write, path::PathBuf, rc::Rc};
+
+/// Writes the output of `command`. Returns `false` if the command is an interactive one.
+pub(crate) fn print_command(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    command: &Commands,
+    out: &mut impl Write,
+) -> Res<bool> {
+    let settings = Settings::from_config(&config);
+    let cache = HunkCache::default();
+
+    match command {
+        Commands::Status { porcelain: false } => status(config, settings, repo, out)?,
+        Commands::Status { porcelain: true } => status_porcelain(&config, &settings, &repo, out)?,
+        Commands::Diff { staged, range } => {
+            let diff = match (staged, range) {
+                (_, Some(range)) => git::diff_range(&config, &settings.diff, &cache, &repo, range)?,
+                (true, None) => git::diff_staged(&config, &settings.diff, &cache, &repo)?,
+                (false, None) => git::diff_unstaged(&config, &settings.diff, &cache, &repo)?,
+            };
+
+            patch(&diff, out)?;
+        }
+        Commands::Show { .. }
+        | Commands::Log { .. }
+        | Commands::Init { .. }
+        | Commands::Clone { .. } => return Ok(false),
+    }
+
+    Ok(true)
+}
+
+/// Prints the status screen as shown on startup, without styling.
+fn status(
+    config: Rc<Config>,
+    settings: Settings,
+    repo: Rc<Repository>,
+    out: &mut impl Write,
+) -> Res<()> {
+    let settings = Rc::new(RefCell::new(settings));
+    let screen = screen::status::create(
+        config,
+        settings,
+        Rc::new(HunkCache::default()),
+        CiStatuses::default(),
+        repo,
+        Rect::default(),
+    )?;
+
+    for item in screen.visible_items() {
+        writeln!(out, "{}", line_text(&item.display).trim_end())?;
+    }
+
+    Ok(())
+}
+
+fn status_porcelain(
+    config: &Config,
+    settings: &Settings,
+    repo: &Repository,
+    out: &mut impl Write,
+) -> Res<()> {
+    let statuses = repo.statuses(Some(&mut git2_opts::status(repo)?))?;
+
+    for status in statuses.iter() {
+        let section = if status.status().is_conflicted() {
+            "unmerged"
+        } else if status.status().is_wt_new() {
+            "untracked"
+        } else {
+            continue;
+        };
+
+        let path = PathBuf::from(status.path().unwrap_or_default());
+        writeln!(out, "{} {}", section, path.display())?;
+    }
+
+    for (section, diff) in [
+        (
+            "unstaged",
+            git::diff_unstaged(config, &settings.diff, &HunkCache::default(), repo)?,
+        ),
+        (
+            "staged",
+            git::diff_staged(config, &settings.diff, &HunkCache::default(), repo)?,
+        ),
+    ] {
+        for delta in diff.deltas {
+            let status = format!("{:?}", delta.status).to_lowercase();
+            if delta.old_file == delta.new_file {
+                writeln!(out, "{} {} {}", section, status, delta.new_file.display())?;
+            } else {
+                writeln!(
+                    out,
+                    "{} {} {} -> {}",
+                    section,
+                    status,
+                    delta.old_file.display(),
+                    delta.new_file.display()
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints a diff in a format that `git apply` accepts.
+fn patch(diff: &Diff, out: &mut impl Write) -> Res<()> {
+    for delta in &diff.deltas {
+        write!(out, "{}", delta.file_header)?;
+
+        for hunk in &delta.hunks {
+            writeln!(out, "{}", hunk.header)?;
+
+            for line in &hunk.content.lines {
+                writeln!(out, "{}", line_text(line))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn line_text(line: &Line) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
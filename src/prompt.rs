@@ -1,17 +1,29 @@
 use super::Res;
 use crate::ops::Action;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{backend::Backend, Terminal};
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::BTreeMap};
 use tui_prompts::{State as _, TextState};
 
+const HISTORY_LIMIT: usize = 100;
+
 pub(crate) struct PromptData {
     pub(crate) prompt_text: Cow<'static, str>,
     pub(crate) update_fn: Action,
+    /// Prompts sharing a key share their input history.
+    pub(crate) history_key: Option<&'static str>,
 }
 
 pub(crate) struct Prompt {
     pub(crate) data: Option<PromptData>,
     pub(crate) state: TextState<'static>,
+    completions: Vec<String>,
+    history: BTreeMap<&'static str, Vec<String>>,
+    /// Position while browsing history, counted from the most recent entry,
+    /// along with the input that was typed before browsing started.
+    history_position: Option<(usize, String)>,
+    /// Hides what's typed, and keeps it out of the history.
+    pub(crate) masked: bool,
 }
 
 impl Prompt {
@@ -19,17 +31,176 @@ impl Prompt {
         Prompt {
             data: None,
             state: TextState::new(),
+            completions: vec![],
+            history: BTreeMap::new(),
+            history_position: None,
+            masked: false,
+        }
+    }
+
+    pub(crate) fn handle_key_event(&mut self, key: KeyEvent) {
+        match (key.code, key.modifiers) {
+            (KeyCode::Tab, _) => self.complete(),
+            (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => self.history_prev(),
+            (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => self.history_next(),
+            (KeyCode::Char('w'), KeyModifiers::CONTROL) => self.delete_word_backward(),
+            _ => self.state.handle_key_event(key),
+        }
+    }
+
+    /// Records a submitted value, so it can be recalled the next time the same prompt is shown.
+    pub(crate) fn push_history(&mut self, key: &'static str, value: &str) {
+        if value.is_empty() || self.masked {
+            return;
+        }
+
+        let entries = self.history.entry(key).or_default();
+        entries.retain(|entry| entry != value);
+        entries.push(value.to_string());
+
+        if entries.len() > HISTORY_LIMIT {
+            entries.remove(0);
+        }
+    }
+
+    fn history_entries(&self) -> &[String] {
+        self.data
+            .as_ref()
+            .and_then(|data| data.history_key)
+            .and_then(|key| self.history.get(key))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    fn history_prev(&mut self) {
+        let len = self.history_entries().len();
+        let position = match &self.history_position {
+            Some((position, _)) => position + 1,
+            None => 0,
+        };
+
+        if position >= len {
+            return;
+        }
+
+        if self.history_position.is_none() {
+            self.history_position = Some((0, self.state.value().to_string()));
+        }
+
+        self.show_history_entry(position);
+    }
+
+    fn history_next(&mut self) {
+        match self.history_position.take() {
+            None => (),
+            Some((0, draft)) => self.set_value(draft),
+            Some((position, draft)) => {
+                self.history_position = Some((position, draft));
+                self.show_history_entry(position - 1);
+            }
         }
     }
 
+    fn show_history_entry(&mut self, position: usize) {
+        let entries = self.history_entries();
+        let entry = entries[entries.len() - 1 - position].clone();
+
+        if let Some((current, _)) = &mut self.history_position {
+            *current = position;
+        }
+
+        self.set_value(entry);
+    }
+
+    fn set_value(&mut self, value: String) {
+        *self.state.value_mut() = value;
+        self.state.move_end();
+    }
+
+    fn delete_word_backward(&mut self) {
+        // The position counts chars, not bytes
+        let value = self.state.value();
+        let split = value
+            .char_indices()
+            .nth(self.state.position())
+            .map_or(value.len(), |(index, _)| index);
+        let (before, after) = value.split_at(split);
+        let kept = before
+            .trim_end()
+            .trim_end_matches(|c: char| !c.is_whitespace());
+        let new_position = kept.chars().count();
+        let value = format!("{}{}", kept, after);
+
+        *self.state.value_mut() = value;
+        *self.state.position_mut() = new_position;
+    }
+
     pub(crate) fn set(&mut self, data: PromptData) {
         self.data = Some(data);
         self.state.focus();
     }
 
+    pub(crate) fn mask_input(&mut self) {
+        self.masked = true;
+    }
+
+    /// Candidates offered when pressing tab in the currently set prompt.
+    pub(crate) fn set_completions(&mut self, completions: Vec<String>) {
+        self.completions = completions;
+    }
+
+    /// Start of the input being completed. Revision ranges like `A..B` complete each side.
+    fn completion_start(&self) -> usize {
+        let input = self.state.value();
+        let start = input.rfind("..").map(|i| i + 2).unwrap_or(0);
+        start + input[start..].len() - input[start..].trim_start_matches('.').len()
+    }
+
+    /// Candidates containing the input being completed, shown below the prompt.
+    pub(crate) fn matching_completions(&self) -> impl Iterator<Item = &str> {
+        let word = &self.state.value()[self.completion_start()..];
+        self.completions
+            .iter()
+            .filter(move |candidate| candidate.contains(word))
+            .map(String::as_str)
+    }
+
+    /// Completes the current input to the longest prefix shared by all matching candidates.
+    pub(crate) fn complete(&mut self) {
+        let start = self.completion_start();
+        let input = &self.state.value()[start..];
+        let mut matching = self
+            .completions
+            .iter()
+            .filter(|candidate| candidate.starts_with(input));
+
+        let Some(first) = matching.next() else {
+            return;
+        };
+
+        let common_prefix = matching.fold(first.as_str(), |prefix, candidate| {
+            let len = prefix
+                .char_indices()
+                .zip(candidate.chars())
+                .find(|((_, a), b)| a != b)
+                .map(|((i, _), _)| i)
+                .unwrap_or(prefix.len().min(candidate.len()));
+
+            &prefix[..len]
+        });
+
+        if common_prefix.len() > input.len() {
+            let completed = format!("{}{}", &self.state.value()[..start], common_prefix);
+            self.set_value(completed);
+        }
+    }
+
     pub(crate) fn reset<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Res<()> {
         self.data = None;
         self.state = TextState::new();
+        self.completions = vec![];
+        self.history_position = None;
+        self.masked = false;
         terminal.hide_cursor()?;
         Ok(())
     }
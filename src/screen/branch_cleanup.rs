@@ -0,0 +1,92 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    git::branch_cleanup,
+    items::{self, Item, TargetData},
+    menu::Menu,
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+use std::rc::Rc;
+
+pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Res<Screen> {
+    let mut screen = Screen::new(
+        Rc::clone(&config),
+        size,
+        "Branch cleanup",
+        Box::new(move || {
+            let style = &config.style;
+            let stale = branch_cleanup::stale_branches(&repo)?;
+            if stale.is_empty() {
+                return Ok(vec![Item {
+                    id: "no_stale_branches".into(),
+                    display: Line::raw("No branches are merged or have lost their upstream."),
+                    depth: 0,
+                    unselectable: true,
+                    ..Default::default()
+                }]);
+            }
+
+            let mut items = vec![];
+            if !stale.merged.is_empty() {
+                items.push(Item {
+                    id: "merged".into(),
+                    display: Line::styled(
+                        format!(
+                            "Merged into {} ({})",
+                            stale.default_branch.as_deref().unwrap_or(""),
+                            stale.merged.len()
+                        ),
+                        &style.section_header,
+                    ),
+                    section: true,
+                    depth: 0,
+                    ..Default::default()
+                });
+                items.extend(stale.merged.iter().map(|name| Item {
+                    id: format!("merged_{}", name).into(),
+                    display: Line::styled(name.clone(), &style.branch),
+                    depth: 1,
+                    target_data: Some(TargetData::Branch(name.clone())),
+                    ..Default::default()
+                }));
+            }
+
+            if !stale.upstream_gone.is_empty() {
+                if !items.is_empty() {
+                    items.push(items::blank_line());
+                }
+                items.push(Item {
+                    id: "upstream_gone".into(),
+                    display: Line::styled(
+                        format!("Upstream gone ({})", stale.upstream_gone.len()),
+                        &style.section_header,
+                    ),
+                    section: true,
+                    depth: 0,
+                    ..Default::default()
+                });
+                items.extend(stale.upstream_gone.iter().map(|(name, upstream)| Item {
+                    id: format!("upstream_gone_{}", name).into(),
+                    display: Line::from(vec![
+                        Span::styled(name.clone(), &style.branch),
+                        Span::raw(" "),
+                        Span::styled(upstream.clone(), &style.remote),
+                    ]),
+                    depth: 1,
+                    target_data: Some(TargetData::Branch(name.clone())),
+                    ..Default::default()
+                }));
+            }
+
+            Ok(items)
+        }),
+    )?;
+
+    screen.keymap = Some(Menu::BranchCleanup);
+    Ok(screen)
+}
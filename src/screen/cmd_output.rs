@@ -0,0 +1,38 @@
+use super::Screen;
+use crate::{
+    cmd_log::{self, CmdLogEntry},
+    config::Config,
+    items::Item,
+    Res,
+};
+use ratatui::prelude::Rect;
+use std::{
+    rc::Rc,
+    sync::{Arc, RwLock},
+};
+
+/// The whole output of commands, which the popup below the screen may only show the end of.
+pub(crate) fn create(
+    config: Rc<Config>,
+    size: Rect,
+    entries: Vec<Arc<RwLock<CmdLogEntry>>>,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        "Output",
+        Box::new(move || {
+            Ok(entries
+                .iter()
+                .flat_map(|entry| cmd_log::format_log_entry(&config, entry))
+                .enumerate()
+                .map(|(i, line)| Item {
+                    id: format!("output_line_{}", i).into(),
+                    display: line,
+                    depth: 0,
+                    ..Default::default()
+                })
+                .collect())
+        }),
+    )
+}
@@ -0,0 +1,50 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    git::{self, diff::HunkCache},
+    items::{self, Item},
+    settings::Settings,
+    Res,
+};
+use git2::Repository;
+use ratatui::{prelude::Rect, text::Line};
+use std::{cell::RefCell, iter, rc::Rc};
+
+pub(crate) fn create(
+    config: Rc<Config>,
+    settings: Rc<RefCell<Settings>>,
+    cache: Rc<HunkCache>,
+    repo: Rc<Repository>,
+    size: Rect,
+    range: String,
+    title: String,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        "Diff",
+        Box::new(move || {
+            let style = &config.style;
+            let settings = settings.borrow();
+            let diff = git::diff_range(&config, &settings.diff, &cache, repo.as_ref(), &range)?;
+
+            Ok(iter::once(Item {
+                id: "diff_range".into(),
+                display: Line::styled(title.clone(), &style.section_header),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain([items::blank_line()])
+            .chain(items::create_diff_items(
+                Rc::clone(&config),
+                &settings,
+                "diff_range",
+                &diff,
+                &0,
+                false,
+            ))
+            .collect())
+        }),
+    )
+}
@@ -0,0 +1,82 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    git::index_flags,
+    items::{self, Item, TargetData},
+    Res,
+};
+use git2::Repository;
+use ratatui::{prelude::Rect, text::Line};
+use std::{iter, path::PathBuf, rc::Rc};
+
+pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        "Hidden files",
+        Box::new(move || {
+            let flagged = index_flags::flagged_files(&repo)?;
+            if flagged.is_empty() {
+                return Ok(vec![Item {
+                    id: "no_flagged_files".into(),
+                    display: Line::raw("No files are assumed unchanged or skip-worktree."),
+                    depth: 0,
+                    unselectable: true,
+                    ..Default::default()
+                }]);
+            }
+
+            let mut items = vec![];
+            for (id, title, files) in [
+                (
+                    "assume_unchanged",
+                    "Assumed unchanged",
+                    flagged.assume_unchanged,
+                ),
+                ("skip_worktree", "Skip-worktree", flagged.skip_worktree),
+            ] {
+                if files.is_empty() {
+                    continue;
+                }
+                if !items.is_empty() {
+                    items.push(items::blank_line());
+                }
+                items.extend(create_section(&config, id, title, files));
+            }
+
+            Ok(items)
+        }),
+    )
+}
+
+fn create_section(
+    config: &Config,
+    id: &'static str,
+    title: &'static str,
+    files: Vec<PathBuf>,
+) -> impl Iterator<Item = Item> {
+    let style = &config.style;
+
+    iter::once(Item {
+        id: id.into(),
+        display: Line::styled(
+            format!("{} ({})", title, files.len()),
+            &style.section_header,
+        ),
+        section: true,
+        depth: 0,
+        ..Default::default()
+    })
+    .chain(
+        files
+            .into_iter()
+            .map(move |file| Item {
+                id: format!("{}_{}", id, file.display()).into(),
+                display: Line::styled(file.to_string_lossy().to_string(), &style.file_header),
+                depth: 1,
+                target_data: Some(TargetData::File(file)),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>(),
+    )
+}
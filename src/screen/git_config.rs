@@ -0,0 +1,117 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    git::{
+        config::{self, ConfigScope, EDITABLE_KEYS},
+        hooks,
+    },
+    items::{self, Item, TargetData},
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+use std::{collections::BTreeMap, iter, rc::Rc};
+
+pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        "Git config",
+        Box::new(move || {
+            let dir = repo.workdir().expect("No workdir");
+
+            Ok(create_scope_section(
+                &config,
+                ConfigScope::Local,
+                "Local",
+                config::list(dir, ConfigScope::Local)?,
+            )
+            .chain([items::blank_line()])
+            .chain(create_scope_section(
+                &config,
+                ConfigScope::Global,
+                "Global",
+                config::list(dir, ConfigScope::Global)?,
+            ))
+            .chain([items::blank_line()])
+            .chain(create_hooks_section(&config, hooks::list(dir)?))
+            .collect())
+        }),
+    )
+}
+
+fn create_scope_section(
+    config: &Config,
+    scope: ConfigScope,
+    title: &'static str,
+    values: BTreeMap<String, String>,
+) -> impl Iterator<Item = Item> {
+    let style = &config.style;
+    let key_width = EDITABLE_KEYS.iter().map(|key| key.len()).max().unwrap_or(0);
+
+    iter::once(Item {
+        id: format!("{}_config", scope).into(),
+        display: Line::styled(title, &style.section_header),
+        section: true,
+        depth: 0,
+        ..Default::default()
+    })
+    .chain(
+        EDITABLE_KEYS
+            .iter()
+            .map(move |&key| {
+                let value = match values.get(key) {
+                    Some(value) => Span::raw(value.clone()),
+                    None => Span::raw("(unset)"),
+                };
+
+                Item {
+                    id: format!("{}_config_{}", scope, key).into(),
+                    display: Line::from(vec![
+                        Span::raw(format!("{:<width$} ", key, width = key_width)),
+                        value,
+                    ]),
+                    depth: 1,
+                    target_data: Some(TargetData::ConfigEntry { key, scope }),
+                    ..Default::default()
+                }
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn create_hooks_section(config: &Config, hooks: Vec<String>) -> impl Iterator<Item = Item> {
+    let style = &config.style;
+    let header = Item {
+        id: "hooks".into(),
+        display: Line::styled("Hooks", &style.section_header),
+        section: true,
+        depth: 0,
+        ..Default::default()
+    };
+
+    let hooks = if hooks.is_empty() {
+        vec![Item {
+            id: "no_hooks".into(),
+            display: Line::raw("(none)"),
+            depth: 1,
+            unselectable: true,
+            ..Default::default()
+        }]
+    } else {
+        hooks
+            .into_iter()
+            .map(|hook| Item {
+                id: format!("hook_{}", hook).into(),
+                display: Line::raw(hook),
+                depth: 1,
+                ..Default::default()
+            })
+            .collect()
+    };
+
+    iter::once(header).chain(hooks)
+}
@@ -1,21 +1,30 @@
 use super::Screen;
-use crate::{config::Config, items::log, Res};
+use crate::{
+    config::Config,
+    forge::ci::CiStatuses,
+    items::{log, LogFilter},
+    Res,
+};
 use git2::{Oid, Repository};
 use ratatui::prelude::Rect;
-use regex::Regex;
 use std::rc::Rc;
 
 pub(crate) fn create(
     config: Rc<Config>,
+    ci_statuses: CiStatuses,
     repo: Rc<Repository>,
     size: Rect,
     limit: usize,
     rev: Option<Oid>,
-    msg_regex: Option<Regex>,
+    filter: LogFilter,
 ) -> Res<Screen> {
-    Screen::new(
+    let mut screen = Screen::new(
         Rc::clone(&config),
         size,
-        Box::new(move || log(&config, &repo, limit, rev, msg_regex.clone())),
-    )
+        "Log",
+        Box::new(move || log(&config, &ci_statuses, &repo, limit, rev, &filter)),
+    )?;
+
+    screen.log_rev = rev;
+    Ok(screen)
 }
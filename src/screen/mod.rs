@@ -1,13 +1,28 @@
-use ratatui::prelude::*;
+use ratatui::{
+    prelude::*,
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState},
+};
 
-use crate::{config::Config, items::TargetData, Res};
+use crate::{config::Config, git::diff::Hunk, items::TargetData, menu::Menu, Res};
+use git2::Oid;
 
 use super::Item;
-use std::{borrow::Cow, collections::HashSet, rc::Rc};
+use std::{borrow::Cow, collections::HashSet, ops::RangeInclusive, rc::Rc};
 
+pub(crate) mod branch_cleanup;
+pub(crate) mod cmd_output;
+pub(crate) mod diff_range;
+pub(crate) mod flagged_files;
+pub(crate) mod git_config;
 pub(crate) mod log;
+pub(crate) mod preview;
+pub(crate) mod pull_requests;
+pub(crate) mod rebase_todo;
+pub(crate) mod shortlog;
 pub(crate) mod show;
 pub(crate) mod show_refs;
+pub(crate) mod sparse_checkout;
+pub(crate) mod stats;
 pub(crate) mod status;
 
 const BOTTOM_CONTEXT_LINES: usize = 2;
@@ -28,12 +43,31 @@ pub(crate) struct Screen {
     items: Vec<Item>,
     line_index: Vec<usize>,
     collapsed: HashSet<Cow<'static, str>>,
+    /// Items marked for a batch operation.
+    marked: HashSet<Cow<'static, str>>,
+    /// The item a region of hunk lines was started at, it extends to the cursor.
+    /// Looked up again by its hunk and line when refreshed.
+    region_anchor: Option<usize>,
+    /// What kind of screen this is, shown in the title bar.
+    pub(crate) name: &'static str,
+    /// The commit shown by this screen, if it displays a single one.
+    pub(crate) commit: Option<String>,
+    /// Where the history shown by this screen starts, if it's a log of another revision than `HEAD`.
+    pub(crate) log_rev: Option<Oid>,
+    /// Whether the cursor and selection are drawn, a preview beside another screen has none.
+    pub(crate) focused: bool,
+    /// Bindings taking precedence over root's while this screen is shown.
+    pub(crate) keymap: Option<Menu>,
+    /// Whether it shows another repository than the state's, like a submodule's. Ops acting on
+    /// the repository would act on the wrong one, so only those moving around are available.
+    pub(crate) other_repo: bool,
 }
 
 impl Screen {
     pub(crate) fn new(
         config: Rc<Config>,
         size: Rect,
+        name: &'static str,
         refresh_items: Box<dyn Fn() -> Res<Vec<Item>>>,
     ) -> Res<Self> {
         let mut screen = Self {
@@ -45,6 +79,14 @@ impl Screen {
             items: vec![],
             line_index: vec![],
             collapsed: HashSet::new(),
+            marked: HashSet::new(),
+            region_anchor: None,
+            name,
+            commit: None,
+            focused: true,
+            keymap: None,
+            other_repo: false,
+            log_rev: None,
         };
 
         screen.update()?;
@@ -88,6 +130,35 @@ impl Screen {
         self.scroll_fit_start();
     }
 
+    /// Moves the cursor to the first visible item matching `predicate`, returns whether there was one.
+    pub(crate) fn select_first(&mut self, predicate: impl Fn(&Item) -> bool) -> bool {
+        let len = self.line_index.len();
+        let Some(line_i) = (0..len).find(|&line_i| predicate(self.at_line(line_i))) else {
+            return false;
+        };
+
+        self.cursor = line_i;
+        self.scroll_fit_end();
+        self.scroll_fit_start();
+        true
+    }
+
+    /// Like [`Self::select_first`], but starting after the cursor and wrapping around.
+    pub(crate) fn select_next_matching(&mut self, predicate: impl Fn(&Item) -> bool) -> bool {
+        let (len, cursor) = (self.line_index.len(), self.cursor);
+        let Some(line_i) = (1..=len)
+            .map(|offset| (cursor + offset) % len)
+            .find(|&line_i| !self.at_line(line_i).unselectable && predicate(self.at_line(line_i)))
+        else {
+            return false;
+        };
+
+        self.cursor = line_i;
+        self.scroll_fit_end();
+        self.scroll_fit_start();
+        true
+    }
+
     fn scroll_fit_start(&mut self) {
         if self.items.is_empty() {
             return;
@@ -154,6 +225,26 @@ impl Screen {
             .unwrap_or(self.cursor)
     }
 
+    pub(crate) fn select_top(&mut self) {
+        if let Some(line_i) =
+            (0..self.line_index.len()).find(|&line_i| self.nav_filter(line_i, NavMode::Normal))
+        {
+            self.cursor = line_i;
+            self.scroll_fit_start();
+        }
+    }
+
+    pub(crate) fn select_bottom(&mut self) {
+        if let Some(line_i) = (0..self.line_index.len())
+            .rev()
+            .find(|&line_i| self.nav_filter(line_i, NavMode::Normal))
+        {
+            self.cursor = line_i;
+            self.scroll_fit_end();
+            self.scroll_fit_start();
+        }
+    }
+
     pub(crate) fn scroll_half_page_up(&mut self) {
         let half_screen = self.size.height as usize / 2;
         self.scroll = self.scroll.saturating_sub(half_screen);
@@ -170,7 +261,7 @@ impl Screen {
                 .copied()
                 .enumerate()
                 .map(|(line, _)| (line + 1).saturating_sub(half_screen))
-                .last()
+                .next_back()
                 .unwrap_or(0),
         );
 
@@ -192,14 +283,104 @@ impl Screen {
         self.update_line_index();
     }
 
+    pub(crate) fn toggle_mark(&mut self) {
+        let selected = &self.items[self.line_index[self.cursor]];
+
+        if !self.marked.remove(&selected.id) {
+            self.marked.insert(selected.id.clone());
+        }
+    }
+
+    pub(crate) fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+
+    /// Starts a region at the selected hunk line, or ends the one there is.
+    pub(crate) fn toggle_region(&mut self) {
+        self.region_anchor = match self.region_items() {
+            Some(_) => None,
+            None => Some(self.line_index[self.cursor]),
+        };
+    }
+
+    pub(crate) fn clear_region(&mut self) {
+        self.region_anchor = None;
+    }
+
+    /// Indices of the region's items, if the cursor is still in the hunk it was started in.
+    fn region_items(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.region_anchor?;
+        let selected = *self.line_index.get(self.cursor)?;
+
+        match (
+            &self.items.get(anchor)?.target_data,
+            &self.items[selected].target_data,
+        ) {
+            (Some(TargetData::HunkLine(hunk, _)), Some(TargetData::HunkLine(other, _)))
+                if Rc::ptr_eq(hunk, other) =>
+            {
+                Some(anchor.min(selected)..=anchor.max(selected))
+            }
+            _ => None,
+        }
+    }
+
+    /// The hunk the cursor is on, or on one of the lines of.
+    fn selected_hunk(&self) -> Option<&Rc<Hunk>> {
+        hunk_of(self.items.get(*self.line_index.get(self.cursor)?)?)
+    }
+
+    /// The hunk lines of the region, acted on instead of the selected one.
+    pub(crate) fn region(&self) -> Option<TargetData> {
+        let items = self.region_items()?;
+
+        match (
+            &self.items[*items.start()].target_data,
+            &self.items[*items.end()].target_data,
+        ) {
+            (Some(TargetData::HunkLine(hunk, first)), Some(TargetData::HunkLine(_, last))) => {
+                Some(TargetData::HunkLine(Rc::clone(hunk), first.start..last.end))
+            }
+            _ => None,
+        }
+    }
+
+    /// Marked items that are still on the screen, in display order.
+    pub(crate) fn marked_items(&self) -> impl Iterator<Item = &Item> {
+        self.items
+            .iter()
+            .filter(|item| self.marked.contains(&item.id))
+    }
+
     pub(crate) fn update(&mut self) -> Res<()> {
         let nav_mode = self.selected_item_nav_mode();
+        let region_anchor = self.region_anchor_line();
         self.items = (self.refresh_items)()?;
+        self.region_anchor =
+            region_anchor.and_then(|(hunk_id, line)| self.find_line(&hunk_id, line));
         self.update_line_index();
         self.update_cursor(nav_mode);
         Ok(())
     }
 
+    /// The id of the hunk the region was started in, and the index of the line into it.
+    fn region_anchor_line(&self) -> Option<(String, usize)> {
+        match &self.items.get(self.region_anchor?)?.target_data {
+            Some(TargetData::HunkLine(hunk, lines)) => Some((hunk.format_patch(), lines.start)),
+            _ => None,
+        }
+    }
+
+    fn find_line(&self, hunk_id: &str, line: usize) -> Option<usize> {
+        let hunk = self.items.iter().position(|item| item.id == hunk_id)?;
+        let i = hunk + 1 + line;
+
+        match &self.items.get(i)?.target_data {
+            Some(TargetData::HunkLine(_, lines)) if lines.start == line => Some(i),
+            _ => None,
+        }
+    }
+
     fn update_cursor(&mut self, nav_mode: NavMode) {
         self.clamp_cursor();
         if self.is_cursor_off_screen() {
@@ -221,6 +402,11 @@ impl Screen {
         }
     }
 
+    /// Items that aren't hidden inside a collapsed section, in display order.
+    pub(crate) fn visible_items(&self) -> impl Iterator<Item = &Item> {
+        self.line_index.iter().map(|&i| &self.items[i])
+    }
+
     fn update_line_index(&mut self) {
         self.line_index = self
             .items
@@ -276,7 +462,7 @@ impl Screen {
         &self.items[self.line_index[self.cursor]]
     }
 
-    fn line_views(&self, area: Rect) -> impl Iterator<Item = LineView> {
+    fn line_views(&self, area: Rect) -> impl Iterator<Item = LineView<'_>> {
         let scan_start = self.scroll.min(self.cursor);
         let scan_end = (self.scroll + area.height as usize).min(self.line_index.len());
         let scan_highlight_range = scan_start..(scan_end);
@@ -303,6 +489,13 @@ impl Screen {
     }
 }
 
+fn hunk_of(item: &Item) -> Option<&Rc<Hunk>> {
+    match &item.target_data {
+        Some(TargetData::Hunk(hunk) | TargetData::HunkLine(hunk, _)) => Some(hunk),
+        _ => None,
+    }
+}
+
 struct LineView<'a> {
     item_index: usize,
     item: &'a Item,
@@ -313,43 +506,109 @@ struct LineView<'a> {
 impl Widget for &Screen {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let style = &self.config.style;
+        let region = self.region_items();
+        let selected_hunk = self.selected_hunk();
 
         for (line_index, line) in self.line_views(area).enumerate() {
+            let y = area.y + line_index as u16;
             let line_area = Rect {
-                x: 0,
-                y: line_index as u16,
-                width: buf.area.width,
+                x: area.x,
+                y,
+                width: area.width,
                 height: 1,
             };
 
-            let indented_line_area = Rect { x: 1, ..line_area };
+            let indented_line_area = Rect {
+                x: area.x + 1,
+                width: area.width.saturating_sub(1),
+                ..line_area
+            };
 
-            if line.highlighted {
+            if self.focused
+                && selected_hunk
+                    .is_some_and(|hunk| hunk_of(line.item).is_some_and(|of| Rc::ptr_eq(hunk, of)))
+            {
+                buf.set_style(line_area, &style.selection_hunk);
+            }
+
+            if self.focused && line.highlighted {
                 buf.set_style(line_area, &style.selection_area);
 
                 if self.line_index[self.cursor] == line.item_index {
                     buf.set_style(line_area, &style.selection_line);
                 } else {
-                    buf.get_mut(0, line_index as u16)
+                    buf.get_mut(area.x, y)
                         .set_char('▌')
                         .set_style(&style.selection_bar);
                 }
             }
 
+            if self.focused
+                && region
+                    .as_ref()
+                    .is_some_and(|region| region.contains(&line.item_index))
+            {
+                buf.set_style(line_area, &style.region);
+                buf.get_mut(area.x, y)
+                    .set_char('▌')
+                    .set_style(&style.selection_bar);
+            }
+
+            if self.marked.contains(&line.item.id) {
+                buf.get_mut(area.x, y)
+                    .set_char('*')
+                    .set_style(&style.marked);
+            }
+
             line.display.render(indented_line_area, buf);
             let overflow = line.display.width() > line_area.width as usize;
 
             if self.is_collapsed(line.item) && line.display.width() > 0 || overflow {
                 let line_end =
-                    (indented_line_area.x + line.display.width() as u16).min(area.width - 1);
-                buf.get_mut(line_end, line_index as u16).set_char('…');
+                    (indented_line_area.x + line.display.width() as u16).min(area.right() - 1);
+                buf.get_mut(line_end, y).set_char('…');
             }
 
-            if self.line_index[self.cursor] == line.item_index {
-                buf.get_mut(0, line_index as u16)
+            if self.focused && self.line_index[self.cursor] == line.item_index {
+                buf.get_mut(area.x, y)
                     .set_char('▌')
                     .set_style(&style.cursor);
             }
         }
+
+        if self.line_index.len() > area.height as usize {
+            self.render_scroll_position(area, buf);
+        }
+    }
+}
+
+impl Screen {
+    fn render_scroll_position(&self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 {
+            return;
+        }
+
+        let style = Style::from(&self.config.style.scrollbar);
+        let total = self.line_index.len();
+
+        let mut scrollbar_state = ScrollbarState::new(total.saturating_sub(area.height as usize))
+            .position(self.scroll)
+            .viewport_content_length(area.height as usize);
+
+        Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .symbols(symbols::scrollbar::VERTICAL)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .style(style)
+            .render(area, buf, &mut scrollbar_state);
+
+        let line = self.cursor + 1;
+        let indicator = format!(" line {} of {} ({}%) ", line, total, line * 100 / total);
+        let x = area
+            .right()
+            .saturating_sub(indicator.chars().count() as u16 + 1)
+            .max(area.x);
+
+        buf.set_string(x, area.bottom() - 1, indicator, style);
     }
 }
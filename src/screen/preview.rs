@@ -0,0 +1,93 @@
+//! The selected commit or file of a screen split in two, shown beside the list.
+
+use super::{show, Screen};
+use crate::{
+    config::Config,
+    git::diff::HunkCache,
+    items::{self, TargetData},
+    settings::Settings,
+    state::State,
+    Res,
+};
+use git2::Repository;
+use ratatui::prelude::Rect;
+use std::{borrow::Cow, cell::RefCell, rc::Rc};
+
+pub(crate) struct Preview {
+    item_id: Cow<'static, str>,
+    refresh_generation: usize,
+    /// None when the selected item has nothing to preview.
+    pub(crate) screen: Option<Screen>,
+}
+
+/// Follows the selected item of the listing screen, recreating the preview when it's stale.
+pub(crate) fn update(preview: &mut Option<Preview>, state: &State, size: Rect) {
+    let item = state.screens.last().unwrap().get_selected_item();
+    let refresh_generation = state.refresh_generation;
+    let is_current = preview.as_ref().is_some_and(|preview| {
+        preview.item_id == item.id && preview.refresh_generation == refresh_generation
+    });
+
+    if !is_current {
+        let screen = item.target_data.as_ref().and_then(|target| {
+            create(
+                &state.config,
+                &state.settings,
+                &state.hunk_cache,
+                &state.repo,
+                size,
+                target,
+            )
+            .map_err(|error| log::debug!("Couldn't preview {}: {}", item.id, error))
+            .ok()
+            .flatten()
+        });
+
+        *preview = Some(Preview {
+            item_id: item.id.clone(),
+            refresh_generation,
+            screen,
+        });
+    }
+
+    if let Some(screen) = preview.as_mut().and_then(|preview| preview.screen.as_mut()) {
+        screen.size = size;
+    }
+}
+
+fn create(
+    config: &Rc<Config>,
+    settings: &Rc<RefCell<Settings>>,
+    cache: &Rc<HunkCache>,
+    repo: &Rc<Repository>,
+    size: Rect,
+    target: &TargetData,
+) -> Res<Option<Screen>> {
+    let mut screen = match target {
+        TargetData::Commit(reference)
+        | TargetData::Branch(reference)
+        | TargetData::Stash {
+            commit: reference, ..
+        } => show::create(
+            Rc::clone(config),
+            Rc::clone(settings),
+            Rc::clone(cache),
+            Rc::clone(repo),
+            size,
+            reference.clone(),
+        )?,
+        TargetData::Delta(delta) => {
+            let (config, delta) = (Rc::clone(config), delta.clone());
+            Screen::new(
+                Rc::clone(&config),
+                size,
+                "Preview",
+                Box::new(move || Ok(items::create_single_delta_items(&config, &delta))),
+            )?
+        }
+        _ => return Ok(None),
+    };
+
+    screen.focused = false;
+    Ok(Some(screen))
+}
@@ -0,0 +1,89 @@
+use std::{iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    forge::PendingPullRequests,
+    items::{Item, TargetData},
+    Res,
+};
+use ratatui::{
+    prelude::Rect,
+    style::Style,
+    text::{Line, Span},
+};
+
+pub(crate) const NAME: &str = "Pull requests";
+
+/// Shows the pull requests as last listed, it's refreshed once they're listed again.
+pub(crate) fn create(config: Rc<Config>, size: Rect, pending: PendingPullRequests) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        NAME,
+        Box::new(move || {
+            let style = &config.style;
+            let forge = pending.forge;
+
+            let (count, items) = match pending.pull_requests() {
+                None => (
+                    "listing".to_string(),
+                    vec![message_item("Waiting for the forge…", Style::new())],
+                ),
+                Some(Err(err)) => (
+                    "?".to_string(),
+                    vec![message_item(
+                        &format!("Couldn't list {}s: {}", forge.pull_request_name(), err),
+                        (&style.cmd_error).into(),
+                    )],
+                ),
+                Some(Ok(pull_requests)) => (
+                    pull_requests.len().to_string(),
+                    pull_requests
+                        .into_iter()
+                        .map(|pr| Item {
+                            id: format!("pull_request_{}", pr.number).into(),
+                            display: Line::from(vec![
+                                Span::styled(format!("#{}", pr.number), &style.hash),
+                                Span::raw(" "),
+                                Span::styled(pr.branch.clone(), &style.branch),
+                                Span::raw(" "),
+                                Span::raw(pr.title),
+                                Span::raw(" "),
+                                Span::styled(format!("@{}", pr.author), &style.remote),
+                            ]),
+                            depth: 1,
+                            target_data: Some(TargetData::PullRequest {
+                                forge,
+                                number: pr.number,
+                            }),
+                            ..Default::default()
+                        })
+                        .collect(),
+                ),
+            };
+
+            let header = Item {
+                id: "pull_requests".into(),
+                display: Line::styled(
+                    format!("Open {}s ({})", forge.pull_request_name(), count),
+                    &style.section_header,
+                ),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            };
+
+            Ok(iter::once(header).chain(items).collect())
+        }),
+    )
+}
+
+fn message_item(message: &str, style: Style) -> Item {
+    Item {
+        display: Line::styled(message.to_string(), style),
+        depth: 1,
+        unselectable: true,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,89 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    git::rebase_todo::Todo,
+    items::{self, Item, TargetData},
+    menu::Menu,
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+use std::{cell::RefCell, rc::Rc};
+
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Rect,
+    todo: Rc<RefCell<Todo>>,
+) -> Res<Screen> {
+    let mut screen = Screen::new(
+        Rc::clone(&config),
+        size,
+        "Rebase todo",
+        Box::new(move || {
+            let style = &config.style;
+            let todo = todo.borrow();
+
+            if todo.lines.is_empty() {
+                return Ok(vec![Item {
+                    id: "no_todo".into(),
+                    display: Line::raw("Nothing to rebase."),
+                    depth: 0,
+                    unselectable: true,
+                    ..Default::default()
+                }]);
+            }
+
+            let mut items = todo
+                .lines
+                .iter()
+                .map(|line| {
+                    let short_id = repo.find_object(line.oid, None)?.short_id()?;
+                    Ok(Item {
+                        id: line.oid.to_string().into(),
+                        display: Line::from(vec![
+                            Span::styled(format!("{:<6}", line.action), &style.command),
+                            Span::raw(" "),
+                            Span::styled(short_id.as_str().unwrap_or("").to_string(), &style.hash),
+                            Span::raw(" "),
+                            Span::raw(line.summary.clone()),
+                        ]),
+                        depth: 0,
+                        target_data: Some(TargetData::Commit(line.oid.to_string())),
+                        ..Default::default()
+                    })
+                })
+                .collect::<Res<Vec<_>>>()?;
+
+            items.push(items::blank_line());
+            items.extend(key_help(&config));
+            Ok(items)
+        }),
+    )?;
+
+    screen.keymap = Some(Menu::RebaseTodo);
+    Ok(screen)
+}
+
+/// Like the comments git puts below the todo list, but with gitu's keys.
+fn key_help(config: &Config) -> impl Iterator<Item = Item> + '_ {
+    config
+        .bindings
+        .get(&Menu::RebaseTodo)
+        .into_iter()
+        .flatten()
+        .map(|(op, keys)| Item {
+            id: format!("rebase_todo_help_{:?}", op).into(),
+            display: Line::from(vec![
+                Span::styled(keys.join("/"), &config.style.hotkey),
+                Span::raw(" "),
+                Span::raw(op.clone().implementation().to_string()),
+            ]),
+            depth: 0,
+            unselectable: true,
+            ..Default::default()
+        })
+}
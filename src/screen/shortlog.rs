@@ -0,0 +1,86 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    items::{Item, TargetData},
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+use std::{
+    iter,
+    process::{Command, Stdio},
+    rc::Rc,
+};
+
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Rect,
+    range: String,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        "Shortlog",
+        Box::new(move || {
+            let style = &config.style;
+            let contributors = shortlog(&repo, &range)?;
+
+            Ok(iter::once(Item {
+                id: "shortlog".into(),
+                display: Line::styled(format!("Contributors to {}", range), &style.section_header),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain(contributors.into_iter().map(|(commits, name, email)| Item {
+                id: email.clone().into(),
+                display: Line::from(vec![
+                    Span::raw(format!("{:>6}  ", commits)),
+                    Span::raw(name),
+                    Span::styled(format!(" <{}>", email), &style.hash),
+                ]),
+                depth: 1,
+                target_data: Some(TargetData::Author {
+                    range: range.clone(),
+                    email,
+                }),
+                ..Default::default()
+            }))
+            .collect())
+        }),
+    )
+}
+
+/// Commits, name and email of each author, like `git shortlog -sne`.
+fn shortlog(repo: &Repository, range: &str) -> Res<Vec<(usize, String, String)>> {
+    let output = Command::new("git")
+        .args(["shortlog", "-sne", range, "--"])
+        .current_dir(repo.workdir().expect("No workdir"))
+        // Shortlog reads a log from stdin when it isn't a terminal
+        .stdin(Stdio::null())
+        .output()?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr)
+            .trim()
+            .to_string()
+            .into());
+    }
+
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| {
+            let (commits, author) = line.trim_start().split_once('\t')?;
+            let (name, email) = author.rsplit_once(" <")?;
+            Some((
+                commits.parse().ok()?,
+                name.to_string(),
+                email.trim_end_matches('>').to_string(),
+            ))
+        })
+        .collect())
+}
@@ -1,33 +1,97 @@
-use std::{iter, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    iter,
+    path::PathBuf,
+    rc::Rc,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
 
 use crate::{
     config::Config,
-    git,
-    items::{self, Item},
+    dates,
+    forge::issues::{self, IssueRef},
+    git::{
+        self,
+        diff::{CachedHunks, DiffSettings, HunkCache},
+    },
+    items::{self, Item, TargetData},
+    settings::Settings,
     Res,
 };
 use git2::Repository;
 use ratatui::{
     prelude::Rect,
-    text::{Line, Text},
+    text::{Line, Span},
 };
 
 use super::Screen;
 
 pub(crate) fn create(
     config: Rc<Config>,
+    settings: Rc<RefCell<Settings>>,
+    cache: Rc<HunkCache>,
     repo: Rc<Repository>,
     size: Rect,
     reference: String,
 ) -> Res<Screen> {
-    Screen::new(
+    let hash = repo
+        .revparse_single(&reference)?
+        .peel_to_commit()?
+        .id()
+        .to_string();
+
+    let mut screen = Screen::new(
         Rc::clone(&config),
         size,
+        "Show",
         Box::new(move || {
             let style = &config.style;
+            let settings = settings.borrow();
             let commit = git::show_summary(repo.as_ref(), &reference)?;
-            let show = git::show(&config, repo.as_ref(), &reference)?;
-            let details = Text::from(commit.details).lines;
+            let show = git::show(&config, &settings.diff, &cache, repo.as_ref(), &reference)?;
+
+            let metadata = [
+                Some(format!("Author:     {}", commit.author)),
+                Some(format!(
+                    "AuthorDate: {}",
+                    dates::format(&settings.date_format, &commit.author_date)
+                )),
+                Some(format!("Commit:     {}", commit.committer)),
+                Some(format!(
+                    "CommitDate: {}",
+                    dates::format(&settings.date_format, &commit.committer_date)
+                )),
+                commit
+                    .signature
+                    .as_ref()
+                    .map(|signature| format!("Signature:  {}", signature)),
+            ]
+            .into_iter()
+            .flatten()
+            .map(|line| Item {
+                id: format!("commit_{}", commit.hash).into(),
+                display: Line::raw(line),
+                depth: 1,
+                unselectable: true,
+                ..Default::default()
+            });
+
+            let parents = commit.parents.iter().map(|parent| Item {
+                id: format!("commit_parent_{}", parent.hash).into(),
+                display: Line::from(vec![
+                    Span::raw("Parent:     "),
+                    Span::styled(parent.short_id.clone(), &style.hash),
+                    Span::raw(" "),
+                    Span::raw(parent.summary.clone()),
+                ]),
+                depth: 1,
+                target_data: Some(TargetData::Commit(parent.hash.clone())),
+                ..Default::default()
+            });
+
+            let issue_patterns = issues::patterns(&repo);
 
             Ok(iter::once(Item {
                 id: format!("commit_section_{}", commit.hash).into(),
@@ -36,21 +100,256 @@ pub(crate) fn create(
                 depth: 0,
                 ..Default::default()
             })
-            .chain(details.into_iter().map(|line| Item {
-                id: format!("commit_{}", commit.hash).into(),
-                display: line,
-                depth: 1,
+            .chain(metadata)
+            .chain(parents)
+            .chain([items::blank_line()])
+            .chain(commit.message.lines().map(|line| {
+                let refs = issues::find(&issue_patterns, line);
+                Item {
+                    id: format!("commit_{}", commit.hash).into(),
+                    display: message_line(config.as_ref(), line, &refs),
+                    depth: 1,
+                    unselectable: refs.is_empty(),
+                    target_data: (!refs.is_empty())
+                        .then(|| TargetData::IssueReferences(Rc::new(refs))),
+                    ..Default::default()
+                }
+            }))
+            .chain([items::blank_line()])
+            .chain(commit.note.iter().flat_map(|note| {
+                iter::once("Notes:".to_string())
+                    .chain(note.lines().map(|line| format!("    {}", line)))
+                    .map(|line| Item {
+                        id: format!("commit_note_{}", commit.hash).into(),
+                        display: Line::raw(line),
+                        depth: 1,
+                        unselectable: true,
+                        ..Default::default()
+                    })
+                    .chain([items::blank_line()])
+            }))
+            .chain((!commit.stats.is_empty()).then(|| Item {
+                id: format!("commit_stats_{}", commit.hash).into(),
+                display: Line::raw(commit.stats.clone()),
+                depth: 0,
                 unselectable: true,
                 ..Default::default()
             }))
             .chain([items::blank_line()])
             .chain(items::create_diff_items(
                 Rc::clone(&config),
+                &settings,
+                "show",
                 &show,
                 &0,
                 false,
             ))
             .collect())
         }),
-    )
+    )?;
+
+    screen.commit = Some(hash);
+    Ok(screen)
+}
+
+/// Show screens prepared while the cursor rested on a commit, so showing it is instant.
+/// Their diffs are made in the background, the screens are then created from the hunks
+/// cached. Each is tagged with the refresh generation it was asked for at, and dropped once
+/// the repo is refreshed. The least recently prepared are dropped once there's more than
+/// `capacity`.
+pub(crate) struct PreparedShows {
+    screens: VecDeque<(usize, Screen)>,
+    capacity: usize,
+    /// Commits being diffed, with the generation they were asked for at.
+    pending: Vec<(String, usize)>,
+    worker: Option<Worker>,
+}
+
+struct Job {
+    hash: String,
+    generation: usize,
+    settings: DiffSettings,
+}
+
+struct Prepared {
+    hash: String,
+    generation: usize,
+    hunks: Result<CachedHunks, String>,
+}
+
+/// A thread diffing commits one at a time, so its syntax highlighters are reused.
+struct Worker {
+    jobs: Sender<Job>,
+    done: Receiver<Prepared>,
+}
+
+impl Worker {
+    fn spawn(config: Config, dir: PathBuf) -> Self {
+        let (jobs, pending_jobs) = mpsc::channel::<Job>();
+        let (done_jobs, done) = mpsc::channel();
+
+        thread::spawn(move || {
+            let repo = Repository::open(dir).map_err(|err| err.to_string());
+            for job in pending_jobs {
+                let hunks = repo.as_ref().map_err(Clone::clone).and_then(|repo| {
+                    let cache = HunkCache::default();
+                    git::show(&config, &job.settings, &cache, repo, &job.hash)
+                        .map_err(|err| err.to_string())?;
+                    Ok(cache.into_cached())
+                });
+
+                let prepared = Prepared {
+                    hash: job.hash,
+                    generation: job.generation,
+                    hunks,
+                };
+                if done_jobs.send(prepared).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self { jobs, done }
+    }
+}
+
+impl PreparedShows {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            screens: VecDeque::new(),
+            capacity,
+            pending: vec![],
+            worker: None,
+        }
+    }
+
+    /// Starts diffing the commit in the background, unless it's prepared or pending already.
+    pub(crate) fn prepare(
+        &mut self,
+        config: &Config,
+        settings: DiffSettings,
+        repo: &Repository,
+        hash: &str,
+        generation: usize,
+    ) {
+        if let Some(i) = self.position(hash, generation) {
+            let screen = self.screens.remove(i).unwrap();
+            self.screens.push_back(screen);
+            return;
+        }
+
+        if self.pending.contains(&(hash.to_string(), generation)) {
+            return;
+        }
+
+        let worker = self
+            .worker
+            .get_or_insert_with(|| Worker::spawn(config.clone(), repo.path().to_path_buf()));
+
+        let job = Job {
+            hash: hash.to_string(),
+            generation,
+            settings,
+        };
+        if worker.jobs.send(job).is_ok() {
+            self.pending.push((hash.to_string(), generation));
+        }
+    }
+
+    /// Creates the screens of the commits diffed since last called, dropping those of an older
+    /// generation than `generation`.
+    pub(crate) fn receive(
+        &mut self,
+        config: &Rc<Config>,
+        settings: &Rc<RefCell<Settings>>,
+        cache: &Rc<HunkCache>,
+        repo: &Rc<Repository>,
+        size: Rect,
+        generation: usize,
+    ) {
+        self.screens
+            .retain(|(prepared_at, _)| *prepared_at == generation);
+
+        let Some(worker) = &self.worker else {
+            return;
+        };
+
+        for prepared in worker.done.try_iter().collect::<Vec<_>>() {
+            self.pending
+                .retain(|pending| *pending != (prepared.hash.clone(), prepared.generation));
+
+            if prepared.generation != generation {
+                continue;
+            }
+
+            let screen = prepared.hunks.map_err(Into::into).and_then(|hunks| {
+                cache.extend(hunks);
+                create(
+                    Rc::clone(config),
+                    Rc::clone(settings),
+                    Rc::clone(cache),
+                    Rc::clone(repo),
+                    size,
+                    prepared.hash.clone(),
+                )
+            });
+
+            match screen {
+                Ok(screen) => {
+                    self.screens.push_back((generation, screen));
+                    if self.screens.len() > self.capacity {
+                        self.screens.pop_front();
+                    }
+                }
+                Err(error) => {
+                    log::debug!(
+                        "Couldn't prepare show screen of {}: {}",
+                        prepared.hash,
+                        error
+                    )
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn contains(&self, hash: &str) -> bool {
+        self.screens
+            .iter()
+            .any(|(_, screen)| screen.commit.as_deref() == Some(hash))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// The screen of the commit, if prepared since the repo was last refreshed.
+    pub(crate) fn take(&mut self, hash: &str, generation: usize) -> Option<Screen> {
+        let i = self.position(hash, generation)?;
+        self.screens.remove(i).map(|(_, screen)| screen)
+    }
+
+    fn position(&self, hash: &str, generation: usize) -> Option<usize> {
+        self.screens.iter().position(|(prepared_at, screen)| {
+            *prepared_at == generation && screen.commit.as_deref() == Some(hash)
+        })
+    }
+}
+
+fn message_line(config: &Config, line: &str, refs: &[IssueRef]) -> Line<'static> {
+    let mut spans = vec![];
+    let mut pos = 0;
+
+    for issue in refs {
+        spans.push(Span::raw(line[pos..issue.start].to_string()));
+        spans.push(Span::styled(
+            issue.text.clone(),
+            &config.style.issue_reference,
+        ));
+        pos = issue.end;
+    }
+
+    spans.push(Span::raw(line[pos..].to_string()));
+    Line::from(spans)
 }
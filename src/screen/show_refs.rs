@@ -7,6 +7,7 @@ use std::{
 use super::Screen;
 use crate::{
     config::{Config, StyleConfigEntry},
+    git::branch_cleanup,
     items::{self, Item, TargetData},
     Res,
 };
@@ -20,6 +21,7 @@ pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Re
     Screen::new(
         Rc::clone(&config),
         size,
+        "Refs",
         Box::new(move || {
             let style = &config.style;
 
@@ -125,13 +127,19 @@ where
         .filter(filter)
         .map(move |reference| {
             let shorthand = reference.shorthand().unwrap().to_owned();
+            let mut spans = vec![
+                create_prefix(repo, &reference),
+                Span::styled(shorthand.clone(), style),
+            ];
+
+            // Like `git branch -vv` shows it
+            if reference.is_branch() && branch_cleanup::gone_upstream(repo, &shorthand).is_some() {
+                spans.push(Span::raw(" [gone]"));
+            }
 
             Item {
                 id: reference.name().unwrap().to_owned().into(),
-                display: Line::from(vec![
-                    create_prefix(repo, &reference),
-                    Span::styled(shorthand.clone(), style),
-                ]),
+                display: Line::from(spans),
                 depth: 1,
                 target_data: Some(TargetData::Branch(shorthand)),
                 ..Default::default()
@@ -0,0 +1,67 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    git::sparse,
+    items::{self, Item},
+    Res,
+};
+use git2::Repository;
+use ratatui::{prelude::Rect, text::Line};
+use std::{iter, rc::Rc};
+
+pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        "Sparse checkout",
+        Box::new(move || {
+            let style = &config.style;
+            let Some(sparse) = sparse::status(&repo)? else {
+                return Ok(vec![Item {
+                    id: "sparse_checkout_disabled".into(),
+                    display: Line::raw(
+                        "Sparse checkout isn't enabled, the whole tree is checked out.",
+                    ),
+                    depth: 0,
+                    unselectable: true,
+                    ..Default::default()
+                }]);
+            };
+
+            let title = if sparse.cone {
+                "Directories (cone mode)"
+            } else {
+                "Patterns"
+            };
+
+            Ok(iter::once(Item {
+                id: "sparse_checkout_patterns".into(),
+                display: Line::styled(title, &style.section_header),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain(sparse.patterns.into_iter().map(|pattern| Item {
+                id: format!("sparse_checkout_pattern_{}", pattern).into(),
+                display: Line::raw(pattern),
+                depth: 1,
+                ..Default::default()
+            }))
+            .chain([
+                items::blank_line(),
+                Item {
+                    id: "sparse_checkout_excluded".into(),
+                    display: Line::raw(format!(
+                        "{} tracked file{} excluded.",
+                        sparse.excluded,
+                        if sparse.excluded == 1 { "" } else { "s" }
+                    )),
+                    depth: 0,
+                    unselectable: true,
+                    ..Default::default()
+                },
+            ])
+            .collect())
+        }),
+    )
+}
@@ -0,0 +1,131 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    git::stats::{self, RepoStats},
+    items::{self, Item},
+    Res,
+};
+use ratatui::{
+    prelude::Rect,
+    text::{Line, Span},
+};
+use std::{iter, rc::Rc};
+
+/// Width of the bar of the month with the most commits.
+const BAR_WIDTH: usize = 40;
+
+/// Shows the statistics once `pending` has them.
+pub(crate) fn create(config: Rc<Config>, size: Rect, pending: stats::Pending) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        "Statistics",
+        Box::new(move || {
+            let style = &config.style;
+            let pending = pending.lock();
+
+            let stats = match &*pending {
+                None => return Ok(vec![message("Computing statistics…")]),
+                Some(Err(error)) => {
+                    return Ok(vec![message(&format!(
+                        "Couldn't compute statistics: {}",
+                        error
+                    ))])
+                }
+                Some(Ok(stats)) => stats,
+            };
+
+            Ok(section(&config, "Repository", summary_items(stats))
+                .into_iter()
+                .chain([items::blank_line()])
+                .chain(section(
+                    &config,
+                    "Contributors",
+                    stats
+                        .contributors
+                        .iter()
+                        .map(|(author, commits)| {
+                            Line::from(vec![
+                                Span::raw(format!("{:>6}  ", commits)),
+                                Span::raw(author.clone()),
+                            ])
+                        })
+                        .collect(),
+                ))
+                .chain([items::blank_line()])
+                .chain(section(&config, "Commits per month", month_items(stats)))
+                .chain([items::blank_line()])
+                .chain(section(
+                    &config,
+                    "Largest files",
+                    stats
+                        .largest_files
+                        .iter()
+                        .map(|(path, size)| {
+                            Line::from(vec![
+                                Span::raw(format!("{:>10}  ", stats::format_size(*size))),
+                                Span::styled(path.clone(), &style.file_header),
+                            ])
+                        })
+                        .collect(),
+                ))
+                .collect())
+        }),
+    )
+}
+
+fn message(text: &str) -> Item {
+    Item {
+        id: "stats_message".into(),
+        display: Line::raw(text.to_string()),
+        unselectable: true,
+        ..Default::default()
+    }
+}
+
+fn section(config: &Config, title: &'static str, lines: Vec<Line<'static>>) -> Vec<Item> {
+    iter::once(Item {
+        id: title.into(),
+        display: Line::styled(title, &config.style.section_header),
+        section: true,
+        depth: 0,
+        ..Default::default()
+    })
+    .chain(lines.into_iter().enumerate().map(|(i, line)| Item {
+        id: format!("{}_{}", title, i).into(),
+        display: line,
+        depth: 1,
+        ..Default::default()
+    }))
+    .collect()
+}
+
+fn summary_items(stats: &RepoStats) -> Vec<Line<'static>> {
+    [
+        ("Commits", stats.commits),
+        ("Contributors", stats.contributors.len()),
+        ("Local branches", stats.local_branches),
+        ("Remote branches", stats.remote_branches),
+        ("Tags", stats.tags),
+    ]
+    .into_iter()
+    .map(|(name, count)| Line::raw(format!("{:<16}{}", name, count)))
+    .collect()
+}
+
+fn month_items(stats: &RepoStats) -> Vec<Line<'static>> {
+    let recent = &stats.months[stats.months.len().saturating_sub(stats::RECENT_MONTHS)..];
+    let most = recent
+        .iter()
+        .map(|(_, commits)| *commits)
+        .max()
+        .unwrap_or(1);
+
+    recent
+        .iter()
+        .map(|(month, commits)| {
+            let bar = "█".repeat((commits * BAR_WIDTH).div_ceil(most));
+            Line::raw(format!("{}  {} {}", month, bar, commits))
+        })
+        .collect()
+}
@@ -1,9 +1,14 @@
 use super::Screen;
 use crate::{
     config::Config,
-    git::{self, diff::Diff},
+    forge::ci::CiStatuses,
+    git::{
+        self,
+        diff::{Diff, HunkCache},
+    },
     git2_opts,
-    items::{self, Item, TargetData},
+    items::{self, Item, LogFilter, TargetData},
+    settings::Settings,
     Res,
 };
 use git2::Repository;
@@ -11,20 +16,38 @@ use ratatui::{
     prelude::Rect,
     text::{Line, Span},
 };
-use std::{path::PathBuf, rc::Rc};
+use std::{cell::RefCell, iter, path::PathBuf, rc::Rc};
 
-pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Res<Screen> {
+pub(crate) fn create(
+    config: Rc<Config>,
+    settings: Rc<RefCell<Settings>>,
+    cache: Rc<HunkCache>,
+    ci_statuses: CiStatuses,
+    repo: Rc<Repository>,
+    size: Rect,
+) -> Res<Screen> {
     Screen::new(
         Rc::clone(&config),
         size,
+        "Status",
         Box::new(move || {
             let style = &config.style;
-            let statuses = repo.statuses(Some(&mut git2_opts::status(&repo)?))?;
+            let settings = settings.borrow();
+            // Files of untracked directories are listed too, sparing a walk of them after
+            let mut status_opts = git2_opts::status(&repo)?;
+            status_opts.recurse_untracked_dirs(true);
+            let statuses = repo.statuses(Some(&mut status_opts))?;
 
-            let untracked_files = statuses
+            let untracked = untracked_dirs(
+                &repo,
+                statuses
+                    .iter()
+                    .filter(|status| status.status().is_wt_new())
+                    .map(|status| PathBuf::from(status.path().unwrap())),
+            );
+            let untracked_files = untracked
                 .iter()
-                .filter(|status| status.status().is_wt_new())
-                .map(|status| PathBuf::from(status.path().unwrap()))
+                .map(|(path, _)| path.clone())
                 .collect::<Vec<_>>();
 
             let unmerged_files = statuses
@@ -33,7 +56,8 @@ pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Re
                 .map(|status| PathBuf::from(status.path().unwrap()))
                 .collect::<Vec<_>>();
 
-            let untracked = items_list(&config, untracked_files.clone());
+            let untracked = untracked_items(&config, untracked);
+            let rerere = rerere_items(&config, &repo, &unmerged_files);
             let unmerged = items_list(&config, unmerged_files);
 
             let items = if let Some(rebase) = git::rebase_status(&repo)? {
@@ -66,8 +90,28 @@ pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Re
                     ..Default::default()
                 }]
                 .into_iter()
+            } else if let Some(cherry_pick) = git::cherry_pick_status(&repo)? {
+                vec![Item {
+                    id: "cherry_pick_status".into(),
+                    display: Line::styled(
+                        format!("Cherry-picking {}", &cherry_pick.head),
+                        &style.section_header,
+                    ),
+                    ..Default::default()
+                }]
+                .into_iter()
+            } else if let Some(am) = git::am_status(&repo)? {
+                vec![Item {
+                    id: "am_status".into(),
+                    display: Line::styled(
+                        format!("Applying patch {} of {}", &am.next, &am.last),
+                        &style.section_header,
+                    ),
+                    ..Default::default()
+                }]
+                .into_iter()
             } else {
-                branch_status_items(&config, &repo)?.into_iter()
+                branch_status_items(&config, &ci_statuses, &repo)?.into_iter()
             }
             .chain(if untracked.is_empty() {
                 vec![]
@@ -100,25 +144,34 @@ pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Re
                 ]
             })
             .chain(unmerged)
+            .chain(rerere)
             .chain(create_status_section_items(
                 Rc::clone(&config),
+                &settings,
                 "Unstaged changes",
                 Some(TargetData::AllUnstaged),
-                &git::diff_unstaged(&config, repo.as_ref())?,
+                &git::diff_unstaged(&config, &settings.diff, &cache, repo.as_ref())?,
             ))
             .chain(create_status_section_items(
                 Rc::clone(&config),
+                &settings,
                 "Staged changes",
                 Some(TargetData::AllStaged),
-                &git::diff_staged(&config, repo.as_ref())?,
+                &git::diff_staged(&config, &settings.diff, &cache, repo.as_ref())?,
             ))
             .chain(create_stash_list_section_items(
                 Rc::clone(&config),
                 repo.as_ref(),
                 "Stashes",
             ))
+            .chain(create_upstream_section_items(
+                &config,
+                &ci_statuses,
+                repo.as_ref(),
+            )?)
             .chain(create_log_section_items(
                 Rc::clone(&config),
+                &ci_statuses,
                 repo.as_ref(),
                 "Recent commits",
             ))
@@ -129,6 +182,46 @@ pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Rect) -> Re
     )
 }
 
+fn rerere_items(config: &Config, repo: &Repository, unmerged_files: &[PathBuf]) -> Vec<Item> {
+    let style = &config.style;
+    let Some(status) = git::rerere::status(repo, unmerged_files) else {
+        return vec![];
+    };
+
+    vec![
+        items::blank_line(),
+        Item {
+            id: "rerere".into(),
+            display: Line::styled(
+                format!("Rerere ({} recorded)", status.recorded),
+                &style.section_header,
+            ),
+            section: true,
+            depth: 0,
+            ..Default::default()
+        },
+    ]
+    .into_iter()
+    .chain(status.conflicts.into_iter().map(|conflict| {
+        let path = conflict.path.to_string_lossy().to_string();
+        Item {
+            id: format!("rerere_{}", path).into(),
+            display: Line::from(vec![
+                Span::raw(if conflict.resolved {
+                    "resolved   "
+                } else {
+                    "recording  "
+                }),
+                Span::styled(path, &style.file_header),
+            ]),
+            depth: 1,
+            target_data: Some(TargetData::File(conflict.path)),
+            ..Default::default()
+        }
+    }))
+    .collect()
+}
+
 fn items_list(config: &Config, files: Vec<PathBuf>) -> Vec<Item> {
     let style = &config.style;
     files
@@ -143,7 +236,166 @@ fn items_list(config: &Config, files: Vec<PathBuf>) -> Vec<Item> {
         .collect::<Vec<_>>()
 }
 
-fn branch_status_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
+/// Untracked directories are collapsed sections of their files.
+fn untracked_items(config: &Config, untracked: Vec<(PathBuf, Vec<PathBuf>)>) -> Vec<Item> {
+    let style = &config.style;
+    untracked
+        .into_iter()
+        .flat_map(|(path, dir_files)| {
+            let display = path.to_string_lossy().to_string();
+            let is_dir = !dir_files.is_empty();
+            let item = Item {
+                id: display.clone().into(),
+                display: Line::styled(display, &style.file_header),
+                section: is_dir,
+                default_collapsed: is_dir,
+                depth: 1,
+                target_data: Some(items::TargetData::File(path)),
+                ..Default::default()
+            };
+
+            iter::once(item).chain(dir_files.into_iter().map(|file| Item {
+                id: file.to_string_lossy().to_string().into(),
+                display: Line::styled(file.to_string_lossy().to_string(), &style.file_header),
+                depth: 2,
+                target_data: Some(items::TargetData::File(file)),
+                ..Default::default()
+            }))
+        })
+        .collect::<Vec<_>>()
+}
+
+/// Groups untracked files by the outermost directory without anything tracked in it, like
+/// git reports them without recursing. Each with its files, none when it's a file itself or
+/// a nested repository.
+fn untracked_dirs(
+    repo: &Repository,
+    files: impl Iterator<Item = PathBuf>,
+) -> Vec<(PathBuf, Vec<PathBuf>)> {
+    let index = repo.index().ok();
+    let has_tracked = |dir: &str| {
+        index
+            .as_ref()
+            .is_none_or(|index| index.find_prefix(dir).is_ok())
+    };
+
+    let mut untracked: Vec<(PathBuf, Vec<PathBuf>)> = vec![];
+    for file in files {
+        let display = file.to_string_lossy();
+        let dir = display
+            .match_indices('/')
+            .map(|(end, _)| &display[..=end])
+            .filter(|dir| dir.len() < display.len())
+            .find(|dir| !has_tracked(dir))
+            .map(PathBuf::from);
+
+        match dir {
+            Some(dir) => match untracked.last_mut() {
+                Some((last, dir_files)) if *last == dir => dir_files.push(file),
+                _ => untracked.push((dir, vec![file])),
+            },
+            None => untracked.push((file, vec![])),
+        }
+    }
+
+    untracked
+}
+
+fn branch_status_items(
+    config: &Config,
+    ci_statuses: &CiStatuses,
+    repo: &Repository,
+) -> Res<Vec<Item>> {
+    let mut items = branch_upstream_items(config, ci_statuses, repo)?;
+    items.extend(shallow_item(repo)?);
+
+    let sparse = sparse_checkout_item(repo)?;
+    let count_skip_worktree = sparse.is_none();
+    items.extend(sparse);
+    items.extend(flagged_files_item(repo, count_skip_worktree)?);
+    Ok(items)
+}
+
+/// Changes to these files are hidden, which is easy to forget about.
+fn flagged_files_item(repo: &Repository, count_skip_worktree: bool) -> Res<Option<Item>> {
+    let flagged = git::index_flags::flagged_files(repo)?;
+    let files = |count: usize| format!("{} file{}", count, if count == 1 { "" } else { "s" });
+
+    let mut hidden = vec![];
+    if !flagged.assume_unchanged.is_empty() {
+        hidden.push(format!(
+            "{} assumed unchanged",
+            files(flagged.assume_unchanged.len())
+        ));
+    }
+    if count_skip_worktree && !flagged.skip_worktree.is_empty() {
+        hidden.push(format!(
+            "{} skip-worktree",
+            files(flagged.skip_worktree.len())
+        ));
+    }
+
+    if hidden.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(Item {
+        id: "flagged_files_status".into(),
+        display: Line::raw(format!("Hidden from status: {}.", hidden.join(", "))),
+        depth: 1,
+        unselectable: true,
+        ..Default::default()
+    }))
+}
+
+/// Commits are missing beyond the ones fetched, like in CI checkouts with `--depth`.
+fn shallow_item(repo: &Repository) -> Res<Option<Item>> {
+    if !repo.is_shallow() {
+        return Ok(None);
+    }
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    let depth = revwalk.count();
+
+    Ok(Some(Item {
+        id: "branch_status".into(),
+        display: Line::raw(format!(
+            "Shallow clone, {} commit{} deep.",
+            depth,
+            if depth == 1 { "" } else { "s" }
+        )),
+        depth: 1,
+        unselectable: true,
+        ..Default::default()
+    }))
+}
+
+/// Files outside the sparse-checkout patterns aren't in the worktree, nor listed as deleted.
+fn sparse_checkout_item(repo: &Repository) -> Res<Option<Item>> {
+    let Some(sparse) = git::sparse::status(repo)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(Item {
+        id: "sparse_checkout_status".into(),
+        display: Line::raw(format!(
+            "Sparse checkout{}, {} file{} excluded.",
+            if sparse.cone { " (cone mode)" } else { "" },
+            sparse.excluded,
+            if sparse.excluded == 1 { "" } else { "s" }
+        )),
+        depth: 1,
+        unselectable: true,
+        ..Default::default()
+    }))
+}
+
+fn branch_upstream_items(
+    config: &Config,
+    ci_statuses: &CiStatuses,
+    repo: &Repository,
+) -> Res<Vec<Item>> {
     let style = &config.style;
     let Ok(head) = repo.head() else {
         return Ok(vec![Item {
@@ -155,12 +407,20 @@ fn branch_status_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
         }]);
     };
 
+    let mut header = vec![Span::raw(format!(
+        "On branch {}",
+        head.shorthand().unwrap()
+    ))];
+    if let Some(ci_status) = head
+        .target()
+        .and_then(|oid| items::ci_status_span(config, ci_statuses, repo, oid))
+    {
+        header.extend([Span::raw(" "), ci_status]);
+    }
+
     let mut items = vec![Item {
         id: "branch_status".into(),
-        display: Line::styled(
-            format!("On branch {}", head.shorthand().unwrap()),
-            &style.section_header,
-        ),
+        display: Line::from(header).style(&style.section_header),
         section: true,
         depth: 0,
         ..Default::default()
@@ -219,6 +479,7 @@ fn branch_status_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
 
 fn create_status_section_items<'a>(
     config: Rc<Config>,
+    settings: &Settings,
     header: &str,
     header_data: Option<TargetData>,
     diff: &'a Diff,
@@ -238,7 +499,13 @@ fn create_status_section_items<'a>(
                 id: header.to_string().into(),
                 display: Line::from(vec![
                     Span::styled(header.to_string(), &style.section_header),
-                    format!(" ({})", diff.deltas.len()).into(),
+                    match items::filtered_deltas(diff, settings.file_filter.as_ref()).len() {
+                        shown if shown < diff.deltas.len() => {
+                            format!(" ({} of {})", shown, diff.deltas.len())
+                        }
+                        _ => format!(" ({})", diff.deltas.len()),
+                    }
+                    .into(),
                 ]),
                 section: true,
                 depth: 0,
@@ -248,7 +515,9 @@ fn create_status_section_items<'a>(
         ]
     }
     .into_iter()
-    .chain(items::create_diff_items(config, diff, &1, true))
+    .chain(items::create_diff_items(
+        config, settings, header, diff, &1, true,
+    ))
 }
 
 fn create_stash_list_section_items<'a>(
@@ -276,8 +545,89 @@ fn create_stash_list_section_items<'a>(
     .chain(stashes)
 }
 
+const UPSTREAM_SECTION_LIMIT: usize = 256;
+
+fn create_upstream_section_items(
+    config: &Config,
+    ci_statuses: &CiStatuses,
+    repo: &Repository,
+) -> Res<Vec<Item>> {
+    let Some((upstream_shortname, upstream_id)) = git::upstream(repo) else {
+        return Ok(vec![]);
+    };
+    let Some(head_id) = repo.head()?.target() else {
+        return Ok(vec![]);
+    };
+
+    let (ahead, behind) = repo.graph_ahead_behind(head_id, upstream_id)?;
+    let unpulled = items::log_range(
+        config,
+        ci_statuses,
+        repo,
+        head_id,
+        upstream_id,
+        UPSTREAM_SECTION_LIMIT,
+    )?;
+    let unpushed = items::log_range(
+        config,
+        ci_statuses,
+        repo,
+        upstream_id,
+        head_id,
+        UPSTREAM_SECTION_LIMIT,
+    )?;
+
+    Ok(commit_section_items(
+        config,
+        "unpulled",
+        format!("Unpulled from {}", upstream_shortname),
+        behind,
+        unpulled,
+    )
+    .into_iter()
+    .chain(commit_section_items(
+        config,
+        "unpushed",
+        format!("Unpushed to {}", upstream_shortname),
+        ahead,
+        unpushed,
+    ))
+    .collect())
+}
+
+fn commit_section_items(
+    config: &Config,
+    id: &'static str,
+    header: String,
+    count: usize,
+    commits: Vec<Item>,
+) -> Vec<Item> {
+    if commits.is_empty() {
+        return vec![];
+    }
+
+    let style = &config.style;
+    vec![
+        items::blank_line(),
+        Item {
+            id: id.into(),
+            display: Line::from(vec![
+                Span::styled(header, &style.section_header),
+                format!(" ({})", count).into(),
+            ]),
+            section: true,
+            depth: 0,
+            ..Default::default()
+        },
+    ]
+    .into_iter()
+    .chain(commits)
+    .collect()
+}
+
 fn create_log_section_items<'a>(
     config: Rc<Config>,
+    ci_statuses: &CiStatuses,
     repo: &Repository,
     header: &str,
 ) -> impl Iterator<Item = Item> + 'a {
@@ -298,5 +648,5 @@ fn create_log_section_items<'a>(
         },
     ]
     .into_iter()
-    .chain(items::log(&config, repo, 10, None, None).unwrap())
+    .chain(items::log(&config, ci_statuses, repo, 10, None, &LogFilter::default()).unwrap())
 }
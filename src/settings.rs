@@ -0,0 +1,32 @@
+//! What the user changed while gitu runs, like the diff context or the order of files.
+//! Kept on `State` and shared with the screens, which read them when refreshing.
+
+use crate::{
+    config::{Config, DateFormat, FileOrder},
+    git::diff::DiffSettings,
+    items::FileFilter,
+};
+
+#[derive(Clone, Debug)]
+pub(crate) struct Settings {
+    pub diff: DiffSettings,
+    /// Whether changed files are grouped by directory.
+    pub file_tree: bool,
+    pub file_order: FileOrder,
+    /// Only files matching it are shown in diffs.
+    pub file_filter: Option<FileFilter>,
+    pub date_format: DateFormat,
+}
+
+impl Settings {
+    /// As configured, before the user changed anything.
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self {
+            diff: DiffSettings::from_config(config),
+            file_tree: config.general.file_tree.enabled,
+            file_order: config.general.file_order,
+            file_filter: None,
+            date_format: config.general.date_format.clone(),
+        }
+    }
+}
@@ -1,12 +1,19 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
 use std::error::Error;
-use std::io::Read;
+use std::ops::Deref;
 use std::ops::DerefMut;
+use std::path::Path;
 use std::process::Child;
 use std::process::Command;
 use std::process::Stdio;
 use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::RwLock;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
 
 use arboard::Clipboard;
 use crossterm::event;
@@ -19,36 +26,139 @@ use ratatui::layout::Rect;
 use tui_prompts::State as _;
 use tui_prompts::Status;
 
+use crate::bindings::Binding;
 use crate::bindings::Bindings;
 use crate::cli;
+use crate::cmd_log;
 use crate::cmd_log::CmdLog;
 use crate::cmd_log::CmdLogEntry;
 use crate::config::Config;
+use crate::credentials;
+use crate::forge;
+use crate::forge::ci::CiStatuses;
+use crate::git;
+use crate::git::diff::HunkCache;
+use crate::items;
+use crate::items::Item;
+use crate::items::TargetData;
 use crate::menu::Menu;
+use crate::menu::MenuArgs;
 use crate::menu::PendingMenu;
+use crate::notify;
+use crate::ops;
 use crate::ops::Op;
+use crate::pipes;
 use crate::prompt;
 use crate::screen;
 use crate::screen::Screen;
+use crate::settings::Settings;
 use crate::term;
 use crate::term::Term;
 use crate::ui;
 
 use super::Res;
 
+/// Show screens of commits the cursor rested on, kept around for showing them.
+const PREPARED_SHOWS: usize = 8;
+/// Times a binding is done at most, however large the count typed before it.
+const MAX_COUNT: usize = 10000;
+
 pub(crate) struct State {
     pub repo: Rc<Repository>,
     pub config: Rc<Config>,
     pub bindings: Bindings,
-    pending_keys: Vec<(KeyModifiers, KeyCode)>,
+    pub(crate) pending_keys: Vec<(KeyModifiers, KeyCode)>,
+    /// When the last of the pending keys was pressed, see `general.key_timeout`.
+    pending_keys_since: Option<Instant>,
     pub quit: bool,
     pub screens: Vec<Screen>,
+    /// Screens left with `root.back`, the most recently left last.
+    pub(crate) forward_screens: Vec<Screen>,
     pub pending_menu: Option<PendingMenu>,
-    pub pending_cmd: Option<(Child, Arc<RwLock<CmdLogEntry>>)>,
+    /// Arguments of previously closed menus, restored when they're opened again.
+    menu_args: BTreeMap<Menu, MenuArgs>,
+    pub pending_cmd: Option<PendingCmd>,
+    queued_cmds: VecDeque<QueuedCmd>,
     enable_async_cmds: bool,
     pub current_cmd_log: CmdLog,
     pub prompt: prompt::Prompt,
     pub clipboard: Option<Clipboard>,
+    pub(crate) repo_summary: Option<ui::title_bar::RepoSummary>,
+    /// Increases every time the current screen is refreshed. Lets views derived from the repo
+    /// know they're stale.
+    pub(crate) refresh_generation: usize,
+    /// Whether the terminal has focus, as far as it reports focus changes.
+    focused: bool,
+    pub(crate) prepared_shows: screen::show::PreparedShows,
+    /// Of the selected item, beside screens split in two, see `general.split_pane`.
+    pub(crate) preview: Option<screen::preview::Preview>,
+    pub(crate) layout: ui::LayoutSettings,
+    /// Computed in the background for the statistics screen, see `root.show_stats`.
+    pub(crate) pending_stats: Option<git::stats::Pending>,
+    /// Listed in the background for the pull requests screen, see `forge.list_pull_requests`.
+    pub(crate) pull_requests: Option<forge::PendingPullRequests>,
+    /// Shared with the screens, which follow changes to them when refreshed.
+    pub(crate) settings: Rc<RefCell<Settings>>,
+    /// Of the diffs shown, shared with the screens. Cleared on explicit refreshes.
+    pub(crate) hunk_cache: Rc<HunkCache>,
+    /// Of the commits shown, fetched from the forge, see `general.ci_status`.
+    pub(crate) ci_statuses: CiStatuses,
+    /// The last searched for, see `root.search_next`.
+    pub(crate) search: Option<String>,
+    /// Edited on the rebase todo screen, until the rebase is started.
+    pub(crate) rebase_todo: Option<Rc<RefCell<git::rebase_todo::Todo>>>,
+    /// Keys pressed since `root.toggle_macro_recording` started recording.
+    pub(crate) macro_recording: Option<Vec<event::KeyEvent>>,
+    /// Replayed by `root.replay_macro`.
+    pub(crate) last_macro: Vec<event::KeyEvent>,
+    /// Typed before a binding, how many times to do it, see [`Op::takes_count`].
+    count: Option<usize>,
+    /// Repeated by `root.repeat_last_action`, with the menu it was done from.
+    pub(crate) last_target_op: Option<(Option<Menu>, Op)>,
+}
+
+pub struct PendingCmd {
+    child: Child,
+    log_entry: Arc<RwLock<CmdLogEntry>>,
+    /// Kept to run it again, should it need credentials or the index be locked.
+    cmd: Command,
+    input: Vec<u8>,
+    attempt: u32,
+    /// Kept until the command has finished, see [`credentials::PasswordFile`].
+    password_file: Option<credentials::PasswordFile>,
+    started: Instant,
+    /// Taken once the command has finished.
+    output: Option<pipes::OutputReaders>,
+    /// Screen the command was started from, to notify once it's left.
+    screen: (usize, &'static str),
+    stopped: Option<Stopped>,
+}
+
+/// A command waiting for the running one to finish.
+struct QueuedCmd {
+    cmd: Command,
+    input: Vec<u8>,
+    log_entry: Arc<RwLock<CmdLogEntry>>,
+    attempt: u32,
+    password_file: Option<credentials::PasswordFile>,
+    not_before: Option<Instant>,
+}
+
+/// Why a command was killed before finishing on its own.
+enum Stopped {
+    Cancelled,
+    TimedOut,
+}
+
+impl PendingCmd {
+    /// Kills the command once it has run for longer than `timeout` seconds, 0 meaning never.
+    fn check_timeout(&mut self, timeout: u64) -> Res<()> {
+        if self.stopped.is_none() && timeout > 0 && self.started.elapsed().as_secs() >= timeout {
+            self.child.kill()?;
+            self.stopped = Some(Stopped::TimedOut);
+        }
+        Ok(())
+    }
 }
 
 impl State {
@@ -59,24 +169,78 @@ impl State {
         config: Rc<Config>,
         enable_async_cmds: bool,
     ) -> Res<Self> {
+        let mut current_cmd_log = CmdLog::new();
+        let settings = Rc::new(RefCell::new(Settings::from_config(&config)));
+        let hunk_cache = Rc::new(HunkCache::default());
+        let ci_statuses = CiStatuses::default();
+
         let screens = match args.command {
             Some(cli::Commands::Show { ref reference }) => {
                 vec![screen::show::create(
                     Rc::clone(&config),
+                    Rc::clone(&settings),
+                    Rc::clone(&hunk_cache),
                     Rc::clone(&repo),
                     size,
                     reference.clone(),
                 )?]
             }
-            None => vec![screen::status::create(
-                Rc::clone(&config),
-                Rc::clone(&repo),
-                size,
-            )?],
+            Some(cli::Commands::Log {
+                ref rev,
+                max_count,
+                ref grep,
+            }) => {
+                let rev = rev
+                    .as_ref()
+                    .map(|rev| cli::resolve_rev(&repo, rev))
+                    .transpose()?;
+
+                vec![screen::log::create(
+                    Rc::clone(&config),
+                    ci_statuses.clone(),
+                    Rc::clone(&repo),
+                    size,
+                    max_count.unwrap_or(usize::MAX),
+                    rev,
+                    items::LogFilter {
+                        msg_regex: grep.clone(),
+                        ..Default::default()
+                    },
+                )?]
+            }
+            None
+            | Some(
+                cli::Commands::Status { .. }
+                | cli::Commands::Diff { .. }
+                | cli::Commands::Init { .. }
+                | cli::Commands::Clone { .. },
+            ) => {
+                let mut status = screen::status::create(
+                    Rc::clone(&config),
+                    Rc::clone(&settings),
+                    Rc::clone(&hunk_cache),
+                    ci_statuses.clone(),
+                    Rc::clone(&repo),
+                    size,
+                )?;
+
+                if let Some(path) = &args.path {
+                    let path = cli::resolve_path(&repo, path)?;
+                    if !status.select_first(|item| is_item_for_path(item, &path)) {
+                        current_cmd_log.push(CmdLogEntry::Error(format!(
+                            "No changes to {}",
+                            path.display()
+                        )));
+                    }
+                }
+
+                vec![status]
+            }
         };
 
         let bindings = Bindings::from(&config.bindings);
         let pending_menu = root_menu(&config).map(PendingMenu::init);
+        let layout = ui::LayoutSettings::new(&config.general);
 
         let clipboard = Clipboard::new()
             .inspect_err(|e| log::warn!("Couldn't initialize clipboard: {}", e))
@@ -87,28 +251,59 @@ impl State {
             config,
             bindings,
             pending_keys: vec![],
+            pending_keys_since: None,
             enable_async_cmds,
             quit: false,
             screens,
+            forward_screens: vec![],
             pending_cmd: None,
+            queued_cmds: VecDeque::new(),
             pending_menu,
-            current_cmd_log: CmdLog::new(),
+            menu_args: BTreeMap::new(),
+            current_cmd_log,
             prompt: prompt::Prompt::new(),
             clipboard,
+            repo_summary: None,
+            refresh_generation: 0,
+            focused: true,
+            prepared_shows: screen::show::PreparedShows::new(PREPARED_SHOWS),
+            preview: None,
+            pending_stats: None,
+            pull_requests: None,
+            layout,
+            settings,
+            hunk_cache,
+            ci_statuses,
+            search: None,
+            rebase_todo: None,
+            macro_recording: None,
+            last_macro: vec![],
+            count: None,
+            last_target_op: None,
         })
     }
 
     pub fn update(&mut self, term: &mut Term, events: &[Event]) -> Res<()> {
+        let keys_timed_out = self.handle_key_timeout(term)?;
+
         for event in events {
             match *event {
+                Event::FocusGained => self.focused = true,
+                Event::FocusLost => self.focused = false,
                 Event::Resize(w, h) => {
                     for screen in self.screens.iter_mut() {
                         screen.size = Rect::new(0, 0, w, h);
                     }
                 }
                 Event::Key(key) => {
+                    if let Some(recording) = &mut self.macro_recording {
+                        if key.kind == KeyEventKind::Press {
+                            recording.push(key);
+                        }
+                    }
+
                     if self.prompt.state.is_focused() {
-                        self.prompt.state.handle_key_event(key)
+                        self.prompt.handle_key_event(key);
                     } else if key.kind == KeyEventKind::Press {
                         if self.pending_cmd.is_none() {
                             self.current_cmd_log.clear();
@@ -123,12 +318,66 @@ impl State {
             self.update_prompt(term)?;
         }
 
-        let handle_pending_cmd_result = self.handle_pending_cmd();
-        let pending_cmd_done = self
-            .handle_result(handle_pending_cmd_result)
-            .unwrap_or(true);
+        let mut pending_cmd_done = false;
+        loop {
+            let handle_pending_cmd_result = self.handle_pending_cmd(term);
+            pending_cmd_done |= self
+                .handle_result(handle_pending_cmd_result)
+                .unwrap_or(true);
+
+            // Without async commands, queued ones have finished by now too
+            let start_queued_cmd_result = self.start_queued_cmd(term);
+            if !self.handle_result(start_queued_cmd_result).unwrap_or(false) {
+                break;
+            }
+        }
 
-        let needs_redraw = !events.is_empty() || pending_cmd_done;
+        let ci_status_updated =
+            self.config.general.ci_status.enabled && self.ci_statuses.take_updated();
+        if ci_status_updated {
+            let result = self.refresh_screen();
+            self.handle_result(result);
+        }
+
+        let stats_updated = self.pending_stats.as_ref().is_some_and(|p| p.is_done());
+        if stats_updated {
+            self.pending_stats = None;
+            let result = self.refresh_screen();
+            self.handle_result(result);
+        }
+
+        let pull_requests_updated = self
+            .pull_requests
+            .as_ref()
+            .is_some_and(|pending| pending.take_updated());
+        if pull_requests_updated {
+            let result = self.refresh_screen();
+            self.handle_result(result);
+        }
+
+        // Running commands show the time spent so far
+        let needs_redraw = !events.is_empty()
+            || keys_timed_out
+            || pending_cmd_done
+            || self.pending_cmd.is_some()
+            || ci_status_updated
+            || stats_updated
+            || pull_requests_updated;
+
+        if let Some(screen) = self.screens.last() {
+            self.prepared_shows.receive(
+                &self.config,
+                &self.settings,
+                &self.hunk_cache,
+                &self.repo,
+                screen.size,
+                self.refresh_generation,
+            );
+        }
+
+        if events.is_empty() {
+            self.prepare_selected_show();
+        }
 
         if needs_redraw && self.screens.last_mut().is_some() {
             term.draw(|frame| ui::ui(frame, self))?;
@@ -137,6 +386,32 @@ impl State {
         Ok(())
     }
 
+    /// Called when idle, the cursor has rested on the selected item since the last update.
+    fn prepare_selected_show(&mut self) {
+        if self.pending_cmd.is_some() || self.pending_menu.is_some() || self.screens.is_empty() {
+            return;
+        }
+
+        let screen = self.screen();
+        let Some(TargetData::Commit(hash)) = &screen.get_selected_item().target_data else {
+            return;
+        };
+
+        if screen.commit.as_ref() == Some(hash) {
+            return;
+        }
+
+        let hash = hash.clone();
+        let settings = self.settings.borrow().diff;
+        self.prepared_shows.prepare(
+            &self.config,
+            settings,
+            &self.repo,
+            &hash,
+            self.refresh_generation,
+        );
+    }
+
     fn update_prompt(&mut self, term: &mut Term) -> Res<()> {
         if self.prompt.state.status() == Status::Aborted {
             self.prompt.reset(term)?;
@@ -145,7 +420,8 @@ impl State {
 
             match result {
                 Ok(()) => {
-                    if self.prompt.state.is_focused() {
+                    // The update_fn may have replaced the prompt with a new one
+                    if self.prompt.state.is_focused() && self.prompt.data.is_none() {
                         self.prompt.data = Some(prompt_data);
                     }
                 }
@@ -159,37 +435,195 @@ impl State {
     }
 
     fn handle_key_input(&mut self, term: &mut Term, key: event::KeyEvent) -> Res<()> {
-        let menu = match &self.pending_menu {
+        self.pending_keys.push((key.modifiers, key.code));
+        self.pending_keys_since = Some(Instant::now());
+
+        let matching_bindings = self.matching_bindings();
+        let exact = matching_bindings
+            .iter()
+            .find(|binding| binding.keys == self.pending_keys)
+            .map(|binding| binding.op.clone());
+
+        match (matching_bindings.len(), exact) {
+            (1, Some(op)) => self.handle_bound_op(op, term)?,
+            (0, _) => {
+                let (last_key, before) = self.pending_keys.split_last().unwrap();
+                let last_key = *last_key;
+                self.pending_keys = before.to_vec();
+
+                // The keys before were bound themselves, waiting to see if a longer binding was meant
+                if let Some(op) = self.exact_binding() {
+                    self.handle_bound_op(op, term)?;
+                    return self
+                        .handle_key_input(term, event::KeyEvent::new(last_key.1, last_key.0));
+                }
+
+                self.count = match (&self.pending_keys[..], last_key) {
+                    ([], (KeyModifiers::NONE, KeyCode::Char(digit @ '0'..='9')))
+                        if self.key_menu() == Menu::Root
+                            && (digit != '0' || self.count.is_some()) =>
+                    {
+                        let digit = digit.to_digit(10).unwrap() as usize;
+                        Some(
+                            self.count
+                                .unwrap_or(0)
+                                .saturating_mul(10)
+                                .saturating_add(digit),
+                        )
+                    }
+                    _ => None,
+                };
+                self.clear_pending_keys();
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// The menu whose bindings the pending keys are matched against.
+    fn key_menu(&self) -> Menu {
+        match &self.pending_menu {
             None => Menu::Root,
             Some(menu) if menu.menu == Menu::Help => Menu::Root,
             Some(menu) => menu.menu,
-        };
+        }
+    }
 
-        self.pending_keys.push((key.modifiers, key.code));
-        let matching_bindings = self
-            .bindings
-            .match_bindings(&menu, &self.pending_keys)
-            .collect::<Vec<_>>();
+    /// Bindings starting with the pending keys, those of the screen's keymap if any do.
+    pub(crate) fn matching_bindings(&self) -> Vec<&Binding> {
+        let menu = self.key_menu();
+        let keymap_bindings = match self.screen().keymap.filter(|_| menu == Menu::Root) {
+            Some(keymap) => self
+                .bindings
+                .match_bindings(keymap, &self.pending_keys)
+                .collect::<Vec<_>>(),
+            None => vec![],
+        };
 
-        match matching_bindings[..] {
-            [binding] => {
-                if binding.keys == self.pending_keys {
-                    self.handle_op(binding.op.clone(), term)?;
-                    self.pending_keys.clear();
-                }
-            }
-            [] => self.pending_keys.clear(),
-            [_, ..] => (),
+        if keymap_bindings.is_empty() {
+            self.bindings
+                .match_bindings(menu, &self.pending_keys)
+                .collect()
+        } else {
+            keymap_bindings
         }
+    }
+
+    /// The op bound to exactly the pending keys, rather than to a longer sequence starting with them.
+    /// Should the screen's keymap only have longer ones, like `<ctrl+c><ctrl+c>`, root's binding
+    /// of the keys is still done once they've timed out.
+    fn exact_binding(&self) -> Option<Op> {
+        let exact = |mut bindings: Vec<&Binding>| {
+            bindings
+                .drain(..)
+                .find(|binding| binding.keys == self.pending_keys)
+                .map(|binding| binding.op.clone())
+        };
+
+        exact(self.matching_bindings()).or_else(|| {
+            exact(
+                self.bindings
+                    .match_bindings(self.key_menu(), &self.pending_keys)
+                    .collect(),
+            )
+        })
+    }
 
+    fn handle_bound_op(&mut self, op: Op, term: &mut Term) -> Res<()> {
+        let times = match self.count.take() {
+            Some(count) if op.takes_count() => count.min(MAX_COUNT),
+            _ => 1,
+        };
+
+        for _ in 0..times {
+            self.handle_op(op.clone(), term)?;
+        }
+        self.clear_pending_keys();
         Ok(())
     }
 
+    fn clear_pending_keys(&mut self) {
+        self.pending_keys.clear();
+        self.pending_keys_since = None;
+    }
+
+    /// Like Vim's `timeoutlen`, once no more keys came for `general.key_timeout`,
+    /// does what the pending keys are bound to. Returns whether there were any.
+    fn handle_key_timeout(&mut self, term: &mut Term) -> Res<bool> {
+        let timeout = self.config.general.key_timeout;
+        let Some(since) = self.pending_keys_since else {
+            return Ok(false);
+        };
+        if timeout == 0 || since.elapsed() < Duration::from_millis(timeout) {
+            return Ok(false);
+        }
+
+        match self.exact_binding() {
+            Some(op) => self.handle_bound_op(op, term)?,
+            None => self.clear_pending_keys(),
+        }
+        Ok(true)
+    }
+
+    /// Leaves the keys of the binding being handled out of the macro being recorded.
+    pub(crate) fn unrecord_pending_keys(&mut self) {
+        if let Some(recording) = &mut self.macro_recording {
+            recording.truncate(recording.len().saturating_sub(self.pending_keys.len()));
+        }
+    }
+
+    /// Presses the keys of the last recorded macro again, prompts included.
+    pub(crate) fn replay_macro(&mut self, term: &mut Term) -> Res<()> {
+        let events = self
+            .last_macro
+            .iter()
+            .copied()
+            .map(Event::Key)
+            .collect::<Vec<_>>();
+
+        self.clear_pending_keys();
+        self.update(term, &events)
+    }
+
     pub(crate) fn handle_op(&mut self, op: Op, term: &mut Term) -> Res<()> {
-        let target = self.screen().get_selected_item().target_data.as_ref();
-        if let Some(mut action) = op.clone().implementation().get_action(target) {
+        let implementation = op.clone().implementation();
+        if self.screen().other_repo && op.acts_on_repo() {
+            self.close_menu();
+            self.display_info(format!("{} isn't available in a submodule", implementation));
+            return Ok(());
+        }
+
+        let region = self
+            .screen()
+            .region()
+            .filter(|_| implementation.is_target_op() && op != Op::ToggleRegion);
+        let target = region
+            .as_ref()
+            .or(self.screen().get_selected_item().target_data.as_ref());
+
+        if let Some(action) = ops::marked_changes_action(self, &op) {
+            let mut action = ops::with_confirmation(self, &op, None, action);
             let result = Rc::get_mut(&mut action).unwrap()(self, term);
             self.handle_result(result);
+        } else if let Some(action) = implementation.get_action(target) {
+            let mut action = ops::with_confirmation(self, &op, target, action);
+            if implementation.is_target_op() {
+                let menu = self
+                    .pending_menu
+                    .as_ref()
+                    .map(|pending| pending.menu)
+                    .filter(|&menu| menu != Menu::Root);
+                self.last_target_op = Some((menu, op.clone()));
+            }
+            if region.is_some() {
+                self.screen_mut().clear_region();
+            }
+
+            let result = Rc::get_mut(&mut action).unwrap()(self, term);
+            self.handle_result(result);
+        } else if implementation.is_target_op() {
+            self.display_info(format!("{} isn't available here", implementation));
         }
 
         Ok(())
@@ -198,6 +632,8 @@ impl State {
     fn handle_result<T>(&mut self, result: Result<T, Box<dyn Error>>) -> Option<T> {
         match result {
             Ok(value) => Some(value),
+            // The prompt for them is shown instead
+            Err(error) if error.is::<credentials::AwaitingCredentials>() => None,
             Err(error) => {
                 self.current_cmd_log
                     .push(CmdLogEntry::Error(error.to_string()));
@@ -207,10 +643,45 @@ impl State {
         }
     }
 
+    pub fn open_menu(&mut self, menu: Menu) {
+        self.stash_menu_args();
+
+        let mut pending_menu = PendingMenu::init(menu);
+        if let Some(args) = self.menu_args.remove(&menu) {
+            pending_menu.args = args;
+        }
+
+        self.pending_menu = Some(pending_menu);
+    }
+
     pub fn close_menu(&mut self) {
+        self.stash_menu_args();
         self.pending_menu = root_menu(&self.config).map(PendingMenu::init)
     }
 
+    fn stash_menu_args(&mut self) {
+        if !self.config.general.remember_menu_args.enabled {
+            return;
+        }
+
+        if let Some(pending_menu) = self.pending_menu.take() {
+            self.menu_args.insert(pending_menu.menu, pending_menu.args);
+        }
+    }
+
+    /// Opens a screen on top of the others, which can no longer be gone forward to.
+    /// Beyond `general.screen_limit` the oldest screens above the first are dropped.
+    pub(crate) fn push_screen(&mut self, screen: Screen) {
+        self.forward_screens.clear();
+        self.screens.push(screen);
+
+        let limit = self.config.general.screen_limit.max(2);
+        if self.config.general.screen_limit > 0 && self.screens.len() > limit {
+            let excess = self.screens.len() - limit;
+            self.screens.drain(1..1 + excess);
+        }
+    }
+
     pub fn screen_mut(&mut self) -> &mut Screen {
         self.screens.last_mut().expect("No screen")
     }
@@ -219,6 +690,36 @@ impl State {
         self.screens.last().expect("No screen")
     }
 
+    pub(crate) fn refresh_screen(&mut self) -> Res<()> {
+        self.refresh_generation += 1;
+        self.screen_mut().update()
+    }
+
+    /// Opens another repository in place of this one, like a freshly cloned one.
+    /// Whatever was kept about the previous one, or still to be done in it, is dropped.
+    pub(crate) fn switch_repo(&mut self, term: &mut Term, repo: Repository) -> Res<()> {
+        self.hunk_cache.clear();
+        self.ci_statuses = CiStatuses::default();
+        self.queued_cmds.clear();
+        self.pending_stats = None;
+        self.pull_requests = None;
+        self.rebase_todo = None;
+        self.repo = Rc::new(repo);
+        self.screens = vec![screen::status::create(
+            Rc::clone(&self.config),
+            Rc::clone(&self.settings),
+            Rc::clone(&self.hunk_cache),
+            self.ci_statuses.clone(),
+            Rc::clone(&self.repo),
+            term.size()?,
+        )?];
+        self.forward_screens.clear();
+        self.prepared_shows = screen::show::PreparedShows::new(PREPARED_SHOWS);
+        self.preview = None;
+        self.repo_summary = None;
+        Ok(())
+    }
+
     /// Displays an `Info` message to the CmdLog.
     pub fn display_info(&mut self, message: String) {
         self.current_cmd_log.push(CmdLogEntry::Info(message));
@@ -229,37 +730,129 @@ impl State {
         self.current_cmd_log.push(CmdLogEntry::Error(error));
     }
 
-    /// Runs a `Command` and handles its output.
-    /// Will block awaiting its completion.
+    /// Runs a `Command` and handles its output. Will block awaiting its completion.
+    /// Fails if another command is running or queued, as callers rely on it having finished.
     pub fn run_cmd(&mut self, term: &mut Term, input: &[u8], cmd: Command) -> Res<()> {
+        if self.is_busy() {
+            return Err("A command is already running".into());
+        }
+
         self.run_cmd_async(term, input, cmd)?;
-        self.await_pending_cmd()?;
-        self.handle_pending_cmd()?;
-        Ok(())
+        loop {
+            self.await_pending_cmd()?;
+            self.handle_pending_cmd(term)?;
+
+            // Nothing else was queued beforehand, so these are retries of the command
+            while !self.start_queued_cmd(term)? {
+                if self.queued_cmds.is_empty() || self.prompt.state.is_focused() {
+                    return Ok(());
+                }
+
+                term.draw(|frame| ui::ui(frame, self))?;
+                thread::sleep(Duration::from_millis(100));
+            }
+        }
+    }
+
+    /// Whether a command is running, or waiting to be run after it.
+    fn is_busy(&self) -> bool {
+        self.pending_cmd.is_some() || !self.queued_cmds.is_empty()
     }
 
     /// Runs a `Command` and handles its output asynchronously (if async commands are enabled).
-    /// Will return `Ok(())` if one is already running.
-    pub fn run_cmd_async(&mut self, term: &mut Term, input: &[u8], mut cmd: Command) -> Res<()> {
-        if self.pending_cmd.is_some() {
-            return Err("A command is already running".into());
+    /// Is queued to run after any command that's already running or queued, so they never race.
+    pub fn run_cmd_async(&mut self, term: &mut Term, input: &[u8], cmd: Command) -> Res<()> {
+        self.run_cmd_async_with(term, input, cmd, None)
+    }
+
+    /// Like `run_cmd_async`, keeping the password for the askpass program until it has finished.
+    pub(crate) fn run_cmd_async_with_password(
+        &mut self,
+        term: &mut Term,
+        cmd: Command,
+        password_file: credentials::PasswordFile,
+    ) -> Res<()> {
+        self.run_cmd_async_with(term, &[], cmd, Some(password_file))
+    }
+
+    fn run_cmd_async_with(
+        &mut self,
+        term: &mut Term,
+        input: &[u8],
+        cmd: Command,
+        password_file: Option<credentials::PasswordFile>,
+    ) -> Res<()> {
+        if self.is_busy() {
+            let log_entry = self.current_cmd_log.push_queued_cmd(&cmd);
+            let is_rerun = password_file.is_some();
+            let queued = QueuedCmd {
+                cmd,
+                input: input.to_vec(),
+                log_entry,
+                attempt: 0,
+                password_file,
+                not_before: None,
+            };
+
+            // Takes the place of the command that needed credentials
+            if is_rerun {
+                self.queued_cmds.push_front(queued);
+            } else {
+                self.queued_cmds.push_back(queued);
+            }
+            return Ok(());
         }
 
+        let log_entry = self.current_cmd_log.push_cmd(&cmd);
+        self.start_cmd(term, input, cmd, log_entry, 0, password_file)
+    }
+
+    fn start_cmd(
+        &mut self,
+        term: &mut Term,
+        input: &[u8],
+        mut cmd: Command,
+        log_entry: Arc<RwLock<CmdLogEntry>>,
+        attempt: u32,
+        password_file: Option<credentials::PasswordFile>,
+    ) -> Res<()> {
         cmd.current_dir(self.repo.workdir().expect("No workdir"));
 
         cmd.stdin(Stdio::piped());
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
+        credentials::disable_terminal_prompt(&self.repo, &mut cmd);
 
-        let log_entry = self.current_cmd_log.push_cmd(&cmd);
         term.draw(|frame| ui::ui(frame, self))?;
 
-        let mut child = cmd.spawn()?;
-
-        use std::io::Write;
-        child.stdin.take().unwrap().write_all(input)?;
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(error) => {
+                // It'd be shown as running forever otherwise
+                if let CmdLogEntry::Cmd { out, .. } = log_entry.write().unwrap().deref_mut() {
+                    *out = Some("".into());
+                }
+                return Err(error.into());
+            }
+        };
+        pipes::write_stdin(&mut child, input);
+        let output = pipes::OutputReaders::start(&mut child);
+        if let CmdLogEntry::Cmd { live, .. } = log_entry.write().unwrap().deref_mut() {
+            *live = Some(output.live());
+        }
 
-        self.pending_cmd = Some((child, log_entry));
+        self.pending_cmd = Some(PendingCmd {
+            child,
+            log_entry,
+            cmd,
+            input: input.to_vec(),
+            attempt,
+            password_file,
+            started: Instant::now(),
+            output: Some(output),
+            screen: self.screen_key(),
+            stopped: None,
+        });
 
         if !self.enable_async_cmds {
             self.await_pending_cmd()?;
@@ -268,33 +861,218 @@ impl State {
         Ok(())
     }
 
+    /// Starts the next queued command, once nothing else is running or being asked for.
+    /// With `block`, waits for any delay before retrying it even if commands are async.
+    /// A retry waiting for `index.lock` stays queued, it's checked again on every update.
+    fn start_queued_cmd(&mut self, term: &mut Term) -> Res<bool> {
+        if self.pending_cmd.is_some() || self.prompt.state.is_focused() {
+            return Ok(false);
+        }
+
+        let Some(queued) = self.queued_cmds.front() else {
+            return Ok(false);
+        };
+
+        // Retried early once the other git process is done with the index
+        let waiting = queued.not_before.is_some_and(|not_before| {
+            Instant::now() < not_before && self.repo.path().join("index.lock").exists()
+        });
+        if waiting {
+            return Ok(false);
+        }
+
+        let QueuedCmd {
+            cmd,
+            input,
+            log_entry,
+            attempt,
+            password_file,
+            ..
+        } = self.queued_cmds.pop_front().unwrap();
+
+        *log_entry.write().unwrap() = CmdLogEntry::Cmd {
+            args: cmd_log::command_args(&cmd),
+            out: None,
+            started: Instant::now(),
+            live: None,
+        };
+        self.start_cmd(term, &input, cmd, log_entry, attempt, password_file)?;
+        Ok(true)
+    }
+
+    /// Queued commands likely depended on the one that failed, so they're dropped.
+    fn skip_queued_cmds(&mut self) {
+        for queued in self.queued_cmds.drain(..) {
+            *queued.log_entry.write().unwrap() =
+                CmdLogEntry::Error(format!("Skipped '{}'", cmd_log::command_args(&queued.cmd)));
+        }
+    }
+
     fn await_pending_cmd(&mut self) -> Res<()> {
-        if let Some((child, _)) = &mut self.pending_cmd {
-            child.wait()?;
+        let timeout = self.config.general.command_timeout;
+        if let Some(pending) = &mut self.pending_cmd {
+            while pending.child.try_wait()?.is_none() {
+                pending.check_timeout(timeout)?;
+                thread::sleep(Duration::from_millis(10));
+            }
         }
         Ok(())
     }
 
+    /// Kills the running command, which is then reported as cancelled.
+    pub(crate) fn cancel_cmd(&mut self) -> Res<()> {
+        let Some(pending) = &mut self.pending_cmd else {
+            return Err("No command is running".into());
+        };
+
+        pending.child.kill()?;
+        pending.stopped = Some(Stopped::Cancelled);
+        Ok(())
+    }
+
     /// Handles any pending_cmd in State without blocking. Returns `true` if a cmd was handled.
-    pub fn handle_pending_cmd(&mut self) -> Res<bool> {
-        let Some((ref mut child, ref mut log_rwlock)) = self.pending_cmd else {
+    pub fn handle_pending_cmd(&mut self, term: &mut Term) -> Res<bool> {
+        let timeout = self.config.general.command_timeout;
+        let Some(PendingCmd {
+            ref mut child,
+            ref mut log_entry,
+            ref stopped,
+            ref cmd,
+            ref mut output,
+            ..
+        }) = self.pending_cmd
+        else {
             return Ok(false);
         };
 
         let Some(status) = child.try_wait()? else {
+            self.pending_cmd.as_mut().unwrap().check_timeout(timeout)?;
             return Ok(false);
         };
 
         log::debug!("pending cmd finished with {:?}", status);
 
-        let result = write_child_output_to_log(log_rwlock, child, status);
-        self.pending_cmd = None;
-        self.screen_mut().update()?;
+        // Output of a killed command isn't waited for, whatever it spawned may still hold on to it
+        if stopped.is_some() {
+            if let CmdLogEntry::Cmd { out, .. } = log_entry.write().unwrap().deref_mut() {
+                *out = Some("".into());
+            }
+        }
+
+        let result = match stopped {
+            None => write_child_output_to_log(log_entry, output.take().unwrap(), status)
+                .map_err(|err| with_hook_hint(&self.repo, cmd, err)),
+            Some(Stopped::Cancelled) => {
+                Err(format!("'{}' was cancelled", cmd_log::command_args(cmd)).into())
+            }
+            Some(Stopped::TimedOut) => Err(format!(
+                "'{}' timed out after {}s",
+                cmd_log::command_args(cmd),
+                timeout
+            )
+            .into()),
+        };
+        let needs = result
+            .is_err()
+            .then(|| cmd_output_needs(log_entry))
+            .flatten();
+        let index_locked = result.is_err() && is_index_locked(&self.repo, log_entry);
+        let pending = self.pending_cmd.take().unwrap();
+        self.refresh_screen()?;
+
+        if index_locked {
+            return self.retry_index_locked(pending);
+        }
+
+        match needs {
+            Some(credentials::Needs::Credentials) => {
+                credentials::prompt_credentials(self, pending.cmd);
+                return Err(credentials::AwaitingCredentials.into());
+            }
+            Some(credentials::Needs::Terminal) => {
+                self.display_info("ssh needs the terminal, running the command there".into());
+                let cmd = credentials::with_terminal_prompt(&pending.cmd);
+                self.run_cmd_suspended(term, cmd)?;
+                return Ok(true);
+            }
+            None => (),
+        }
+
+        self.notify_finished(term, &pending, result.is_ok());
+        if result.is_err() {
+            self.skip_queued_cmds();
+        }
         result?;
 
         Ok(true)
     }
 
+    /// Another git process holds `index.lock`, the command is retried with an increasing delay.
+    fn retry_index_locked(&mut self, pending: PendingCmd) -> Res<bool> {
+        if let CmdLogEntry::Cmd { out, .. } = pending.log_entry.write().unwrap().deref_mut() {
+            *out = Some("".into());
+        }
+
+        if pending.attempt >= self.config.general.index_lock_retries {
+            self.skip_queued_cmds();
+            let lock = self.repo.path().join("index.lock");
+            let lock = self
+                .repo
+                .workdir()
+                .and_then(|workdir| lock.strip_prefix(workdir).ok())
+                .unwrap_or(&lock);
+
+            return Err(format!(
+                "Another git process holds {}, remove it if none is running",
+                lock.display()
+            )
+            .into());
+        }
+
+        let delay = Duration::from_secs(1 << pending.attempt.min(6));
+        self.display_info(format!(
+            "Waiting for another git process to release index.lock, retrying in {}s",
+            delay.as_secs()
+        ));
+
+        let log_entry = self.current_cmd_log.push_queued_cmd(&pending.cmd);
+        self.queued_cmds.push_front(QueuedCmd {
+            cmd: pending.cmd,
+            input: pending.input,
+            log_entry,
+            attempt: pending.attempt + 1,
+            password_file: pending.password_file,
+            not_before: Some(Instant::now() + delay),
+        });
+        Ok(true)
+    }
+
+    fn screen_key(&self) -> (usize, &'static str) {
+        (self.screens.len(), self.screen().name)
+    }
+
+    /// Gets the user's attention if they likely moved on while the command ran.
+    fn notify_finished(&mut self, term: &mut Term, pending: &PendingCmd, success: bool) {
+        let notify = &self.config.general.notify;
+        let elapsed = pending.started.elapsed();
+
+        if elapsed.as_secs() < notify.after_seconds
+            || (self.focused && pending.screen == self.screen_key())
+        {
+            return;
+        }
+
+        let summary = format!(
+            "{} {} after {}s",
+            cmd_log::command_args(&pending.cmd),
+            if success { "finished" } else { "failed" },
+            elapsed.as_secs()
+        );
+
+        notify::notify(term, notify.method, &summary);
+        self.display_info(summary);
+    }
+
     pub fn run_cmd_interactive(&mut self, term: &mut Term, mut cmd: Command) -> Res<()> {
         if self.pending_cmd.is_some() {
             return Err("A command is already running".into());
@@ -303,12 +1081,12 @@ impl State {
         cmd.current_dir(self.repo.workdir().expect("No workdir"));
 
         cmd.stdin(Stdio::piped());
+        // Editors only need stdout, this keeps what hooks print from being lost behind the UI
+        cmd.stderr(Stdio::piped());
         let child = cmd.spawn()?;
 
         let out = child.wait_with_output()?;
-        let out_utf8 = String::from_utf8(out.stderr.clone())
-            .expect("Error turning command output to String")
-            .into();
+        let out_utf8 = String::from_utf8_lossy(&out.stderr).into_owned().into();
 
         self.current_cmd_log.push_cmd_with_output(&cmd, out_utf8);
 
@@ -319,15 +1097,46 @@ impl State {
         term::enter_alternate_screen()?;
 
         term.clear()?;
-        self.screen_mut().update()?;
+        self.refresh_screen()?;
 
         if !out.status.success() {
-            return Err(format!(
+            let err = format!(
                 "exited with code: {}",
                 out.status
                     .code()
                     .map(|c| c.to_string())
                     .unwrap_or("".to_string())
+            );
+            return Err(with_hook_hint(&self.repo, &cmd, err.into()));
+        }
+
+        Ok(())
+    }
+
+    /// Runs a `Command` attached to the terminal, with the TUI suspended until it exits.
+    /// For commands that need to ask something themselves, like ssh for a passphrase.
+    pub fn run_cmd_suspended(&mut self, term: &mut Term, mut cmd: Command) -> Res<()> {
+        if self.pending_cmd.is_some() {
+            return Err("A command is already running".into());
+        }
+
+        cmd.current_dir(self.repo.workdir().expect("No workdir"));
+        cmd.stdin(Stdio::inherit());
+        cmd.stdout(Stdio::inherit());
+        cmd.stderr(Stdio::inherit());
+
+        let status = term::suspended(term, || Ok(cmd.status()?))?;
+        // The output went to the terminal
+        self.current_cmd_log.push_cmd_with_output(&cmd, "".into());
+        self.refresh_screen()?;
+
+        if !status.success() {
+            return Err(format!(
+                "exited with code: {}",
+                status
+                    .code()
+                    .map(|c| c.to_string())
+                    .unwrap_or("".to_string())
             )
             .into());
         }
@@ -356,38 +1165,49 @@ pub(crate) fn root_menu(config: &Config) -> Option<Menu> {
     }
 }
 
+fn cmd_output_needs(log_entry: &Arc<RwLock<CmdLogEntry>>) -> Option<credentials::Needs> {
+    match log_entry.read().unwrap().deref() {
+        CmdLogEntry::Cmd { out: Some(out), .. } => credentials::needs(out),
+        _ => None,
+    }
+}
+
+/// Some commands, like `git stash push`, fail on a locked index without saying so. Those
+/// failing silently are only taken to be locked out while `index.lock` exists.
+fn is_index_locked(repo: &Repository, log_entry: &Arc<RwLock<CmdLogEntry>>) -> bool {
+    match log_entry.read().unwrap().deref() {
+        CmdLogEntry::Cmd { out: Some(out), .. } => {
+            out.contains("index.lock': File exists")
+                || (out.trim().is_empty() && repo.path().join("index.lock").exists())
+        }
+        _ => false,
+    }
+}
+
+/// Hook output reads like git's own, so a failure it caused is pointed out.
+fn with_hook_hint(repo: &Repository, cmd: &Command, err: Box<dyn Error>) -> Box<dyn Error> {
+    match git::hooks::failure_hint(repo.workdir().expect("No workdir"), cmd) {
+        Some(hint) => format!("{} {}", err, hint).into(),
+        None => err,
+    }
+}
+
 fn write_child_output_to_log(
     log_rwlock: &mut Arc<RwLock<CmdLogEntry>>,
-    child: &mut Child,
+    output: pipes::OutputReaders,
     status: std::process::ExitStatus,
 ) -> Result<(), Box<dyn Error>> {
     let mut log = log_rwlock.write().unwrap();
 
-    let CmdLogEntry::Cmd { args, out: out_log } = log.deref_mut() else {
+    let CmdLogEntry::Cmd {
+        args, out: out_log, ..
+    } = log.deref_mut()
+    else {
         unreachable!("pending_cmd is always CmdLogEntry::Cmd variant");
     };
 
-    drop(child.stdin.take());
-
-    let mut out_bytes = vec![];
-    log::debug!("Reading stderr");
-
-    child
-        .stderr
-        .take()
-        .unwrap()
-        .read_to_end(&mut out_bytes)
-        .map_err(|e| format!("Couldn't read cmd output: {}", e))?;
-
-    child
-        .stdout
-        .take()
-        .unwrap()
-        .read_to_end(&mut out_bytes)
-        .map_err(|e| format!("Couldn't read cmd output: {}", e))?;
-
-    let out_string = String::from_utf8(out_bytes.clone())?;
-    *out_log = Some(out_string.into());
+    log::debug!("Reading output");
+    *out_log = Some(output.join()?.into());
 
     if !status.success() {
         return Err(format!(
@@ -403,3 +1223,11 @@ fn write_child_output_to_log(
 
     Ok(())
 }
+
+fn is_item_for_path(item: &Item, path: &Path) -> bool {
+    match &item.target_data {
+        Some(TargetData::File(file)) => file == path,
+        Some(TargetData::Delta(delta)) => delta.new_file == path || delta.old_file == path,
+        _ => false,
+    }
+}
@@ -1,4 +1,6 @@
 use crate::Res;
+use crossterm::event::DisableFocusChange;
+use crossterm::event::EnableFocusChange;
 use crossterm::terminal::disable_raw_mode;
 use crossterm::terminal::enable_raw_mode;
 use crossterm::terminal::EnterAlternateScreen;
@@ -30,6 +32,13 @@ pub fn alternate_screen<T, F: Fn() -> Res<T>>(fun: F) -> Res<T> {
     result
 }
 
+pub fn focus_changes<T, F: Fn() -> Res<T>>(fun: F) -> Res<T> {
+    stderr().execute(EnableFocusChange)?;
+    let result = fun();
+    stderr().execute(DisableFocusChange)?;
+    result
+}
+
 pub fn raw_mode<T, F: Fn() -> Res<T>>(fun: F) -> Res<T> {
     enable_raw_mode()?;
     let result = fun();
@@ -37,10 +46,40 @@ pub fn raw_mode<T, F: Fn() -> Res<T>>(fun: F) -> Res<T> {
     result
 }
 
+/// Hands the terminal over to `fun`, restoring the TUI afterwards.
+pub fn suspended<T, F: FnOnce() -> Res<T>>(term: &mut Term, fun: F) -> Res<T> {
+    if let TermBackend::Test(_) = term.backend() {
+        return fun();
+    }
+
+    stderr().execute(LeaveAlternateScreen)?;
+    disable_raw_mode()?;
+    term.show_cursor()?;
+
+    let result = fun();
+
+    enable_raw_mode()?;
+    stderr().execute(EnterAlternateScreen)?;
+    term.hide_cursor()?;
+    term.clear()?;
+    result
+}
+
+pub(crate) fn bell(term: &mut Term) {
+    if let TermBackend::Crossterm(_) = term.backend() {
+        use std::io::Write;
+        print_err(stderr().write_all(b"\x07").and_then(|_| stderr().flush()));
+    }
+}
+
 pub fn cleanup_alternate_screen() {
     print_err(stderr().execute(LeaveAlternateScreen));
 }
 
+pub fn cleanup_focus_changes() {
+    print_err(stderr().execute(DisableFocusChange));
+}
+
 pub fn cleanup_raw_mode() {
     print_err(disable_raw_mode());
 }
@@ -1,3 +1,4 @@
+use super::*;
 use crate::menu::arg::{self, Arg};
 
 #[test]
@@ -48,3 +49,15 @@ fn value_as_concrete_type() {
     assert_eq!(arg.value_as::<String>(), None);
     assert_eq!(arg.value_as::<u32>(), Some(&1u32));
 }
+
+#[test]
+fn args_persist_between_menus() {
+    snapshot!(TestContext::setup_clone(), "c-a<esc>c");
+}
+
+#[test]
+fn args_reset_when_not_remembered() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.remember_menu_args.enabled = false;
+    snapshot!(ctx, "c-a<esc>c");
+}
@@ -0,0 +1,42 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    let dir = ctx.dir.path();
+    run(dir, &["git", "branch", "merged-feature"]);
+
+    run(dir, &["git", "checkout", "-b", "gone-feature"]);
+    commit(dir, "gone-file", "hello");
+    run(dir, &["git", "push", "-u", "origin", "gone-feature"]);
+    run(dir, &["git", "push", "origin", "--delete", "gone-feature"]);
+
+    run(dir, &["git", "checkout", "-b", "unmerged-feature", "main"]);
+    commit(dir, "unmerged-file", "hello");
+    run(dir, &["git", "checkout", "main"]);
+    ctx
+}
+
+#[test]
+fn branch_cleanup() {
+    snapshot!(setup(), "bx");
+}
+
+#[test]
+fn nothing_to_clean_up() {
+    snapshot!(TestContext::setup_clone(), "bx");
+}
+
+#[test]
+fn delete_selected_branch() {
+    snapshot!(setup(), "bxjdy");
+}
+
+#[test]
+fn delete_marked_branches() {
+    snapshot!(setup(), "bxjmjjmd");
+}
+
+#[test]
+fn delete_marked_branches_confirmed() {
+    snapshot!(setup(), "bxjmjjmdy");
+}
@@ -0,0 +1,68 @@
+use super::*;
+use std::fs;
+
+#[test]
+fn cancel_without_running_command() {
+    snapshot!(TestContext::setup_clone(), "<ctrl+c>");
+}
+
+#[cfg(unix)]
+#[test]
+fn cancel_running_command() {
+    use std::os::unix::fs::PermissionsExt;
+    use std::{thread, time::Duration};
+
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "");
+
+    // The hook's parent is the `git push` started by gitu
+    let hook = ctx.dir.child(".git/hooks/pre-push");
+    fs::write(&hook, "#!/bin/sh\necho $PPID > .git/push-pid\nsleep 5\n").unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut state = ctx.init_async_state();
+    state.update(&mut ctx.term, &keys("Pp")).unwrap();
+
+    let pid_file = ctx.dir.child(".git/push-pid");
+    let pid = loop {
+        match fs::read_to_string(&pid_file) {
+            Ok(pid) if pid.ends_with('\n') => break pid.trim().to_string(),
+            _ => thread::sleep(Duration::from_millis(10)),
+        }
+    };
+
+    state.update(&mut ctx.term, &keys("<ctrl+c>")).unwrap();
+    while state.pending_cmd.is_some() {
+        thread::sleep(Duration::from_millis(10));
+        state.update(&mut ctx.term, &[]).unwrap();
+    }
+
+    let alive = std::process::Command::new("kill")
+        .args(["-0", &pid])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .unwrap()
+        .success();
+    assert!(!alive, "git push ({}) is still running", pid);
+
+    ctx.term
+        .draw(|frame| crate::ui::ui(frame, &mut state))
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[cfg(unix)]
+#[test]
+fn command_timeout() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.command_timeout = 1;
+    commit(ctx.dir.path(), "new-file", "");
+
+    let hook = ctx.dir.child(".git/hooks/pre-push");
+    fs::write(&hook, "#!/bin/sh\nsleep 5\n").unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+
+    snapshot!(ctx, "Pp");
+}
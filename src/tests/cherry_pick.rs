@@ -0,0 +1,55 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other"]);
+    commit(ctx.dir.path(), "first-file", "first");
+    commit(ctx.dir.path(), "second-file", "second");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    ctx
+}
+
+#[test]
+fn cherry_pick_menu() {
+    snapshot!(setup(), "A");
+}
+
+#[test]
+fn mark_commits() {
+    snapshot!(setup(), "loother<enter>mm");
+}
+
+#[test]
+fn unmark_commit() {
+    snapshot!(setup(), "loother<enter>mkm");
+}
+
+#[test]
+fn cherry_pick_marked_prompt() {
+    snapshot!(setup(), "loother<enter>mmAA");
+}
+
+#[test]
+fn cherry_pick_marked() {
+    snapshot!(setup(), "loother<enter>mmAA<enter>q");
+}
+
+#[test]
+fn cherry_pick_range() {
+    snapshot!(setup(), "AAmain..other<enter>");
+}
+
+#[test]
+fn cherry_pick_conflict() {
+    let ctx = setup();
+    commit(ctx.dir.path(), "second-file", "conflicting");
+    snapshot!(ctx, "AAother<enter>");
+}
+
+#[test]
+fn cherry_pick_abort() {
+    let ctx = setup();
+    commit(ctx.dir.path(), "second-file", "conflicting");
+    run(ctx.dir.path(), &["git", "cherry-pick", "other"]);
+    snapshot!(ctx, "Aa");
+}
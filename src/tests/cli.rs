@@ -0,0 +1,152 @@
+use super::*;
+use crate::cli::{Args, Commands};
+use clap::Parser;
+
+fn render(mut ctx: TestContext, args: Args) -> String {
+    let path = ctx.dir.path().to_path_buf();
+    ctx.init_state_with_args(path, &args).unwrap();
+    ctx.redact_buffer()
+}
+
+#[test]
+fn start_at_path() {
+    let ctx = TestContext::setup_init();
+    for file in ["a", "b", "c"] {
+        commit(ctx.dir.path(), file, "");
+        fs::write(ctx.dir.child(file), "changed\n").unwrap();
+    }
+
+    let args = Args {
+        path: Some(ctx.dir.child("b")),
+        ..Default::default()
+    };
+
+    insta::assert_snapshot!(render(ctx, args));
+}
+
+#[test]
+fn start_at_unchanged_path() {
+    let ctx = TestContext::setup_clone();
+    let args = Args {
+        path: Some(ctx.dir.child("initial-file")),
+        ..Default::default()
+    };
+
+    insta::assert_snapshot!(render(ctx, args));
+}
+
+#[test]
+fn start_at_missing_path() {
+    let mut ctx = TestContext::setup_clone();
+    let args = Args {
+        path: Some(ctx.dir.child("missing-file")),
+        ..Default::default()
+    };
+
+    let path = ctx.dir.path().to_path_buf();
+    let err = ctx.init_state_with_args(path, &args).err().unwrap();
+    assert!(err.to_string().starts_with("No such file: "), "{}", err);
+}
+
+#[test]
+fn log_rev() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "second-file", "");
+    commit(ctx.dir.path(), "third-file", "");
+
+    let args = Args {
+        command: Some(Commands::Log {
+            rev: Some("HEAD~1".into()),
+            max_count: None,
+            grep: None,
+        }),
+        ..Default::default()
+    };
+
+    insta::assert_snapshot!(render(ctx, args));
+}
+
+#[test]
+fn log_max_count() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "second-file", "");
+    commit(ctx.dir.path(), "third-file", "");
+
+    let args = Args {
+        command: Some(Commands::Log {
+            rev: None,
+            max_count: Some(2),
+            grep: None,
+        }),
+        ..Default::default()
+    };
+
+    insta::assert_snapshot!(render(ctx, args));
+}
+
+#[test]
+fn invalid_rev() {
+    let mut ctx = TestContext::setup_clone();
+    let args = Args {
+        command: Some(Commands::Show {
+            reference: "nonexistent".into(),
+        }),
+        ..Default::default()
+    };
+
+    let repo = git2::Repository::open(ctx.dir.path()).unwrap();
+    let err = args.validate(&repo).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid revision 'nonexistent': revspec 'nonexistent' not found"
+    );
+
+    let path = ctx.dir.path().to_path_buf();
+    assert!(ctx.init_state_with_args(path, &args).is_err());
+}
+
+#[test]
+fn not_in_a_repo() {
+    let dir = temp_dir::TempDir::new().unwrap();
+    let error = crate::open_repo_in(dir.path()).err().unwrap().to_string();
+    assert_eq!(
+        error,
+        format!(
+            "{} isn't in a git repository.\n\
+             Run `gitu init` to create one there, or start gitu from within a repository.",
+            dir.path().display()
+        )
+    );
+}
+
+#[test]
+fn bare_repo() {
+    let dir = temp_dir::TempDir::new().unwrap();
+    run(dir.path(), &["git", "init", "--bare"]);
+    let error = crate::open_repo_in(dir.path()).err().unwrap().to_string();
+    assert!(error.ends_with(
+        " is a bare repository, which has no worktree for gitu to show.\n\
+         Start gitu from within a worktree of it, see `git worktree add`."
+    ));
+}
+
+#[test]
+fn open_from_subdirectory() {
+    let ctx = TestContext::setup_clone();
+    fs::create_dir(ctx.dir.child("subdir")).unwrap();
+    let repo = crate::open_repo_in(&ctx.dir.child("subdir")).unwrap();
+    assert_eq!(
+        repo.workdir().unwrap().canonicalize().unwrap(),
+        ctx.dir.path().canonicalize().unwrap()
+    );
+}
+
+#[test]
+fn path_named_like_subcommand() {
+    let args = Args::try_parse_from(["gitu", "--", "log"]).unwrap();
+    assert!(args.command.is_none());
+    assert_eq!(args.path, Some("log".into()));
+
+    let args = Args::try_parse_from(["gitu", "log"]).unwrap();
+    assert!(matches!(args.command, Some(Commands::Log { .. })));
+}
@@ -0,0 +1,40 @@
+use super::*;
+use crate::forge::{Forge, PendingPullRequests};
+
+#[test]
+fn clone_prompt() {
+    snapshot!(TestContext::setup_clone(), "C");
+}
+
+#[test]
+fn clone_dir_prompt() {
+    snapshot!(
+        TestContext::setup_clone(),
+        "Chttps://example.com/user/project.git<enter>"
+    );
+}
+
+#[test]
+fn clone_without_url() {
+    snapshot!(TestContext::setup_clone(), "C<enter>");
+}
+
+#[test]
+fn clone_opens_the_clone() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "firstfile", "first\n");
+    snapshot!(ctx, "C.<enter>cloned<enter>");
+}
+
+#[test]
+fn clone_drops_state_of_previous_repo() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "firstfile", "first\n");
+    let mut state = ctx.init_state();
+    state.pull_requests = Some(PendingPullRequests::unlisted(Forge::GitHub));
+
+    state
+        .update(&mut ctx.term, &keys("C.<enter>cloned<enter>"))
+        .unwrap();
+    assert!(state.pull_requests.is_none());
+}
@@ -0,0 +1,55 @@
+use super::*;
+
+fn setup_conflict() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "hello\n");
+    commit(ctx.dir.path(), "first-file", "world\n");
+    fs::write(ctx.dir.child("first-file"), "conflict\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "first-file"]);
+    ctx
+}
+
+#[test]
+fn show_cmd_output() {
+    let mut ctx = setup_conflict();
+    ctx.config().general.popup_max_height = 4;
+    snapshot!(ctx, "lljcF$");
+}
+
+#[test]
+fn no_cmd_output() {
+    snapshot!(TestContext::setup_clone(), "$");
+}
+
+#[test]
+fn search_output() {
+    snapshot!(setup_conflict(), "lljcF$<ctrl+s>conflict<enter>");
+}
+
+#[test]
+fn search_next() {
+    snapshot!(setup_conflict(), "lljcF$<ctrl+s>conflict<enter><ctrl+n>");
+}
+
+#[test]
+fn search_wraps_around() {
+    snapshot!(
+        setup_conflict(),
+        "lljcF$<ctrl+s>conflict<enter><ctrl+n><ctrl+n><ctrl+n><ctrl+n>"
+    );
+}
+
+#[test]
+fn search_case_sensitive() {
+    snapshot!(setup_conflict(), "lljcF$<ctrl+s>CONFLICT<enter>");
+}
+
+#[test]
+fn search_no_match() {
+    snapshot!(TestContext::setup_clone(), "<ctrl+s>nothing like it<enter>");
+}
+
+#[test]
+fn search_next_without_search() {
+    snapshot!(TestContext::setup_clone(), "<ctrl+n>");
+}
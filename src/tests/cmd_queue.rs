@@ -0,0 +1,71 @@
+use super::*;
+#[test]
+fn queue_behind_running_command() {
+    snapshot!(TestContext::setup_clone(), "faFp");
+}
+
+#[test]
+fn skip_queued_after_failure() {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &["git", "remote", "set-url", "origin", "/nonexistent"],
+    );
+    snapshot!(ctx, "faFp");
+}
+
+#[test]
+fn multi_step_op_behind_running_command() {
+    let ctx = TestContext::setup_clone();
+    let content = (1..=20)
+        .map(|i| format!("line {}\n", i))
+        .collect::<String>();
+    commit(ctx.dir.path(), "file-one", &content);
+    fs::write(
+        ctx.dir.child("file-one"),
+        content.replace("line 2\n", "changed 2\n"),
+    )
+    .unwrap();
+    fs::write(ctx.dir.child("file-two"), "staged\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "file-two"]);
+
+    snapshot!(ctx, "fajj<tab>jzstest<enter>");
+}
+
+fn setup_index_locked() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "new-file"]);
+    run(ctx.dir.path(), &["touch", ".git/index.lock"]);
+    ctx
+}
+
+#[test]
+fn index_locked() {
+    let mut ctx = setup_index_locked();
+    ctx.config().general.index_lock_retries = 0;
+    snapshot!(ctx, "js");
+}
+
+#[test]
+fn index_locked_retry() {
+    let mut ctx = setup_index_locked();
+    ctx.config().general.index_lock_retries = 1;
+    snapshot!(ctx, "js");
+}
+
+#[test]
+fn index_locked_retry_once_released() {
+    let mut ctx = setup_index_locked();
+    ctx.config().general.index_lock_retries = 1;
+
+    let lock = ctx.dir.path().join(".git/index.lock");
+    let release = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        fs::remove_file(lock).unwrap();
+    });
+
+    let started = std::time::Instant::now();
+    snapshot!(ctx, "js");
+    assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    release.join().unwrap();
+}
@@ -0,0 +1,167 @@
+use super::*;
+
+#[test]
+fn commit_menu() {
+    snapshot!(TestContext::setup_clone(), "c");
+}
+
+#[test]
+fn co_author_completion() {
+    snapshot!(TestContext::setup_clone(), "c-TAu<tab>");
+}
+
+#[test]
+fn co_author_set() {
+    snapshot!(TestContext::setup_clone(), "c-TAu<tab><enter>");
+}
+
+#[test]
+fn co_author_invalid() {
+    snapshot!(TestContext::setup_clone(), "c-Tnobody<enter>");
+}
+
+fn setup_staged() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("new-file"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "new-file"]);
+    ctx
+}
+
+#[test]
+fn conventional_type_completion() {
+    snapshot!(setup_staged(), "cCfe<tab>");
+}
+
+#[test]
+fn conventional_invalid_type() {
+    snapshot!(setup_staged(), "cCfeature<enter>");
+}
+
+#[test]
+fn conventional_scope_prompt() {
+    snapshot!(setup_staged(), "cCfeat<enter>");
+}
+
+#[test]
+fn conventional_commit() {
+    snapshot!(setup_staged(), "cCfeat<enter>ui<enter>add new file<enter>");
+}
+
+#[test]
+fn conventional_commit_breaking_with_scope() {
+    snapshot!(setup_staged(), "cCfeat!<enter>ui<enter>add new file<enter>");
+}
+
+#[test]
+fn conventional_commit_no_scope() {
+    snapshot!(setup_staged(), "cCfix!<enter><enter>add new file<enter>");
+}
+
+#[test]
+fn conventional_configured_types() {
+    let ctx = setup_staged();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "config",
+            "gitu.conventionalCommitTypes",
+            "feature,bugfix",
+        ],
+    );
+    snapshot!(ctx, "cCfeature<enter><enter>add new file<enter>");
+}
+
+#[test]
+fn instant_fixup() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "hello\n");
+    commit(ctx.dir.path(), "second-file", "world\n");
+    fs::write(ctx.dir.child("first-file"), "hello\nfixed\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "first-file"]);
+
+    snapshot!(ctx, "lljcF");
+}
+
+#[test]
+fn instant_fixup_conflict() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "hello\n");
+    commit(ctx.dir.path(), "first-file", "world\n");
+    fs::write(ctx.dir.child("first-file"), "conflict\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "first-file"]);
+
+    snapshot!(ctx, "lljcF");
+}
+
+fn setup_commit_lint() -> TestContext {
+    let mut ctx = setup_staged();
+    fs::write(ctx.dir.child(".git/msg-template"), "add new file\n").unwrap();
+    ctx.config().general.commit_template = Some(".git/msg-template".into());
+    ctx.config().general.commit_lint = Some(
+        "sh -c 'grep -q \"^feat\" \"$0\" || { echo \"subject must start with feat:\"; exit 1; }'"
+            .into(),
+    );
+    ctx
+}
+
+#[test]
+fn commit_lint_rejected() {
+    snapshot!(setup_commit_lint(), "cc");
+}
+
+#[test]
+fn commit_lint_edit_again() {
+    snapshot!(setup_commit_lint(), "cce");
+}
+
+#[test]
+fn commit_lint_commit_anyway() {
+    snapshot!(setup_commit_lint(), "ccc");
+}
+
+#[test]
+fn commit_lint_abort() {
+    snapshot!(setup_commit_lint(), "ccn");
+}
+
+#[test]
+fn amend_author() {
+    snapshot!(TestContext::setup_clone(), "c-AAu<tab><enter>a");
+}
+
+#[test]
+fn author_invalid() {
+    snapshot!(TestContext::setup_clone(), "c-Anobody<enter>");
+}
+
+#[test]
+fn amend_date() {
+    snapshot!(TestContext::setup_clone(), "c-D2024-01-31 13:37<enter>a");
+}
+
+#[test]
+fn date_invalid() {
+    snapshot!(TestContext::setup_clone(), "c-Dsoon<enter>");
+}
+
+#[test]
+fn reword_head() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "hello\n");
+    fs::write(ctx.dir.child("staged-file"), "staged\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "staged-file"]);
+
+    snapshot!(ctx, "llcw");
+}
+
+#[test]
+fn reword_older_commit() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "hello\n");
+    commit(ctx.dir.path(), "second-file", "world\n");
+    fs::write(ctx.dir.child("staged-file"), "staged\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "staged-file"]);
+
+    snapshot!(ctx, "lljcw");
+}
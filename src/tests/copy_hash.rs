@@ -0,0 +1,17 @@
+use super::*;
+
+#[test]
+fn copy_hash_of_branch() {
+    snapshot!(TestContext::setup_clone(), "Yjy");
+}
+
+#[test]
+fn copy_hash_of_stash() {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("file-one"), "blahonga\n").unwrap();
+    run(
+        ctx.dir.path(),
+        &["git", "stash", "push", "--include-untracked"],
+    );
+    snapshot!(ctx, "jjy");
+}
@@ -0,0 +1,119 @@
+use super::*;
+use crate::credentials::AwaitingCredentials;
+use std::process::Command;
+
+/// Stands in for a push to a remote that wants a username and password.
+/// Hooks it runs would see its environment, which mustn't hold the password.
+fn needs_credentials_cmd() -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args([
+        "-c",
+        r#"[ "$GITU_ASKPASS_USERNAME" ] || { echo "fatal: terminal prompts disabled" >&2; exit 128; }
+        env | grep -q secret && echo "password in the environment"
+        echo "authenticated as $GITU_ASKPASS_USERNAME with $(cat "$GITU_ASKPASS_PASSWORD_FILE")""#,
+    ]);
+    cmd
+}
+
+fn run_needing_credentials(input: &str) -> String {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+    let result = state.run_cmd(&mut ctx.term, &[], needs_credentials_cmd());
+    assert!(result.unwrap_err().is::<AwaitingCredentials>());
+    state.update(&mut ctx.term, &keys(input)).unwrap();
+    ctx.redact_buffer()
+}
+
+#[test]
+fn credentials_prompt() {
+    insta::assert_snapshot!(run_needing_credentials("us"));
+}
+
+#[test]
+fn password_is_masked() {
+    insta::assert_snapshot!(run_needing_credentials("user<enter>secret"));
+}
+
+#[test]
+fn rerun_with_credentials() {
+    insta::assert_snapshot!(run_needing_credentials("user<enter>secret<enter>"));
+}
+
+#[test]
+fn rerun_with_terminal_for_ssh() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+
+    // Stands in for ssh asking to trust a host, which it can't in batch mode
+    let mut cmd = Command::new("sh");
+    cmd.args([
+        "-c",
+        r#"[ -z "$GIT_SSH_COMMAND" ] || { echo "Host key verification failed." >&2; exit 128; }"#,
+    ]);
+
+    state.run_cmd(&mut ctx.term, &[], cmd).unwrap();
+    ctx.term
+        .draw(|frame| crate::ui::ui(frame, &mut state))
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn password_file_removed_after_rerun() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+
+    let mut cmd = Command::new("sh");
+    cmd.args([
+        "-c",
+        r#"[ "$GITU_ASKPASS_USERNAME" ] || { echo "fatal: terminal prompts disabled" >&2; exit 128; }
+        echo "$GITU_ASKPASS_PASSWORD_FILE" > password-file-path"#,
+    ]);
+
+    let _ = state.run_cmd(&mut ctx.term, &[], cmd);
+    state
+        .update(&mut ctx.term, &keys("user<enter>secret<enter>"))
+        .unwrap();
+
+    let path = fs::read_to_string(ctx.dir.child("password-file-path")).unwrap();
+    assert!(!std::path::Path::new(path.trim()).exists());
+}
+
+#[test]
+fn rerun_ahead_of_queued() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+
+    let mut cmd = Command::new("sh");
+    cmd.args([
+        "-c",
+        r#"[ "$GITU_ASKPASS_USERNAME" ] || { echo "fatal: terminal prompts disabled" >&2; exit 128; }
+        echo rerun >> order"#,
+    ]);
+    state.run_cmd_async(&mut ctx.term, &[], cmd).unwrap();
+
+    let mut queued = Command::new("sh");
+    queued.args(["-c", "echo queued >> order"]);
+    state.run_cmd_async(&mut ctx.term, &[], queued).unwrap();
+
+    state.update(&mut ctx.term, &[]).unwrap();
+    state
+        .update(&mut ctx.term, &keys("user<enter>secret<enter>"))
+        .unwrap();
+
+    let order = fs::read_to_string(ctx.dir.child("order")).unwrap();
+    assert_eq!(order, "rerun\nqueued\n");
+}
+
+#[test]
+fn credentials_not_passed_to_terminal() {
+    let mut cmd = Command::new("git");
+    cmd.env("GIT_ASKPASS", "gitu");
+    cmd.env("GITU_ASKPASS_USERNAME", "user");
+    cmd.env("GITU_ASKPASS_PASSWORD_FILE", "/tmp/password");
+
+    let cmd = crate::credentials::with_terminal_prompt(&cmd);
+    assert!(cmd
+        .get_envs()
+        .all(|(key, value)| !key.to_string_lossy().contains("ASKPASS") || value.is_none()));
+}
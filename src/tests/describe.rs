@@ -0,0 +1,14 @@
+use super::*;
+
+#[test]
+fn describe_tagged() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+    commit(ctx.dir.path(), "first-file", "first");
+    snapshot!(ctx, "llD");
+}
+
+#[test]
+fn describe_untagged() {
+    snapshot!(TestContext::setup_clone(), "llD");
+}
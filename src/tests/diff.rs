@@ -0,0 +1,186 @@
+use super::*;
+use crate::config::DiffAlgorithm;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "firstfile", "first\n");
+    commit(ctx.dir.path(), "secondfile", "second\n");
+    ctx
+}
+
+#[test]
+fn diff_menu() {
+    snapshot!(setup(), "d");
+}
+
+#[test]
+fn diff_range() {
+    snapshot!(setup(), "drHEAD~2..HEAD<enter>");
+}
+
+#[test]
+fn diff_range_merge_base() {
+    let ctx = setup();
+    run(
+        ctx.dir.path(),
+        &["git", "checkout", "-b", "other", "HEAD~1"],
+    );
+    commit(ctx.dir.path(), "thirdfile", "third\n");
+
+    snapshot!(ctx, "drmain...other<enter>");
+}
+
+#[test]
+fn diff_range_invalid() {
+    snapshot!(setup(), "drnonexistent..HEAD<enter>");
+}
+
+#[test]
+fn diff_worktree() {
+    let ctx = setup();
+    fs::write(ctx.dir.child("firstfile"), "modified\n").unwrap();
+
+    snapshot!(ctx, "lljdw");
+}
+
+#[test]
+fn diff_merge_base_prompt() {
+    snapshot!(setup(), "dm");
+}
+
+#[test]
+fn diff_merge_base() {
+    let ctx = setup();
+    run(
+        ctx.dir.path(),
+        &["git", "checkout", "-b", "feature", "HEAD~1"],
+    );
+    commit(ctx.dir.path(), "thirdfile", "third\n");
+    fs::write(ctx.dir.child("firstfile"), "modified\n").unwrap();
+
+    snapshot!(ctx, "dmmain<enter>");
+}
+
+fn setup_context() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    let lines = (1..=12)
+        .map(|i| format!("line {}\n", i))
+        .collect::<String>();
+    commit(ctx.dir.path(), "file", &lines);
+    fs::write(
+        ctx.dir.child("file"),
+        lines.replace("line 6\n", "changed 6\n"),
+    )
+    .unwrap();
+    ctx
+}
+
+#[test]
+fn more_context() {
+    snapshot!(setup_context(), "jj<tab>++");
+}
+
+#[test]
+fn less_context() {
+    snapshot!(setup_context(), "jj<tab>---");
+}
+
+#[test]
+fn stage_hunk_without_context() {
+    snapshot!(setup_context(), "jj<tab>---js");
+}
+
+#[test]
+fn whole_file_context() {
+    snapshot!(setup_context(), "jj<tab>=");
+}
+
+#[test]
+fn configured_context() {
+    let mut ctx = setup_context();
+    ctx.config().general.diff_context_lines = 1;
+    snapshot!(ctx, "jj<tab>");
+}
+
+fn setup_algorithm() -> TestContext {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.diff_context_lines = 1;
+    commit(
+        ctx.dir.path(),
+        "file",
+        "fn one() {\n    1\n}\n\nfn two() {\n    2\n}\n",
+    );
+    fs::write(
+        ctx.dir.child("file"),
+        "fn two() {\n    2\n}\n\nfn one() {\n    1\n}\n",
+    )
+    .unwrap();
+    ctx
+}
+
+#[test]
+fn cycle_diff_algorithm() {
+    snapshot!(setup_algorithm(), "jj<tab>d-A");
+}
+
+#[test]
+fn configured_diff_algorithm() {
+    let mut ctx = setup_algorithm();
+    ctx.config().general.diff_algorithm = DiffAlgorithm::Myers;
+    snapshot!(ctx, "jj<tab>");
+}
+
+fn setup_whitespace() -> TestContext {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.diff_context_lines = 0;
+    let lines = (1..=12)
+        .map(|i| format!("line {}\n", i))
+        .collect::<String>();
+    commit(ctx.dir.path(), "file", &lines);
+    let changed = lines
+        .replace("line 2\n", "line  2 \n")
+        .replace("line 6\n", "line6\n")
+        .replace("line 10\n", "line 10\n\n");
+    fs::write(ctx.dir.child("file"), changed).unwrap();
+    ctx
+}
+
+#[test]
+fn ignore_all_space() {
+    snapshot!(setup_whitespace(), "jj<tab>d-w");
+}
+
+#[test]
+fn discard_ignoring_all_space() {
+    let mut ctx = TestContext::setup_clone();
+    let lines = (1..=8).map(|i| format!("line {}\n", i)).collect::<String>();
+    commit(ctx.dir.path(), "file", &lines);
+    let changed = lines
+        .replace("line 2\n", "line  2\n")
+        .replace("line 4\n", "changed 4\n");
+    fs::write(ctx.dir.child("file"), &changed).unwrap();
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jj<tab>d-wjKy")).unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert_eq!(
+        fs::read_to_string(ctx.dir.child("file")).unwrap(),
+        changed.replace("changed 4\n", "line 4\n")
+    );
+}
+
+#[test]
+fn ignore_space_change() {
+    snapshot!(setup_whitespace(), "jj<tab>d-b");
+}
+
+#[test]
+fn ignore_blank_lines() {
+    snapshot!(setup_whitespace(), "jj<tab>d-B");
+}
+
+#[test]
+fn ignore_all_space_toggled_off() {
+    snapshot!(setup_whitespace(), "jj<tab>d-wd-w");
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::config::Confirm;
 
 #[test]
 pub(crate) fn discard_branch_confirm_prompt() {
@@ -41,9 +42,7 @@ pub(crate) fn discard_file_move() {
     let ctx = TestContext::setup_clone();
     commit(ctx.dir.path(), "new-file", "hello");
     run(ctx.dir.path(), &["git", "mv", "new-file", "moved-file"]);
-
-    // TODO: Moved file is shown as 1 new and 1 deleted file.
-    snapshot!(ctx, "jjKyKy");
+    snapshot!(ctx, "jjKy");
 }
 
 #[test]
@@ -71,6 +70,35 @@ pub(crate) fn discard_staged_file() {
     snapshot!(ctx, "jjKy");
 }
 
+#[test]
+pub(crate) fn discard_region() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-one", "FOO\nBAR\n");
+    fs::write(ctx.dir.child("file-one"), "blahonga\nBAR\nbaz\n").unwrap();
+    snapshot!(ctx, "jj<tab><ctrl+j><ctrl+j>v<ctrl+j>Ky");
+}
+
+fn setup_two_changed_files() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-one", "FOO\nBAR\n");
+    commit(ctx.dir.path(), "file-two", "FOO\nBAR\n");
+    fs::write(ctx.dir.child("file-one"), "blahonga\n").unwrap();
+    fs::write(ctx.dir.child("file-two"), "blahonga\n").unwrap();
+    ctx
+}
+
+#[test]
+pub(crate) fn discard_marked_confirm_prompt() {
+    let ctx = setup_two_changed_files();
+    snapshot!(ctx, "jjmmK");
+}
+
+#[test]
+pub(crate) fn discard_marked_yes() {
+    let ctx = setup_two_changed_files();
+    snapshot!(ctx, "jjmmKy");
+}
+
 // FIXME Deleting branches doesn't work with the test-setup
 // #[test]
 // fn discard_branch() {
@@ -81,3 +109,27 @@ pub(crate) fn discard_staged_file() {
 //         .unwrap();
 //     insta::assert_snapshot!(ctx.redact_buffer());
 // }
+
+#[test]
+fn discard_never_confirm() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.confirm.discard = Confirm::Never;
+    fs::write(ctx.dir.child("new-file"), "").unwrap();
+    snapshot!(ctx, "jjK");
+}
+
+#[test]
+fn discard_double_confirm_prompt() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.confirm.discard = Confirm::DoubleConfirm;
+    fs::write(ctx.dir.child("new-file"), "").unwrap();
+    snapshot!(ctx, "jjKy");
+}
+
+#[test]
+fn discard_double_confirm() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.confirm.discard = Confirm::DoubleConfirm;
+    fs::write(ctx.dir.child("new-file"), "").unwrap();
+    snapshot!(ctx, "jjKyy");
+}
@@ -1,4 +1,7 @@
 use super::*;
+use crate::{menu::Menu, ops::Op};
+use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+use std::{thread, time::Duration};
 
 fn setup_scroll() -> (TestContext, crate::state::State) {
     let mut ctx = TestContext::setup_init();
@@ -64,3 +67,111 @@ fn move_next_then_parent_section() {
         .unwrap();
     insta::assert_snapshot!(ctx.redact_buffer());
 }
+
+#[test]
+fn move_down_count() {
+    let (mut ctx, mut state) = setup_scroll();
+    state.update(&mut ctx.term, &keys("3j")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn move_down_huge_count() {
+    let (mut ctx, mut state) = setup_scroll();
+    state
+        .update(&mut ctx.term, &keys("99999999999999999999999j"))
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn render_without_height() {
+    let (_ctx, state) = setup_scroll();
+    let area = Rect::new(0, 0, 80, 0);
+    state.screen().render(area, &mut Buffer::empty(area));
+}
+
+#[test]
+fn count_forgotten_by_other_key() {
+    let (mut ctx, mut state) = setup_scroll();
+    state.update(&mut ctx.term, &keys("3<tab>j")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn repeat_last_action() {
+    let ctx = TestContext::setup_init();
+    for file in ["file-a", "file-b", "file-c"] {
+        commit(ctx.dir.path(), file, "testing\n");
+        fs::write(ctx.dir.child(file), "blahonga\n").unwrap();
+    }
+    snapshot!(ctx, "jjs.");
+}
+
+#[test]
+fn repeat_without_last_action() {
+    snapshot!(TestContext::setup_init(), ".");
+}
+
+/// `jk` moves to the top, while `j` on its own still moves down.
+fn setup_chord() -> TestContext {
+    let mut ctx = TestContext::setup_init();
+    for file in ["file-a", "file-b", "file-c"] {
+        commit(ctx.dir.path(), file, "testing\n");
+        fs::write(ctx.dir.child(file), "blahonga\n").unwrap();
+    }
+
+    ctx.config().general.key_timeout = 1;
+    ctx.config()
+        .bindings
+        .get_mut(&Menu::Root)
+        .unwrap()
+        .insert(Op::MoveToTop, vec!["jk".to_string()]);
+    ctx
+}
+
+#[test]
+fn chord() {
+    snapshot!(setup_chord(), "jjjk");
+}
+
+#[test]
+fn chord_pending() {
+    snapshot!(setup_chord(), "jjj");
+}
+
+#[test]
+fn chord_prefix_then_other_key() {
+    snapshot!(setup_chord(), "jjj<tab>");
+}
+
+#[test]
+fn chord_prefix_after_timeout() {
+    let mut ctx = setup_chord();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjj")).unwrap();
+
+    thread::sleep(Duration::from_millis(10));
+    state.update(&mut ctx.term, &[]).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn which_key() {
+    let mut ctx = setup_chord();
+    let root = ctx.config().bindings.get_mut(&Menu::Root).unwrap();
+    root.insert(Op::MoveToTop, vec!["gg".to_string()]);
+    root.insert(Op::MoveToBottom, vec!["gG".to_string()]);
+    root.insert(Op::OpenMenu(Menu::Branch), vec!["gb".to_string()]);
+
+    snapshot!(ctx, "g");
+}
+
+#[test]
+fn move_to_bottom_and_top() {
+    let (mut ctx, mut state) = setup_scroll();
+    state.update(&mut ctx.term, &keys("<end>")).unwrap();
+    let bottom = ctx.redact_buffer();
+    state.update(&mut ctx.term, &keys("<home>")).unwrap();
+    insta::assert_snapshot!(format!("{}\n{}", bottom, ctx.redact_buffer()));
+}
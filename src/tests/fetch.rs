@@ -1,11 +1,99 @@
-use super::*;
-
-#[test]
-fn fetch_from_elsewhere_prompt() {
-    snapshot!(TestContext::setup_clone(), "fe");
-}
-
-#[test]
-fn fetch_from_elsewhere() {
-    snapshot!(TestContext::setup_clone(), "feorigin<enter>");
-}
+use super::*;
+
+#[test]
+fn fetch_from_elsewhere_prompt() {
+    snapshot!(TestContext::setup_clone(), "fe");
+}
+
+#[test]
+fn fetch_from_elsewhere() {
+    snapshot!(TestContext::setup_clone(), "feorigin<enter>");
+}
+
+#[test]
+fn fetch_from_default_remote() {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    snapshot!(ctx, "fe<enter>");
+}
+
+#[test]
+fn fetch_refspec_prompt() {
+    snapshot!(TestContext::setup_clone(), "fr<enter>");
+}
+
+#[test]
+fn fetch_refspec() {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    snapshot!(ctx, "fr<enter>main:refs/remotes/origin/fetched<enter>");
+}
+
+#[test]
+fn fetch_with_prune() {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &["git", "update-ref", "refs/remotes/origin/stale", "HEAD"],
+    );
+    snapshot!(ctx, "f-pa");
+}
+
+fn setup_shallow() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "firstfile", "first\n");
+    run(ctx.dir.path(), &["git", "fetch", "--depth", "1"]);
+    ctx
+}
+
+#[test]
+fn shallow_status() {
+    snapshot!(setup_shallow(), "");
+}
+
+#[test]
+fn fetch_menu_shallow_args() {
+    snapshot!(TestContext::setup_clone(), "f-d3<enter>-u");
+}
+
+#[test]
+fn fetch_unshallow() {
+    snapshot!(setup_shallow(), "f-ue<enter>");
+}
+
+fn setup_deleted_upstream() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    run(ctx.dir.path(), &["git", "push", "-u", "origin", "feature"]);
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    run(ctx.remote_dir.path(), &["git", "branch", "-D", "feature"]);
+    ctx
+}
+
+#[test]
+fn fetch_prune() {
+    snapshot!(setup_deleted_upstream(), "fp");
+}
+
+#[test]
+fn fetch_prune_delete_branches() {
+    snapshot!(setup_deleted_upstream(), "fpy");
+}
+
+#[test]
+fn fetch_prune_force_delete_unmerged() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    commit(ctx.dir.path(), "feature-file", "");
+    run(ctx.dir.path(), &["git", "push", "-u", "origin", "feature"]);
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    run(ctx.remote_dir.path(), &["git", "branch", "-D", "feature"]);
+    snapshot!(ctx, "fpyy");
+}
+
+#[test]
+fn refs_show_gone_upstream() {
+    let ctx = setup_deleted_upstream();
+    run(ctx.dir.path(), &["git", "fetch", "--prune"]);
+    snapshot!(ctx, "Y");
+}
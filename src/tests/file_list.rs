@@ -0,0 +1,60 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    for file in [
+        "README",
+        "src/a/one.rs",
+        "src/a/two.rs",
+        "src/b.rs",
+        "docs/guide/intro.md",
+    ] {
+        fs::create_dir_all(ctx.dir.child(file).parent().unwrap()).unwrap();
+        commit(ctx.dir.path(), file, "original\n");
+        fs::write(ctx.dir.child(file), "changed\n").unwrap();
+    }
+    ctx
+}
+
+fn setup_sizes() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "deleted", "original\n");
+    commit(ctx.dir.path(), "large", "original\n");
+    commit(ctx.dir.path(), "small", "original\n");
+    fs::remove_file(ctx.dir.child("deleted")).unwrap();
+    fs::write(ctx.dir.child("large"), "changed\nadded\nadded\n").unwrap();
+    fs::write(ctx.dir.child("small"), "changed\n").unwrap();
+    fs::write(ctx.dir.child("new"), "added\nadded\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "-N", "new"]);
+    ctx
+}
+
+#[test]
+fn sort_files_by_size() {
+    snapshot!(setup_sizes(), "O");
+}
+
+#[test]
+fn sort_files_by_status() {
+    snapshot!(setup_sizes(), "OO");
+}
+
+#[test]
+fn filter_files_by_glob() {
+    snapshot!(setup(), "/src/**<enter>");
+}
+
+#[test]
+fn filter_files_by_file_name_glob() {
+    snapshot!(setup(), "/*.md<enter>");
+}
+
+#[test]
+fn filter_files_by_substring() {
+    snapshot!(setup(), "/ONE<enter>");
+}
+
+#[test]
+fn clear_file_filter() {
+    snapshot!(setup(), "/ONE<enter>/<enter>");
+}
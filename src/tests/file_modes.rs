@@ -0,0 +1,87 @@
+use super::*;
+use std::os::unix::fs::symlink;
+
+fn setup_mode_change() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["chmod", "+x", "initial-file"]);
+    ctx
+}
+
+#[test]
+fn mode_change() {
+    snapshot!(setup_mode_change(), "jj<tab>");
+}
+
+#[test]
+fn stage_mode_change() {
+    snapshot!(setup_mode_change(), "jjs");
+}
+
+#[test]
+fn unstage_mode_change() {
+    let ctx = setup_mode_change();
+    run(ctx.dir.path(), &["git", "add", "initial-file"]);
+    snapshot!(ctx, "jju");
+}
+
+#[test]
+fn mode_change_with_content() {
+    let ctx = setup_mode_change();
+    fs::write(ctx.dir.child("initial-file"), "changed").unwrap();
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn new_symlink() {
+    let ctx = TestContext::setup_clone();
+    symlink("initial-file", ctx.dir.child("link")).unwrap();
+    run(ctx.dir.path(), &["git", "add", "link"]);
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn changed_symlink() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "other-file", "other");
+    symlink("initial-file", ctx.dir.child("link")).unwrap();
+    run(ctx.dir.path(), &["git", "add", "link"]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add link"]);
+    fs::remove_file(ctx.dir.child("link")).unwrap();
+    symlink("other-file", ctx.dir.child("link")).unwrap();
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn stage_changed_symlink() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "other-file", "other");
+    symlink("initial-file", ctx.dir.child("link")).unwrap();
+    run(ctx.dir.path(), &["git", "add", "link"]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add link"]);
+    fs::remove_file(ctx.dir.child("link")).unwrap();
+    symlink("other-file", ctx.dir.child("link")).unwrap();
+    snapshot!(ctx, "jjs");
+}
+
+#[test]
+fn deleted_file() {
+    let ctx = TestContext::setup_clone();
+    fs::remove_file(ctx.dir.child("initial-file")).unwrap();
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn stage_deleted_file() {
+    let ctx = TestContext::setup_clone();
+    fs::remove_file(ctx.dir.child("initial-file")).unwrap();
+    snapshot!(ctx, "jjs");
+}
+
+#[test]
+fn file_replaced_by_symlink() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "other-file", "other");
+    fs::remove_file(ctx.dir.child("initial-file")).unwrap();
+    symlink("other-file", ctx.dir.child("initial-file")).unwrap();
+    snapshot!(ctx, "jj<tab>");
+}
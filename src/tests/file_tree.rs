@@ -0,0 +1,48 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    for file in [
+        "README",
+        "src/a/one.rs",
+        "src/a/two.rs",
+        "src/b.rs",
+        "docs/guide/intro.md",
+    ] {
+        fs::create_dir_all(ctx.dir.child(file).parent().unwrap()).unwrap();
+        commit(ctx.dir.path(), file, "original\n");
+        fs::write(ctx.dir.child(file), "changed\n").unwrap();
+    }
+    ctx
+}
+
+#[test]
+fn toggle_file_tree() {
+    snapshot!(setup(), "T");
+}
+
+#[test]
+fn file_tree_from_config() {
+    let mut ctx = setup();
+    ctx.config().general.file_tree.enabled = true;
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn stage_directory() {
+    snapshot!(setup(), "Tjjjjjs");
+}
+
+#[test]
+fn unstage_directory() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    snapshot!(ctx, "Tjjjjju");
+}
+
+#[test]
+fn collapse_directory_of_one_section() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "add", "src/a/one.rs"]);
+    snapshot!(ctx, "Tjjjjjjjjjj<tab>");
+}
@@ -0,0 +1,174 @@
+use super::*;
+use crate::{
+    forge::{ci::CiStatus, Forge, PendingPullRequests, PullRequest},
+    screen,
+    state::State,
+};
+use std::rc::Rc;
+
+#[test]
+fn forge_menu() {
+    snapshot!(TestContext::setup_clone(), "N");
+}
+
+#[test]
+fn list_pull_requests_without_forge() {
+    snapshot!(TestContext::setup_clone(), "Nl");
+}
+
+fn setup_github_branch() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "remote",
+            "set-url",
+            "--push",
+            "origin",
+            "git@github.com:owner/repo.git",
+        ],
+    );
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "config",
+            "remote.origin.url",
+            "git@github.com:owner/repo.git",
+        ],
+    );
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    commit(ctx.dir.path(), "feature-file", "");
+    ctx
+}
+
+#[test]
+fn create_pull_request_unpushed() {
+    snapshot!(setup_github_branch(), "Nc");
+}
+
+#[test]
+fn create_pull_request_prompt() {
+    let ctx = setup_github_branch();
+    run(
+        ctx.dir.path(),
+        &["git", "branch", "--set-upstream-to", "origin/main"],
+    );
+    snapshot!(ctx, "Nc");
+}
+
+#[test]
+fn pull_requests_listed_in_background() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+    let pending = PendingPullRequests::unlisted(Forge::GitHub);
+    state.pull_requests = Some(pending.clone());
+    state.push_screen(
+        screen::pull_requests::create(Rc::clone(&state.config), ctx.size, pending.clone()).unwrap(),
+    );
+    ctx.term
+        .draw(|frame| crate::ui::ui(frame, &mut state))
+        .unwrap();
+    insta::assert_snapshot!("pull_requests_listing", ctx.redact_buffer());
+
+    pending.set_listed(vec![PullRequest {
+        number: 7,
+        title: "Add feature".into(),
+        branch: "feature".into(),
+        author: "someone".into(),
+    }]);
+    state.update(&mut ctx.term, &[]).unwrap();
+    insta::assert_snapshot!("pull_requests_listed", ctx.redact_buffer());
+}
+
+fn setup_ci_status() -> TestContext {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.ci_status.enabled = true;
+    commit(ctx.dir.path(), "second-file", "");
+    commit(ctx.dir.path(), "third-file", "");
+    ctx
+}
+
+fn set_ci_statuses(ctx: &TestContext, state: &State) {
+    let repo = git2::Repository::open(ctx.dir.path()).unwrap();
+    for (rev, status) in [
+        ("HEAD", CiStatus::Pending),
+        ("HEAD~1", CiStatus::Fail),
+        ("HEAD~2", CiStatus::Pass),
+    ] {
+        let oid = repo.revparse_single(rev).unwrap().id();
+        state.ci_statuses.set_status(oid, Some(status));
+    }
+}
+
+#[test]
+fn ci_status() {
+    let mut ctx = setup_ci_status();
+    let mut state = ctx.init_state();
+    set_ci_statuses(&ctx, &state);
+    state.update(&mut ctx.term, &keys("g")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn ci_status_in_log() {
+    let mut ctx = setup_ci_status();
+    let mut state = ctx.init_state();
+    set_ci_statuses(&ctx, &state);
+    state.update(&mut ctx.term, &keys("ll")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn ci_status_not_shared_with_switched_repo() {
+    let mut ctx = setup_ci_status();
+    let mut state = ctx.init_state();
+    set_ci_statuses(&ctx, &state);
+
+    let repo = git2::Repository::open(ctx.dir.path()).unwrap();
+    state.switch_repo(&mut ctx.term, repo).unwrap();
+    state.update(&mut ctx.term, &keys("ll")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+fn setup_issue_references() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "config",
+            "gitu.issueReference",
+            r"PROJ-\d+ https://tracker.example.com/browse/$0",
+        ],
+    );
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "commit",
+            "--allow-empty",
+            "-m",
+            "Fix crash on startup",
+            "-m",
+            "Fixes PROJ-12, and PROJ-34 too.",
+        ],
+    );
+    ctx
+}
+
+#[test]
+fn show_issue_references() {
+    snapshot!(setup_issue_references(), "ll<enter>");
+}
+
+#[test]
+fn open_issue_prompt() {
+    snapshot!(setup_issue_references(), "llI");
+}
+
+#[test]
+fn open_issue_without_references() {
+    snapshot!(setup_issue_references(), "lljI");
+}
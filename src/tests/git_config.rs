@@ -0,0 +1,36 @@
+use super::*;
+
+#[test]
+fn show_git_config() {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &["git", "config", "user.name", "Local Name"],
+    );
+    snapshot!(ctx, "G");
+}
+
+#[test]
+fn set_config_value() {
+    snapshot!(TestContext::setup_clone(), "Gj<enter>Jane Doe<enter>");
+}
+
+#[test]
+fn set_invalid_email() {
+    snapshot!(TestContext::setup_clone(), "Gjj<enter>jane<enter>");
+}
+
+#[test]
+fn pull_rebase_prompt() {
+    snapshot!(TestContext::setup_clone(), "Gjjj<enter>");
+}
+
+#[test]
+fn unset_config_value() {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &["git", "config", "user.name", "Local Name"],
+    );
+    snapshot!(ctx, "GjKy");
+}
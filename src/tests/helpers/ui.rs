@@ -5,6 +5,7 @@ use crate::{
     state::State,
     term::{Term, TermBackend},
     tests::helpers::RepoTestContext,
+    Res,
 };
 use crossterm::event::{Event, KeyEvent};
 use git2::Repository;
@@ -73,18 +74,31 @@ impl TestContext {
     }
 
     pub fn init_state_at_path(&mut self, path: PathBuf) -> State {
+        self.init_state_with_args(path, &Args::default()).unwrap()
+    }
+
+    pub fn init_state_with_args(&mut self, path: PathBuf, args: &Args) -> Res<State> {
+        self.create_state(path, args, false)
+    }
+
+    /// Commands run without blocking, as they do outside of tests.
+    pub fn init_async_state(&mut self) -> State {
+        self.create_state(self.dir.path().to_path_buf(), &Args::default(), true)
+            .unwrap()
+    }
+
+    fn create_state(&mut self, path: PathBuf, args: &Args, enable_async_cmds: bool) -> Res<State> {
         let mut state = State::create(
-            Rc::new(Repository::open(path).unwrap()),
+            Rc::new(Repository::open(path)?),
             self.size,
-            &Args::default(),
+            args,
             Rc::clone(&self.config),
-            false,
-        )
-        .unwrap();
+            enable_async_cmds,
+        )?;
 
         // hack: Pass in an event just to force re-rendering
-        state.update(&mut self.term, &[Event::FocusGained]).unwrap();
-        state
+        state.update(&mut self.term, &[Event::FocusGained])?;
+        Ok(state)
     }
 
     pub fn redact_buffer(&self) -> String {
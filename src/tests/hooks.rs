@@ -0,0 +1,72 @@
+use super::*;
+use itertools::Itertools;
+use std::os::unix::fs::PermissionsExt;
+
+fn install_hook(ctx: &TestContext, name: &str, script: &str) {
+    let path = ctx.dir.child(".git").join("hooks").join(name);
+    fs::write(&path, script).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn setup_failing_pre_commit() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    install_hook(
+        &ctx,
+        "pre-commit",
+        "#!/bin/sh\necho 'lint: trailing whitespace in new-file' >&2\nexit 1\n",
+    );
+    fs::write(ctx.dir.child("new-file"), "hello \n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "new-file"]);
+    ctx
+}
+
+#[test]
+fn show_hooks() {
+    let ctx = TestContext::setup_clone();
+    install_hook(&ctx, "pre-push", "#!/bin/sh\nexit 0\n");
+    install_hook(&ctx, "commit-msg", "#!/bin/sh\nexit 0\n");
+    snapshot!(ctx, "G");
+}
+
+#[test]
+fn commit_stopped_by_hook() {
+    snapshot!(setup_failing_pre_commit(), "cc");
+}
+
+#[test]
+fn commit_stopped_by_hook_printing_invalid_utf8() {
+    let ctx = TestContext::setup_clone();
+    install_hook(
+        &ctx,
+        "pre-commit",
+        "#!/bin/sh\nprintf 'lint: bad byte \\377 in new-file\\n' >&2\nexit 1\n",
+    );
+    fs::write(ctx.dir.child("new-file"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "new-file"]);
+    snapshot!(ctx, "cc");
+}
+
+#[test]
+fn commit_no_verify() {
+    snapshot!(setup_failing_pre_commit(), "c-nc");
+}
+
+#[test]
+fn push_stopped_by_hook() {
+    let mut ctx = TestContext::setup_clone();
+    install_hook(
+        &ctx,
+        "pre-push",
+        "#!/bin/sh\necho 'tests failed, not pushing' >&2\nexit 1\n",
+    );
+    commit(ctx.dir.path(), "new-file", "hello\n");
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("Pp")).unwrap();
+    // Names the remote, whose redacted path varies in length
+    insta::assert_snapshot!(ctx
+        .redact_buffer()
+        .lines()
+        .filter(|line| !line.contains("failed to push some refs"))
+        .join("\n"));
+}
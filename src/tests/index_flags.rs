@@ -0,0 +1,55 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "config.local", "debug = false\n");
+    commit(ctx.dir.path(), "vendored", "v1\n");
+    ctx
+}
+
+fn setup_flagged() -> TestContext {
+    let ctx = setup();
+    run(
+        ctx.dir.path(),
+        &["git", "update-index", "--assume-unchanged", "config.local"],
+    );
+    run(
+        ctx.dir.path(),
+        &["git", "update-index", "--skip-worktree", "vendored"],
+    );
+    fs::write(ctx.dir.child("config.local"), "debug = true\n").unwrap();
+    fs::write(ctx.dir.child("vendored"), "v2\n").unwrap();
+    ctx
+}
+
+#[test]
+fn hidden_files_status() {
+    snapshot!(setup_flagged(), "");
+}
+
+#[test]
+fn show_flagged_files() {
+    snapshot!(setup_flagged(), "Hl");
+}
+
+#[test]
+fn show_no_flagged_files() {
+    snapshot!(setup(), "Hl");
+}
+
+#[test]
+fn assume_unchanged() {
+    let ctx = setup();
+    fs::write(ctx.dir.child("config.local"), "debug = true\n").unwrap();
+    snapshot!(ctx, "jjHa");
+}
+
+#[test]
+fn no_longer_assume_unchanged() {
+    snapshot!(setup_flagged(), "HljHA");
+}
+
+#[test]
+fn no_longer_skip_worktree() {
+    snapshot!(setup_flagged(), "HljjjjHS");
+}
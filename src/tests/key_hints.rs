@@ -0,0 +1,43 @@
+use super::*;
+use crate::{menu::Menu, ops::Op};
+
+fn setup() -> TestContext {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.key_hints.enabled = true;
+    fs::write(ctx.dir.child("initial-file"), "changed\n").unwrap();
+    ctx
+}
+
+#[test]
+fn key_hints_for_file() {
+    snapshot!(setup(), "jj");
+}
+
+#[test]
+fn key_hints_for_commit() {
+    snapshot!(setup(), "jjjjj");
+}
+
+#[test]
+fn key_hints_follow_remapping() {
+    let mut ctx = setup();
+    ctx.config()
+        .bindings
+        .get_mut(&Menu::Root)
+        .unwrap()
+        .insert(Op::Stage, vec!["S".into()]);
+
+    snapshot!(ctx, "jj");
+}
+
+#[test]
+fn key_hints_hidden_in_menu() {
+    snapshot!(setup(), "jjc");
+}
+
+#[test]
+fn key_hints_of_screen_keymap() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "branch", "merged-feature"]);
+    snapshot!(ctx, "bxj");
+}
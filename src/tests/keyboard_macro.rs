@@ -0,0 +1,57 @@
+use super::*;
+
+fn setup_three_changed_files() -> TestContext {
+    let ctx = TestContext::setup_init();
+    for file in ["file-a", "file-b", "file-c"] {
+        commit(ctx.dir.path(), file, "testing\n");
+        fs::write(ctx.dir.child(file), "blahonga\n").unwrap();
+    }
+    ctx
+}
+
+#[test]
+fn record_macro() {
+    let ctx = setup_three_changed_files();
+    snapshot!(ctx, "jjQs");
+}
+
+#[test]
+fn stop_recording_macro() {
+    let ctx = setup_three_changed_files();
+    snapshot!(ctx, "jjQsQ");
+}
+
+#[test]
+fn replay_macro() {
+    let ctx = setup_three_changed_files();
+    snapshot!(ctx, "jjQsQ@@");
+}
+
+#[test]
+fn replay_macro_with_prompt() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("Qbcfeature<enter>Q"))
+        .unwrap();
+
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    run(
+        ctx.dir.path(),
+        &["git", "branch", "-m", "feature", "recorded"],
+    );
+    state.update(&mut ctx.term, &keys("@")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn replay_without_macro() {
+    let ctx = TestContext::setup_init();
+    snapshot!(ctx, "@");
+}
+
+#[test]
+fn replay_while_recording() {
+    let ctx = setup_three_changed_files();
+    snapshot!(ctx, "Qjj@s");
+}
@@ -0,0 +1,64 @@
+use super::*;
+use crate::config::CmdOutput;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "firstfile", "first\n");
+    ctx
+}
+
+#[test]
+fn layout_menu() {
+    snapshot!(setup(), "L");
+}
+
+#[test]
+fn widen_list() {
+    let mut ctx = setup();
+    ctx.config().general.split_pane.screens = vec!["log".to_string()];
+    snapshot!(ctx, "llLll");
+}
+
+#[test]
+fn narrow_list_to_minimum() {
+    let mut ctx = setup();
+    ctx.config().general.split_pane.screens = vec!["log".to_string()];
+    snapshot!(ctx, "llLhhhhhh");
+}
+
+#[test]
+fn popup_max_height() {
+    let mut ctx = setup();
+    ctx.config().general.popup_max_height = 4;
+    snapshot!(ctx, "h");
+}
+
+#[test]
+fn cmd_output_shows_its_end() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.popup_max_height = 4;
+    commit(ctx.dir.path(), "first-file", "hello\n");
+    commit(ctx.dir.path(), "first-file", "world\n");
+    fs::write(ctx.dir.child("first-file"), "conflict\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "first-file"]);
+
+    snapshot!(ctx, "lljcF");
+}
+
+#[test]
+fn shorter_popups() {
+    snapshot!(setup(), "L------");
+}
+
+#[test]
+fn cmd_output_overlaid() {
+    let mut ctx = setup();
+    ctx.config().general.cmd_output = CmdOutput::Overlaid;
+    fs::write(ctx.dir.child("firstfile"), "changed\n").unwrap();
+    snapshot!(ctx, "jjs");
+}
+
+#[test]
+fn toggle_cmd_output_dock() {
+    snapshot!(setup(), "Lo");
+}
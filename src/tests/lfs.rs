@@ -0,0 +1,42 @@
+use super::*;
+
+fn pointer(oid_char: char, size: u64) -> String {
+    format!(
+        "version https://git-lfs.github.com/spec/v1\noid sha256:{}\nsize {}\n",
+        oid_char.to_string().repeat(64),
+        size
+    )
+}
+
+fn setup_lfs() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(
+        ctx.dir.path(),
+        ".gitattributes",
+        "*.bin filter=lfs diff=lfs merge=lfs -text\n",
+    );
+    commit(ctx.dir.path(), "file.bin", &pointer('a', 1024));
+    ctx
+}
+
+#[test]
+fn lfs_content_changed() {
+    let ctx = setup_lfs();
+    fs::write(ctx.dir.child("file.bin"), "x".repeat(100_000)).unwrap();
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn lfs_not_pulled() {
+    let ctx = setup_lfs();
+    fs::write(ctx.dir.child("file.bin"), pointer('b', 2048)).unwrap();
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn lfs_staged() {
+    let ctx = setup_lfs();
+    fs::write(ctx.dir.child("file.bin"), pointer('b', 2048)).unwrap();
+    run(ctx.dir.path(), &["git", "add", "file.bin"]);
+    snapshot!(ctx, "jj<tab>");
+}
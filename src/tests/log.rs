@@ -1,4 +1,6 @@
 use super::*;
+use crate::{config::LogAuthor, state::State};
+use std::{thread, time::Duration};
 
 fn setup() -> TestContext {
     let ctx = TestContext::setup_clone();
@@ -77,3 +79,123 @@ fn log_other_input() {
 fn log_other_invalid() {
     snapshot!(setup(), "lo <enter>");
 }
+
+#[test]
+fn show_prepared_while_idle() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "content\n");
+    let head = git2::Repository::open(ctx.dir.path())
+        .unwrap()
+        .head()
+        .unwrap()
+        .target()
+        .unwrap()
+        .to_string();
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("ll")).unwrap();
+    wait_for_prepared_shows(&mut ctx, &mut state);
+    assert!(state.prepared_shows.contains(&head));
+
+    state.update(&mut ctx.term, &keys("<enter>")).unwrap();
+    assert!(!state.prepared_shows.contains(&head));
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn prepared_show_dropped_on_refresh() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "content\n");
+    let head = git2::Repository::open(ctx.dir.path())
+        .unwrap()
+        .head()
+        .unwrap()
+        .target()
+        .unwrap()
+        .to_string();
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("ll")).unwrap();
+    wait_for_prepared_shows(&mut ctx, &mut state);
+    assert!(state.prepared_shows.contains(&head));
+
+    state.update(&mut ctx.term, &keys("g")).unwrap();
+    assert!(!state.prepared_shows.contains(&head));
+}
+
+fn wait_for_prepared_shows(ctx: &mut TestContext, state: &mut State) {
+    state.update(&mut ctx.term, &[]).unwrap();
+    while state.prepared_shows.is_pending() {
+        thread::sleep(Duration::from_millis(10));
+        state.update(&mut ctx.term, &[]).unwrap();
+    }
+}
+
+fn setup_authors() -> TestContext {
+    let ctx = setup();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "commit",
+            "--allow-empty",
+            "--author",
+            "Other Name <other@email.com>",
+            "-m",
+            "other commit",
+        ],
+    );
+    ctx
+}
+
+#[test]
+fn shortlog() {
+    snapshot!(setup_authors(), "la<enter>");
+}
+
+#[test]
+fn shortlog_range() {
+    snapshot!(setup_authors(), "laHEAD~2..HEAD<enter>");
+}
+
+#[test]
+fn shortlog_author_log() {
+    snapshot!(setup_authors(), "la<enter>jj<enter>");
+}
+
+#[test]
+fn log_author_initials() {
+    let mut ctx = setup_authors();
+    ctx.config().general.log.author = LogAuthor::Initials;
+    snapshot!(ctx, "ll");
+}
+
+#[test]
+fn log_columns() {
+    let mut ctx = setup_authors();
+    let log = &mut ctx.config().general.log;
+    log.hash_length = 12;
+    log.author = LogAuthor::Email;
+    log.refs.enabled = false;
+    log.subject_width = 8;
+    snapshot!(ctx, "ll");
+}
+
+fn setup_lines() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "1\n2\n3\n4\n5\n6\n7\n8\n9\n");
+    commit(ctx.dir.path(), "file", "1\ntwo\n3\n4\n5\n6\n7\n8\n9\n");
+    commit(ctx.dir.path(), "file", "1\ntwo\n3\n4\n5\n6\n7\n8\nnine\n");
+    fs::write(ctx.dir.child("file"), "1\n2\n3\n4\n5\n6\n7\n8\nnine\n").unwrap();
+    ctx
+}
+
+#[test]
+fn log_hunk_lines() {
+    snapshot!(setup_lines(), "jj<tab>jlL");
+}
+
+#[test]
+fn log_shown_hunk_lines() {
+    snapshot!(setup_lines(), "ll<enter>jjjjjjjjjjjjjlL");
+}
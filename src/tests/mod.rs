@@ -3,19 +3,56 @@ use std::fs;
 #[macro_use]
 mod helpers;
 mod arg;
+mod branch_cleanup;
+mod cancel;
+mod cherry_pick;
+mod cli;
+mod clone;
+mod cmd_output;
+mod cmd_queue;
+mod commit;
+mod copy_hash;
+mod credentials;
+mod describe;
+mod diff;
 mod discard;
 mod editor;
 mod fetch;
+mod file_list;
+mod file_modes;
+mod file_tree;
+mod forge;
+mod git_config;
+mod hooks;
+mod index_flags;
+mod key_hints;
+mod keyboard_macro;
+mod layout;
+mod lfs;
 mod log;
+mod notes;
+mod notify;
+mod patch;
+mod pre_commit;
+mod print;
+mod prompt;
 mod pull;
 mod push;
 mod quit;
 mod rebase;
+mod rerere;
 mod reset;
+mod screens;
+mod sparse_checkout;
+mod split_pane;
 mod stage;
 mod stash;
+mod stats;
+mod submodule;
+mod title_bar;
 mod unstage;
 
+use crate::config::DateFormat;
 use helpers::{clone_and_commit, commit, keys, run, TestContext};
 
 #[test]
@@ -35,6 +72,11 @@ fn help_menu() {
     insta::assert_snapshot!(ctx.redact_buffer());
 }
 
+#[test]
+fn target_op_not_available() {
+    snapshot!(TestContext::setup_clone(), "s");
+}
+
 #[test]
 fn fresh_init() {
     let mut ctx = TestContext::setup_init();
@@ -85,6 +127,86 @@ fn show() {
     snapshot!(ctx, "ll<enter>");
 }
 
+#[test]
+fn show_custom_date_format() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "firstfile", "This should be visible\n");
+    ctx.config().general.date_format = DateFormat::Format("%d/%m/%Y %H:%M".to_string());
+    snapshot!(ctx, "ll<enter>");
+}
+
+#[test]
+fn cycle_date_format() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "firstfile", "This should be visible\n");
+    snapshot!(ctx, "ll<enter>ld");
+}
+
+fn setup_two_commits() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "firstfile", "first\n");
+    commit(ctx.dir.path(), "secondfile", "second\n");
+    ctx
+}
+
+#[test]
+fn show_parent() {
+    snapshot!(setup_two_commits(), "ll<enter>kk<enter>");
+}
+
+#[test]
+fn show_next_commit() {
+    snapshot!(setup_two_commits(), "ll<enter>]");
+}
+
+#[test]
+fn show_prev_commit() {
+    snapshot!(setup_two_commits(), "llj<enter>[");
+}
+
+#[test]
+fn show_next_commit_of_other_log() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other"]);
+    commit(ctx.dir.path(), "firstfile", "first\n");
+    commit(ctx.dir.path(), "secondfile", "second\n");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    snapshot!(ctx, "loother<enter><enter>]");
+}
+
+#[test]
+fn show_prev_commit_at_newest() {
+    snapshot!(setup_two_commits(), "ll<enter>[");
+}
+
+fn setup_moved_lines() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "1\n2\n3\n4\n5\n6\n");
+    commit(ctx.dir.path(), "file", "1\n2\n3\n4\nfive\n6\n");
+    fs::write(ctx.dir.child("file"), "new\nnew\n1\n2\n3\n4\nfive\n6\n").unwrap();
+    ctx
+}
+
+#[test]
+fn visit_worktree_file_hunk() {
+    snapshot!(setup_moved_lines(), "ll<enter>jj<alt+enter>");
+}
+
+#[test]
+fn visit_worktree_file_line() {
+    snapshot!(
+        setup_moved_lines(),
+        "ll<enter>jj<ctrl+j><ctrl+j><alt+enter>"
+    );
+}
+
+#[test]
+fn visit_file_with_editor_template() {
+    let mut ctx = setup_moved_lines();
+    ctx.config().general.editor_template = Some("true --goto {file}:{line}:{col}".into());
+    snapshot!(ctx, "ll<enter>jj<alt+enter>");
+}
+
 #[test]
 fn rebase_conflict() {
     let mut ctx = TestContext::setup_clone();
@@ -161,6 +283,14 @@ fn revert_commit() {
     snapshot!(ctx, "llV-eV<enter>");
 }
 
+#[test]
+fn revert_marked_commits() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "first");
+    commit(ctx.dir.path(), "second-file", "second");
+    snapshot!(ctx, "llmmV-eV<enter>");
+}
+
 #[test]
 fn moved_file() {
     let mut ctx = TestContext::setup_clone();
@@ -171,6 +301,15 @@ fn moved_file() {
     insta::assert_snapshot!(ctx.redact_buffer());
 }
 
+#[test]
+fn show_moved_file() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hello");
+    run(ctx.dir.path(), &["git", "mv", "new-file", "moved-file"]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "move file"]);
+    snapshot!(ctx, "ll<enter>");
+}
+
 #[test]
 fn hide_untracked() {
     let mut ctx = TestContext::setup_clone();
@@ -207,6 +346,22 @@ fn pull() {
     snapshot!(ctx, "Fp");
 }
 
+#[test]
+fn unpushed_and_unpulled() {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    run(ctx.dir.path(), &["git", "fetch"]);
+    commit(ctx.dir.path(), "local-file", "hello");
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn show_unpushed_commit() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "local-file", "hello");
+    snapshot!(ctx, "jj<enter>");
+}
+
 mod show_refs {
     use super::*;
 
@@ -220,7 +375,12 @@ mod show_refs {
     #[test]
     fn show_refs_at_remote_branch() {
         let ctx = TestContext::setup_clone();
-        snapshot!(ctx, "Yjjjjbb<enter>Y");
+        // Checking out `origin/main` from gitu would switch to `main`, which tracks it
+        run(
+            ctx.dir.path(),
+            &["git", "checkout", "--detach", "origin/main"],
+        );
+        snapshot!(ctx, "Y");
     }
 
     #[test]
@@ -255,10 +415,136 @@ mod checkout {
         snapshot!(ctx, "Yjjbbhi<enter>");
     }
 
+    #[test]
+    pub(crate) fn switch_branch_autostash() {
+        let mut ctx = TestContext::setup_clone();
+        ctx.config().general.autostash.enabled = true;
+        run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+        commit(ctx.dir.path(), "other-file", "hello");
+        run(ctx.dir.path(), &["git", "checkout", "main"]);
+        fs::write(ctx.dir.child("initial-file"), "changed").unwrap();
+        snapshot!(ctx, "bbother-branch<enter>");
+    }
+
+    #[test]
+    pub(crate) fn switch_branch_autostash_conflict() {
+        let mut ctx = TestContext::setup_clone();
+        ctx.config().general.autostash.enabled = true;
+        run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+        commit(ctx.dir.path(), "initial-file", "theirs");
+        run(ctx.dir.path(), &["git", "checkout", "main"]);
+        fs::write(ctx.dir.child("initial-file"), "ours").unwrap();
+        snapshot!(ctx, "bbother-branch<enter>");
+    }
+
+    #[test]
+    pub(crate) fn switch_branch_autostash_index_locked() {
+        let mut ctx = TestContext::setup_clone();
+        ctx.config().general.autostash.enabled = true;
+        ctx.config().general.index_lock_retries = 1;
+        run(ctx.dir.path(), &["git", "branch", "other-branch"]);
+        fs::write(ctx.dir.child("initial-file"), "changed").unwrap();
+        run(ctx.dir.path(), &["touch", ".git/index.lock"]);
+        snapshot!(ctx, "bbother-branch<enter>");
+    }
+
+    fn setup_remote_branch() -> TestContext {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "push", "origin", "main:feature-x"]);
+        commit(ctx.dir.path(), "new-file", "hello");
+        run(ctx.dir.path(), &["git", "push"]);
+        ctx
+    }
+
+    #[test]
+    pub(crate) fn checkout_remote_branch_prompt() {
+        snapshot!(setup_remote_branch(), "bborigin/feature-x<enter>");
+    }
+
+    #[test]
+    pub(crate) fn checkout_remote_branch() {
+        snapshot!(setup_remote_branch(), "bborigin/feature-x<enter><enter>");
+    }
+
+    #[test]
+    pub(crate) fn checkout_remote_branch_renamed() {
+        snapshot!(
+            setup_remote_branch(),
+            "bborigin/feature-x<enter>mine<enter>"
+        );
+    }
+
+    #[test]
+    pub(crate) fn checkout_remote_branch_from_log() {
+        snapshot!(setup_remote_branch(), "lljbb<enter>");
+    }
+
+    #[test]
+    pub(crate) fn checkout_remote_branch_of_local_branch() {
+        snapshot!(setup_remote_branch(), "bborigin/main<enter>");
+    }
+
+    #[test]
+    pub(crate) fn checkout_remote_branch_of_local_branch_ahead() {
+        let ctx = setup_remote_branch();
+        commit(ctx.dir.path(), "unpushed-file", "");
+        snapshot!(ctx, "bborigin/main<enter>");
+    }
+
     #[test]
     pub(crate) fn checkout_new_branch() {
         snapshot!(TestContext::setup_clone(), "bcf<esc>bcx<enter>");
     }
+
+    #[test]
+    pub(crate) fn checkout_new_orphan_branch() {
+        snapshot!(TestContext::setup_clone(), "b-ocorphan<enter>");
+    }
+
+    #[test]
+    pub(crate) fn create_branch_without_checkout() {
+        snapshot!(TestContext::setup_clone(), "b-ncfeature<enter>");
+    }
+
+    #[test]
+    pub(crate) fn create_orphan_branch_without_checkout() {
+        snapshot!(TestContext::setup_clone(), "b-o-ncfeature<enter>");
+    }
+
+    #[test]
+    pub(crate) fn set_upstream_prompt() {
+        snapshot!(TestContext::setup_clone(), "bu");
+    }
+
+    #[test]
+    pub(crate) fn set_upstream_selected() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "branch", "other-branch"]);
+        snapshot!(ctx, "Yjjbuorigin/main<enter>");
+    }
+
+    #[test]
+    pub(crate) fn spinoff() {
+        let ctx = TestContext::setup_clone();
+        commit(ctx.dir.path(), "new-file", "hello");
+        fs::write(ctx.dir.child("new-file"), "changed").unwrap();
+        snapshot!(ctx, "bsfeature<enter>");
+    }
+
+    #[test]
+    pub(crate) fn spinoff_up_to_date() {
+        let ctx = TestContext::setup_clone();
+        fs::write(ctx.dir.child("initial-file"), "changed").unwrap();
+        snapshot!(ctx, "bsfeature<enter>");
+    }
+
+    #[test]
+    pub(crate) fn spinoff_existing_branch() {
+        let ctx = TestContext::setup_clone();
+        commit(ctx.dir.path(), "new-file", "hello");
+        run(ctx.dir.path(), &["git", "branch", "feature"]);
+        snapshot!(ctx, "bsfeature<enter>");
+    }
 }
 
 #[test]
@@ -334,6 +620,16 @@ fn syntax_highlighted() {
     snapshot!(ctx, "jj<tab>");
 }
 
+#[test]
+fn selected_hunk_style() {
+    let mut ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "testfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("testfile"), "test\ntesttest\n").unwrap();
+    ctx.config().style.selection_hunk = toml::from_str(r#"bg = "dark gray""#).unwrap();
+
+    snapshot!(ctx, "jj<tab>j<ctrl+j>");
+}
+
 #[test]
 fn crlf_diff() {
     let mut ctx = TestContext::setup_init();
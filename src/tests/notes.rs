@@ -0,0 +1,38 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "first");
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "notes",
+            "add",
+            "-m",
+            "Reviewed\n\nLooks fine",
+            "HEAD",
+        ],
+    );
+    ctx
+}
+
+#[test]
+fn notes_menu() {
+    snapshot!(setup(), "lln");
+}
+
+#[test]
+fn show_note() {
+    snapshot!(setup(), "ll<enter>");
+}
+
+#[test]
+fn edit_note() {
+    snapshot!(setup(), "lljnn");
+}
+
+#[test]
+fn remove_note() {
+    snapshot!(setup(), "llnr");
+}
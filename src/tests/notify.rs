@@ -0,0 +1,32 @@
+use super::*;
+use crossterm::event::Event;
+
+fn setup() -> TestContext {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.notify.after_seconds = 0;
+    ctx
+}
+
+#[test]
+fn notify_when_unfocused() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &[Event::FocusLost]).unwrap();
+    state.update(&mut ctx.term, &keys("fa")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn no_notify_when_focused() {
+    snapshot!(setup(), "fa");
+}
+
+#[test]
+fn no_notify_when_refocused() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &[Event::FocusLost]).unwrap();
+    state.update(&mut ctx.term, &[Event::FocusGained]).unwrap();
+    state.update(&mut ctx.term, &keys("fa")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
@@ -0,0 +1,116 @@
+use super::*;
+
+#[test]
+fn patch_menu() {
+    snapshot!(TestContext::setup_clone(), "W");
+}
+
+#[test]
+fn format_patch() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "first");
+    snapshot!(ctx, "llWc<enter>");
+}
+
+#[test]
+fn format_patch_marked() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "first");
+    commit(ctx.dir.path(), "second-file", "second");
+    snapshot!(ctx, "llmmWc<enter>");
+}
+
+#[test]
+fn format_patch_range() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "first");
+    commit(ctx.dir.path(), "second-file", "second");
+    snapshot!(ctx, "Wcorigin/main..main<enter>");
+}
+
+#[test]
+fn format_patch_output_directory() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "first");
+    snapshot!(ctx, "llmW-opatches<enter>c<enter>");
+}
+
+fn setup_patch_file() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "first");
+    run(ctx.dir.path(), &["git", "format-patch", "-1"]);
+    run(ctx.dir.path(), &["git", "reset", "--hard", "HEAD~1"]);
+    ctx
+}
+
+#[test]
+fn am_menu() {
+    snapshot!(TestContext::setup_clone(), "w");
+}
+
+#[test]
+fn am_apply_prompt() {
+    snapshot!(setup_patch_file(), "ww");
+}
+
+#[test]
+fn am_apply() {
+    snapshot!(setup_patch_file(), "ww0001-add-first-file.patch<enter>");
+}
+
+#[test]
+fn am_apply_selected() {
+    snapshot!(setup_patch_file(), "jjww<enter>");
+}
+
+#[test]
+fn am_apply_nothing() {
+    snapshot!(TestContext::setup_clone(), "ww<enter>");
+}
+
+fn setup_am_conflict() -> TestContext {
+    let ctx = setup_patch_file();
+    commit(ctx.dir.path(), "first-file", "conflicting");
+    run(ctx.dir.path(), &["git", "am", "0001-add-first-file.patch"]);
+    ctx
+}
+
+#[test]
+fn am_conflict() {
+    snapshot!(setup_am_conflict(), "");
+}
+
+#[test]
+fn am_abort() {
+    snapshot!(setup_am_conflict(), "wa");
+}
+
+#[test]
+fn am_skip() {
+    snapshot!(setup_am_conflict(), "ws");
+}
+
+#[test]
+fn archive_prompt() {
+    snapshot!(TestContext::setup_clone(), "Wamain<enter>");
+}
+
+#[test]
+fn archive_prompt_hex_branch() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "branch", "cafe"]);
+    run(ctx.dir.path(), &["git", "branch", "deadbeef"]);
+    snapshot!(ctx, "Wacafe<enter><enter>Wadeadbeef<enter>");
+}
+
+#[test]
+fn archive() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "first");
+    snapshot!(ctx, "Wamain<enter><enter>");
+}
+
+#[test]
+fn archive_zip() {
+    snapshot!(TestContext::setup_clone(), "Wamain<enter>main.zip<enter>");
+}
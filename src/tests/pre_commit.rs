@@ -0,0 +1,32 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(
+        ctx.dir.path(),
+        ".pre-commit-config.yaml",
+        "repos:\n- repo: local\n  hooks: []\n",
+    );
+    fs::write(ctx.dir.child("script.py"), "x=1  \n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "script.py"]);
+    ctx
+}
+
+#[test]
+fn pre_commit_not_configured() {
+    snapshot!(TestContext::setup_clone(), "cp");
+}
+
+#[test]
+fn stage_hook_fixes() {
+    let ctx = setup();
+    // As a formatting hook would leave it
+    fs::write(ctx.dir.child("script.py"), "x = 1\n").unwrap();
+    fs::write(ctx.dir.child("unrelated"), "untracked\n").unwrap();
+    snapshot!(ctx, "cP");
+}
+
+#[test]
+fn no_hook_fixes() {
+    snapshot!(setup(), "cP");
+}
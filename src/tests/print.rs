@@ -0,0 +1,81 @@
+use super::*;
+use crate::{cli::Commands, print::print_command};
+use std::rc::Rc;
+
+fn print(ctx: &mut TestContext, command: Commands) -> String {
+    let state = ctx.init_state();
+    let mut out = vec![];
+    print_command(
+        Rc::clone(&state.config),
+        Rc::clone(&state.repo),
+        &command,
+        &mut out,
+    )
+    .unwrap();
+
+    String::from_utf8(out).unwrap()
+}
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "modified-file", "one\n");
+    fs::write(ctx.dir.child("modified-file"), "one\ntwo\n").unwrap();
+    fs::write(ctx.dir.child("staged-file"), "staged\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "staged-file"]);
+    fs::write(ctx.dir.child("untracked-file"), "").unwrap();
+    ctx
+}
+
+#[test]
+fn status() {
+    let mut ctx = setup();
+    insta::assert_snapshot!(print(&mut ctx, Commands::Status { porcelain: false }));
+}
+
+#[test]
+fn status_porcelain() {
+    let mut ctx = setup();
+    insta::assert_snapshot!(print(&mut ctx, Commands::Status { porcelain: true }));
+}
+
+#[test]
+fn diff() {
+    let mut ctx = setup();
+    let patch = print(
+        &mut ctx,
+        Commands::Diff {
+            staged: false,
+            range: None,
+        },
+    );
+
+    insta::assert_snapshot!(patch);
+}
+
+#[test]
+fn diff_staged() {
+    let mut ctx = setup();
+    let patch = print(
+        &mut ctx,
+        Commands::Diff {
+            staged: true,
+            range: None,
+        },
+    );
+
+    insta::assert_snapshot!(patch);
+}
+
+#[test]
+fn diff_range() {
+    let mut ctx = setup();
+    let patch = print(
+        &mut ctx,
+        Commands::Diff {
+            staged: false,
+            range: Some("HEAD~1..HEAD".into()),
+        },
+    );
+
+    insta::assert_snapshot!(patch);
+}
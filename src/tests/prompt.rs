@@ -0,0 +1,60 @@
+use super::*;
+
+#[test]
+fn history_recall() {
+    snapshot!(
+        TestContext::setup_clone(),
+        "drfirst..HEAD<enter>drsecond..HEAD<enter>dr<up><up>"
+    );
+}
+
+#[test]
+fn history_restores_draft() {
+    snapshot!(
+        TestContext::setup_clone(),
+        "drfirst..HEAD<enter>drdraft<up><down>"
+    );
+}
+
+#[test]
+fn history_is_per_prompt() {
+    snapshot!(TestContext::setup_clone(), "drfirst..HEAD<enter>dm<up>");
+}
+
+#[test]
+fn delete_word_backward() {
+    snapshot!(TestContext::setup_clone(), "drHEAD~1 HEAD<ctrl+w>");
+}
+
+#[test]
+fn delete_word_backward_non_ascii() {
+    snapshot!(TestContext::setup_clone(), "drhé é a<ctrl+w>b");
+}
+
+fn setup_branches() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "branch", "feature-one"]);
+    run(ctx.dir.path(), &["git", "branch", "feature-two"]);
+    run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+    ctx
+}
+
+#[test]
+fn rev_candidates() {
+    snapshot!(setup_branches(), "lo");
+}
+
+#[test]
+fn rev_candidates_filtered() {
+    snapshot!(setup_branches(), "lotwo");
+}
+
+#[test]
+fn rev_completion() {
+    snapshot!(setup_branches(), "lofe<tab>");
+}
+
+#[test]
+fn rev_completion_in_range() {
+    snapshot!(setup_branches(), "drmain..feature-t<tab>");
+}
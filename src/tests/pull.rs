@@ -9,3 +9,12 @@ fn pull_from_elsewhere_prompt() {
 fn pull_from_elsewhere() {
     snapshot!(TestContext::setup_clone(), "Feorigin<enter>");
 }
+
+#[test]
+fn pull_autostash() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.autostash.enabled = true;
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    fs::write(ctx.dir.child("initial-file"), "changed").unwrap();
+    snapshot!(ctx, "Fp");
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::config::Confirm;
 
 #[test]
 fn push() {
@@ -30,3 +31,154 @@ fn push_elsewhere_prompt() {
 fn push_elsewhere() {
     snapshot!(TestContext::setup_clone(), "Peorigin<enter>");
 }
+
+#[test]
+fn force_push_confirm() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.confirm.force_push = Confirm::Ask;
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "P-fp");
+}
+
+#[test]
+fn plain_force_push_confirm() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "P-Fp");
+}
+
+#[test]
+fn force_push_shows_discarded_commits() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.confirm.force_push = Confirm::Ask;
+    clone_and_commit(&ctx.remote_dir, "remote-file", "");
+    run(ctx.dir.path(), &["git", "fetch"]);
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "P-fp");
+}
+
+#[test]
+fn force_push_lists_discarded_commits_without_confirm() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.confirm.force_push = Confirm::Never;
+    clone_and_commit(&ctx.remote_dir, "remote-file", "");
+    run(ctx.dir.path(), &["git", "fetch"]);
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "P-fp");
+}
+
+#[test]
+fn plain_force_push_lists_discarded_commits() {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "");
+    run(ctx.dir.path(), &["git", "fetch"]);
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "P-Fpy");
+}
+
+#[test]
+fn force_push_elsewhere_confirm() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.confirm.force_push = Confirm::Ask;
+    clone_and_commit(&ctx.remote_dir, "remote-file", "");
+    run(ctx.dir.path(), &["git", "fetch"]);
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "P-fe");
+}
+
+#[test]
+fn push_without_upstream_prompt() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "Pp");
+}
+
+#[test]
+fn push_without_upstream() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "Pp<enter>");
+}
+
+#[test]
+fn push_without_upstream_invalid() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    snapshot!(ctx, "Ppnowhere/feature<enter>");
+}
+
+fn setup_push_remote_with_slash() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    let url = ctx.remote_dir.path().to_str().unwrap();
+    run(ctx.dir.path(), &["git", "remote", "add", "my/fork", url]);
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    commit(ctx.dir.path(), "new-file", "");
+    ctx
+}
+
+#[test]
+fn push_without_upstream_to_push_remote() {
+    let ctx = setup_push_remote_with_slash();
+    run(
+        ctx.dir.path(),
+        &["git", "config", "branch.feature.pushRemote", "my/fork"],
+    );
+    snapshot!(ctx, "Pp<enter>");
+}
+
+#[test]
+fn push_without_upstream_pushing_nothing() {
+    let ctx = setup_push_remote_with_slash();
+    run(
+        ctx.dir.path(),
+        &["git", "config", "push.default", "nothing"],
+    );
+    snapshot!(ctx, "Pp");
+}
+
+#[test]
+fn push_commit_prompt() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "llPo");
+}
+
+#[test]
+fn push_commit() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "");
+    commit(ctx.dir.path(), "other-file", "");
+    snapshot!(ctx, "lljPoorigin/fix<enter>");
+}
+
+#[test]
+fn push_commit_to_existing_branch() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "llPoorigin/main<enter>");
+}
+
+#[test]
+fn push_commit_invalid() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "llPofix<enter>");
+}
+
+#[test]
+fn push_options_prompt() {
+    snapshot!(TestContext::setup_clone(), "P-o");
+}
+
+#[test]
+fn push_options() {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.remote_dir.path(),
+        &["git", "config", "receive.advertisePushOptions", "true"],
+    );
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, r#"P-o["ci.skip", "topic=a,b"]<enter>p"#);
+}
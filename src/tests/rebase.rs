@@ -1,4 +1,6 @@
 use super::*;
+use crate::{menu::Menu, ops::Op};
+use std::{thread, time::Duration};
 
 fn setup() -> TestContext {
     let ctx = TestContext::setup_clone();
@@ -23,3 +25,73 @@ fn rebase_elsewhere_prompt() {
 fn rebase_elsewhere() {
     snapshot!(setup(), "remain<enter>");
 }
+
+fn setup_stack() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "one");
+    commit(ctx.dir.path(), "second-file", "two");
+    ctx
+}
+
+#[test]
+fn drop_commit() {
+    snapshot!(setup_stack(), "lljrk");
+}
+
+#[test]
+fn drop_commit_repeated() {
+    snapshot!(setup_stack(), "llrk.");
+}
+
+#[test]
+fn squash_into_parent() {
+    snapshot!(setup_stack(), "llrs");
+}
+
+#[test]
+fn squash_root_commit() {
+    snapshot!(setup_stack(), "lljjrs");
+}
+
+#[test]
+fn drop_commit_conflict() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hello");
+    commit(ctx.dir.path(), "new-file", "hello again");
+    snapshot!(ctx, "lljrk");
+}
+
+#[test]
+fn rebase_todo() {
+    snapshot!(setup_stack(), "lljjri");
+}
+
+#[test]
+fn rebase_todo_prefix_of_root_binding() {
+    let mut ctx = setup_stack();
+    ctx.config().general.key_timeout = 1;
+    ctx.config()
+        .bindings
+        .get_mut(&Menu::RebaseTodo)
+        .unwrap()
+        .insert(Op::RebaseTodoStart, vec!["<ctrl+c><ctrl+c>".to_string()]);
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("lljri<ctrl+c>")).unwrap();
+    thread::sleep(Duration::from_millis(10));
+    state.update(&mut ctx.term, &[]).unwrap();
+    ctx.term
+        .draw(|frame| crate::ui::ui(frame, &mut state))
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn rebase_todo_reorder() {
+    snapshot!(setup_stack(), "lljri<alt+j>x");
+}
+
+#[test]
+fn rebase_todo_fixup() {
+    snapshot!(setup_stack(), "lljrijfx");
+}
@@ -0,0 +1,44 @@
+use super::*;
+
+fn setup_conflict() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    let dir = ctx.dir.path();
+    run(dir, &["git", "config", "rerere.enabled", "true"]);
+    commit(dir, "new-file", "hello");
+
+    run(dir, &["git", "checkout", "-b", "other-branch"]);
+    commit(dir, "new-file", "hey");
+
+    run(dir, &["git", "checkout", "main"]);
+    commit(dir, "new-file", "hi");
+
+    run(dir, &["git", "merge", "other-branch"]);
+    ctx
+}
+
+/// Records a resolution, then redoes the merge for rerere to resolve it.
+fn setup_resolved() -> TestContext {
+    let ctx = setup_conflict();
+    let dir = ctx.dir.path();
+    fs::write(dir.join("new-file"), "hey hi").unwrap();
+    run(dir, &["git", "commit", "-am", "merge"]);
+
+    run(dir, &["git", "reset", "--hard", "HEAD^"]);
+    run(dir, &["git", "merge", "other-branch"]);
+    ctx
+}
+
+#[test]
+fn rerere_unresolved() {
+    snapshot!(setup_conflict(), "");
+}
+
+#[test]
+fn rerere_resolved() {
+    snapshot!(setup_resolved(), "");
+}
+
+#[test]
+fn rerere_forget() {
+    snapshot!(setup_resolved(), "jjjjXr");
+}
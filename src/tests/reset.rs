@@ -1,4 +1,5 @@
 use super::*;
+use crate::config::Confirm;
 
 fn setup() -> TestContext {
     let ctx = TestContext::setup_clone();
@@ -30,3 +31,32 @@ pub(crate) fn reset_mixed() {
 fn reset_hard() {
     snapshot!(setup(), "lljXh<enter>q");
 }
+
+fn setup_modified_file() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-one", "original\n");
+    fs::write(ctx.dir.child("file-one"), "modified\n").unwrap();
+    ctx
+}
+
+#[test]
+fn checkout_file_prompt() {
+    snapshot!(setup_modified_file(), "jjXf");
+}
+
+#[test]
+fn checkout_file_completion() {
+    snapshot!(setup_modified_file(), "jjXfma<tab>");
+}
+
+#[test]
+fn checkout_file() {
+    snapshot!(setup_modified_file(), "jjXfHEAD<enter>");
+}
+
+#[test]
+fn reset_hard_confirm() {
+    let mut ctx = setup();
+    ctx.config().general.confirm.reset_hard = Confirm::Ask;
+    snapshot!(ctx, "lljXh");
+}
@@ -0,0 +1,47 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "firstfile", "first\n");
+    commit(ctx.dir.path(), "secondfile", "second\n");
+    ctx
+}
+
+#[test]
+fn back() {
+    snapshot!(setup(), "ll<enter><backspace>");
+}
+
+#[test]
+fn back_at_first_screen() {
+    snapshot!(setup(), "<backspace>");
+}
+
+#[test]
+fn forward() {
+    snapshot!(setup(), "ll<enter><backspace><backspace><ctrl+r><ctrl+r>");
+}
+
+#[test]
+fn forward_after_opening_screen() {
+    snapshot!(setup(), "ll<enter><backspace>j<enter><ctrl+r>");
+}
+
+#[test]
+fn screen_limit() {
+    let mut ctx = setup();
+    ctx.config().general.screen_limit = 3;
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("ll<enter>kk<enter>kk<enter>"))
+        .unwrap();
+
+    assert_eq!(
+        state
+            .screens
+            .iter()
+            .map(|screen| screen.name)
+            .collect::<Vec<_>>(),
+        ["Status", "Show", "Show"]
+    );
+}
@@ -0,0 +1,52 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    fs::create_dir(ctx.dir.child("app")).unwrap();
+    fs::create_dir(ctx.dir.child("docs")).unwrap();
+    commit(ctx.dir.path(), "app/main.rs", "fn main() {}\n");
+    commit(ctx.dir.path(), "docs/guide.md", "# Guide\n");
+    commit(ctx.dir.path(), "docs/faq.md", "# FAQ\n");
+    ctx
+}
+
+fn setup_sparse() -> TestContext {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "sparse-checkout", "set", "app"]);
+    ctx
+}
+
+#[test]
+fn sparse_checkout_menu() {
+    snapshot!(setup(), "S");
+}
+
+#[test]
+fn set_patterns() {
+    snapshot!(setup(), "Ssapp<enter>");
+}
+
+#[test]
+fn add_patterns() {
+    snapshot!(setup_sparse(), "Sadocs<enter>Sl");
+}
+
+#[test]
+fn show_patterns() {
+    snapshot!(setup_sparse(), "Sl");
+}
+
+#[test]
+fn show_patterns_disabled() {
+    snapshot!(setup(), "Sl");
+}
+
+#[test]
+fn toggle_cone_mode() {
+    snapshot!(setup_sparse(), "ScSl");
+}
+
+#[test]
+fn disable() {
+    snapshot!(setup_sparse(), "Sd");
+}
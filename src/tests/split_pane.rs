@@ -0,0 +1,45 @@
+use super::*;
+
+fn setup(screens: &[&str]) -> TestContext {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "firstfile", "first\n");
+    commit(ctx.dir.path(), "secondfile", "second\n");
+    ctx.config().general.split_pane.screens = screens.iter().map(|s| s.to_string()).collect();
+    ctx
+}
+
+#[test]
+fn log() {
+    snapshot!(setup(&["log"]), "ll");
+}
+
+#[test]
+fn log_preview_follows_cursor() {
+    snapshot!(setup(&["log"]), "llj");
+}
+
+#[test]
+fn status_file() {
+    let ctx = setup(&["Status"]);
+    fs::write(ctx.dir.child("firstfile"), "first\nchanged\n").unwrap();
+    snapshot!(ctx, "jj");
+}
+
+#[test]
+fn nothing_to_preview() {
+    let ctx = setup(&["status"]);
+    fs::write(ctx.dir.child("untracked"), "new\n").unwrap();
+    snapshot!(ctx, "jj");
+}
+
+#[test]
+fn not_split_screen() {
+    snapshot!(setup(&["log"]), "ll<enter>");
+}
+
+#[test]
+fn list_width() {
+    let mut ctx = setup(&["log"]);
+    ctx.config().general.split_pane.list_width = 30;
+    snapshot!(ctx, "ll");
+}
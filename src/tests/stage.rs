@@ -1,4 +1,6 @@
 use super::*;
+use crate::git;
+use std::rc::Rc;
 
 #[test]
 fn staged_file() {
@@ -29,6 +31,41 @@ fn stage_all_untracked() {
     snapshot!(ctx, "js");
 }
 
+fn setup_untracked_dir() -> TestContext {
+    let ctx = TestContext::setup_init();
+    fs::create_dir_all(ctx.dir.child("dir/nested")).unwrap();
+    fs::write(ctx.dir.child("dir/file-a"), "").unwrap();
+    fs::write(ctx.dir.child("dir/nested/file-b"), "").unwrap();
+    fs::write(ctx.dir.child("dir/ignored"), "").unwrap();
+    fs::write(ctx.dir.child(".gitignore"), "ignored\n").unwrap();
+    ctx
+}
+
+#[test]
+fn untracked_dir() {
+    snapshot!(setup_untracked_dir(), "");
+}
+
+#[test]
+fn expand_untracked_dir() {
+    snapshot!(setup_untracked_dir(), "jjj<tab>");
+}
+
+#[test]
+fn untracked_dir_in_tracked_dir() {
+    let ctx = TestContext::setup_init();
+    fs::create_dir_all(ctx.dir.child("dir/new")).unwrap();
+    commit(ctx.dir.path(), "dir/tracked", "");
+    fs::write(ctx.dir.child("dir/new/file-a"), "").unwrap();
+    fs::write(ctx.dir.child("dir/file-b"), "").unwrap();
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn stage_file_in_untracked_dir() {
+    snapshot!(setup_untracked_dir(), "jjj<tab>js");
+}
+
 #[test]
 fn stage_removed_line() {
     let ctx = TestContext::setup_init();
@@ -54,3 +91,123 @@ fn stage_changes_crlf() {
 
     snapshot!(ctx, "jj<tab>");
 }
+
+#[test]
+fn refresh_only_rediffs_changed_files() {
+    let ctx = TestContext::setup_clone();
+    let dir = ctx.dir.path();
+    commit(dir, "file-a", "a\n");
+    commit(dir, "file-b", "b\n");
+    fs::write(dir.join("file-a"), "a changed\n").unwrap();
+    fs::write(dir.join("file-b"), "b changed\n").unwrap();
+
+    let config = crate::config::init_test_config().unwrap();
+    let settings = git::diff::DiffSettings::from_config(&config);
+    let cache = git::diff::HunkCache::default();
+    let repo = git2::Repository::open(dir).unwrap();
+    let before = git::diff_unstaged(&config, &settings, &cache, &repo).unwrap();
+
+    fs::write(dir.join("file-b"), "b changed again\n").unwrap();
+    run(dir, &["git", "add", "file-a"]);
+
+    let unstaged = git::diff_unstaged(&config, &settings, &cache, &repo).unwrap();
+    let staged = git::diff_staged(&config, &settings, &cache, &repo).unwrap();
+
+    assert!(Rc::ptr_eq(
+        &staged.deltas[0].hunks[0],
+        &before.deltas[0].hunks[0]
+    ));
+    assert!(!Rc::ptr_eq(
+        &unstaged.deltas[0].hunks[0],
+        &before.deltas[1].hunks[0]
+    ));
+}
+
+fn setup_three_changed_files() -> TestContext {
+    let ctx = TestContext::setup_init();
+    for file in ["file-a", "file-b", "file-c"] {
+        commit(ctx.dir.path(), file, "testing\n");
+        fs::write(ctx.dir.child(file), "blahonga\n").unwrap();
+    }
+    ctx
+}
+
+#[test]
+fn mark_files() {
+    let ctx = setup_three_changed_files();
+    snapshot!(ctx, "jjmjm");
+}
+
+#[test]
+fn stage_marked_files() {
+    let ctx = setup_three_changed_files();
+    snapshot!(ctx, "jjmjms");
+}
+
+#[test]
+fn stage_marked_hunks() {
+    let ctx = TestContext::setup_init();
+    let lines = (1..=20)
+        .map(|i| format!("line {}\n", i))
+        .collect::<String>();
+    commit(ctx.dir.path(), "file-a", &lines);
+    fs::write(
+        ctx.dir.child("file-a"),
+        lines
+            .replace("line 2\n", "changed 2\n")
+            .replace("line 19\n", "changed 19\n"),
+    )
+    .unwrap();
+    snapshot!(ctx, "jj<tab>jmms");
+}
+
+#[test]
+fn stage_marked_file_and_its_hunk() {
+    let ctx = TestContext::setup_init();
+    let lines = (1..=20)
+        .map(|i| format!("line {}\n", i))
+        .collect::<String>();
+    commit(ctx.dir.path(), "file-a", &lines);
+    fs::write(
+        ctx.dir.child("file-a"),
+        lines
+            .replace("line 2\n", "changed 2\n")
+            .replace("line 19\n", "changed 19\n"),
+    )
+    .unwrap();
+    snapshot!(ctx, "jjm<tab>jms");
+}
+
+#[test]
+fn select_region() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("firstfile"), "weehooo\nblrergh\n").unwrap();
+    snapshot!(ctx, "jj<tab><ctrl+j><ctrl+j><ctrl+j>v<ctrl+j>");
+}
+
+#[test]
+fn select_region_across_refresh() {
+    let mut ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("firstfile"), "weehooo\nblrergh\n").unwrap();
+
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("jj<tab><ctrl+j><ctrl+j><ctrl+j>v"))
+        .unwrap();
+    // Items above the region shift it down
+    fs::write(ctx.dir.child("untracked-file"), "").unwrap();
+    state
+        .update(&mut ctx.term, &keys("g<ctrl+j><ctrl+j><ctrl+j><ctrl+j>"))
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn stage_region() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("firstfile"), "weehooo\nblrergh\n").unwrap();
+    snapshot!(ctx, "jj<tab><ctrl+j><ctrl+j><ctrl+j>v<ctrl+j>s");
+}
@@ -130,3 +130,137 @@ pub(crate) fn stash_drop() {
 pub(crate) fn stash_drop_default() {
     snapshot!(setup_two_stashes(), "zk<enter>");
 }
+
+fn setup_two_modified_files() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-one", "one\n");
+    commit(ctx.dir.path(), "file-two", "two\n");
+    fs::write(ctx.dir.child("file-one"), "blahonga\n").unwrap();
+    fs::write(ctx.dir.child("file-two"), "blahonga\n").unwrap();
+    ctx
+}
+
+#[test]
+pub(crate) fn stash_selected_file() {
+    snapshot!(setup_two_modified_files(), "jjzstest<enter>");
+}
+
+#[test]
+pub(crate) fn stash_selected_rename() {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &["git", "mv", "initial-file", "renamed-file"],
+    );
+    snapshot!(ctx, "jjzstest<enter>");
+}
+
+fn setup_two_hunks() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    let content = (1..=20)
+        .map(|i| format!("line {}\n", i))
+        .collect::<String>();
+    commit(ctx.dir.path(), "file-one", &content);
+    fs::write(
+        ctx.dir.child("file-one"),
+        content
+            .replace("line 2\n", "changed 2\n")
+            .replace("line 19\n", "changed 19\n"),
+    )
+    .unwrap();
+    ctx
+}
+
+#[test]
+pub(crate) fn stash_selected_hunk() {
+    snapshot!(setup_two_hunks(), "jj<tab>jzstest<enter>");
+}
+
+#[test]
+pub(crate) fn stash_selected_hunk_with_staged_changes() {
+    let ctx = setup_two_hunks();
+    fs::write(ctx.dir.child("file-two"), "staged\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "file-two"]);
+    snapshot!(ctx, "jj<tab>jzstest<enter>");
+}
+
+#[test]
+pub(crate) fn stash_selected_hunk_next_to_staged_change() {
+    let mut ctx = TestContext::setup_clone();
+    let content = (1..=20)
+        .map(|i| format!("line {}\n", i))
+        .collect::<String>();
+    commit(ctx.dir.path(), "file-one", &content);
+    let staged = content.replace("line 2\n", "changed 2\n");
+    fs::write(ctx.dir.child("file-one"), &staged).unwrap();
+    run(ctx.dir.path(), &["git", "add", "file-one"]);
+    fs::write(
+        ctx.dir.child("file-one"),
+        staged.replace("line 12\n", "changed 12\n"),
+    )
+    .unwrap();
+
+    // With the whole file as context the hunk no longer applies once the staged change is set aside
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("=jj<tab>jzstest<enter>"))
+        .unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+    let repo = git2::Repository::open(ctx.dir.path()).unwrap();
+    assert_eq!(
+        repo.status_file(std::path::Path::new("file-one")).unwrap(),
+        git2::Status::INDEX_MODIFIED | git2::Status::WT_MODIFIED
+    );
+    assert!(repo.find_reference("refs/stash").is_err());
+}
+
+fn setup_untracked_stash() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "tracked", "one\ntwo\n");
+    fs::write(ctx.dir.child("tracked"), "one\nchanged\n").unwrap();
+    fs::write(ctx.dir.child("untracked"), "new\n").unwrap();
+    run(
+        ctx.dir.path(),
+        &["git", "stash", "push", "--include-untracked"],
+    );
+    ctx
+}
+
+#[test]
+fn show_stash_with_untracked() {
+    snapshot!(setup_untracked_stash(), "jj<enter>");
+}
+
+#[test]
+fn apply_stash_hunk() {
+    let mut ctx = setup_untracked_stash();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jj<enter>a")).unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert_eq!(
+        fs::read_to_string(ctx.dir.child("tracked")).unwrap(),
+        "one\nchanged\n"
+    );
+}
+
+#[test]
+fn apply_stash_line() {
+    let mut ctx = setup_untracked_stash();
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("jj<enter><ctrl+j>a"))
+        .unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+    assert_eq!(
+        fs::read_to_string(ctx.dir.child("tracked")).unwrap(),
+        "one\n"
+    );
+}
+
+#[test]
+fn apply_worktree_hunk() {
+    snapshot!(setup_two_hunks(), "jj<tab>ja");
+}
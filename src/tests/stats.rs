@@ -0,0 +1,19 @@
+use super::*;
+
+#[test]
+fn show_stats() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "small", "content\n");
+    commit(ctx.dir.path(), "large", &"content\n".repeat(200));
+    run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("#")).unwrap();
+
+    state.pending_stats.as_ref().unwrap().wait();
+    state.screen_mut().update().unwrap();
+    ctx.term
+        .draw(|frame| crate::ui::ui(frame, &mut state))
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
@@ -0,0 +1,50 @@
+use super::*;
+
+fn setup_submodule() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "-c",
+            "protocol.file.allow=always",
+            "submodule",
+            "add",
+            ctx.remote_dir.path().to_str().unwrap(),
+            "sub",
+        ],
+    );
+    ctx
+}
+
+fn setup_moved_submodule() -> TestContext {
+    let ctx = setup_submodule();
+    run(ctx.dir.path(), &["git", "commit", "-m", "add sub"]);
+    commit(&ctx.dir.child("sub"), "sub-file", "hello");
+    ctx
+}
+
+#[test]
+fn added_submodule() {
+    snapshot!(setup_submodule(), "jjjj<tab>");
+}
+
+#[test]
+fn moved_submodule() {
+    snapshot!(setup_moved_submodule(), "jj<tab>");
+}
+
+#[test]
+fn stage_moved_submodule() {
+    snapshot!(setup_moved_submodule(), "jjs");
+}
+
+#[test]
+fn show_submodule_commit() {
+    snapshot!(setup_moved_submodule(), "jj<tab>jj<enter>");
+}
+
+#[test]
+fn no_ops_on_submodule_commit() {
+    snapshot!(setup_moved_submodule(), "jj<tab>jj<enter>AA");
+}
@@ -0,0 +1,83 @@
+use super::*;
+use std::path::PathBuf;
+
+/// A clone in a directory with a fixed name, since it's shown in the title bar.
+fn setup() -> (TestContext, PathBuf) {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.title_bar.enabled = true;
+
+    let repo = ctx.dir.child("repo");
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "clone",
+            ctx.remote_dir.path().to_str().unwrap(),
+            "repo",
+        ],
+    );
+
+    (ctx, repo)
+}
+
+fn render_at(mut ctx: TestContext, repo: PathBuf, input: &str) -> String {
+    let mut state = ctx.init_state_at_path(repo);
+    state.update(&mut ctx.term, &keys(input)).unwrap();
+    ctx.redact_buffer()
+}
+
+#[test]
+fn title_bar() {
+    let (ctx, repo) = setup();
+    insta::assert_snapshot!(render_at(ctx, repo, ""));
+}
+
+#[test]
+fn title_bar_dirty() {
+    let (ctx, repo) = setup();
+    fs::write(repo.join("new-file"), "").unwrap();
+    insta::assert_snapshot!(render_at(ctx, repo, ""));
+}
+
+#[test]
+fn title_bar_detached_rebasing() {
+    let (ctx, repo) = setup();
+    commit(&repo, "file", "one");
+    run(&repo, &["git", "checkout", "-b", "other", "HEAD~1"]);
+    commit(&repo, "file", "two");
+    run(&repo, &["git", "rebase", "main"]);
+    insta::assert_snapshot!(render_at(ctx, repo, ""));
+}
+
+#[test]
+fn title_bar_screen_name() {
+    let (ctx, repo) = setup();
+    insta::assert_snapshot!(render_at(ctx, repo, "ll"));
+}
+
+#[test]
+fn title_bar_updates_on_refresh() {
+    let (mut ctx, repo) = setup();
+    let mut state = ctx.init_state_at_path(repo.clone());
+    fs::write(repo.join("new-file"), "").unwrap();
+    state.update(&mut ctx.term, &keys("g")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn title_bar_breadcrumbs() {
+    let (ctx, repo) = setup();
+    insta::assert_snapshot!(render_at(ctx, repo, "ll<enter>"));
+}
+
+#[test]
+fn title_bar_breadcrumbs_elided() {
+    let (ctx, repo) = setup();
+    insta::assert_snapshot!(render_at(ctx, repo, "ll<enter>#G"));
+}
+
+#[test]
+fn title_bar_recording_macro() {
+    let (ctx, repo) = setup();
+    insta::assert_snapshot!(render_at(ctx, repo, "Q"));
+}
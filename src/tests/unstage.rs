@@ -25,3 +25,29 @@ fn unstage_added_line() {
     run(ctx.dir.path(), &["git", "add", "."]);
     snapshot!(ctx, "jj<tab><ctrl+j><ctrl+j><ctrl+j><ctrl+j>u");
 }
+
+#[test]
+fn unstage_moved_file() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hello");
+    run(ctx.dir.path(), &["git", "mv", "new-file", "moved-file"]);
+    snapshot!(ctx, "jju");
+}
+
+#[test]
+fn unstage_marked_files() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "one", "two", "three"]);
+    run(ctx.dir.path(), &["git", "add", "one", "two", "three"]);
+    snapshot!(ctx, "jjmjmu");
+}
+
+#[test]
+fn unstage_region() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("firstfile"), "weehooo\nblrergh\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+
+    snapshot!(ctx, "jj<tab><ctrl+j><ctrl+j><ctrl+j>v<ctrl+j>u");
+}
@@ -1,3 +1,6 @@
+use crate::config::{CmdOutput, Config, GeneralConfig};
+use crate::menu::Menu;
+use crate::screen::preview::{self, Preview};
 use crate::state::State;
 use ratatui::prelude::*;
 use ratatui::style::Stylize;
@@ -5,8 +8,11 @@ use ratatui::widgets::*;
 use ratatui::Frame;
 use tui_prompts::State as _;
 use tui_prompts::TextPrompt;
+use tui_prompts::TextRenderStyle;
 
+mod key_hints;
 mod menu;
+pub(crate) mod title_bar;
 
 pub(crate) struct SizedWidget<W> {
     height: u16,
@@ -27,65 +33,284 @@ impl<W: StatefulWidget> StatefulWidget for SizedWidget<W> {
     }
 }
 
+pub(crate) const MIN_LIST_WIDTH: u16 = 10;
+pub(crate) const MAX_LIST_WIDTH: u16 = 90;
+
+/// Starts out as configured, and can be adjusted for the rest of the session with `root.layout_menu`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LayoutSettings {
+    /// In percent of the terminal width, see `general.split_pane`.
+    pub list_width: u16,
+    /// 0 when popups aren't limited.
+    pub popup_max_height: u16,
+    pub cmd_output: CmdOutput,
+}
+
+impl LayoutSettings {
+    pub(crate) fn new(config: &GeneralConfig) -> Self {
+        Self {
+            list_width: config
+                .split_pane
+                .list_width
+                .clamp(MIN_LIST_WIDTH, MAX_LIST_WIDTH),
+            popup_max_height: config.popup_max_height,
+            cmd_output: config.cmd_output,
+        }
+    }
+
+    fn limit_height(&self, height: u16) -> u16 {
+        match self.popup_max_height {
+            0 => height,
+            max => height.min(max),
+        }
+    }
+}
+
 pub(crate) fn ui(frame: &mut Frame, state: &mut State) {
+    let layout_settings = state.layout;
+
     let maybe_log = if !state.current_cmd_log.is_empty() {
         let text: Text = state.current_cmd_log.format_log(&state.config);
+        let height = layout_settings.limit_height(1 + text.lines.len() as u16);
+        // The end of the output is the most telling
+        let hidden_lines = (1 + text.lines.len() as u16).saturating_sub(height);
 
         Some(SizedWidget {
-            widget: Paragraph::new(text.clone()).block(popup_block()),
-            height: 1 + text.lines.len() as u16,
+            widget: Paragraph::new(text.clone())
+                .block(popup_block(&state.config))
+                .scroll((hidden_lines, 0)),
+            height,
         })
     } else {
         None
     };
 
-    let maybe_menu = state.pending_menu.as_ref().and_then(|menu| {
-        if menu.is_hidden {
-            None
-        } else {
-            Some(menu::MenuWidget::new(
-                &state.config,
-                &state.bindings,
-                menu,
-                state.screens.last().unwrap().get_selected_item(),
-            ))
-        }
-    });
+    // Menus list their own keys, and show the pending ones below
+    let continuations = state
+        .matching_bindings()
+        .into_iter()
+        .filter(|binding| binding.keys.len() > state.pending_keys.len())
+        .collect::<Vec<_>>();
+    let show_which_key = !state.pending_keys.is_empty()
+        && !continuations.is_empty()
+        && state
+            .pending_menu
+            .as_ref()
+            .is_none_or(|menu| matches!(menu.menu, Menu::Root | Menu::Help));
+
+    let maybe_menu = if show_which_key {
+        let mut widget =
+            menu::MenuWidget::which_key(&state.config, &continuations, &state.pending_keys);
+        widget.height = layout_settings.limit_height(widget.height);
+        Some(widget)
+    } else {
+        state.pending_menu.as_ref().and_then(|menu| {
+            if menu.is_hidden {
+                None
+            } else {
+                let mut widget = menu::MenuWidget::new(
+                    &state.config,
+                    &state.bindings,
+                    menu,
+                    state.screens.last().unwrap().get_selected_item(),
+                );
+                widget.height = layout_settings.limit_height(widget.height);
+                Some(widget)
+            }
+        })
+    };
 
     let maybe_prompt = state.prompt.data.as_ref().map(|prompt_data| SizedWidget {
         height: 2,
-        widget: TextPrompt::new(prompt_data.prompt_text.clone()).with_block(popup_block()),
+        widget: TextPrompt::new(prompt_data.prompt_text.clone())
+            .with_block(popup_block(&state.config))
+            .with_render_style(if state.prompt.masked {
+                TextRenderStyle::Password
+            } else {
+                TextRenderStyle::Default
+            }),
     });
 
+    let maybe_candidates = maybe_prompt
+        .as_ref()
+        .and_then(|_| completion_candidates(state));
+
+    if state.config.general.title_bar.enabled
+        && state
+            .repo_summary
+            .as_ref()
+            .is_none_or(|s| s.is_stale(state.refresh_generation))
+    {
+        state.repo_summary = Some(title_bar::RepoSummary::new(
+            &state.repo,
+            state.refresh_generation,
+        ));
+    }
+
+    let maybe_title_bar = state
+        .repo_summary
+        .as_ref()
+        .filter(|_| state.config.general.title_bar.enabled)
+        .map(|summary| SizedWidget {
+            height: 1,
+            widget: title_bar::TitleBar::new(
+                &state.config,
+                summary,
+                &state
+                    .screens
+                    .iter()
+                    .map(|screen| screen.name)
+                    .collect::<Vec<_>>(),
+                state.macro_recording.is_some(),
+            ),
+        });
+
+    let show_key_hints = state.config.general.key_hints.enabled
+        && state.pending_menu.is_none()
+        && state.prompt.data.is_none();
+
+    let maybe_key_hints = if !state.pending_keys.is_empty() && !show_which_key {
+        Some(key_hints::pending_keys(&state.config, &state.pending_keys))
+    } else {
+        show_key_hints
+            .then(|| {
+                key_hints::key_hints(
+                    &state.config,
+                    &state.bindings,
+                    state.screens.last().unwrap().keymap,
+                    state.screens.last().unwrap().get_selected_item(),
+                )
+            })
+            .flatten()
+    };
+
     let layout = Layout::new(
         Direction::Vertical,
         [
+            widget_height(&maybe_title_bar),
             Constraint::Min(1),
             widget_height(&maybe_prompt),
+            widget_height(&maybe_candidates),
             widget_height(&maybe_menu),
-            widget_height(&maybe_log),
+            match layout_settings.cmd_output {
+                CmdOutput::Docked => widget_height(&maybe_log),
+                CmdOutput::Overlaid => Constraint::Length(0),
+            },
+            widget_height(&maybe_key_hints),
         ],
     )
     .split(frame.size());
 
-    frame.render_widget(state.screens.last().unwrap(), layout[0]);
+    maybe_render(maybe_title_bar, frame, layout[0]);
+
+    let screen_area = match split_pane(state, layout[1]) {
+        Some((list_area, preview_area)) => {
+            let block = Block::new()
+                .borders(Borders::LEFT)
+                .border_style(&state.config.style.popup);
+            let inner = block.inner(preview_area);
+            frame.render_widget(block, preview_area);
+
+            let mut preview = state.preview.take();
+            preview::update(&mut preview, state, inner);
+            state.preview = preview;
+            render_preview(frame, state.preview.as_ref(), inner);
+            list_area
+        }
+        None => {
+            state.preview = None;
+            layout[1]
+        }
+    };
+    frame.render_widget(state.screens.last().unwrap(), screen_area);
 
     if let Some(prompt) = maybe_prompt {
-        frame.render_stateful_widget(prompt, layout[1], &mut state.prompt.state);
+        frame.render_stateful_widget(prompt, layout[2], &mut state.prompt.state);
         let (cx, cy) = state.prompt.state.cursor();
         frame.set_cursor(cx, cy);
     }
 
-    maybe_render(maybe_menu, frame, layout[2]);
-    maybe_render(maybe_log, frame, layout[3]);
+    maybe_render(maybe_candidates, frame, layout[3]);
+    maybe_render(maybe_menu, frame, layout[4]);
+    match layout_settings.cmd_output {
+        CmdOutput::Docked => maybe_render(maybe_log, frame, layout[5]),
+        CmdOutput::Overlaid => {
+            if let Some(log) = maybe_log {
+                let height = log.height.min(layout[1].height);
+                let area = Rect {
+                    y: layout[1].bottom() - height,
+                    height,
+                    ..layout[1]
+                };
+                frame.render_widget(Clear, area);
+                frame.render_widget(log, area);
+            }
+        }
+    }
+    maybe_render(maybe_key_hints, frame, layout[6]);
+
+    state.screens.last_mut().unwrap().size = screen_area;
+}
+
+/// Too narrow, and neither pane would be readable.
+const MIN_SPLIT_WIDTH: u16 = 40;
+
+fn split_pane(state: &State, area: Rect) -> Option<(Rect, Rect)> {
+    let config = &state.config.general.split_pane;
+    if !config.splits(state.screens.last().unwrap().name) || area.width < MIN_SPLIT_WIDTH {
+        return None;
+    }
+
+    let panes = Layout::new(
+        Direction::Horizontal,
+        [
+            Constraint::Percentage(state.layout.list_width),
+            Constraint::Min(1),
+        ],
+    )
+    .split(area);
+
+    Some((panes[0], panes[1]))
+}
+
+fn render_preview(frame: &mut Frame, preview: Option<&Preview>, area: Rect) {
+    match preview.and_then(|preview| preview.screen.as_ref()) {
+        Some(screen) => frame.render_widget(screen, area),
+        None => frame.render_widget(Paragraph::new("Nothing to preview").dim(), area),
+    }
+}
+
+const MAX_SHOWN_CANDIDATES: usize = 5;
+
+fn completion_candidates(state: &State) -> Option<SizedWidget<Paragraph<'static>>> {
+    let matching = state.prompt.matching_completions().collect::<Vec<_>>();
+    if matching.is_empty() {
+        return None;
+    }
+
+    let mut lines = matching
+        .iter()
+        .take(MAX_SHOWN_CANDIDATES)
+        .map(|candidate| Line::raw(candidate.to_string()))
+        .collect::<Vec<_>>();
+
+    if matching.len() > MAX_SHOWN_CANDIDATES {
+        lines.push(Line::raw(format!(
+            "… {} more",
+            matching.len() - MAX_SHOWN_CANDIDATES
+        )));
+    }
 
-    state.screens.last_mut().unwrap().size = layout[0];
+    Some(SizedWidget {
+        height: lines.len() as u16,
+        widget: Paragraph::new(lines).dim(),
+    })
 }
 
-fn popup_block() -> Block<'static> {
+fn popup_block(config: &Config) -> Block<'static> {
     Block::new()
         .borders(Borders::TOP)
-        .border_style(Style::new().dim())
+        .border_style(&config.style.popup)
         .border_type(ratatui::widgets::BorderType::Plain)
 }
 
@@ -0,0 +1,85 @@
+use super::SizedWidget;
+use crate::{
+    bindings::{Binding, Bindings},
+    config::Config,
+    items::Item,
+    key_parser,
+    menu::Menu,
+    ops::Op,
+};
+use crossterm::event::{KeyCode, KeyModifiers};
+use itertools::Itertools;
+use ratatui::{
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+/// A line of the keys that act on the selected item, followed by the key to open help.
+/// Those of the screen's own keymap come first.
+pub(crate) fn key_hints<'a>(
+    config: &Config,
+    bindings: &'a Bindings,
+    keymap: Option<Menu>,
+    item: &'a Item,
+) -> Option<SizedWidget<Paragraph<'a>>> {
+    let style = &config.style;
+
+    let target_binds = item.target_data.iter().flat_map(|target_data| {
+        let keymap_binds: Vec<_> = keymap
+            .map(|keymap| bindings.target_list(&keymap, target_data).collect())
+            .unwrap_or_default();
+
+        keymap_binds
+            .into_iter()
+            .chain(bindings.target_list(&Menu::Root, target_data))
+    });
+
+    let help_binds = bindings
+        .list(&Menu::Root)
+        .filter(|keybind| keybind.op == Op::OpenMenu(Menu::Help));
+
+    // Only the first key of each op, to keep the line short
+    let hints = target_binds
+        .chain(help_binds)
+        .dedup_by(|a, b| a.op == b.op)
+        .map(|keybind| hint(keybind, config))
+        .collect::<Vec<_>>();
+
+    if hints.is_empty() {
+        return None;
+    }
+
+    let spans = Itertools::intersperse(hints.into_iter(), vec![Span::raw("  ")])
+        .flatten()
+        .collect::<Vec<_>>();
+
+    Some(SizedWidget {
+        height: 1,
+        widget: Paragraph::new(Line::from(spans)).style(&style.key_hints),
+    })
+}
+
+/// The keys pressed so far of a longer binding, like Emacs echoes `C-x-`.
+pub(crate) fn pending_keys<'a>(
+    config: &Config,
+    keys: &[(KeyModifiers, KeyCode)],
+) -> SizedWidget<Paragraph<'a>> {
+    let keys = keys.iter().map(key_parser::format_key).collect::<String>();
+
+    SizedWidget {
+        height: 1,
+        widget: Paragraph::new(Line::from(vec![
+            Span::styled(keys, &config.style.hotkey),
+            Span::raw("-"),
+        ]))
+        .style(&config.style.key_hints),
+    }
+}
+
+fn hint<'a>(keybind: &'a Binding, config: &Config) -> Vec<Span<'a>> {
+    vec![
+        Span::styled(keybind.raw.as_str(), &config.style.hotkey),
+        Span::raw(" "),
+        Span::raw(keybind.op.clone().implementation().to_string()),
+    ]
+}
@@ -1,5 +1,13 @@
 use super::SizedWidget;
-use crate::{bindings::Bindings, config::Config, items::Item, menu::PendingMenu, ops::Op};
+use crate::{
+    bindings::{Binding, Bindings},
+    config::Config,
+    items::Item,
+    key_parser,
+    menu::PendingMenu,
+    ops::Op,
+};
+use crossterm::event::{KeyCode, KeyModifiers};
 use itertools::{EitherOrBoth, Itertools};
 use ratatui::{
     buffer::Buffer,
@@ -33,7 +41,7 @@ impl<'a> MenuWidget<'a> {
         pending_binds_column.push(Line::styled(format!("{}", pending.menu), &style.command));
         for (op, binds) in non_target_binds
             .iter()
-            .chunk_by(|bind| &bind.op)
+            .chunk_by(|bind: &&&Binding| &bind.op)
             .into_iter()
             .filter(|(op, _binds)| !matches!(op, Op::OpenMenu(_)))
         {
@@ -69,16 +77,7 @@ impl<'a> MenuWidget<'a> {
         let mut right_column = vec![];
         if let Some(target_data) = &item.target_data {
             let target_binds = bindings
-                .list(&pending.menu)
-                .filter(|keybind| keybind.op.clone().implementation().is_target_op())
-                .filter(|keybind| {
-                    keybind
-                        .op
-                        .clone()
-                        .implementation()
-                        .get_action(Some(target_data))
-                        .is_some()
-                })
+                .target_list(&pending.menu, target_data)
                 .collect::<Vec<_>>();
 
             if !target_binds.is_empty() {
@@ -150,7 +149,76 @@ impl<'a> MenuWidget<'a> {
         SizedWidget {
             height: 1 + lines as u16,
             widget: MenuWidget {
-                table: table.block(super::popup_block()),
+                table: table.block(super::popup_block(config)),
+            },
+        }
+    }
+}
+
+impl MenuWidget<'static> {
+    /// Like Emacs' which-key, lists what can follow the keys pressed so far,
+    /// with `bindings` those starting with them.
+    pub fn which_key(
+        config: &Config,
+        bindings: &[&Binding],
+        pending_keys: &[(KeyModifiers, KeyCode)],
+    ) -> SizedWidget<Self> {
+        let style = &config.style;
+        let rest = |bind: &Binding| {
+            bind.keys[pending_keys.len()..]
+                .iter()
+                .map(key_parser::format_key)
+                .collect::<String>()
+        };
+
+        let prefix = pending_keys
+            .iter()
+            .map(key_parser::format_key)
+            .collect::<String>();
+
+        let (menus, ops): (Vec<_>, Vec<_>) = bindings
+            .iter()
+            .partition(|bind| matches!(bind.op, Op::OpenMenu(_)));
+
+        let mut ops_column = vec![Line::styled(format!("{}-", prefix), &style.command)];
+        for (op, binds) in ops.iter().chunk_by(|bind: &&&Binding| &bind.op).into_iter() {
+            ops_column.push(Line::from(vec![
+                Span::styled(binds.map(|bind| rest(bind)).join("/"), &style.hotkey),
+                Span::raw(format!(" {}", op.clone().implementation())),
+            ]));
+        }
+
+        let mut menus_column = vec![];
+        if !menus.is_empty() {
+            menus_column.push(Line::styled("Submenu", &style.command));
+        }
+        for bind in menus {
+            let Op::OpenMenu(menu) = bind.op else {
+                unreachable!();
+            };
+
+            menus_column.push(Line::from(vec![
+                Span::styled(rest(bind), &style.hotkey),
+                Span::raw(format!(" {}", menu)),
+            ]));
+        }
+
+        let widths = [col_width(&ops_column), Constraint::Fill(1)];
+        let rows = ops_column
+            .into_iter()
+            .zip_longest(menus_column)
+            .map(|lines| {
+                let (a, b) = lines.or(Line::raw(""), Line::raw(""));
+                Row::new([a, b])
+            })
+            .collect::<Vec<_>>();
+
+        let (lines, table) = (rows.len(), Table::new(rows, widths).column_spacing(3));
+
+        SizedWidget {
+            height: 1 + lines as u16,
+            widget: MenuWidget {
+                table: table.block(super::popup_block(config)),
             },
         }
     }
@@ -0,0 +1,155 @@
+use crate::{config::Config, git, git2_opts};
+use git2::{Repository, RepositoryState};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::Widget,
+};
+
+/// What the title bar shows about the repo. Only recomputed when the screen refreshes.
+pub(crate) struct RepoSummary {
+    refresh_generation: usize,
+    repo_name: String,
+    head: String,
+    dirty: bool,
+    operation: Option<&'static str>,
+}
+
+impl RepoSummary {
+    pub(crate) fn new(repo: &Repository, refresh_generation: usize) -> Self {
+        let repo_name = repo
+            .workdir()
+            .unwrap_or(repo.path())
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let head = git::current_branch(repo)
+            .or_else(|| {
+                let head = repo.head().ok()?.peel_to_commit().ok()?;
+                Some(format!(
+                    "(detached {})",
+                    head.as_object().short_id().ok()?.as_str()?
+                ))
+            })
+            .unwrap_or_else(|| "(no branch)".to_string());
+
+        let dirty = git2_opts::status(repo)
+            .and_then(|mut opts| Ok(!repo.statuses(Some(&mut opts))?.is_empty()))
+            .unwrap_or(false);
+
+        let operation = match repo.state() {
+            RepositoryState::Clean => None,
+            RepositoryState::Merge => Some("Merging"),
+            RepositoryState::Revert | RepositoryState::RevertSequence => Some("Reverting"),
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+                Some("Cherry-picking")
+            }
+            RepositoryState::Bisect => Some("Bisecting"),
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge => Some("Rebasing"),
+            RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => {
+                Some("Applying patches")
+            }
+        };
+
+        Self {
+            refresh_generation,
+            repo_name,
+            head,
+            dirty,
+            operation,
+        }
+    }
+
+    pub(crate) fn is_stale(&self, refresh_generation: usize) -> bool {
+        self.refresh_generation != refresh_generation
+    }
+}
+
+/// Screens of the stack shown in the title bar, the first ones are elided beyond it.
+const BREADCRUMBS: usize = 4;
+
+pub(crate) struct TitleBar<'a> {
+    left: Line<'a>,
+    right: Line<'a>,
+    style: Style,
+}
+
+impl<'a> TitleBar<'a> {
+    /// With `screen_names` of the stack, the current one last.
+    pub(crate) fn new(
+        config: &Config,
+        summary: &'a RepoSummary,
+        screen_names: &[&str],
+        recording_macro: bool,
+    ) -> Self {
+        let style = &config.style;
+
+        let mut left = vec![
+            Span::raw(summary.repo_name.as_str()),
+            Span::raw(" "),
+            Span::styled(summary.head.as_str(), &style.branch),
+        ];
+
+        if summary.dirty {
+            left.push(Span::raw("*"));
+        }
+
+        if let Some(operation) = summary.operation {
+            left.push(Span::raw(" "));
+            left.push(Span::styled(operation, &style.section_header));
+        }
+
+        if recording_macro {
+            left.push(Span::raw(" "));
+            left.push(Span::styled("Recording macro", &style.section_header));
+        }
+
+        let elided = screen_names.len().saturating_sub(BREADCRUMBS);
+        let breadcrumbs = (elided > 0)
+            .then_some("…")
+            .into_iter()
+            .chain(screen_names[elided..].iter().copied())
+            .collect::<Vec<_>>()
+            .join(" › ");
+
+        Self {
+            left: Line::from(left),
+            right: Line::raw(breadcrumbs).dim(),
+            style: (&style.title_bar).into(),
+        }
+    }
+}
+
+impl Widget for TitleBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        buf.set_style(area, self.style);
+
+        let inner = Rect {
+            x: area.x + 1,
+            width: area.width.saturating_sub(2),
+            ..area
+        };
+
+        let right_width = (self.right.width() as u16).min(inner.width);
+        self.left.render(
+            Rect {
+                width: inner.width.saturating_sub(right_width + 1),
+                ..inner
+            },
+            buf,
+        );
+        self.right.render(
+            Rect {
+                x: inner.right() - right_width,
+                width: right_width,
+                ..inner
+            },
+            buf,
+        );
+    }
+}